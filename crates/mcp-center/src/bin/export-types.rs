@@ -4,16 +4,28 @@
 //! 运行: cargo run --bin export-types
 
 use mcp_center::{
-    config::ServerProtocol,
+    config::{ServerProtocol, settings::NewProjectPolicy},
     daemon::{
+        approvals::{ApprovalEvent, ApprovalOutcome, ApprovalRequest},
         logging::{LogCategory, LogEntry, LogLevel, ServerContext, ToolContext},
         server_manager::ServerSnapshot,
     },
+    project::{
+        ProjectStatus,
+        annotations::{AnnotationSwitch, ToolHints},
+        arguments::ArgumentConstraint,
+    },
     web::http::{
-        CreateMcpRequest, LogEntriesResponse, LogFileSummary, LogListResponse, LogServerSummary,
-        McpListResponse, ProjectAssignRequest, ProjectListResponse, ProjectSummary,
-        ProjectToolDescRequest, ProjectToolResetRequest, ProjectToolsRequest, ServerDetail,
-        ServerDetailResponse, ToolListResponse, ToolSummary, UpdateMcpEnabled,
+        AnnotationSwitches, ApprovalListResponse, CreateMcpRequest, EffectiveReason,
+        EffectiveSwitchSummary, EffectiveToolSummary, GrantSummary, LogEntriesResponse,
+        LogFileSummary, LogListResponse, LogServerSummary, McpListResponse,
+        ProjectAnnotationPolicyRequest, ProjectApproveRequest, ProjectAssignRequest,
+        ProjectEffectiveResponse, ProjectGrantRequest, ProjectGrantsResponse, ProjectListResponse,
+        ProjectRequireApprovalRequest, ProjectSummary, ProjectToolAliasRequest,
+        ProjectToolArgumentsRequest, ProjectToolDescRequest, ProjectToolHintsRequest,
+        ProjectToolResetRequest, ProjectToolSearchRequest, ProjectToolsRequest,
+        ResolveApprovalRequest, ServerDetail, ServerDetailResponse, SettingsResponse,
+        ToolListResponse, ToolSummary, UpdateMcpEnabled, UpdateSettingsRequest,
     },
 };
 use specta::ts::{BigIntExportBehavior, ExportConfiguration, TsExportError};
@@ -29,13 +41,46 @@ const EXPORT_TARGETS: &[(&str, ExportFn)] = &[
     ("ToolListResponse", specta::ts::export::<ToolListResponse>),
     ("ServerDetail", specta::ts::export::<ServerDetail>),
     ("ServerDetailResponse", specta::ts::export::<ServerDetailResponse>),
+    ("ProjectStatus (enum)", specta::ts::export::<ProjectStatus>),
+    ("AnnotationSwitch (enum)", specta::ts::export::<AnnotationSwitch>),
+    ("AnnotationSwitches", specta::ts::export::<AnnotationSwitches>),
     ("ProjectSummary", specta::ts::export::<ProjectSummary>),
+    ("EffectiveReason (enum)", specta::ts::export::<EffectiveReason>),
+    ("ToolHints", specta::ts::export::<ToolHints>),
+    ("EffectiveToolSummary", specta::ts::export::<EffectiveToolSummary>),
+    ("EffectiveSwitchSummary", specta::ts::export::<EffectiveSwitchSummary>),
+    ("ProjectEffectiveResponse", specta::ts::export::<ProjectEffectiveResponse>),
+    ("ProjectGrantRequest", specta::ts::export::<ProjectGrantRequest>),
+    ("GrantSummary", specta::ts::export::<GrantSummary>),
+    ("ProjectGrantsResponse", specta::ts::export::<ProjectGrantsResponse>),
     ("CreateMcpRequest", specta::ts::export::<CreateMcpRequest>),
     ("UpdateMcpEnabled", specta::ts::export::<UpdateMcpEnabled>),
     ("ProjectAssignRequest", specta::ts::export::<ProjectAssignRequest>),
+    ("ProjectApproveRequest", specta::ts::export::<ProjectApproveRequest>),
     ("ProjectToolsRequest", specta::ts::export::<ProjectToolsRequest>),
     ("ProjectToolDescRequest", specta::ts::export::<ProjectToolDescRequest>),
     ("ProjectToolResetRequest", specta::ts::export::<ProjectToolResetRequest>),
+    ("ProjectToolAliasRequest", specta::ts::export::<ProjectToolAliasRequest>),
+    (
+        "ProjectAnnotationPolicyRequest",
+        specta::ts::export::<ProjectAnnotationPolicyRequest>,
+    ),
+    ("ProjectToolHintsRequest", specta::ts::export::<ProjectToolHintsRequest>),
+    ("ProjectToolSearchRequest", specta::ts::export::<ProjectToolSearchRequest>),
+    ("ArgumentConstraint", specta::ts::export::<ArgumentConstraint>),
+    ("ProjectToolArgumentsRequest", specta::ts::export::<ProjectToolArgumentsRequest>),
+    (
+        "ProjectRequireApprovalRequest",
+        specta::ts::export::<ProjectRequireApprovalRequest>,
+    ),
+    ("ApprovalRequest", specta::ts::export::<ApprovalRequest>),
+    ("ApprovalOutcome (enum)", specta::ts::export::<ApprovalOutcome>),
+    ("ApprovalEvent (enum)", specta::ts::export::<ApprovalEvent>),
+    ("ApprovalListResponse", specta::ts::export::<ApprovalListResponse>),
+    ("ResolveApprovalRequest", specta::ts::export::<ResolveApprovalRequest>),
+    ("NewProjectPolicy (enum)", specta::ts::export::<NewProjectPolicy>),
+    ("SettingsResponse", specta::ts::export::<SettingsResponse>),
+    ("UpdateSettingsRequest", specta::ts::export::<UpdateSettingsRequest>),
     ("LogFileSummary", specta::ts::export::<LogFileSummary>),
     ("LogServerSummary", specta::ts::export::<LogServerSummary>),
     ("LogListResponse", specta::ts::export::<LogListResponse>),
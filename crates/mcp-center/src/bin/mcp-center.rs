@@ -12,11 +12,22 @@ use clap::{ArgAction, Args, CommandFactory, FromArgMatches, Parser, Subcommand,
 use interprocess::local_socket::traits::tokio::Stream as _;
 use interprocess::local_socket::{GenericFilePath, ToFsName, tokio::prelude::LocalSocketStream};
 use mcp_center::cli_i18n as i18n;
+use mcp_center::config::{WorkspaceSettings, settings::MonorepoMode};
 use mcp_center::daemon::{
+    auth, cache,
     logging::{self, LogEntry, LogFileMeta, LogLevel},
     rpc::{DaemonRequest, DaemonResponse, ResponseData},
 };
-use mcp_center::project::{ToolCustomization, ToolPermission};
+use mcp_center::project::{
+    ToolCustomization, ToolPermission,
+    annotations::{AnnotationOverride, AnnotationSwitch, ToolHints},
+    arguments::{ArgumentConstraint, ArgumentPreset, Pattern},
+    detect::{self, DetectionRule},
+    grant::{self, TemporaryGrant},
+    policy::{DecisionReason, EffectivePolicy, PolicySource},
+    repo_config::RepoWarning,
+    schema,
+};
 use mcp_center::{
     Layout, ProjectId, ProjectRecord, ProjectRegistry, ServerConfig, ServerDefinition,
     ServerProtocol, default_root,
@@ -80,6 +91,18 @@ enum Command {
         #[command(subcommand)]
         command: LogsCommand,
     },
+
+    #[command(about = "i18n:command.approvals.about")]
+    Approvals {
+        #[command(subcommand)]
+        command: ApprovalsCommand,
+    },
+
+    #[command(about = "i18n:command.cache.about")]
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -109,17 +132,43 @@ enum ProjectCommand {
     #[command(about = "i18n:command.project.list.about")]
     List,
     #[command(about = "i18n:command.project.allow.about")]
-    Allow(ProjectAssignArgs),
+    Allow(ProjectAllowArgs),
     #[command(about = "i18n:command.project.deny.about")]
     Deny(ProjectAssignArgs),
     #[command(about = "i18n:command.project.allow_tools.about")]
-    AllowTools(ProjectToolsArgs),
+    AllowTools(ProjectAllowToolsArgs),
     #[command(about = "i18n:command.project.deny_tools.about")]
     DenyTools(ProjectToolsArgs),
     #[command(about = "i18n:command.project.set_tool_desc.about")]
     SetToolDesc(ProjectToolDescArgs),
     #[command(about = "i18n:command.project.reset_tool_desc.about")]
     ResetToolDesc(ProjectResetToolDescArgs),
+    #[command(about = "i18n:command.project.set_mode.about")]
+    SetMode(ProjectSetModeArgs),
+    #[command(about = "i18n:command.project.set_tool_hints.about")]
+    SetToolHints(ProjectToolHintsArgs),
+    #[command(about = "i18n:command.project.detect.about")]
+    Detect(ProjectDetectArgs),
+    #[command(about = "i18n:command.project.move.about")]
+    Move(ProjectMoveArgs),
+    #[command(about = "i18n:command.project.approve.about")]
+    Approve(ProjectApproveArgs),
+    #[command(about = "i18n:command.project.explain.about")]
+    Explain(ProjectExplainArgs),
+    #[command(about = "i18n:command.project.effective.about")]
+    Effective(ProjectEffectiveArgs),
+    #[command(about = "i18n:command.project.grants.about")]
+    Grants(ProjectGrantsArgs),
+    #[command(about = "i18n:command.project.require_approval.about")]
+    RequireApproval(ProjectRequireApprovalArgs),
+    #[command(about = "i18n:command.project.set_arg_rule.about")]
+    SetArgRule(ProjectArgRuleArgs),
+    #[command(about = "i18n:command.project.set_preset.about")]
+    SetPreset(ProjectPresetArgs),
+    #[command(about = "i18n:command.project.set_tool_alias.about")]
+    SetToolAlias(ProjectToolAliasArgs),
+    #[command(about = "i18n:command.project.tool_search.about")]
+    ToolSearch(ProjectToolSearchArgs),
 }
 
 #[derive(Subcommand, Debug)]
@@ -132,6 +181,24 @@ enum LogsCommand {
     Tail(LogsTailArgs),
 }
 
+#[derive(Subcommand, Debug)]
+enum ApprovalsCommand {
+    #[command(about = "i18n:command.approvals.list.about")]
+    List,
+    #[command(about = "i18n:command.approvals.approve.about")]
+    Approve(ApprovalIdArgs),
+    #[command(about = "i18n:command.approvals.deny.about")]
+    Deny(ApprovalDenyArgs),
+}
+
+#[derive(Subcommand, Debug)]
+enum CacheCommand {
+    #[command(about = "i18n:command.cache.stats.about")]
+    Stats,
+    #[command(about = "i18n:command.cache.clear.about")]
+    Clear(CacheClearArgs),
+}
+
 #[derive(Args, Debug)]
 struct McpAddArgs {
     #[arg(value_name = "NAME_OR_PATH", help = "i18n:args.mcp_add.name_or_path")]
@@ -199,6 +266,18 @@ struct ProjectAssignArgs {
     servers: Vec<String>,
 }
 
+#[derive(Args, Debug)]
+struct ProjectAllowArgs {
+    #[command(flatten)]
+    assign: ProjectAssignArgs,
+    #[arg(
+        long = "for",
+        value_name = "DURATION",
+        help = "i18n:args.project.grant_for"
+    )]
+    duration: Option<String>,
+}
+
 #[derive(Args, Debug)]
 struct McpListToolsArgs {
     #[arg(value_name = "SERVER", help = "i18n:args.mcp_list_tools.server")]
@@ -213,6 +292,26 @@ struct ProjectToolsArgs {
     tools: Vec<String>,
 }
 
+#[derive(Args, Debug)]
+struct ProjectAllowToolsArgs {
+    #[command(flatten)]
+    tools: ProjectToolsArgs,
+    #[arg(
+        long = "for",
+        value_name = "DURATION",
+        help = "i18n:args.project.grant_for"
+    )]
+    duration: Option<String>,
+}
+
+#[derive(Args, Debug)]
+struct ProjectGrantsArgs {
+    #[arg(value_name = "PATH_OR_ID", help = "i18n:args.project.target")]
+    target: String,
+    #[arg(long, help = "i18n:args.project_grants.prune")]
+    prune: bool,
+}
+
 #[derive(Args, Debug)]
 struct ProjectToolDescArgs {
     #[arg(value_name = "PATH_OR_ID", help = "i18n:args.project_tool_desc.target")]
@@ -243,6 +342,214 @@ struct ProjectResetToolDescArgs {
     tool_name: String,
 }
 
+#[derive(Args, Debug)]
+struct ProjectSetModeArgs {
+    #[arg(value_name = "PATH_OR_ID", help = "i18n:args.project.target")]
+    target: String,
+    #[arg(
+        value_enum,
+        value_name = "SWITCH",
+        help = "i18n:args.project_set_mode.switch"
+    )]
+    switch: SwitchArg,
+    #[arg(
+        value_enum,
+        value_name = "STATE",
+        help = "i18n:args.project_set_mode.state"
+    )]
+    state: SwitchStateArg,
+}
+
+#[derive(Args, Debug)]
+struct ProjectToolHintsArgs {
+    #[arg(value_name = "PATH_OR_ID", help = "i18n:args.project.target")]
+    target: String,
+    #[arg(
+        value_name = "TOOL_NAME",
+        help = "i18n:args.project_tool_desc.tool_name"
+    )]
+    tool_name: String,
+    #[arg(
+        long,
+        value_name = "BOOL",
+        help = "i18n:args.project_tool_hints.read_only"
+    )]
+    read_only: Option<bool>,
+    #[arg(
+        long,
+        value_name = "BOOL",
+        help = "i18n:args.project_tool_hints.destructive"
+    )]
+    destructive: Option<bool>,
+    #[arg(
+        long,
+        value_name = "BOOL",
+        help = "i18n:args.project_tool_hints.open_world"
+    )]
+    open_world: Option<bool>,
+}
+
+#[derive(Args, Debug)]
+struct ProjectRequireApprovalArgs {
+    #[arg(value_name = "PATH_OR_ID", help = "i18n:args.project.target")]
+    target: String,
+    #[arg(
+        value_enum,
+        value_name = "STATE",
+        help = "i18n:args.project_require_approval.state"
+    )]
+    state: SwitchStateArg,
+    #[arg(
+        long,
+        value_name = "TOOL_NAME",
+        help = "i18n:args.project_require_approval.tool"
+    )]
+    tool: Option<String>,
+}
+
+#[derive(Args, Debug)]
+struct ProjectToolSearchArgs {
+    #[arg(value_name = "PATH_OR_ID", help = "i18n:args.project.target")]
+    target: String,
+    #[arg(
+        value_enum,
+        value_name = "STATE",
+        help = "i18n:args.project_tool_search.state"
+    )]
+    state: SwitchStateArg,
+}
+
+#[derive(Args, Debug)]
+struct ProjectArgRuleArgs {
+    #[arg(value_name = "PATH_OR_ID", help = "i18n:args.project.target")]
+    target: String,
+    #[arg(
+        value_name = "TOOL_NAME",
+        help = "i18n:args.project_tool_desc.tool_name"
+    )]
+    tool_name: String,
+    #[arg(value_name = "POINTER", help = "i18n:args.project_arg_rule.pointer")]
+    pointer: String,
+    #[arg(long, action = ArgAction::SetTrue, help = "i18n:args.project_arg_rule.required")]
+    required: bool,
+    #[arg(long, value_name = "DIR", help = "i18n:args.project_arg_rule.within")]
+    within: Option<String>,
+    #[arg(long, value_name = "REGEX", help = "i18n:args.project_arg_rule.allow")]
+    allow: Vec<String>,
+    #[arg(long, value_name = "REGEX", help = "i18n:args.project_arg_rule.deny")]
+    deny: Vec<String>,
+    #[arg(long, value_name = "NUMBER", help = "i18n:args.project_arg_rule.min")]
+    min: Option<f64>,
+    #[arg(long, value_name = "NUMBER", help = "i18n:args.project_arg_rule.max")]
+    max: Option<f64>,
+}
+
+#[derive(Args, Debug)]
+struct ProjectPresetArgs {
+    #[arg(value_name = "PATH_OR_ID", help = "i18n:args.project.target")]
+    target: String,
+    #[arg(
+        value_name = "TOOL_NAME",
+        help = "i18n:args.project_tool_desc.tool_name"
+    )]
+    tool_name: String,
+    #[arg(value_name = "ARGUMENT", help = "i18n:args.project_preset.argument")]
+    argument: String,
+    #[arg(value_name = "VALUE", help = "i18n:args.project_preset.value")]
+    value: Option<String>,
+    #[arg(long, action = ArgAction::SetTrue, help = "i18n:args.project_preset.secret")]
+    secret: bool,
+}
+
+#[derive(Args, Debug)]
+struct ProjectToolAliasArgs {
+    #[arg(value_name = "PATH_OR_ID", help = "i18n:args.project.target")]
+    target: String,
+    #[arg(
+        value_name = "TOOL_NAME",
+        help = "i18n:args.project_tool_desc.tool_name"
+    )]
+    tool_name: String,
+    #[arg(value_name = "ALIAS", help = "i18n:args.project_tool_alias.alias")]
+    alias: Option<String>,
+    #[arg(
+        long,
+        value_name = "TITLE",
+        help = "i18n:args.project_tool_alias.title"
+    )]
+    title: Option<String>,
+    #[arg(
+        long,
+        value_name = "JSON",
+        help = "i18n:args.project_tool_alias.schema_patch"
+    )]
+    schema_patch: Option<String>,
+    #[arg(long, value_name = "PARAM", help = "i18n:args.project_tool_alias.hide")]
+    hide: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+struct ApprovalIdArgs {
+    #[arg(value_name = "ID", help = "i18n:args.approvals.id")]
+    id: u64,
+}
+
+#[derive(Args, Debug)]
+struct ApprovalDenyArgs {
+    #[arg(value_name = "ID", help = "i18n:args.approvals.id")]
+    id: u64,
+    #[arg(long, value_name = "TEXT", help = "i18n:args.approvals.reason")]
+    reason: Option<String>,
+}
+
+#[derive(Args, Debug)]
+struct ProjectDetectArgs {
+    #[arg(value_name = "DIR", help = "i18n:args.project_detect.dir")]
+    dir: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct ProjectMoveArgs {
+    #[arg(value_name = "OLD", help = "i18n:args.project_move.from")]
+    from: String,
+    #[arg(value_name = "NEW", help = "i18n:args.project_move.to")]
+    to: PathBuf,
+    #[arg(long, help = "i18n:args.project_move.force")]
+    force: bool,
+}
+
+#[derive(Args, Debug)]
+struct ProjectApproveArgs {
+    #[arg(value_name = "PATH_OR_ID", help = "i18n:args.project.target")]
+    target: String,
+    #[arg(value_name = "SERVER", help = "i18n:args.project_approve.servers")]
+    servers: Vec<String>,
+    #[arg(
+        long,
+        conflicts_with = "servers",
+        help = "i18n:args.project_approve.all"
+    )]
+    all: bool,
+}
+
+#[derive(Args, Debug)]
+struct ProjectExplainArgs {
+    #[arg(value_name = "PATH", help = "i18n:args.project_explain.path")]
+    path: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct ProjectEffectiveArgs {
+    #[arg(value_name = "PATH", help = "i18n:args.project_effective.path")]
+    path: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+struct CacheClearArgs {
+    #[arg(long, value_name = "SERVER", help = "i18n:args.cache.server")]
+    server: Option<String>,
+}
+
 #[derive(Args, Debug)]
 struct LogsListArgs {
     #[arg(long, value_name = "SERVER", help = "i18n:args.logs.server")]
@@ -286,6 +593,43 @@ enum ProtocolArg {
     Http,
 }
 
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum SwitchArg {
+    #[value(name = "read-only")]
+    ReadOnly,
+    #[value(name = "no-destructive")]
+    NoDestructive,
+    #[value(name = "no-open-world")]
+    NoOpenWorld,
+}
+
+impl From<SwitchArg> for AnnotationSwitch {
+    fn from(value: SwitchArg) -> Self {
+        match value {
+            SwitchArg::ReadOnly => AnnotationSwitch::ReadOnly,
+            SwitchArg::NoDestructive => AnnotationSwitch::NoDestructive,
+            SwitchArg::NoOpenWorld => AnnotationSwitch::NoOpenWorld,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, ValueEnum)]
+enum SwitchStateArg {
+    On,
+    Off,
+    Inherit,
+}
+
+impl From<SwitchStateArg> for Option<bool> {
+    fn from(value: SwitchStateArg) -> Self {
+        match value {
+            SwitchStateArg::On => Some(true),
+            SwitchStateArg::Off => Some(false),
+            SwitchStateArg::Inherit => None,
+        }
+    }
+}
+
 impl From<ProtocolArg> for ServerProtocol {
     fn from(value: ProtocolArg) -> Self {
         match value {
@@ -332,12 +676,20 @@ async fn run(cli: Cli) -> Result<()> {
         }
         Command::Project { command } => {
             let layout = resolve_layout(cli.root.clone())?;
-            handle_project_command(&layout, command)
+            handle_project_command(&layout, command).await
         }
         Command::Logs { command } => {
             let layout = resolve_layout(cli.root.clone())?;
             handle_logs_command(&layout, command).await
         }
+        Command::Approvals { command } => {
+            let layout = resolve_layout(cli.root.clone())?;
+            handle_approvals_command(&layout, command).await
+        }
+        Command::Cache { command } => {
+            let layout = resolve_layout(cli.root.clone())?;
+            handle_cache_command(&layout, command)
+        }
     }
 }
 
@@ -357,9 +709,7 @@ fn handle_init(layout: &Layout) -> Result<()> {
 
     let samples = vec![
         ServerDefinition {
-            id: String::new(),
             name: Some("Context7".to_string()),
-            protocol: ServerProtocol::StdIo,
             command: "npx".to_string(),
             args: vec![
                 "-y".to_string(),
@@ -367,21 +717,15 @@ fn handle_init(layout: &Layout) -> Result<()> {
                 "--api-key".to_string(),
                 "YOUR_API_KEY".to_string(),
             ],
-            env: BTreeMap::new(),
-            endpoint: None,
-            headers: BTreeMap::new(),
             enabled: false,
+            ..Default::default()
         },
         ServerDefinition {
-            id: String::new(),
             name: Some("DeepWiki".to_string()),
             protocol: ServerProtocol::Sse,
-            command: String::new(),
-            args: Vec::new(),
-            env: BTreeMap::new(),
             endpoint: Some("https://mcp.deepwiki.com/sse".to_string()),
-            headers: BTreeMap::new(),
             enabled: false,
+            ..Default::default()
         },
     ];
 
@@ -509,15 +853,14 @@ fn add_inline(layout: &Layout, args: McpAddArgs) -> Result<()> {
     ensure_unique_name(layout, &display_name, None)?;
 
     let definition = ServerDefinition {
-        id: String::new(),
         name: Some(display_name.clone()),
         protocol: protocol.into(),
         command: executable,
         args: command_args,
         env,
         endpoint,
-        headers: BTreeMap::new(),
         enabled: false,
+        ..Default::default()
     };
     let mut config = ServerConfig::new(definition)?;
     config.definition_mut().id.clear();
@@ -559,30 +902,30 @@ fn parse_env_pair(raw: &str) -> Result<(String, String)> {
 }
 
 fn expand_tilde(path: PathBuf) -> Result<PathBuf> {
-    if let Some(str_path) = path.to_str() {
-        if let Some(stripped) = str_path.strip_prefix("~") {
-            let messages = i18n::messages();
-            let home = dirs_home().context(messages.expand_home_missing())?;
-            if stripped.is_empty() {
-                return Ok(home);
-            }
-            let stripped = stripped.strip_prefix('/').unwrap_or(stripped);
-            return Ok(home.join(stripped));
+    if let Some(str_path) = path.to_str()
+        && let Some(stripped) = str_path.strip_prefix("~")
+    {
+        let messages = i18n::messages();
+        let home = dirs_home().context(messages.expand_home_missing())?;
+        if stripped.is_empty() {
+            return Ok(home);
         }
+        let stripped = stripped.strip_prefix('/').unwrap_or(stripped);
+        return Ok(home.join(stripped));
     }
     Ok(path)
 }
 
 fn dirs_home() -> Option<PathBuf> {
-    if let Ok(home) = std::env::var("HOME") {
-        if !home.is_empty() {
-            return Some(PathBuf::from(home));
-        }
+    if let Ok(home) = std::env::var("HOME")
+        && !home.is_empty()
+    {
+        return Some(PathBuf::from(home));
     }
-    if let Ok(profile) = std::env::var("USERPROFILE") {
-        if !profile.is_empty() {
-            return Some(PathBuf::from(profile));
-        }
+    if let Ok(profile) = std::env::var("USERPROFILE")
+        && !profile.is_empty()
+    {
+        return Some(PathBuf::from(profile));
     }
     None
 }
@@ -612,6 +955,7 @@ fn handle_mcp_list(layout: &Layout) -> Result<()> {
             ServerProtocol::StdIo => "stdio",
             ServerProtocol::Sse => "sse",
             ServerProtocol::Http => "http",
+            ServerProtocol::Script => "script",
             ServerProtocol::Unknown => "unknown",
         };
         let enabled = messages.enabled_label(definition.enabled);
@@ -645,6 +989,7 @@ fn handle_mcp_info(layout: &Layout, args: McpNameArgs) -> Result<()> {
             ServerProtocol::StdIo => "stdio",
             ServerProtocol::Sse => "sse",
             ServerProtocol::Http => "http",
+            ServerProtocol::Script => "script",
             ServerProtocol::Unknown => "unknown",
         },
         "command": definition.command,
@@ -653,6 +998,7 @@ fn handle_mcp_info(layout: &Layout, args: McpNameArgs) -> Result<()> {
         "endpoint": definition.endpoint.clone(),
         "headers": definition.headers,
         "enabled": definition.enabled,
+        "tools": definition.tools.iter().map(|tool| &tool.name).collect::<Vec<_>>(),
         "configPath": config.source().map(|p| p.display().to_string()),
         "logPath": layout.server_log_path(&definition.id).display().to_string(),
         "pidPath": layout.server_pid_path(&definition.id).display().to_string(),
@@ -730,7 +1076,7 @@ fn ensure_unique_name(layout: &Layout, name: &str, skip_id: Option<&str>) -> Res
     Ok(())
 }
 
-fn handle_project_command(layout: &Layout, command: ProjectCommand) -> Result<()> {
+async fn handle_project_command(layout: &Layout, command: ProjectCommand) -> Result<()> {
     match command {
         ProjectCommand::Add(args) => handle_project_add(layout, args),
         ProjectCommand::Remove(args) => handle_project_remove(layout, args),
@@ -741,6 +1087,19 @@ fn handle_project_command(layout: &Layout, command: ProjectCommand) -> Result<()
         ProjectCommand::DenyTools(args) => handle_project_deny_tools(layout, args),
         ProjectCommand::SetToolDesc(args) => handle_project_set_tool_desc(layout, args),
         ProjectCommand::ResetToolDesc(args) => handle_project_reset_tool_desc(layout, args),
+        ProjectCommand::SetMode(args) => handle_project_set_mode(layout, args),
+        ProjectCommand::SetToolHints(args) => handle_project_set_tool_hints(layout, args),
+        ProjectCommand::Detect(args) => handle_project_detect(layout, args),
+        ProjectCommand::Move(args) => handle_project_move(layout, args),
+        ProjectCommand::Approve(args) => handle_project_approve(layout, args),
+        ProjectCommand::Explain(args) => handle_project_explain(layout, args),
+        ProjectCommand::Effective(args) => handle_project_effective(layout, args).await,
+        ProjectCommand::Grants(args) => handle_project_grants(layout, args),
+        ProjectCommand::RequireApproval(args) => handle_project_require_approval(layout, args),
+        ProjectCommand::SetArgRule(args) => handle_project_set_arg_rule(layout, args),
+        ProjectCommand::SetPreset(args) => handle_project_set_preset(layout, args),
+        ProjectCommand::SetToolAlias(args) => handle_project_set_tool_alias(layout, args),
+        ProjectCommand::ToolSearch(args) => handle_project_tool_search(layout, args),
     }
 }
 
@@ -772,19 +1131,24 @@ fn handle_project_list(layout: &Layout) -> Result<()> {
         return Ok(());
     }
 
-    records.sort_by(|a, b| b.last_seen_at.cmp(&a.last_seen_at));
+    records.sort_by_key(|record| std::cmp::Reverse(record.last_seen_at));
     let (project_header, agent_header, servers_header, seen_header) = messages.project_headers();
     println!("{project_header:<40}  {agent_header:<18}  {servers_header:<30}  {seen_header}");
 
+    let mut any_pending = false;
     for record in records {
         let home_dir = env::var("HOME").ok().map(PathBuf::from);
         let is_home = home_dir.as_ref().is_some_and(|home| record.path == *home);
 
-        let project_display = if is_home {
+        let mut project_display = if is_home {
             format!("~ (global) ({})", record.id)
         } else {
             format!("{} ({})", record.path.display(), record.id)
         };
+        if record.is_pending() {
+            any_pending = true;
+            project_display = format!("{project_display} {}", messages.project_list_pending());
+        }
         let agent = record.agent.as_deref().filter(|s| !s.is_empty()).unwrap_or("-");
         let servers = if record.allowed_server_ids.is_empty() {
             "-".to_string()
@@ -799,10 +1163,15 @@ fn handle_project_list(layout: &Layout) -> Result<()> {
         let last_seen = format_timestamp(record.last_seen_at);
         println!("{project_display:<40}  {agent:<18}  {servers:<30}  {last_seen}");
     }
+    if any_pending {
+        println!();
+        println!("{}", messages.project_list_pending_hint());
+    }
     Ok(())
 }
 
-fn handle_project_allow(layout: &Layout, args: ProjectAssignArgs) -> Result<()> {
+fn handle_project_allow(layout: &Layout, args: ProjectAllowArgs) -> Result<()> {
+    let ProjectAllowArgs { assign: args, duration } = args;
     let messages = i18n::messages();
     let registry = ProjectRegistry::new(layout);
     registry.ensure()?;
@@ -824,6 +1193,35 @@ fn handle_project_allow(layout: &Layout, args: ProjectAssignArgs) -> Result<()>
         .map(|cfg| cfg.definition().id.clone())
         .collect();
 
+    if let Some(duration) = duration {
+        let duration = grant::parse_duration(&duration)?;
+        let mut granted = Vec::new();
+        for server in &args.servers {
+            let trimmed = server.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            if !existing_ids.contains(trimmed) {
+                bail!(messages.project_server_unknown(trimmed));
+            }
+            record.grants.push(TemporaryGrant::new(trimmed, Vec::new(), duration));
+            granted.push(trimmed.to_string());
+        }
+        record.path = canonical.clone();
+        record.touch();
+        registry.store(&record)?;
+        let expires_at = record.grants.last().map(|grant| grant.expires_at).unwrap_or_default();
+        println!(
+            "{}",
+            messages.project_grant_done(
+                &canonical,
+                &granted.join(", "),
+                &format_timestamp(expires_at)
+            )
+        );
+        return Ok(());
+    }
+
     let mut allowed =
         record.allowed_server_ids.into_iter().collect::<std::collections::BTreeSet<_>>();
     let mut added = Vec::new();
@@ -930,6 +1328,484 @@ fn handle_project_add(layout: &Layout, args: ProjectAddArgs) -> Result<()> {
     Ok(())
 }
 
+fn handle_project_detect(layout: &Layout, args: ProjectDetectArgs) -> Result<()> {
+    let messages = i18n::messages();
+    let dir = match args.dir {
+        Some(dir) => normalize_project_path(&dir.display().to_string())?,
+        None => env::current_dir().context("Failed to get current directory")?,
+    };
+    let settings_path = layout.settings_path();
+    let settings = WorkspaceSettings::load(layout)?.project_detection;
+    let detected = detect::detect_project(&dir, &settings);
+
+    println!("{}", messages.project_detect_root(&detected.path));
+    println!("{}", messages.project_detect_rule(&describe_detection_rule(&detected.rule)));
+    println!("{}", messages.project_detect_settings(&settings_path, settings_path.exists()));
+    let strategies = settings
+        .strategies
+        .iter()
+        .map(|strategy| format!("{strategy:?}").to_lowercase());
+    println!(
+        "{}",
+        messages.project_detect_strategies(&strategies.collect::<Vec<_>>().join(" → "))
+    );
+    let monorepo = match settings.monorepo {
+        MonorepoMode::RepoRoot => "repo_root",
+        MonorepoMode::NearestPackage => "nearest_package",
+    };
+    println!("{}", messages.project_detect_monorepo(monorepo));
+    Ok(())
+}
+
+fn describe_detection_rule(rule: &DetectionRule) -> String {
+    let messages = i18n::messages();
+    match rule {
+        DetectionRule::EnvOverride => {
+            messages.project_detect_rule_env(detect::PROJECT_PATH_ENV_KEY)
+        }
+        DetectionRule::Marker { marker } => messages.project_detect_rule_marker(marker),
+        DetectionRule::Git => messages.project_detect_rule_git().to_string(),
+        DetectionRule::NearestPackage { marker, repo_root, base } => messages
+            .project_detect_rule_nearest_package(marker, repo_root, &describe_detection_rule(base)),
+        DetectionRule::CurrentDir => messages.project_detect_rule_cwd().to_string(),
+    }
+}
+
+fn handle_project_move(layout: &Layout, args: ProjectMoveArgs) -> Result<()> {
+    let messages = i18n::messages();
+    let registry = ProjectRegistry::new(layout);
+    registry.ensure()?;
+
+    // 旧路径通常已不存在，因此按原始路径、再按 ID 查找
+    let record = match load_project_record(&registry, &args.from) {
+        Ok(record) => record,
+        Err(err) => registry.find_by_path(&normalize_project_path(&args.from)?)?.ok_or(err)?,
+    };
+    let destination = normalize_project_path(&args.to.display().to_string())?;
+    if !destination.is_dir() {
+        bail!("{}", messages.project_move_not_directory(&destination));
+    }
+    if record.path == destination {
+        println!("{}", messages.project_move_same_path(&destination));
+        return Ok(());
+    }
+    if registry.load(&ProjectId::from_path(&destination)).is_ok() && !args.force {
+        bail!("{}", messages.project_move_destination_exists(&destination));
+    }
+
+    let moved = registry.rebind(&record, &destination)?;
+    println!("{}", messages.project_move_done(&record.path, &moved.path, &moved.id));
+    Ok(())
+}
+
+fn handle_project_approve(layout: &Layout, args: ProjectApproveArgs) -> Result<()> {
+    let messages = i18n::messages();
+    let registry = ProjectRegistry::new(layout);
+    registry.ensure()?;
+
+    let mut record = load_project_record(&registry, &args.target)?;
+    if !record.is_pending() && args.servers.is_empty() {
+        println!("{}", messages.project_approve_already(&record.path));
+        return Ok(());
+    }
+
+    if !args.servers.is_empty() {
+        let existing_ids: HashSet<String> = layout
+            .list_server_configs()?
+            .into_iter()
+            .map(|cfg| cfg.definition().id.clone())
+            .collect();
+        let mut granted = BTreeSet::new();
+        for server in &args.servers {
+            let trimmed = server.trim();
+            if !existing_ids.contains(trimmed) {
+                bail!(messages.project_server_unknown(trimmed));
+            }
+            granted.insert(trimmed.to_string());
+        }
+        record.allowed_server_ids = granted.into_iter().collect();
+    }
+
+    record.approve(args.all)?;
+    record.touch();
+    registry.store(&record)?;
+
+    println!("{}", messages.project_approve_done(&record.path));
+    if record.allowed_server_ids.is_empty() && record.allowed_server_tools.is_empty() {
+        println!("{}", messages.project_approve_unrestricted());
+    }
+    Ok(())
+}
+
+/// Resolve the project path argument (default: current directory) and its effective policy.
+fn load_effective_policy(
+    layout: &Layout,
+    path: Option<PathBuf>,
+) -> Result<(ProjectRegistry, Vec<ServerDefinition>, EffectivePolicy)> {
+    let registry = ProjectRegistry::new(layout);
+    registry.ensure()?;
+
+    let path = match path {
+        Some(path) => normalize_project_path(&path.display().to_string())?,
+        None => env::current_dir().context("Failed to get current directory")?,
+    };
+    let settings = WorkspaceSettings::load(layout)?;
+    let definitions = layout
+        .list_server_configs()?
+        .into_iter()
+        .map(|cfg| cfg.definition().clone())
+        .collect::<Vec<_>>();
+    let policy = EffectivePolicy::resolve(&registry, &settings.project_rules, &path)
+        .with_repo_config(&definitions);
+    Ok((registry, definitions, policy))
+}
+
+fn handle_project_explain(layout: &Layout, args: ProjectExplainArgs) -> Result<()> {
+    let messages = i18n::messages();
+    let (registry, definitions, policy) = load_effective_policy(layout, args.path)?;
+    let path = policy.path.clone();
+
+    println!("{}", messages.project_explain_project(&path));
+    match registry.load(&ProjectId::from_path(&path)) {
+        Ok(record) => {
+            let status = format!("{:?}", record.status).to_lowercase();
+            println!("{}", messages.project_explain_record(&record.id, &status));
+        }
+        Err(_) => println!("{}", messages.project_explain_no_record()),
+    }
+
+    println!("{}", messages.project_explain_layers());
+    if policy.layers.is_empty() {
+        println!("{}", messages.project_explain_none());
+    }
+    for (index, layer) in policy.layers.iter().enumerate() {
+        println!("  {}. {}", index + 1, describe_policy_source(&layer.source));
+    }
+
+    println!("{}", messages.project_explain_servers());
+    let mut server_ids = definitions
+        .iter()
+        .map(|definition| definition.id.clone())
+        .collect::<BTreeSet<_>>();
+    server_ids.extend(policy.tool_permissions().into_iter().map(|(server, _)| server));
+    if let Some(servers) = policy.servers() {
+        server_ids.extend(servers.value.iter().cloned());
+    }
+    if server_ids.is_empty() {
+        println!("{}", messages.project_explain_none());
+    }
+    for server in &server_ids {
+        let (verdict, reason) = match policy.tool_permission(server) {
+            Some(permission) => (
+                describe_tool_permission(permission.value),
+                messages.project_explain_reason_tool_permission(&describe_policy_source(
+                    permission.source,
+                )),
+            ),
+            None => {
+                let decision = policy.decide(server, "");
+                let reason = describe_decision_reason(&decision.reason);
+                (messages.project_explain_verdict(decision.allowed).to_string(), reason)
+            }
+        };
+        println!("{}", messages.project_explain_server_line(server, &verdict, &reason));
+        if let Some(rule) = policy.repo.as_ref().and_then(|repo| repo.tools.get(server)) {
+            let list = |tools: &[String]| {
+                if tools.is_empty() {
+                    "-".to_string()
+                } else {
+                    tools.join(", ")
+                }
+            };
+            println!(
+                "{}",
+                messages.project_explain_repo_rule(&list(&rule.allow), &list(&rule.deny))
+            );
+        }
+    }
+
+    let switches = AnnotationSwitch::ALL
+        .into_iter()
+        .filter_map(|switch| Some((switch, policy.annotation_switch(switch)?)))
+        .collect::<Vec<_>>();
+    if !switches.is_empty() {
+        println!("{}", messages.project_explain_switches());
+        for (switch, setting) in switches {
+            println!(
+                "{}",
+                messages.project_explain_switch_line(
+                    switch.as_str(),
+                    messages.project_mode_state(Some(*setting.value)),
+                    &describe_policy_source(setting.source),
+                )
+            );
+        }
+    }
+
+    let customizations = policy.customizations();
+    let project_approval = policy.project_approval();
+    let tool_approvals = customizations
+        .iter()
+        .filter_map(|customization| {
+            let tool = customization.value.tool_name.as_str();
+            Some((tool, policy.tool_approval(tool)?))
+        })
+        .collect::<Vec<_>>();
+    if project_approval.is_some() || !tool_approvals.is_empty() {
+        println!("{}", messages.project_explain_approvals());
+        let all_tools =
+            project_approval.map(|setting| (messages.project_approval_all_tools(), setting));
+        for (scope, setting) in all_tools.into_iter().chain(tool_approvals) {
+            println!(
+                "{}",
+                messages.project_explain_switch_line(
+                    scope,
+                    messages.project_mode_state(Some(*setting.value)),
+                    &describe_policy_source(setting.source),
+                )
+            );
+        }
+    }
+
+    if let Some(setting) = policy.tool_search() {
+        println!(
+            "{}",
+            messages.project_explain_tool_search(
+                messages.project_mode_state(Some(*setting.value)),
+                &describe_policy_source(setting.source),
+            )
+        );
+    }
+
+    let argument_rules = customizations
+        .iter()
+        .flat_map(|customization| {
+            let tool = customization.value.tool_name.as_str();
+            policy.argument_constraints(tool).into_iter().map(move |rule| (tool, rule))
+        })
+        .collect::<Vec<_>>();
+    if !argument_rules.is_empty() {
+        println!("{}", messages.project_explain_arg_rules());
+        for (tool, rule) in argument_rules {
+            println!(
+                "{}",
+                messages.project_explain_arg_rule_line(
+                    tool,
+                    &rule.value.pointer,
+                    &rule.value.to_string(),
+                    &describe_policy_source(rule.source),
+                )
+            );
+        }
+    }
+
+    let overrides = customizations
+        .iter()
+        .filter(|customization| customization.value.has_overrides())
+        .collect::<Vec<_>>();
+    if !overrides.is_empty() {
+        println!("{}", messages.project_explain_overrides());
+        for customization in overrides {
+            println!(
+                "{}",
+                messages.project_explain_override_line(
+                    &customization.value.tool_name,
+                    &describe_tool_overrides(customization.value),
+                    &describe_policy_source(customization.source),
+                )
+            );
+        }
+    }
+
+    let presets = customizations
+        .iter()
+        .flat_map(|customization| {
+            let tool = customization.value.tool_name.as_str();
+            policy
+                .argument_presets(tool)
+                .into_iter()
+                .map(move |(name, preset)| (tool, name, preset))
+        })
+        .collect::<Vec<_>>();
+    if !presets.is_empty() {
+        println!("{}", messages.project_explain_presets());
+        for (tool, argument, preset) in presets {
+            println!(
+                "{}",
+                messages.project_explain_preset_line(
+                    tool,
+                    argument,
+                    &preset.value.display_value(),
+                    &describe_policy_source(preset.source),
+                )
+            );
+        }
+    }
+
+    let customizations = customizations
+        .into_iter()
+        .filter(|customization| customization.value.description.is_some())
+        .collect::<Vec<_>>();
+    if !customizations.is_empty() {
+        println!("{}", messages.project_explain_descriptions());
+        for customization in customizations {
+            println!(
+                "{}",
+                messages.project_explain_description_line(
+                    &customization.value.tool_name,
+                    &describe_policy_source(customization.source),
+                )
+            );
+        }
+    }
+
+    if !policy.repo_warnings.is_empty() {
+        println!("{}", messages.project_explain_warnings());
+        for warning in &policy.repo_warnings {
+            let line = describe_repo_warning(warning);
+            println!("{line}");
+        }
+    }
+    Ok(())
+}
+
+async fn handle_project_effective(layout: &Layout, args: ProjectEffectiveArgs) -> Result<()> {
+    let messages = i18n::messages();
+    let (_, _, policy) = load_effective_policy(layout, args.path)?;
+
+    let tools =
+        match send_rpc_request(layout, DaemonRequest::ListTools { server_name: None }).await? {
+            DaemonResponse::Success { data: ResponseData::ToolList(tools) } => tools,
+            DaemonResponse::Success { .. } => bail!("{}", messages.unexpected_response()),
+            DaemonResponse::Error { message } => bail!("{}: {}", messages.rpc_error(), message),
+        };
+    let server_names = tools
+        .iter()
+        .map(|tool| (tool.server_id.clone(), tool.server_name.clone()))
+        .collect::<HashMap<_, _>>();
+    let effective = policy.simulate(tools.into_iter().map(|tool| {
+        let hints = ToolHints::from_annotations(tool.annotations.as_ref());
+        let description = (!tool.description.is_empty()).then_some(tool.description);
+        (tool.server_id, tool.name, description, hints)
+    }));
+
+    println!("{}", messages.project_explain_project(&policy.path));
+    if effective.is_empty() {
+        println!("{}", messages.no_tools_found());
+        return Ok(());
+    }
+    let allowed = effective.iter().filter(|tool| tool.decision.allowed).count();
+    println!("{}", messages.project_effective_summary(allowed, effective.len()));
+    for switch in AnnotationSwitch::ALL {
+        let Some(setting) = policy.annotation_switch(switch).filter(|setting| *setting.value)
+        else {
+            continue;
+        };
+        let hidden = effective
+            .iter()
+            .filter(|tool| {
+                matches!(tool.decision.reason, DecisionReason::Annotation { switch: hidden_by, .. } if hidden_by == switch)
+            })
+            .count();
+        println!(
+            "{}",
+            messages.project_effective_switch_line(
+                switch.as_str(),
+                &describe_policy_source(setting.source),
+                hidden
+            )
+        );
+    }
+    for tool in &effective {
+        let server = server_names.get(&tool.server_id).unwrap_or(&tool.server_id);
+        println!(
+            "{}",
+            messages.project_effective_tool_line(
+                &tool.name,
+                server,
+                messages.project_explain_verdict(tool.decision.allowed),
+                &describe_decision_reason(&tool.decision.reason),
+            )
+        );
+        if !tool.decision.allowed {
+            continue;
+        }
+        match (&tool.description, &tool.description_source) {
+            (Some(description), Some(source)) => println!(
+                "{}",
+                messages.project_effective_custom_description(
+                    description,
+                    &describe_policy_source(source)
+                )
+            ),
+            (Some(description), None) => {
+                println!("{}", messages.project_effective_description(description))
+            }
+            _ => {}
+        }
+    }
+    for warning in &policy.repo_warnings {
+        eprintln!("{}", describe_repo_warning(warning));
+    }
+    Ok(())
+}
+
+fn describe_repo_warning(warning: &RepoWarning) -> String {
+    let messages = i18n::messages();
+    match warning {
+        RepoWarning::NotInstalled { server } => {
+            messages.project_explain_warning_not_installed(server)
+        }
+        RepoWarning::NotAllowed { server } => messages.project_explain_warning_not_allowed(server),
+        RepoWarning::Invalid { error } => messages.project_explain_warning_invalid(error),
+    }
+}
+
+fn describe_decision_reason(reason: &DecisionReason) -> String {
+    let messages = i18n::messages();
+    match reason {
+        DecisionReason::TemporaryGrant { source, expires_at } => messages
+            .project_explain_reason_grant(
+                &describe_policy_source(source),
+                &format_timestamp(*expires_at),
+            ),
+        DecisionReason::ServerList { source } => {
+            messages.project_explain_reason_server_list(&describe_policy_source(source))
+        }
+        DecisionReason::ToolPermission { source } => {
+            messages.project_explain_reason_tool_permission(&describe_policy_source(source))
+        }
+        DecisionReason::PendingApproval => messages.project_explain_reason_pending().to_string(),
+        DecisionReason::Unrestricted => messages.project_explain_reason_unrestricted().to_string(),
+        DecisionReason::RepoConfig { path } => messages.project_explain_reason_repo_config(path),
+        DecisionReason::Annotation { switch, source } => messages
+            .project_explain_reason_annotation(switch.as_str(), &describe_policy_source(source)),
+    }
+}
+
+fn describe_policy_source(source: &PolicySource) -> String {
+    let messages = i18n::messages();
+    match source {
+        PolicySource::Rule { pattern } => messages.project_explain_source_rule(pattern),
+        PolicySource::Parent { path, .. } => messages.project_explain_source_parent(path),
+        PolicySource::RepoFile { path } => messages.project_explain_source_repo_file(path),
+        PolicySource::Project { .. } => messages.project_explain_source_project().to_string(),
+    }
+}
+
+fn describe_tool_permission(permission: &ToolPermission) -> String {
+    let messages = i18n::messages();
+    match permission {
+        ToolPermission::All => messages.project_explain_permission_all().to_string(),
+        ToolPermission::AllowList { tools } => {
+            messages.project_explain_permission_allow(&tools.join(", "))
+        }
+        ToolPermission::DenyList { tools } => {
+            messages.project_explain_permission_deny(&tools.join(", "))
+        }
+    }
+}
+
 fn handle_project_remove(layout: &Layout, args: ProjectRemoveArgs) -> Result<()> {
     let registry = ProjectRegistry::new(layout);
     registry.ensure()?;
@@ -1025,6 +1901,70 @@ fn persist_server_config(config: &ServerConfig) -> Result<()> {
     Ok(())
 }
 
+// ============= Approvals Commands =============
+
+async fn handle_approvals_command(layout: &Layout, command: ApprovalsCommand) -> Result<()> {
+    match command {
+        ApprovalsCommand::List => handle_approvals_list(layout).await,
+        ApprovalsCommand::Approve(args) => resolve_approval(layout, args.id, true, None).await,
+        ApprovalsCommand::Deny(args) => resolve_approval(layout, args.id, false, args.reason).await,
+    }
+}
+
+async fn handle_approvals_list(layout: &Layout) -> Result<()> {
+    let messages = i18n::messages();
+    let approvals = match send_rpc_request(layout, DaemonRequest::ListApprovals).await? {
+        DaemonResponse::Success { data: ResponseData::Approvals { approvals } } => approvals,
+        DaemonResponse::Success { .. } => bail!("{}", messages.unexpected_response()),
+        DaemonResponse::Error { message } => bail!("{}: {}", messages.rpc_error(), message),
+    };
+
+    if approvals.is_empty() {
+        println!("{}", messages.approvals_none());
+        return Ok(());
+    }
+    println!("{}", messages.approvals_header());
+    for approval in approvals {
+        println!(
+            "{}",
+            messages.approvals_line(
+                approval.id,
+                &approval.tool_name,
+                &approval.server_id,
+                &approval.project_path,
+                &format_timestamp(approval.expires_at),
+            )
+        );
+        if let Some(arguments) = &approval.arguments {
+            for line in arguments.lines() {
+                println!("      {line}");
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn resolve_approval(
+    layout: &Layout,
+    id: u64,
+    approve: bool,
+    reason: Option<String>,
+) -> Result<()> {
+    let messages = i18n::messages();
+    let request = DaemonRequest::ResolveApproval { id, approve, reason };
+    let approval = match send_rpc_request(layout, request).await? {
+        DaemonResponse::Success { data: ResponseData::Approval { approval } } => approval,
+        DaemonResponse::Success { .. } => bail!("{}", messages.unexpected_response()),
+        DaemonResponse::Error { message } => bail!("{}: {}", messages.rpc_error(), message),
+    };
+    if approve {
+        println!("{}", messages.approvals_approved(approval.id, &approval.tool_name));
+    } else {
+        println!("{}", messages.approvals_denied(approval.id, &approval.tool_name));
+    }
+    Ok(())
+}
+
 // ============= RPC Client Helper =============
 
 async fn send_rpc_request(layout: &Layout, request: DaemonRequest) -> Result<DaemonResponse> {
@@ -1048,6 +1988,17 @@ async fn send_rpc_request(layout: &Layout, request: DaemonRequest) -> Result<Dae
     let (reader, mut writer) = tokio::io::split(stream);
     let mut reader = BufReader::new(reader);
 
+    // Authenticate first when the daemon requires a handshake token
+    if let Some(token) = auth::client_token(layout) {
+        let auth_json = serde_json::to_string(&DaemonRequest::Authenticate { token })? + "\n";
+        writer.write_all(auth_json.as_bytes()).await?;
+        let mut auth_line = String::new();
+        reader.read_line(&mut auth_line).await?;
+        if let DaemonResponse::Error { message } = serde_json::from_str(auth_line.trim())? {
+            return Ok(DaemonResponse::Error { message });
+        }
+    }
+
     // Send request
     let request_json = serde_json::to_string(&request)? + "\n";
     writer.write_all(request_json.as_bytes()).await?;
@@ -1110,7 +2061,8 @@ fn parse_tool_spec(spec: &str) -> Result<(String, String)> {
     Ok((parts[0].to_string(), parts[1].to_string()))
 }
 
-fn handle_project_allow_tools(layout: &Layout, args: ProjectToolsArgs) -> Result<()> {
+fn handle_project_allow_tools(layout: &Layout, args: ProjectAllowToolsArgs) -> Result<()> {
+    let ProjectAllowToolsArgs { tools: args, duration } = args;
     let messages = i18n::messages();
     let registry = ProjectRegistry::new(layout);
     registry.ensure()?;
@@ -1124,6 +2076,25 @@ fn handle_project_allow_tools(layout: &Layout, args: ProjectToolsArgs) -> Result
         server_tools.entry(server).or_default().push(tool);
     }
 
+    if let Some(duration) = duration {
+        let duration = grant::parse_duration(&duration)?;
+        for (server, tools) in server_tools {
+            let grant = TemporaryGrant::new(server.clone(), tools.clone(), duration);
+            println!(
+                "{}",
+                messages.project_grant_tools_done(
+                    &server,
+                    &tools.join(", "),
+                    &format_timestamp(grant.expires_at)
+                )
+            );
+            record.grants.push(grant);
+        }
+        registry.store(&record)?;
+        println!("{}", messages.project_config_updated(&record.path.display().to_string()));
+        return Ok(());
+    }
+
     // Update allowed_server_tools
     for (server, tools) in server_tools {
         record
@@ -1137,6 +2108,55 @@ fn handle_project_allow_tools(layout: &Layout, args: ProjectToolsArgs) -> Result
     Ok(())
 }
 
+fn handle_project_grants(layout: &Layout, args: ProjectGrantsArgs) -> Result<()> {
+    let messages = i18n::messages();
+    let registry = ProjectRegistry::new(layout);
+    registry.ensure()?;
+
+    let mut record = load_project_record(&registry, &args.target)?;
+    let now = grant::now();
+
+    if args.prune {
+        let before = record.grants.len();
+        record.grants.retain(|grant| grant.is_active_at(now));
+        let removed = before - record.grants.len();
+        if removed > 0 {
+            registry.store(&record)?;
+        }
+        println!("{}", messages.project_grants_pruned(removed));
+    }
+
+    if record.grants.is_empty() {
+        println!("{}", messages.project_grants_none(&record.path));
+        return Ok(());
+    }
+    println!("{}", messages.project_grants_header(&record.path));
+    let mut grants = record.grants.clone();
+    grants.sort_by_key(|grant| std::cmp::Reverse(grant.expires_at));
+    for grant in grants {
+        let scope = if grant.tools.is_empty() {
+            messages.project_grants_all_tools().to_string()
+        } else {
+            grant.tools.join(", ")
+        };
+        let line = if grant.is_active_at(now) {
+            messages.project_grants_active(
+                &grant.server_id,
+                &scope,
+                &format_timestamp(grant.expires_at),
+            )
+        } else {
+            messages.project_grants_expired(
+                &grant.server_id,
+                &scope,
+                &format_timestamp(grant.expires_at),
+            )
+        };
+        println!("{line}");
+    }
+    Ok(())
+}
+
 fn handle_project_deny_tools(layout: &Layout, args: ProjectToolsArgs) -> Result<()> {
     let messages = i18n::messages();
     let registry = ProjectRegistry::new(layout);
@@ -1173,14 +2193,8 @@ fn handle_project_set_tool_desc(layout: &Layout, args: ProjectToolDescArgs) -> R
 
     let mut record = load_project_record(&registry, &args.target)?;
 
-    // Remove existing customization for this tool
-    record.tool_customizations.retain(|c| c.tool_name != args.tool_name);
-
-    // Add new customization
-    record.tool_customizations.push(ToolCustomization {
-        tool_name: args.tool_name.clone(),
-        description: Some(args.description.clone()),
-    });
+    // Replace the description, keeping other customizations of this tool
+    record.customization_mut(&args.tool_name).description = Some(args.description.clone());
 
     registry.store(&record)?;
     println!("{}", messages.tool_desc_set(&args.tool_name));
@@ -1188,19 +2202,257 @@ fn handle_project_set_tool_desc(layout: &Layout, args: ProjectToolDescArgs) -> R
     Ok(())
 }
 
-fn handle_project_reset_tool_desc(layout: &Layout, args: ProjectResetToolDescArgs) -> Result<()> {
+fn handle_project_set_mode(layout: &Layout, args: ProjectSetModeArgs) -> Result<()> {
     let messages = i18n::messages();
     let registry = ProjectRegistry::new(layout);
     registry.ensure()?;
 
     let mut record = load_project_record(&registry, &args.target)?;
+    let switch = AnnotationSwitch::from(args.switch);
+    let state = Option::<bool>::from(args.state);
+    record.annotation_policy.set(switch, state);
 
-    // Remove customization for this tool
-    let before_len = record.tool_customizations.len();
-    record.tool_customizations.retain(|c| c.tool_name != args.tool_name);
-    let after_len = record.tool_customizations.len();
+    registry.store(&record)?;
+    println!(
+        "{}",
+        messages.project_mode_updated(
+            switch.as_str(),
+            messages.project_mode_state(state),
+            &record.path
+        )
+    );
+    Ok(())
+}
+
+fn handle_project_set_tool_hints(layout: &Layout, args: ProjectToolHintsArgs) -> Result<()> {
+    let messages = i18n::messages();
+    let registry = ProjectRegistry::new(layout);
+    registry.ensure()?;
+
+    let mut record = load_project_record(&registry, &args.target)?;
+    let patch = AnnotationOverride {
+        read_only: args.read_only,
+        destructive: args.destructive,
+        open_world: args.open_world,
+    };
+    record.customization_mut(&args.tool_name).annotations = (!patch.is_empty()).then_some(patch);
+    record.tool_customizations.retain(|c| !c.is_empty());
+
+    registry.store(&record)?;
+    if patch.is_empty() {
+        println!("{}", messages.project_tool_hints_cleared(&args.tool_name));
+    } else {
+        println!("{}", messages.project_tool_hints_set(&args.tool_name));
+    }
+    println!("{}", messages.project_config_updated(&record.path.display().to_string()));
+    Ok(())
+}
+
+fn handle_project_require_approval(
+    layout: &Layout,
+    args: ProjectRequireApprovalArgs,
+) -> Result<()> {
+    let messages = i18n::messages();
+    let registry = ProjectRegistry::new(layout);
+    registry.ensure()?;
+
+    let mut record = load_project_record(&registry, &args.target)?;
+    let state = Option::<bool>::from(args.state);
+    match &args.tool {
+        Some(tool) => {
+            record.customization_mut(tool).require_approval = state;
+            record.tool_customizations.retain(|c| !c.is_empty());
+        }
+        None => record.require_approval = state,
+    }
+
+    registry.store(&record)?;
+    let scope = args.tool.as_deref().unwrap_or(messages.project_approval_all_tools());
+    println!(
+        "{}",
+        messages.project_approval_updated(scope, messages.project_mode_state(state), &record.path)
+    );
+    Ok(())
+}
+
+fn handle_project_tool_search(layout: &Layout, args: ProjectToolSearchArgs) -> Result<()> {
+    let messages = i18n::messages();
+    let registry = ProjectRegistry::new(layout);
+    registry.ensure()?;
+
+    let mut record = load_project_record(&registry, &args.target)?;
+    let state = Option::<bool>::from(args.state);
+    record.tool_search = state;
+
+    registry.store(&record)?;
+    println!(
+        "{}",
+        messages.project_tool_search_updated(messages.project_mode_state(state), &record.path)
+    );
+    Ok(())
+}
+
+fn handle_project_set_arg_rule(layout: &Layout, args: ProjectArgRuleArgs) -> Result<()> {
+    let messages = i18n::messages();
+    let registry = ProjectRegistry::new(layout);
+    registry.ensure()?;
+
+    if !args.pointer.starts_with('/') {
+        bail!("{}", messages.invalid_json_pointer(&args.pointer));
+    }
+    let compile = |patterns: &[String]| {
+        patterns
+            .iter()
+            .map(|pattern| {
+                Pattern::new(pattern)
+                    .map_err(|err| anyhow!("{}", messages.invalid_regex(pattern, &err.to_string())))
+            })
+            .collect::<Result<Vec<_>>>()
+    };
+    let allow = compile(&args.allow)?;
+    let deny = compile(&args.deny)?;
+
+    let mut record = load_project_record(&registry, &args.target)?;
+    let constraint = ArgumentConstraint {
+        pointer: args.pointer.clone(),
+        required: args.required,
+        within: args.within,
+        allow,
+        deny,
+        min: args.min,
+        max: args.max,
+    };
+    let customization = record.customization_mut(&args.tool_name);
+    customization
+        .arguments
+        .retain(|existing| existing.pointer != constraint.pointer);
+    let cleared = constraint.is_empty();
+    if !cleared {
+        customization.arguments.push(constraint);
+    }
+    record.tool_customizations.retain(|c| !c.is_empty());
+
+    registry.store(&record)?;
+    if cleared {
+        println!("{}", messages.project_arg_rule_cleared(&args.tool_name, &args.pointer));
+    } else {
+        println!("{}", messages.project_arg_rule_set(&args.tool_name, &args.pointer));
+    }
+    println!("{}", messages.project_config_updated(&record.path.display().to_string()));
+    Ok(())
+}
+
+fn handle_project_set_tool_alias(layout: &Layout, args: ProjectToolAliasArgs) -> Result<()> {
+    let messages = i18n::messages();
+    let registry = ProjectRegistry::new(layout);
+    registry.ensure()?;
+
+    let alias = args.alias.filter(|alias| *alias != args.tool_name);
+    if let Some(alias) = &alias
+        && !schema::is_valid_tool_name(alias)
+    {
+        bail!("{}", messages.invalid_tool_alias(alias));
+    }
+    // `@file` reads the patch from a file
+    let schema_patch = args
+        .schema_patch
+        .map(|raw| match raw.strip_prefix('@') {
+            Some(path) => fs::read_to_string(path)
+                .with_context(|| format!("failed to read schema patch {path}")),
+            None => Ok(raw),
+        })
+        .transpose()?
+        .map(|raw| schema::parse_schema_patch(&raw))
+        .transpose()
+        .map_err(|err| anyhow!("{}", messages.invalid_schema_patch(&err)))?;
+
+    let mut record = load_project_record(&registry, &args.target)?;
+    if let Some(alias) = &alias
+        && let Some(other) = record.tool_customizations.iter().find(|c| {
+            c.tool_name != args.tool_name
+                && (c.alias.as_ref() == Some(alias) || c.tool_name == *alias)
+        })
+    {
+        bail!("{}", messages.tool_alias_taken(alias, &other.tool_name));
+    }
+    let customization = record.customization_mut(&args.tool_name);
+    customization.alias = alias;
+    customization.title = args.title;
+    customization.schema_patch = schema_patch;
+    customization.hidden_parameters = args.hide;
+    let cleared = !customization.has_overrides();
+    record.tool_customizations.retain(|c| !c.is_empty());
+
+    registry.store(&record)?;
+    if cleared {
+        println!("{}", messages.project_tool_overrides_cleared(&args.tool_name));
+    } else {
+        println!("{}", messages.project_tool_overrides_set(&args.tool_name));
+    }
+    println!("{}", messages.project_config_updated(&record.path.display().to_string()));
+    Ok(())
+}
+
+/// Compact summary of a tool's alias, title, schema patch and hidden parameters.
+fn describe_tool_overrides(customization: &ToolCustomization) -> String {
+    let mut parts = Vec::new();
+    if let Some(alias) = &customization.alias {
+        parts.push(format!("alias {alias}"));
+    }
+    if let Some(title) = &customization.title {
+        parts.push(format!("title \"{title}\""));
+    }
+    if let Some(patch) = &customization.schema_patch {
+        parts.push(format!("schema patch {patch}"));
+    }
+    if !customization.hidden_parameters.is_empty() {
+        parts.push(format!("hidden {}", customization.hidden_parameters.join(", ")));
+    }
+    parts.join(", ")
+}
+
+fn handle_project_set_preset(layout: &Layout, args: ProjectPresetArgs) -> Result<()> {
+    let messages = i18n::messages();
+    let registry = ProjectRegistry::new(layout);
+    registry.ensure()?;
+
+    let mut record = load_project_record(&registry, &args.target)?;
+    let customization = record.customization_mut(&args.tool_name);
+    let cleared = match args.value {
+        // JSON literals (numbers, booleans, objects) keep their type; anything else is a string
+        Some(raw) => {
+            let value = serde_json::from_str(&raw).unwrap_or(serde_json::Value::String(raw));
+            customization
+                .presets
+                .insert(args.argument.clone(), ArgumentPreset { value, secret: args.secret });
+            false
+        }
+        None => {
+            customization.presets.remove(&args.argument);
+            true
+        }
+    };
+    record.tool_customizations.retain(|c| !c.is_empty());
+
+    registry.store(&record)?;
+    if cleared {
+        println!("{}", messages.project_preset_cleared(&args.tool_name, &args.argument));
+    } else {
+        println!("{}", messages.project_preset_set(&args.tool_name, &args.argument));
+    }
+    println!("{}", messages.project_config_updated(&record.path.display().to_string()));
+    Ok(())
+}
+
+fn handle_project_reset_tool_desc(layout: &Layout, args: ProjectResetToolDescArgs) -> Result<()> {
+    let messages = i18n::messages();
+    let registry = ProjectRegistry::new(layout);
+    registry.ensure()?;
 
-    if before_len == after_len {
+    let mut record = load_project_record(&registry, &args.target)?;
+
+    // Clear the description (annotation overrides stay)
+    if !record.reset_tool_description(&args.tool_name) {
         println!("{}", messages.tool_desc_not_customized(&args.tool_name));
         return Ok(());
     }
@@ -1224,10 +2476,10 @@ async fn handle_logs_list(layout: &Layout, args: LogsListArgs) -> Result<()> {
         }
         if let Ok(mut entries) = tokio::fs::read_dir(layout.server_logs_dir()).await {
             while let Some(entry) = entries.next_entry().await? {
-                if entry.file_type().await?.is_dir() {
-                    if let Some(name) = entry.file_name().to_str() {
-                        server_ids.insert(name.to_string());
-                    }
+                if entry.file_type().await?.is_dir()
+                    && let Some(name) = entry.file_name().to_str()
+                {
+                    server_ids.insert(name.to_string());
                 }
             }
         }
@@ -1352,10 +2604,10 @@ fn load_project_record(registry: &ProjectRegistry, target: &str) -> Result<Proje
 
     // Try as path first
     let path = expand_tilde(PathBuf::from(target))?;
-    if path.exists() {
-        if let Ok(Some(record)) = registry.find_by_path(&path) {
-            return Ok(record);
-        }
+    if path.exists()
+        && let Ok(Some(record)) = registry.find_by_path(&path)
+    {
+        return Ok(record);
     }
 
     // Try as project ID
@@ -1365,6 +2617,44 @@ fn load_project_record(registry: &ProjectRegistry, target: &str) -> Result<Proje
     }
 }
 
+fn handle_cache_command(layout: &Layout, command: CacheCommand) -> Result<()> {
+    let messages = i18n::messages();
+    match command {
+        CacheCommand::Stats => {
+            let stats = cache::stats(layout)?;
+            if stats.is_empty() {
+                println!("{}", messages.cache_empty());
+                return Ok(());
+            }
+            let (server_header, entries_header, size_header, newest_header) =
+                messages.cache_header();
+            println!(
+                "{server_header:<20}  {entries_header:>10}  {size_header:>10}  {newest_header}"
+            );
+            for stats in stats {
+                let newest = stats
+                    .newest
+                    .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|age| format_timestamp(age.as_secs()))
+                    .unwrap_or_else(|| "-".to_string());
+                println!(
+                    "{:<20}  {:>10}  {:>10}  {newest}",
+                    stats.server_id,
+                    stats.entries,
+                    format_size(stats.bytes)
+                );
+            }
+        }
+        CacheCommand::Clear(args) => {
+            let removed = cache::clear(layout, args.server.as_deref())?;
+            let count = removed.iter().map(|stats| stats.entries).sum();
+            let bytes = removed.iter().map(|stats| stats.bytes).sum();
+            println!("{}", messages.cache_cleared(count, &format_size(bytes)));
+        }
+    }
+    Ok(())
+}
+
 fn select_log_file<'a>(
     files: &'a [LogFileMeta],
     requested: Option<&str>,
@@ -1441,17 +2731,17 @@ fn print_log_entry(entry: &LogEntry, as_json: bool) {
         );
     }
 
-    if let Some(details) = entry.details.as_ref() {
-        if !details.is_null() {
-            println!("    details:");
-            match serde_json::to_string_pretty(details) {
-                Ok(json) => {
-                    for line in json.lines() {
-                        println!("      {line}");
-                    }
+    if let Some(details) = entry.details.as_ref()
+        && !details.is_null()
+    {
+        println!("    details:");
+        match serde_json::to_string_pretty(details) {
+            Ok(json) => {
+                for line in json.lines() {
+                    println!("      {line}");
                 }
-                Err(_) => println!("      {details}"),
             }
+            Err(_) => println!("      {details}"),
         }
     }
 }
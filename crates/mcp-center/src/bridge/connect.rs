@@ -8,16 +8,22 @@ use std::{
     time::Duration,
 };
 
-use crate::{Layout, ProjectId, bridge::control::ControlMessage, default_root};
+use crate::{
+    Layout, ProjectId,
+    bridge::control::{
+        self, BridgeReady, ControlMessage, FEATURE_SESSION_SCOPE, FEATURE_SHUTDOWN, SessionScope,
+    },
+    config::{WorkspaceSettings, settings::ProjectDetectionSettings},
+    daemon::auth,
+    default_root,
+    project::detect,
+};
 use anyhow::{Context, Result, bail};
 use clap::Args;
 use interprocess::local_socket::traits::tokio::Stream as _;
 use interprocess::local_socket::{GenericFilePath, ToFsName, tokio::prelude::LocalSocketStream};
 use serde_json::{Value, json};
-use tokio::{
-    io::{self, AsyncBufReadExt, AsyncWriteExt},
-    process::Command as TokioCommand,
-};
+use tokio::io::{self, AsyncBufReadExt, AsyncWriteExt};
 use tracing::{debug, info, warn};
 
 #[derive(Args, Debug)]
@@ -35,6 +41,48 @@ pub struct ConnectArgs {
         help = "Path to the mcp-center executable (for spawning serve mode)."
     )]
     pub daemon: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Restart the running daemon when it is older than this bridge \
+                (fallback env MCP_CENTER_RESTART_OUTDATED_DAEMON=1)."
+    )]
+    pub restart_outdated_daemon: bool,
+
+    #[arg(
+        long,
+        value_name = "SERVERS",
+        value_delimiter = ',',
+        help = "Only expose tools from these MCP servers (ids or names, comma separated)."
+    )]
+    pub servers: Option<Vec<String>>,
+
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Apply a session profile defined under [profiles.<name>] in config/settings.toml."
+    )]
+    pub profile: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Use this project path instead of detecting it from the working directory."
+    )]
+    pub project: Option<PathBuf>,
+
+    #[arg(long, help = "Only expose tools annotated as read-only.")]
+    pub read_only: bool,
+}
+
+impl ConnectArgs {
+    fn scope(&self) -> SessionScope {
+        SessionScope {
+            servers: self.servers.clone(),
+            profile: self.profile.clone(),
+            read_only: self.read_only,
+        }
+    }
 }
 
 pub async fn run(args: ConnectArgs) -> Result<()> {
@@ -47,24 +95,122 @@ async fn run_impl(args: ConnectArgs) -> Result<()> {
     let layout = resolve_layout(args.root.clone())?;
     layout.ensure()?;
 
-    let project_path = detect_project_path().await?;
+    let project_path = match args.project.as_ref() {
+        Some(path) => {
+            let canonical = tokio::fs::canonicalize(path)
+                .await
+                .with_context(|| format!("invalid --project path {}", path.display()))?;
+            if !canonical.is_dir() {
+                bail!("--project path {} is not a directory", canonical.display());
+            }
+            canonical
+        }
+        None => detect_project_path(&layout).await?,
+    };
+    let scope = args.scope();
     let project_id = ProjectId::from_path(&project_path);
     debug!(project = %project_id.as_str(), path = %project_path.display(), "detected project path");
 
     let mut stream = connect_or_launch(&layout, &args).await?;
 
-    perform_handshake(&mut stream, &project_path).await?;
+    let mut ready = perform_handshake(&mut stream, &layout, &project_path, &scope).await?;
+    if ready.is_outdated() {
+        let daemon_version = ready.binary_version.as_deref().unwrap_or("unknown");
+        if !restart_outdated_requested(&args) {
+            warn!(
+                daemon_version,
+                daemon_protocol = ready.protocol_version,
+                bridge_version = control::binary_version(),
+                bridge_protocol = control::CONTROL_PROTOCOL_VERSION,
+                "daemon is older than this bridge; pass --restart-outdated-daemon \
+                 (or set MCP_CENTER_RESTART_OUTDATED_DAEMON=1) to restart it"
+            );
+        } else if !ready.supports(FEATURE_SHUTDOWN) {
+            warn!(
+                daemon_version,
+                "daemon is older than this bridge and cannot be restarted remotely; \
+                 stop the running `mcp-center serve` process manually"
+            );
+        } else {
+            info!(daemon_version, "restarting outdated daemon");
+            drop(stream);
+            request_daemon_shutdown(&layout).await?;
+            stream = connect_or_launch(&layout, &args).await?;
+            ready = perform_handshake(&mut stream, &layout, &project_path, &scope).await?;
+        }
+    }
+
+    if !scope.is_unrestricted() && !ready.supports(FEATURE_SESSION_SCOPE) {
+        bail!(
+            "the running daemon does not support --servers/--profile/--read-only; \
+             restart it with --restart-outdated-daemon"
+        );
+    }
+
+    for notice in &ready.notices {
+        warn!("{notice}");
+    }
 
     tunnel_stdio(stream).await
 }
 
-async fn perform_handshake(stream: &mut LocalSocketStream, project_path: &Path) -> Result<()> {
+fn restart_outdated_requested(args: &ConnectArgs) -> bool {
+    args.restart_outdated_daemon
+        || env::var("MCP_CENTER_RESTART_OUTDATED_DAEMON")
+            .map(|value| matches!(value.trim(), "1" | "true" | "yes"))
+            .unwrap_or(false)
+}
+
+/// Ask the running daemon to exit and wait until its control socket goes away.
+async fn request_daemon_shutdown(layout: &Layout) -> Result<()> {
+    let socket_path = layout.daemon_socket_path();
+    let socket_name = socket_path.to_string_lossy().into_owned();
+
+    let mut stream =
+        LocalSocketStream::connect(socket_name.as_str().to_fs_name::<GenericFilePath>()?)
+            .await
+            .context("failed to reconnect to daemon for shutdown")?;
+    let shutdown = ControlMessage::Shutdown { auth_token: auth::client_token(layout) };
+    let mut payload = serde_json::to_vec(&shutdown)?;
+    payload.push(b'\n');
+    stream.write_all(&payload).await?;
+    drop(stream);
+
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(30);
+    loop {
+        match LocalSocketStream::connect(socket_name.as_str().to_fs_name::<GenericFilePath>()?)
+            .await
+        {
+            Ok(_) => {
+                if tokio::time::Instant::now() > deadline {
+                    bail!("timed out while waiting for outdated daemon to exit");
+                }
+                tokio::time::sleep(Duration::from_millis(200)).await;
+            }
+            Err(err)
+                if matches!(err.kind(), ErrorKind::NotFound | ErrorKind::ConnectionRefused) =>
+            {
+                return Ok(());
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+async fn perform_handshake(
+    stream: &mut LocalSocketStream,
+    layout: &Layout,
+    project_path: &Path,
+    scope: &SessionScope,
+) -> Result<BridgeReady> {
     let metadata = gather_metadata().await?;
     let hello = ControlMessage::hello(
         project_path.to_path_buf(),
         detect_agent_name(),
         Some(std::process::id()),
         metadata,
+        auth::client_token(layout),
+        scope.clone(),
     );
 
     let mut payload = serde_json::to_vec(&hello)?;
@@ -82,8 +228,15 @@ async fn perform_handshake(stream: &mut LocalSocketStream, project_path: &Path)
 
     match message {
         ControlMessage::BridgeReady(ready) => {
-            info!(project = ready.project_id, servers = ?ready.allowed_server_ids, "connected to daemon");
-            Ok(())
+            info!(
+                project = ready.project_id,
+                servers = ?ready.allowed_server_ids,
+                daemon_version = ready.binary_version.as_deref().unwrap_or("unknown"),
+                protocol = ready.protocol_version,
+                features = ?ready.features,
+                "connected to daemon"
+            );
+            Ok(ready)
         }
         ControlMessage::Error { message } => bail!("daemon rejected connection: {message}"),
         other => bail!("unexpected control response: {other:?}"),
@@ -225,7 +378,7 @@ fn configure_detached_process(_command: &mut StdCommand) -> Result<()> {
     Ok(())
 }
 
-async fn detect_project_path() -> Result<PathBuf> {
+async fn detect_project_path(layout: &Layout) -> Result<PathBuf> {
     debug!("=== DEBUG: Starting project path detection ===");
 
     // Log ALL environment variables for complete diagnosis
@@ -277,91 +430,22 @@ async fn detect_project_path() -> Result<PathBuf> {
         debug!("  cwd = {}", cwd.display());
     }
 
-    // Check for MCP_CENTER_PROJECT_PATH override
-    if let Some(env_override) = env::var_os("MCP_CENTER_PROJECT_PATH") {
-        let path = PathBuf::from(&env_override);
-        debug!("Found MCP_CENTER_PROJECT_PATH override: {}", path.display());
-        let canonical = canonicalize_best_effort(&path).await.unwrap_or_else(|| path.clone());
-        debug!("Resolved to: {}", canonical.display());
-        return Ok(canonical);
-    }
-
     let cwd = env::current_dir().context("failed to determine current directory")?;
     debug!("Base CWD: {}", cwd.display());
 
-    // Try marker-based detection
-    debug!("Probing for project markers...");
-    if let Some(marker_dir) = probe_markers(&cwd).await? {
-        debug!("Found project via marker: {}", marker_dir.display());
-        return Ok(marker_dir);
-    }
-    debug!("No project markers found");
-
-    // Try git toplevel
-    debug!("Checking for git repository...");
-    if let Some(git_root) = git_toplevel(&cwd).await? {
-        debug!("Found git root: {}", git_root.display());
-        return Ok(git_root);
-    }
-    debug!("Not in a git repository");
-
-    let final_path = canonicalize_best_effort(&cwd).await.unwrap_or_else(|| cwd.clone());
-    debug!("Falling back to CWD: {}", final_path.display());
-    debug!("=== DEBUG: Project path detection complete ===");
-    Ok(final_path)
-}
-
-async fn probe_markers(base: &Path) -> Result<Option<PathBuf>> {
-    const FILE_MARKERS: &[&str] = &[".cursor/settings.json", "cursor.json", ".windsurfrc"];
-
-    const DIR_MARKERS: &[&str] = &[".cursor", ".windsurf"];
-
-    for ancestor in base.ancestors() {
-        for marker in FILE_MARKERS {
-            if ancestor.join(marker).exists() {
-                return Ok(canonicalize_best_effort(ancestor)
-                    .await
-                    .or_else(|| Some(ancestor.to_path_buf())));
-            }
-        }
-        for marker in DIR_MARKERS {
-            if ancestor.join(marker).exists() {
-                return Ok(canonicalize_best_effort(ancestor)
-                    .await
-                    .or_else(|| Some(ancestor.to_path_buf())));
-            }
-        }
-    }
-    Ok(None)
-}
-
-async fn git_toplevel(base: &Path) -> Result<Option<PathBuf>> {
-    let output = TokioCommand::new("git")
-        .args(["rev-parse", "--show-toplevel"])
-        .current_dir(base)
-        .output()
-        .await;
-
-    match output {
-        Ok(result) if result.status.success() => {
-            let raw = String::from_utf8_lossy(&result.stdout).trim().to_string();
-            let path = PathBuf::from(raw);
-            Ok(Some(canonicalize_best_effort(&path).await.unwrap_or(path)))
-        }
-        Ok(_) => Ok(None),
-        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
-        Err(err) => Err(err.into()),
-    }
-}
-
-async fn canonicalize_best_effort(path: &Path) -> Option<PathBuf> {
-    match tokio::fs::canonicalize(path).await {
-        Ok(canonical) => Some(canonical),
+    let settings = match WorkspaceSettings::load(layout) {
+        Ok(settings) => settings.project_detection,
         Err(err) => {
-            debug!(path = %path.display(), error = ?err, "failed to canonicalize path");
-            None
+            warn!(error = ?err, "failed to load workspace settings, using default detection rules");
+            ProjectDetectionSettings::default()
         }
-    }
+    };
+    let detected = tokio::task::spawn_blocking(move || detect::detect_project(&cwd, &settings))
+        .await
+        .context("project detection task failed")?;
+    debug!("Detected project: {} ({:?})", detected.path.display(), detected.rule);
+    debug!("=== DEBUG: Project path detection complete ===");
+    Ok(detected.path)
 }
 
 fn detect_agent_name() -> Option<String> {
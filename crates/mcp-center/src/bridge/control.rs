@@ -1,7 +1,42 @@
+//! Line-oriented control protocol shared by the bridge (`mcp-center connect`) and the daemon.
+
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::{cmp::Ordering, path::PathBuf};
+
+/// Version of the control protocol spoken by this binary.
+///
+/// Bump whenever a message changes in a way the peer has to know about.
+pub const CONTROL_PROTOCOL_VERSION: u32 = 2;
+
+/// Oldest bridge protocol version the daemon still accepts.
+///
+/// Bridges that predate versioning report `0`; they cannot negotiate features, so the daemon
+/// asks them to upgrade instead of guessing what they understand.
+pub const MIN_SUPPORTED_PROTOCOL_VERSION: u32 = 1;
+
+/// Feature flag: the daemon accepts a [`ControlMessage::Shutdown`] request.
+pub const FEATURE_SHUTDOWN: &str = "shutdown";
+
+/// Feature flag: the daemon resolves the project from the client's `roots/list`.
+pub const FEATURE_ROOTS: &str = "roots";
+
+/// Feature flag: the daemon honours [`BridgeHello::scope`].
+pub const FEATURE_SESSION_SCOPE: &str = "session_scope";
 
-/// Message sent by `mcp-center-bridge` upon establishing a control connection.
+/// Version of the running binary, reported during the handshake.
+pub fn binary_version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
+/// Feature flags supported by this binary.
+pub fn supported_features() -> Vec<String> {
+    [FEATURE_SHUTDOWN, FEATURE_ROOTS]
+        .iter()
+        .map(|feature| feature.to_string())
+        .collect()
+}
+
+/// Message sent by the bridge upon establishing a control connection.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BridgeHello {
     pub project_path: PathBuf,
@@ -11,6 +46,52 @@ pub struct BridgeHello {
     pub bridge_pid: Option<u32>,
     #[serde(default)]
     pub metadata: serde_json::Value,
+    /// Control protocol version of the bridge (`0` for bridges that predate versioning).
+    #[serde(default)]
+    pub protocol_version: u32,
+    /// `CARGO_PKG_VERSION` of the bridge binary.
+    #[serde(default)]
+    pub binary_version: Option<String>,
+    /// Optional capabilities understood by the bridge.
+    #[serde(default)]
+    pub features: Vec<String>,
+    /// Handshake token from `state_dir/daemon.token`, when the daemon requires one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_token: Option<String>,
+    /// Extra restrictions requested on the `connect` command line.
+    #[serde(default, skip_serializing_if = "SessionScope::is_unrestricted")]
+    pub scope: SessionScope,
+}
+
+/// Session restrictions applied on top of the project permissions.
+///
+/// A scope can only narrow what the project already allows, never widen it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SessionScope {
+    /// Server ids or names the session may use (`None` = every allowed server).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub servers: Option<Vec<String>>,
+    /// Named profile from the workspace settings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Only expose tools annotated with `readOnlyHint: true`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub read_only: bool,
+}
+
+impl SessionScope {
+    pub fn is_unrestricted(&self) -> bool {
+        self.servers.is_none() && self.profile.is_none() && !self.read_only
+    }
+}
+
+impl BridgeHello {
+    /// Whether the daemon can still talk to this bridge.
+    ///
+    /// Newer bridges are accepted; they decide themselves whether the daemon is too old.
+    pub fn is_supported(&self) -> bool {
+        self.protocol_version >= MIN_SUPPORTED_PROTOCOL_VERSION
+    }
 }
 
 /// Response returned by the daemon after registering the bridge.
@@ -20,6 +101,35 @@ pub struct BridgeReady {
     pub allowed_server_ids: Vec<String>,
     #[serde(default)]
     pub project_path: PathBuf,
+    /// Control protocol version of the daemon (`0` for daemons that predate versioning).
+    #[serde(default)]
+    pub protocol_version: u32,
+    /// `CARGO_PKG_VERSION` of the daemon binary.
+    #[serde(default)]
+    pub binary_version: Option<String>,
+    /// Optional capabilities offered by the daemon.
+    #[serde(default)]
+    pub features: Vec<String>,
+    /// Hints for the user (e.g. a suggested `project move`), logged by the bridge.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notices: Vec<String>,
+}
+
+impl BridgeReady {
+    pub fn supports(&self, feature: &str) -> bool {
+        self.features.iter().any(|candidate| candidate == feature)
+    }
+
+    /// Whether the daemon was built from an older release than this binary.
+    pub fn is_outdated(&self) -> bool {
+        if self.protocol_version < CONTROL_PROTOCOL_VERSION {
+            return true;
+        }
+        match self.binary_version.as_deref() {
+            Some(version) => compare_versions(version, binary_version()) == Ordering::Less,
+            None => true,
+        }
+    }
 }
 
 /// Line-oriented control protocol messages.
@@ -28,7 +138,14 @@ pub struct BridgeReady {
 pub enum ControlMessage {
     BridgeHello(BridgeHello),
     BridgeReady(BridgeReady),
-    Error { message: String },
+    /// Ask the daemon to exit (used to replace an outdated daemon).
+    Shutdown {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        auth_token: Option<String>,
+    },
+    Error {
+        message: String,
+    },
 }
 
 impl ControlMessage {
@@ -37,21 +154,125 @@ impl ControlMessage {
         agent: Option<String>,
         bridge_pid: Option<u32>,
         metadata: serde_json::Value,
+        auth_token: Option<String>,
+        scope: SessionScope,
     ) -> Self {
-        ControlMessage::BridgeHello(BridgeHello { project_path, agent, bridge_pid, metadata })
+        ControlMessage::BridgeHello(BridgeHello {
+            project_path,
+            agent,
+            bridge_pid,
+            metadata,
+            protocol_version: CONTROL_PROTOCOL_VERSION,
+            binary_version: Some(binary_version().to_string()),
+            features: supported_features(),
+            auth_token,
+            scope,
+        })
     }
 
-    #[allow(dead_code)]
     pub fn ready(
         project_id: String,
         project_path: PathBuf,
         allowed_server_ids: Vec<String>,
+        notices: Vec<String>,
     ) -> Self {
-        ControlMessage::BridgeReady(BridgeReady { project_id, project_path, allowed_server_ids })
+        ControlMessage::BridgeReady(BridgeReady {
+            project_id,
+            allowed_server_ids,
+            project_path,
+            protocol_version: CONTROL_PROTOCOL_VERSION,
+            binary_version: Some(binary_version().to_string()),
+            features: supported_features(),
+            notices,
+        })
     }
 
-    #[allow(dead_code)]
     pub fn error(message: impl Into<String>) -> Self {
         ControlMessage::Error { message: message.into() }
     }
 }
+
+/// Compare two dotted release versions numerically (`0.10.0` > `0.9.3`).
+///
+/// Pre-release and build suffixes are ignored; missing components count as zero.
+pub fn compare_versions(left: &str, right: &str) -> Ordering {
+    let parse = |raw: &str| -> Vec<u64> {
+        raw.trim()
+            .trim_start_matches('v')
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|part| part.parse::<u64>().unwrap_or(0))
+            .collect()
+    };
+    let (left, right) = (parse(left), parse(right));
+    let len = left.len().max(right.len());
+    for idx in 0..len {
+        let a = left.get(idx).copied().unwrap_or(0);
+        let b = right.get(idx).copied().unwrap_or(0);
+        match a.cmp(&b) {
+            Ordering::Equal => continue,
+            other => return other,
+        }
+    }
+    Ordering::Equal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_versions_numerically() {
+        assert_eq!(compare_versions("0.10.0", "0.9.3"), Ordering::Greater);
+        assert_eq!(compare_versions("0.0.1", "0.0.1"), Ordering::Equal);
+        assert_eq!(compare_versions("1.0", "1.0.1"), Ordering::Less);
+        assert_eq!(compare_versions("v1.2.0-beta.1", "1.2.0"), Ordering::Equal);
+    }
+
+    #[test]
+    fn legacy_hello_is_not_supported() {
+        let legacy = r#"{"type":"bridge_hello","project_path":"/tmp"}"#;
+        let ControlMessage::BridgeHello(hello) = serde_json::from_str(legacy).unwrap() else {
+            panic!("expected bridge_hello");
+        };
+        assert_eq!(hello.protocol_version, 0);
+        assert!(!hello.is_supported());
+
+        let ControlMessage::BridgeHello(current) = ControlMessage::hello(
+            PathBuf::from("/tmp"),
+            None,
+            None,
+            serde_json::Value::Null,
+            None,
+            SessionScope::default(),
+        ) else {
+            panic!("expected bridge_hello");
+        };
+        assert!(current.is_supported());
+        assert!(
+            BridgeHello { protocol_version: CONTROL_PROTOCOL_VERSION + 1, ..current }
+                .is_supported()
+        );
+    }
+
+    #[test]
+    fn legacy_ready_message_is_outdated() {
+        let legacy = r#"{"type":"bridge_ready","project_id":"abc","allowed_server_ids":[]}"#;
+        let ControlMessage::BridgeReady(ready) = serde_json::from_str(legacy).unwrap() else {
+            panic!("expected bridge_ready");
+        };
+        assert_eq!(ready.protocol_version, 0);
+        assert!(ready.is_outdated());
+        assert!(!ready.supports(FEATURE_SHUTDOWN));
+
+        let ControlMessage::BridgeReady(current) =
+            ControlMessage::ready("abc".into(), PathBuf::from("/tmp"), Vec::new(), Vec::new())
+        else {
+            panic!("expected bridge_ready");
+        };
+        assert!(!current.is_outdated());
+        assert!(current.supports(FEATURE_SHUTDOWN));
+    }
+}
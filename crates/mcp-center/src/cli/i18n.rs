@@ -36,10 +36,10 @@ pub fn messages() -> &'static Messages {
 }
 
 fn detect_language() -> Language {
-    if let Ok(value) = env::var("MCP_CENTER_LANG") {
-        if let Some(lang) = parse_language_tag(&value) {
-            return lang;
-        }
+    if let Ok(value) = env::var("MCP_CENTER_LANG")
+        && let Some(lang) = parse_language_tag(&value)
+    {
+        return lang;
     }
 
     let locale = Locale::user_default();
@@ -408,6 +408,659 @@ impl Messages {
         interpolate(self.text("tools.desc.not_customized"), &[("tool", tool.to_string())])
     }
 
+    pub fn project_detect_root(&self, path: &Path) -> String {
+        interpolate(self.text("project.detect.root"), &[("path", path.display().to_string())])
+    }
+
+    pub fn project_detect_rule(&self, rule: &str) -> String {
+        interpolate(self.text("project.detect.rule"), &[("rule", rule.to_string())])
+    }
+
+    pub fn project_detect_settings(&self, path: &Path, exists: bool) -> String {
+        let key = if exists {
+            "project.detect.settings"
+        } else {
+            "project.detect.settings_default"
+        };
+        interpolate(self.text(key), &[("path", path.display().to_string())])
+    }
+
+    pub fn project_detect_strategies(&self, strategies: &str) -> String {
+        interpolate(
+            self.text("project.detect.strategies"),
+            &[("strategies", strategies.to_string())],
+        )
+    }
+
+    pub fn project_detect_monorepo(&self, mode: &str) -> String {
+        interpolate(self.text("project.detect.monorepo"), &[("mode", mode.to_string())])
+    }
+
+    pub fn project_detect_rule_env(&self, var: &str) -> String {
+        interpolate(self.text("project.detect.rule.env"), &[("var", var.to_string())])
+    }
+
+    pub fn project_detect_rule_marker(&self, marker: &str) -> String {
+        interpolate(self.text("project.detect.rule.marker"), &[("marker", marker.to_string())])
+    }
+
+    pub fn project_detect_rule_git(&self) -> &'static str {
+        self.text("project.detect.rule.git")
+    }
+
+    pub fn project_detect_rule_nearest_package(
+        &self,
+        marker: &str,
+        repo_root: &Path,
+        base: &str,
+    ) -> String {
+        interpolate(
+            self.text("project.detect.rule.nearest_package"),
+            &[
+                ("marker", marker.to_string()),
+                ("root", repo_root.display().to_string()),
+                ("base", base.to_string()),
+            ],
+        )
+    }
+
+    pub fn project_detect_rule_cwd(&self) -> &'static str {
+        self.text("project.detect.rule.cwd")
+    }
+
+    pub fn project_move_done(&self, from: &Path, to: &Path, id: &str) -> String {
+        interpolate(
+            self.text("project.move.done"),
+            &[
+                ("from", from.display().to_string()),
+                ("to", to.display().to_string()),
+                ("id", id.to_string()),
+            ],
+        )
+    }
+
+    pub fn project_move_destination_exists(&self, path: &Path) -> String {
+        interpolate(
+            self.text("project.move.destination_exists"),
+            &[("path", path.display().to_string())],
+        )
+    }
+
+    pub fn project_move_not_directory(&self, path: &Path) -> String {
+        interpolate(
+            self.text("project.move.not_directory"),
+            &[("path", path.display().to_string())],
+        )
+    }
+
+    pub fn project_move_same_path(&self, path: &Path) -> String {
+        interpolate(self.text("project.move.same_path"), &[("path", path.display().to_string())])
+    }
+
+    pub fn project_list_pending(&self) -> &'static str {
+        self.text("project.list.pending")
+    }
+
+    pub fn project_list_pending_hint(&self) -> &'static str {
+        self.text("project.list.pending_hint")
+    }
+
+    pub fn project_approve_done(&self, path: &Path) -> String {
+        interpolate(self.text("project.approve.done"), &[("path", path.display().to_string())])
+    }
+
+    pub fn project_approve_already(&self, path: &Path) -> String {
+        interpolate(self.text("project.approve.already"), &[("path", path.display().to_string())])
+    }
+
+    pub fn project_approve_unrestricted(&self) -> &'static str {
+        self.text("project.approve.unrestricted")
+    }
+
+    pub fn project_explain_project(&self, path: &Path) -> String {
+        interpolate(self.text("project.explain.project"), &[("path", path.display().to_string())])
+    }
+
+    pub fn project_explain_record(&self, id: &str, status: &str) -> String {
+        interpolate(
+            self.text("project.explain.record"),
+            &[("id", id.to_string()), ("status", status.to_string())],
+        )
+    }
+
+    pub fn project_explain_no_record(&self) -> &'static str {
+        self.text("project.explain.no_record")
+    }
+
+    pub fn project_explain_layers(&self) -> &'static str {
+        self.text("project.explain.layers")
+    }
+
+    pub fn project_explain_none(&self) -> &'static str {
+        self.text("project.explain.none")
+    }
+
+    pub fn project_explain_servers(&self) -> &'static str {
+        self.text("project.explain.servers")
+    }
+
+    pub fn project_explain_server_line(&self, server: &str, verdict: &str, reason: &str) -> String {
+        interpolate(
+            self.text("project.explain.server_line"),
+            &[
+                ("server", server.to_string()),
+                ("verdict", verdict.to_string()),
+                ("reason", reason.to_string()),
+            ],
+        )
+    }
+
+    pub fn project_explain_verdict(&self, allowed: bool) -> &'static str {
+        if allowed {
+            self.text("project.explain.allowed")
+        } else {
+            self.text("project.explain.denied")
+        }
+    }
+
+    pub fn project_explain_permission_all(&self) -> &'static str {
+        self.text("project.explain.permission.all")
+    }
+
+    pub fn project_explain_permission_allow(&self, tools: &str) -> String {
+        interpolate(self.text("project.explain.permission.allow"), &[("tools", tools.to_string())])
+    }
+
+    pub fn project_explain_permission_deny(&self, tools: &str) -> String {
+        interpolate(self.text("project.explain.permission.deny"), &[("tools", tools.to_string())])
+    }
+
+    pub fn project_explain_reason_tool_permission(&self, source: &str) -> String {
+        interpolate(
+            self.text("project.explain.reason.tool_permission"),
+            &[("source", source.to_string())],
+        )
+    }
+
+    pub fn project_explain_reason_server_list(&self, source: &str) -> String {
+        interpolate(
+            self.text("project.explain.reason.server_list"),
+            &[("source", source.to_string())],
+        )
+    }
+
+    pub fn project_explain_reason_pending(&self) -> &'static str {
+        self.text("project.explain.reason.pending")
+    }
+
+    pub fn project_explain_reason_unrestricted(&self) -> &'static str {
+        self.text("project.explain.reason.unrestricted")
+    }
+
+    pub fn project_explain_reason_grant(&self, source: &str, expires: &str) -> String {
+        interpolate(
+            self.text("project.explain.reason.grant"),
+            &[("source", source.to_string()), ("expires", expires.to_string())],
+        )
+    }
+
+    pub fn project_grant_done(&self, path: &Path, servers: &str, expires: &str) -> String {
+        interpolate(
+            self.text("project.grant.done"),
+            &[
+                ("path", path.display().to_string()),
+                ("servers", servers.to_string()),
+                ("expires", expires.to_string()),
+            ],
+        )
+    }
+
+    pub fn project_grant_tools_done(&self, server: &str, tools: &str, expires: &str) -> String {
+        interpolate(
+            self.text("project.grant.tools_done"),
+            &[
+                ("server", server.to_string()),
+                ("tools", tools.to_string()),
+                ("expires", expires.to_string()),
+            ],
+        )
+    }
+
+    pub fn project_grants_header(&self, path: &Path) -> String {
+        interpolate(self.text("project.grants.header"), &[("path", path.display().to_string())])
+    }
+
+    pub fn project_grants_none(&self, path: &Path) -> String {
+        interpolate(self.text("project.grants.none"), &[("path", path.display().to_string())])
+    }
+
+    pub fn project_grants_all_tools(&self) -> &'static str {
+        self.text("project.grants.all_tools")
+    }
+
+    pub fn project_grants_active(&self, server: &str, scope: &str, expires: &str) -> String {
+        interpolate(
+            self.text("project.grants.active"),
+            &[
+                ("server", server.to_string()),
+                ("scope", scope.to_string()),
+                ("expires", expires.to_string()),
+            ],
+        )
+    }
+
+    pub fn project_grants_expired(&self, server: &str, scope: &str, expires: &str) -> String {
+        interpolate(
+            self.text("project.grants.expired"),
+            &[
+                ("server", server.to_string()),
+                ("scope", scope.to_string()),
+                ("expires", expires.to_string()),
+            ],
+        )
+    }
+
+    pub fn project_grants_pruned(&self, count: usize) -> String {
+        interpolate(self.text("project.grants.pruned"), &[("count", count.to_string())])
+    }
+
+    pub fn project_mode_state(&self, state: Option<bool>) -> &'static str {
+        match state {
+            Some(true) => self.text("project.mode.state.on"),
+            Some(false) => self.text("project.mode.state.off"),
+            None => self.text("project.mode.state.inherit"),
+        }
+    }
+
+    pub fn project_mode_updated(&self, switch: &str, state: &str, path: &Path) -> String {
+        interpolate(
+            self.text("project.mode.updated"),
+            &[
+                ("switch", switch.to_string()),
+                ("state", state.to_string()),
+                ("path", path.display().to_string()),
+            ],
+        )
+    }
+
+    pub fn project_approval_all_tools(&self) -> &'static str {
+        self.text("project.approval.all_tools")
+    }
+
+    pub fn project_approval_updated(&self, scope: &str, state: &str, path: &Path) -> String {
+        interpolate(
+            self.text("project.approval.updated"),
+            &[
+                ("scope", scope.to_string()),
+                ("state", state.to_string()),
+                ("path", path.display().to_string()),
+            ],
+        )
+    }
+
+    pub fn project_tool_search_updated(&self, state: &str, path: &Path) -> String {
+        interpolate(
+            self.text("project.tool_search.updated"),
+            &[("state", state.to_string()), ("path", path.display().to_string())],
+        )
+    }
+
+    pub fn project_explain_tool_search(&self, state: &str, source: &str) -> String {
+        interpolate(
+            self.text("project.explain.tool_search"),
+            &[("state", state.to_string()), ("source", source.to_string())],
+        )
+    }
+
+    pub fn invalid_json_pointer(&self, pointer: &str) -> String {
+        interpolate(
+            self.text("project.arg_rule.invalid_pointer"),
+            &[("pointer", pointer.to_string())],
+        )
+    }
+
+    pub fn invalid_regex(&self, pattern: &str, error: &str) -> String {
+        interpolate(
+            self.text("project.arg_rule.invalid_regex"),
+            &[("pattern", pattern.to_string()), ("error", error.to_string())],
+        )
+    }
+
+    pub fn project_arg_rule_set(&self, tool: &str, pointer: &str) -> String {
+        interpolate(
+            self.text("project.arg_rule.set"),
+            &[("tool", tool.to_string()), ("pointer", pointer.to_string())],
+        )
+    }
+
+    pub fn project_arg_rule_cleared(&self, tool: &str, pointer: &str) -> String {
+        interpolate(
+            self.text("project.arg_rule.cleared"),
+            &[("tool", tool.to_string()), ("pointer", pointer.to_string())],
+        )
+    }
+
+    pub fn project_preset_set(&self, tool: &str, argument: &str) -> String {
+        interpolate(
+            self.text("project.preset.set"),
+            &[("tool", tool.to_string()), ("argument", argument.to_string())],
+        )
+    }
+
+    pub fn project_preset_cleared(&self, tool: &str, argument: &str) -> String {
+        interpolate(
+            self.text("project.preset.cleared"),
+            &[("tool", tool.to_string()), ("argument", argument.to_string())],
+        )
+    }
+
+    pub fn invalid_tool_alias(&self, alias: &str) -> String {
+        interpolate(self.text("project.tool_alias.invalid"), &[("alias", alias.to_string())])
+    }
+
+    pub fn invalid_schema_patch(&self, error: &str) -> String {
+        interpolate(self.text("project.tool_alias.invalid_patch"), &[("error", error.to_string())])
+    }
+
+    pub fn tool_alias_taken(&self, alias: &str, tool: &str) -> String {
+        interpolate(
+            self.text("project.tool_alias.taken"),
+            &[("alias", alias.to_string()), ("tool", tool.to_string())],
+        )
+    }
+
+    pub fn project_tool_overrides_set(&self, tool: &str) -> String {
+        interpolate(self.text("project.tool_alias.set"), &[("tool", tool.to_string())])
+    }
+
+    pub fn project_tool_overrides_cleared(&self, tool: &str) -> String {
+        interpolate(self.text("project.tool_alias.cleared"), &[("tool", tool.to_string())])
+    }
+
+    pub fn project_explain_overrides(&self) -> &'static str {
+        self.text("project.explain.overrides")
+    }
+
+    pub fn project_explain_override_line(
+        &self,
+        tool: &str,
+        overrides: &str,
+        source: &str,
+    ) -> String {
+        interpolate(
+            self.text("project.explain.override_line"),
+            &[
+                ("tool", tool.to_string()),
+                ("overrides", overrides.to_string()),
+                ("source", source.to_string()),
+            ],
+        )
+    }
+
+    pub fn project_explain_presets(&self) -> &'static str {
+        self.text("project.explain.presets")
+    }
+
+    pub fn project_explain_preset_line(
+        &self,
+        tool: &str,
+        argument: &str,
+        value: &str,
+        source: &str,
+    ) -> String {
+        interpolate(
+            self.text("project.explain.preset_line"),
+            &[
+                ("tool", tool.to_string()),
+                ("argument", argument.to_string()),
+                ("value", value.to_string()),
+                ("source", source.to_string()),
+            ],
+        )
+    }
+
+    pub fn project_explain_arg_rules(&self) -> &'static str {
+        self.text("project.explain.arg_rules")
+    }
+
+    pub fn project_explain_arg_rule_line(
+        &self,
+        tool: &str,
+        pointer: &str,
+        rule: &str,
+        source: &str,
+    ) -> String {
+        interpolate(
+            self.text("project.explain.arg_rule_line"),
+            &[
+                ("tool", tool.to_string()),
+                ("pointer", pointer.to_string()),
+                ("rule", rule.to_string()),
+                ("source", source.to_string()),
+            ],
+        )
+    }
+
+    pub fn project_explain_approvals(&self) -> &'static str {
+        self.text("project.explain.approvals")
+    }
+
+    pub fn approvals_none(&self) -> &'static str {
+        self.text("approvals.none")
+    }
+
+    pub fn approvals_header(&self) -> &'static str {
+        self.text("approvals.header")
+    }
+
+    pub fn approvals_line(
+        &self,
+        id: u64,
+        tool: &str,
+        server: &str,
+        project: &str,
+        expires: &str,
+    ) -> String {
+        interpolate(
+            self.text("approvals.line"),
+            &[
+                ("id", id.to_string()),
+                ("tool", tool.to_string()),
+                ("server", server.to_string()),
+                ("project", project.to_string()),
+                ("expires", expires.to_string()),
+            ],
+        )
+    }
+
+    pub fn approvals_approved(&self, id: u64, tool: &str) -> String {
+        interpolate(
+            self.text("approvals.approved"),
+            &[("id", id.to_string()), ("tool", tool.to_string())],
+        )
+    }
+
+    pub fn approvals_denied(&self, id: u64, tool: &str) -> String {
+        interpolate(
+            self.text("approvals.denied"),
+            &[("id", id.to_string()), ("tool", tool.to_string())],
+        )
+    }
+
+    pub fn cache_empty(&self) -> &'static str {
+        self.text("cache.empty")
+    }
+
+    pub fn cache_header(&self) -> (&'static str, &'static str, &'static str, &'static str) {
+        (
+            self.text("cache.header.server"),
+            self.text("cache.header.entries"),
+            self.text("cache.header.size"),
+            self.text("cache.header.newest"),
+        )
+    }
+
+    pub fn cache_cleared(&self, count: usize, size: &str) -> String {
+        interpolate(
+            self.text("cache.cleared"),
+            &[("count", count.to_string()), ("size", size.to_string())],
+        )
+    }
+
+    pub fn project_tool_hints_set(&self, tool: &str) -> String {
+        interpolate(self.text("project.tool_hints.set"), &[("tool", tool.to_string())])
+    }
+
+    pub fn project_tool_hints_cleared(&self, tool: &str) -> String {
+        interpolate(self.text("project.tool_hints.cleared"), &[("tool", tool.to_string())])
+    }
+
+    pub fn project_explain_switches(&self) -> &'static str {
+        self.text("project.explain.switches")
+    }
+
+    pub fn project_explain_switch_line(&self, switch: &str, state: &str, source: &str) -> String {
+        interpolate(
+            self.text("project.explain.switch_line"),
+            &[
+                ("switch", switch.to_string()),
+                ("state", state.to_string()),
+                ("source", source.to_string()),
+            ],
+        )
+    }
+
+    pub fn project_explain_reason_annotation(&self, switch: &str, source: &str) -> String {
+        interpolate(
+            self.text("project.explain.reason.annotation"),
+            &[("switch", switch.to_string()), ("source", source.to_string())],
+        )
+    }
+
+    pub fn project_effective_switch_line(
+        &self,
+        switch: &str,
+        source: &str,
+        count: usize,
+    ) -> String {
+        interpolate(
+            self.text("project.effective.switch_line"),
+            &[
+                ("switch", switch.to_string()),
+                ("source", source.to_string()),
+                ("count", count.to_string()),
+            ],
+        )
+    }
+
+    pub fn project_explain_descriptions(&self) -> &'static str {
+        self.text("project.explain.descriptions")
+    }
+
+    pub fn project_explain_description_line(&self, tool: &str, source: &str) -> String {
+        interpolate(
+            self.text("project.explain.description_line"),
+            &[("tool", tool.to_string()), ("source", source.to_string())],
+        )
+    }
+
+    pub fn project_explain_source_rule(&self, pattern: &str) -> String {
+        interpolate(self.text("project.explain.source.rule"), &[("pattern", pattern.to_string())])
+    }
+
+    pub fn project_explain_source_parent(&self, path: &Path) -> String {
+        interpolate(
+            self.text("project.explain.source.parent"),
+            &[("path", path.display().to_string())],
+        )
+    }
+
+    pub fn project_explain_source_project(&self) -> &'static str {
+        self.text("project.explain.source.project")
+    }
+
+    pub fn project_effective_summary(&self, allowed: usize, total: usize) -> String {
+        interpolate(
+            self.text("project.effective.summary"),
+            &[("allowed", allowed.to_string()), ("total", total.to_string())],
+        )
+    }
+
+    pub fn project_effective_tool_line(
+        &self,
+        tool: &str,
+        server: &str,
+        verdict: &str,
+        reason: &str,
+    ) -> String {
+        interpolate(
+            self.text("project.effective.tool_line"),
+            &[
+                ("tool", tool.to_string()),
+                ("server", server.to_string()),
+                ("verdict", verdict.to_string()),
+                ("reason", reason.to_string()),
+            ],
+        )
+    }
+
+    pub fn project_effective_description(&self, description: &str) -> String {
+        interpolate(
+            self.text("project.effective.description"),
+            &[("description", description.to_string())],
+        )
+    }
+
+    pub fn project_effective_custom_description(&self, description: &str, source: &str) -> String {
+        interpolate(
+            self.text("project.effective.custom_description"),
+            &[("description", description.to_string()), ("source", source.to_string())],
+        )
+    }
+
+    pub fn project_explain_source_repo_file(&self, path: &Path) -> String {
+        interpolate(
+            self.text("project.explain.source.repo_file"),
+            &[("path", path.display().to_string())],
+        )
+    }
+
+    pub fn project_explain_reason_repo_config(&self, path: &Path) -> String {
+        interpolate(
+            self.text("project.explain.reason.repo_config"),
+            &[("path", path.display().to_string())],
+        )
+    }
+
+    pub fn project_explain_repo_rule(&self, allow: &str, deny: &str) -> String {
+        interpolate(
+            self.text("project.explain.repo_rule"),
+            &[("allow", allow.to_string()), ("deny", deny.to_string())],
+        )
+    }
+
+    pub fn project_explain_warnings(&self) -> &'static str {
+        self.text("project.explain.warnings")
+    }
+
+    pub fn project_explain_warning_not_installed(&self, server: &str) -> String {
+        interpolate(
+            self.text("project.explain.warning.not_installed"),
+            &[("server", server.to_string())],
+        )
+    }
+
+    pub fn project_explain_warning_not_allowed(&self, server: &str) -> String {
+        interpolate(
+            self.text("project.explain.warning.not_allowed"),
+            &[("server", server.to_string())],
+        )
+    }
+
+    pub fn project_explain_warning_invalid(&self, error: &str) -> String {
+        interpolate(self.text("project.explain.warning.invalid"), &[("error", error.to_string())])
+    }
+
     pub fn project_not_found(&self, target: &str) -> String {
         interpolate(self.text("project.not_found"), &[("target", target.to_string())])
     }
@@ -651,6 +1304,13 @@ fn english_text(key: &str) -> &'static str {
         "core.server_endpoint_invalid_with_id" => {
             "Invalid MCP server endpoint for id {id} ('{endpoint}'): {error}"
         }
+        "core.script_tool_invalid" => "Invalid script tool '{tool}': {reason}",
+        "core.tool_hook_invalid" => "Invalid hook '{hook}': {reason}",
+        "core.cache_config_invalid" => "Invalid cache settings: {reason}",
+        "core.output_limits_invalid" => "Invalid output limits: {reason}",
+        "core.project_approval_unrestricted" => {
+            "Approving {path} without granting any server would allow every server; name the servers to grant or pass --all"
+        }
         "core.server_config_not_found" => "MCP server configuration '{id}' not found",
         "core.server_config_not_found_name" => "MCP server '{name}' not found",
         "core.create_dir_failed" => "Failed to create directory {path}: {error}",
@@ -660,7 +1320,13 @@ fn english_text(key: &str) -> &'static str {
         "core.parse_toml_failed" => "Failed to parse TOML server config at {path}: {error}",
         "core.serialise_toml_failed" => "Failed to serialise server definition to TOML: {error}",
         "core.remove_file_failed" => "Failed to remove {path}: {error}",
+        "core.settings_parse_failed" => "Failed to parse settings file {path}: {error}",
+        "core.settings_serialise_failed" => "Failed to serialise workspace settings: {error}",
+        "core.settings_write_failed" => "Failed to write settings file {path}: {error}",
         "core.home_dir_unknown" => "Unable to determine user home directory for MCP_CENTER_ROOT",
+        "core.invalid_duration" => {
+            "Invalid duration '{value}' (expected e.g. 30m, 2h, 1d or 1h30m)"
+        }
         // New translations for tool-level permissions
         "command.mcp.list_tools.about" => "List all tools from MCP servers",
         "command.project.allow_tools.about" => "Allow specific tools for a project",
@@ -689,6 +1355,225 @@ fn english_text(key: &str) -> &'static str {
         "tools.desc.reset" => "Description reset to default for tool '{tool}'",
         "tools.desc.not_customized" => "Tool '{tool}' has no custom description",
         "project.not_found" => "Project not found: {target}",
+        "command.project.detect.about" => {
+            "Show which project root the bridge would detect for a directory and why."
+        }
+        "args.project_detect.dir" => "Directory to inspect (defaults to current directory).",
+        "project.detect.root" => "Project root: {path}",
+        "project.detect.rule" => "Matched rule: {rule}",
+        "project.detect.settings" => "Settings: {path}",
+        "project.detect.settings_default" => "Settings: built-in defaults ({path} not found)",
+        "project.detect.strategies" => "Strategy order: {strategies}",
+        "project.detect.monorepo" => "Monorepo mode: {mode}",
+        "project.detect.rule.env" => "environment override {var}",
+        "project.detect.rule.marker" => "marker '{marker}'",
+        "project.detect.rule.git" => "git repository root",
+        "project.detect.rule.nearest_package" => {
+            "nearest package '{marker}' inside {root} (found via {base})"
+        }
+        "project.detect.rule.cwd" => "no rule matched, using the directory itself",
+        "command.project.move.about" => {
+            "Rebind a project record (and its permissions) to a new path after a move or rename."
+        }
+        "args.project_move.from" => "Old project path or ID (the path may no longer exist).",
+        "args.project_move.to" => "New project path.",
+        "args.project_move.force" => "Replace a record that already exists at the new path.",
+        "project.move.done" => "Moved project {from} → {to} (ID: {id})",
+        "project.move.destination_exists" => {
+            "A project record already exists at {path}; pass --force to replace it."
+        }
+        "project.move.same_path" => "Project is already registered at {path}.",
+        "project.move.not_directory" => "New project path {path} is not a directory.",
+        "command.project.approve.about" => "Approve a project that is pending after first use.",
+        "command.project.explain.about" => {
+            "Show the effective permissions of a path and the rule or project behind each one."
+        }
+        "args.project_explain.path" => "Project path (defaults to current directory).",
+        "project.explain.project" => "Project: {path}",
+        "project.explain.record" => "Record: {id} ({status})",
+        "project.explain.no_record" => "Record: none",
+        "project.explain.layers" => "Layers (general → specific):",
+        "project.explain.none" => "  (none)",
+        "project.explain.servers" => "Servers:",
+        "project.explain.server_line" => "  {server}: {verdict} — {reason}",
+        "project.explain.allowed" => "allowed",
+        "project.explain.denied" => "denied",
+        "project.explain.permission.all" => "all tools",
+        "project.explain.permission.allow" => "only {tools}",
+        "project.explain.permission.deny" => "all tools except {tools}",
+        "project.explain.reason.tool_permission" => "tool permission from {source}",
+        "project.explain.reason.server_list" => "server list from {source}",
+        "project.explain.reason.pending" => "project is pending approval",
+        "project.explain.reason.unrestricted" => "nothing configured, all servers allowed",
+        "project.explain.reason.grant" => "temporary grant from {source} until {expires}",
+        "args.project.grant_for" => {
+            "Grant temporarily for this long (e.g. 30m, 2h, 1d) instead of permanently."
+        }
+        "project.grant.done" => "Granted {servers} to {path} until {expires}.",
+        "project.grant.tools_done" => "Granted {server}: {tools} until {expires}.",
+        "command.project.grants.about" => "List active and expired temporary grants of a project.",
+        "args.project_grants.prune" => "Remove expired grants from the project record.",
+        "project.grants.header" => "Temporary grants for {path}:",
+        "project.grants.none" => "No temporary grants for {path}.",
+        "project.grants.all_tools" => "all tools",
+        "project.grants.active" => "  {server} ({scope}): active until {expires}",
+        "project.grants.expired" => "  {server} ({scope}): expired at {expires}",
+        "project.grants.pruned" => "Removed {count} expired grant(s).",
+        "command.project.set_mode.about" => {
+            "Turn an annotation switch (read-only, no-destructive, no-open-world) on or off for a project."
+        }
+        "args.project_set_mode.switch" => "Switch to change.",
+        "args.project_set_mode.state" => {
+            "on, off, or inherit to follow project rules and parent projects."
+        }
+        "project.mode.state.on" => "on",
+        "project.mode.state.off" => "off",
+        "project.mode.state.inherit" => "inherited",
+        "project.mode.updated" => "{switch} is now {state} for {path}",
+        "command.project.set_tool_hints.about" => {
+            "Override the annotation hints a server reports for a tool (no flags clears the override)."
+        }
+        "args.project_tool_hints.read_only" => "Treat the tool as read-only (true/false).",
+        "args.project_tool_hints.destructive" => "Treat the tool as destructive (true/false).",
+        "args.project_tool_hints.open_world" => {
+            "Treat the tool as reaching external systems (true/false)."
+        }
+        "project.tool_hints.set" => "Annotation hints for '{tool}' overridden",
+        "project.tool_hints.cleared" => "Annotation hint overrides for '{tool}' cleared",
+        "command.project.require_approval.about" => {
+            "Require a person to approve tool calls of a project before they run."
+        }
+        "args.project_require_approval.state" => {
+            "on, off, or inherit to follow project rules and parent projects."
+        }
+        "args.project_require_approval.tool" => "Only change the setting for this tool.",
+        "project.approval.all_tools" => "all tools",
+        "project.approval.updated" => "Approval for {scope} is now {state} for {path}",
+        "project.explain.approvals" => "Approval required:",
+        "command.project.tool_search.about" => {
+            "Expose only search_tools/describe_tool/invoke_tool instead of every tool."
+        }
+        "args.project_tool_search.state" => {
+            "on, off, or inherit to follow project rules and parent projects."
+        }
+        "project.tool_search.updated" => "Tool search is now {state} for {path}",
+        "project.explain.tool_search" => "Tool search: {state} ({source})",
+        "command.project.set_arg_rule.about" => {
+            "Constrain one argument of a tool call (no constraint flags removes the rule)."
+        }
+        "args.project_arg_rule.pointer" => "JSON pointer to the argument, e.g. /path.",
+        "args.project_arg_rule.required" => "Reject calls that omit the argument.",
+        "args.project_arg_rule.within" => {
+            "Paths must stay under this directory; ${PROJECT_ROOT} is the project root."
+        }
+        "args.project_arg_rule.allow" => "Regex the value must match (repeatable).",
+        "args.project_arg_rule.deny" => "Regex the value must not match (repeatable).",
+        "args.project_arg_rule.min" => "Smallest allowed number.",
+        "args.project_arg_rule.max" => "Largest allowed number.",
+        "project.arg_rule.invalid_pointer" => {
+            "'{pointer}' is not a JSON pointer (it must start with '/')."
+        }
+        "project.arg_rule.invalid_regex" => "Invalid regular expression '{pattern}': {error}",
+        "project.arg_rule.set" => "Argument rule for {tool} {pointer} saved",
+        "project.arg_rule.cleared" => "Argument rule for {tool} {pointer} removed",
+        "project.explain.arg_rules" => "Argument rules:",
+        "project.explain.arg_rule_line" => "  {tool} {pointer}: {rule} ({source})",
+        "command.project.set_preset.about" => {
+            "Pin an argument of a tool and hide it from agents (omit VALUE to remove it)."
+        }
+        "args.project_preset.argument" => "Top-level argument name, e.g. repo.",
+        "args.project_preset.value" => "Value to inject; parsed as JSON when valid, else a string.",
+        "args.project_preset.secret" => "Redact the value from tool logs and CLI output.",
+        "project.preset.set" => "Argument {argument} of {tool} is now pinned",
+        "project.preset.cleared" => "Argument {argument} of {tool} is no longer pinned",
+        "project.explain.presets" => "Pinned arguments:",
+        "project.explain.preset_line" => "  {tool} {argument} = {value} ({source})",
+        "command.project.set_tool_alias.about" => {
+            "Rename, retitle or reshape a tool for this project (no ALIAS or flags resets it)."
+        }
+        "args.project_tool_alias.alias" => "Name agents see instead of the upstream name.",
+        "args.project_tool_alias.title" => "Title agents see instead of the upstream title.",
+        "args.project_tool_alias.schema_patch" => {
+            "JSON merge patch for the input schema, or @FILE to read it from a file."
+        }
+        "args.project_tool_alias.hide" => "Parameter to hide from agents (repeatable).",
+        "project.tool_alias.invalid" => {
+            "'{alias}' is not a valid tool name (use 1-64 letters, digits, '_' or '-')."
+        }
+        "project.tool_alias.invalid_patch" => "Invalid schema patch: {error}",
+        "project.tool_alias.taken" => "'{alias}' is already used by tool {tool}",
+        "project.tool_alias.set" => "Overrides for {tool} saved",
+        "project.tool_alias.cleared" => "Overrides for {tool} removed",
+        "project.explain.overrides" => "Tool overrides:",
+        "project.explain.override_line" => "  {tool}: {overrides} ({source})",
+        "command.approvals.about" => {
+            "Review tool calls waiting for approval (requires a running daemon)."
+        }
+        "command.approvals.list.about" => "List tool calls waiting for approval.",
+        "command.approvals.approve.about" => "Let a waiting tool call run.",
+        "command.approvals.deny.about" => "Reject a waiting tool call.",
+        "args.approvals.id" => "Id shown by `mcp-center approvals list`.",
+        "args.approvals.reason" => "Reason returned to the agent.",
+        "approvals.none" => "No tool calls are waiting for approval.",
+        "approvals.header" => "Tool calls waiting for approval:",
+        "approvals.line" => "  #{id} {tool} ({server}) from {project}, expires {expires}",
+        "approvals.approved" => "Approved #{id} ({tool}).",
+        "approvals.denied" => "Denied #{id} ({tool}).",
+        "command.cache.about" => "Inspect or clear cached tool results.",
+        "command.cache.stats.about" => "Show cached tool results per MCP server.",
+        "command.cache.clear.about" => "Remove cached tool results.",
+        "args.cache.server" => "Only the cache of this server (id).",
+        "cache.empty" => "No cached tool results.",
+        "cache.header.server" => "MCP Server",
+        "cache.header.entries" => "Entries",
+        "cache.header.size" => "Size",
+        "cache.header.newest" => "Last Stored",
+        "cache.cleared" => "Removed {count} cached result(s), {size}.",
+        "project.explain.switches" => "Annotation switches:",
+        "project.explain.switch_line" => "  {switch}: {state} ({source})",
+        "project.explain.reason.annotation" => "hidden by {switch} mode ({source})",
+        "project.effective.switch_line" => "  {switch} ({source}) hides {count} tool(s)",
+        "project.explain.descriptions" => "Tool descriptions:",
+        "project.explain.description_line" => "  {tool} — {source}",
+        "project.explain.source.rule" => "rule '{pattern}'",
+        "project.explain.source.parent" => "parent project {path}",
+        "project.explain.source.project" => "project record",
+        "project.explain.source.repo_file" => "repository config {path}",
+        "command.project.effective.about" => {
+            "Simulate the tool list an agent would see for a project (requires a running daemon)."
+        }
+        "args.project_effective.path" => "Project path (defaults to the current directory).",
+        "project.effective.summary" => "{allowed} of {total} tools visible to agents:",
+        "project.effective.tool_line" => "  {tool} ({server}): {verdict} — {reason}",
+        "project.effective.description" => "      {description}",
+        "project.effective.custom_description" => "      {description} [custom, from {source}]",
+        "project.explain.reason.repo_config" => "excluded by repository config {path}",
+        "project.explain.repo_rule" => {
+            "    repository config narrows it (allow: {allow}; deny: {deny})"
+        }
+        "project.explain.warnings" => "Repository config warnings:",
+        "project.explain.warning.not_installed" => {
+            "  '{server}' is referenced by .mcp-center.toml but not installed"
+        }
+        "project.explain.warning.not_allowed" => {
+            "  '{server}' is required by .mcp-center.toml but not allowed for this project"
+        }
+        "project.explain.warning.invalid" => "  .mcp-center.toml was ignored: {error}",
+        "args.project_approve.servers" => {
+            "Server IDs to grant (defaults to the permissions the project already has)."
+        }
+        "args.project_approve.all" => {
+            "Approve a project that grants no servers, allowing it to use every server."
+        }
+        "project.list.pending" => "[pending]",
+        "project.list.pending_hint" => {
+            "Pending projects only get their default permissions; run 'mcp-center project approve <PATH>' to approve them."
+        }
+        "project.approve.done" => "Approved project {path}",
+        "project.approve.already" => "Project {path} is already approved",
+        "project.approve.unrestricted" => {
+            "No servers are granted explicitly, so the project may now use every server."
+        }
         "logs.list.no_files" => "No log files found.",
         "logs.list.no_files_for" => "No log files found for MCP server '{server}'.",
         "logs.list.header.server" => "MCP Server",
@@ -824,6 +1709,13 @@ fn zh_hans_text(key: &str) -> Option<&'static str> {
         "core.server_endpoint_invalid_with_id" => {
             "MCP 服务器端点无效（ID {id}，“{endpoint}”）：{error}"
         }
+        "core.script_tool_invalid" => "脚本工具“{tool}”无效：{reason}",
+        "core.tool_hook_invalid" => "钩子“{hook}”无效：{reason}",
+        "core.cache_config_invalid" => "缓存设置无效：{reason}",
+        "core.output_limits_invalid" => "输出限制无效：{reason}",
+        "core.project_approval_unrestricted" => {
+            "批准 {path} 时未授予任何服务器将允许所有服务器；请指定要授予的服务器，或传入 --all"
+        }
         "core.server_config_not_found" => "未找到 ID 为“{id}”的 MCP 服务器配置。",
         "core.server_config_not_found_name" => "未找到名称为“{name}”的 MCP 服务器。",
         "core.create_dir_failed" => "创建目录 {path} 失败：{error}",
@@ -833,7 +1725,11 @@ fn zh_hans_text(key: &str) -> Option<&'static str> {
         "core.parse_toml_failed" => "解析 {path} 的 TOML MCP 服务器配置失败：{error}",
         "core.serialise_toml_failed" => "序列化 MCP 服务器定义到 TOML 失败：{error}",
         "core.remove_file_failed" => "删除 {path} 失败：{error}",
+        "core.settings_parse_failed" => "解析设置文件 {path} 失败：{error}",
+        "core.settings_serialise_failed" => "序列化工作区设置失败：{error}",
+        "core.settings_write_failed" => "写入设置文件 {path} 失败：{error}",
         "core.home_dir_unknown" => "无法确定用户主目录（MCP_CENTER_ROOT）",
+        "core.invalid_duration" => "无效的时长“{value}”（示例：30m、2h、1d、1h30m）",
         // 工具级权限控制新增翻译
         "command.mcp.list_tools.about" => "列出 MCP 服务器的所有工具",
         "command.project.allow_tools.about" => "允许项目使用特定工具",
@@ -872,6 +1768,195 @@ fn zh_hans_text(key: &str) -> Option<&'static str> {
         "logs.file.not_found" => "服务器“{server}”未找到日志文件“{file}”。",
         "logs.show.header" => "显示最新 {count} 条记录（文件：{file}）",
         "logs.show.no_entries" => "所选日志文件没有可显示的记录。",
+        "command.project.detect.about" => "显示桥接层会为目录识别出的项目根目录及原因。",
+        "args.project_detect.dir" => "要检查的目录（默认当前目录）。",
+        "project.detect.root" => "项目根目录：{path}",
+        "project.detect.rule" => "命中规则：{rule}",
+        "project.detect.settings" => "设置文件：{path}",
+        "project.detect.settings_default" => "设置：内置默认值（未找到 {path}）",
+        "project.detect.strategies" => "策略顺序：{strategies}",
+        "project.detect.monorepo" => "Monorepo 模式：{mode}",
+        "project.detect.rule.env" => "环境变量覆盖 {var}",
+        "project.detect.rule.marker" => "标记“{marker}”",
+        "project.detect.rule.git" => "git 仓库根目录",
+        "project.detect.rule.nearest_package" => "{root} 内最近的包“{marker}”（经由 {base} 找到）",
+        "project.detect.rule.cwd" => "没有规则命中，使用该目录本身",
+        "command.project.move.about" => {
+            "在移动或重命名后，将项目记录（及其权限）重新绑定到新路径。"
+        }
+        "args.project_move.from" => "原项目路径或 ID（路径可以已不存在）。",
+        "args.project_move.to" => "新的项目路径。",
+        "args.project_move.force" => "替换新路径上已存在的项目记录。",
+        "project.move.done" => "已将项目 {from} 移动到 {to}（ID：{id}）",
+        "project.move.destination_exists" => "{path} 已存在项目记录；使用 --force 进行替换。",
+        "project.move.same_path" => "项目已登记在 {path}。",
+        "project.move.not_directory" => "新的项目路径 {path} 不是目录。",
+        "command.project.approve.about" => "批准首次使用后处于待审批状态的项目。",
+        "command.project.explain.about" => {
+            "显示路径的有效权限，以及每项权限来自哪条规则或哪个项目。"
+        }
+        "args.project_explain.path" => "项目路径（默认当前目录）。",
+        "project.explain.project" => "项目：{path}",
+        "project.explain.record" => "记录：{id}（{status}）",
+        "project.explain.no_record" => "记录：无",
+        "project.explain.layers" => "层级（从通用到具体）：",
+        "project.explain.none" => "  （无）",
+        "project.explain.servers" => "服务器：",
+        "project.explain.server_line" => "  {server}：{verdict} — {reason}",
+        "project.explain.allowed" => "允许",
+        "project.explain.denied" => "拒绝",
+        "project.explain.permission.all" => "全部工具",
+        "project.explain.permission.allow" => "仅 {tools}",
+        "project.explain.permission.deny" => "除 {tools} 外的全部工具",
+        "project.explain.reason.tool_permission" => "来自 {source} 的工具权限",
+        "project.explain.reason.server_list" => "来自 {source} 的服务器列表",
+        "project.explain.reason.pending" => "项目待审批",
+        "project.explain.reason.unrestricted" => "未配置任何权限，允许所有服务器",
+        "project.explain.reason.grant" => "来自{source}的临时授权，有效期至 {expires}",
+        "args.project.grant_for" => "临时授权的时长（如 30m、2h、1d），不指定则永久授权。",
+        "project.grant.done" => "已授予 {path} 使用 {servers}，有效期至 {expires}。",
+        "project.grant.tools_done" => "已授予 {server}：{tools}，有效期至 {expires}。",
+        "command.project.grants.about" => "列出项目有效和已过期的临时授权。",
+        "args.project_grants.prune" => "从项目记录中删除已过期的授权。",
+        "project.grants.header" => "{path} 的临时授权：",
+        "project.grants.none" => "{path} 没有临时授权。",
+        "project.grants.all_tools" => "全部工具",
+        "project.grants.active" => "  {server}（{scope}）：有效期至 {expires}",
+        "project.grants.expired" => "  {server}（{scope}）：已于 {expires} 过期",
+        "project.grants.pruned" => "已删除 {count} 条过期授权。",
+        "command.project.set_mode.about" => {
+            "为项目开启或关闭注解开关（read-only、no-destructive、no-open-world）。"
+        }
+        "args.project_set_mode.switch" => "要修改的开关。",
+        "args.project_set_mode.state" => "on、off，或 inherit 表示沿用项目规则和父项目。",
+        "project.mode.state.on" => "开启",
+        "project.mode.state.off" => "关闭",
+        "project.mode.state.inherit" => "继承",
+        "project.mode.updated" => "{path} 的 {switch} 已设为{state}",
+        "command.project.set_tool_hints.about" => {
+            "覆盖服务器为某个工具报告的注解提示（不带参数则清除覆盖）。"
+        }
+        "args.project_tool_hints.read_only" => "将工具视为只读（true/false）。",
+        "args.project_tool_hints.destructive" => "将工具视为具有破坏性（true/false）。",
+        "args.project_tool_hints.open_world" => "将工具视为会访问外部系统（true/false）。",
+        "project.tool_hints.set" => "已覆盖工具“{tool}”的注解提示",
+        "project.tool_hints.cleared" => "已清除工具“{tool}”的注解提示覆盖",
+        "command.project.require_approval.about" => "要求项目的工具调用在执行前经人工批准。",
+        "args.project_require_approval.state" => "on、off，或 inherit 表示沿用项目规则和父项目。",
+        "args.project_require_approval.tool" => "仅修改此工具的设置。",
+        "project.approval.all_tools" => "全部工具",
+        "project.approval.updated" => "{path} 中{scope}的审批要求已设为{state}",
+        "project.explain.approvals" => "需要审批：",
+        "command.project.tool_search.about" => {
+            "仅提供 search_tools/describe_tool/invoke_tool，而不列出全部工具。"
+        }
+        "args.project_tool_search.state" => "on、off，或 inherit 表示沿用项目规则和父项目。",
+        "project.tool_search.updated" => "{path} 的工具搜索模式已设为{state}",
+        "project.explain.tool_search" => "工具搜索：{state}（{source}）",
+        "command.project.set_arg_rule.about" => {
+            "约束工具调用的某个参数（不带约束参数则删除该规则）。"
+        }
+        "args.project_arg_rule.pointer" => "指向参数的 JSON 指针，例如 /path。",
+        "args.project_arg_rule.required" => "拒绝缺少该参数的调用。",
+        "args.project_arg_rule.within" => {
+            "路径必须位于此目录之下；${PROJECT_ROOT} 表示项目根目录。"
+        }
+        "args.project_arg_rule.allow" => "参数值必须匹配的正则表达式（可重复）。",
+        "args.project_arg_rule.deny" => "参数值不得匹配的正则表达式（可重复）。",
+        "args.project_arg_rule.min" => "允许的最小数值。",
+        "args.project_arg_rule.max" => "允许的最大数值。",
+        "project.arg_rule.invalid_pointer" => "“{pointer}”不是有效的 JSON 指针（必须以“/”开头）。",
+        "project.arg_rule.invalid_regex" => "无效的正则表达式“{pattern}”：{error}",
+        "project.arg_rule.set" => "已保存 {tool} 的参数规则 {pointer}",
+        "project.arg_rule.cleared" => "已删除 {tool} 的参数规则 {pointer}",
+        "project.explain.arg_rules" => "参数规则：",
+        "project.explain.arg_rule_line" => "  {tool} {pointer}：{rule}（{source}）",
+        "command.project.set_preset.about" => {
+            "固定工具的某个参数并对智能体隐藏（省略 VALUE 则删除）。"
+        }
+        "args.project_preset.argument" => "顶层参数名，例如 repo。",
+        "args.project_preset.value" => "注入的值；若为合法 JSON 则按 JSON 解析，否则视为字符串。",
+        "args.project_preset.secret" => "在工具日志和命令行输出中隐藏该值。",
+        "project.preset.set" => "已固定 {tool} 的参数 {argument}",
+        "project.preset.cleared" => "已取消固定 {tool} 的参数 {argument}",
+        "project.explain.presets" => "固定参数：",
+        "project.explain.preset_line" => "  {tool} {argument} = {value}（{source}）",
+        "command.project.set_tool_alias.about" => {
+            "为此项目重命名工具、修改标题或调整输入结构（不带 ALIAS 和选项则重置）。"
+        }
+        "args.project_tool_alias.alias" => "智能体看到的名称，替代上游名称。",
+        "args.project_tool_alias.title" => "智能体看到的标题，替代上游标题。",
+        "args.project_tool_alias.schema_patch" => {
+            "输入结构的 JSON Merge Patch，或用 @FILE 从文件读取。"
+        }
+        "args.project_tool_alias.hide" => "对智能体隐藏的参数（可重复）。",
+        "project.tool_alias.invalid" => {
+            "“{alias}”不是有效的工具名（只能包含 1-64 个字母、数字、“_”或“-”）。"
+        }
+        "project.tool_alias.invalid_patch" => "无效的结构补丁：{error}",
+        "project.tool_alias.taken" => "“{alias}”已被工具 {tool} 使用",
+        "project.tool_alias.set" => "已保存 {tool} 的覆盖设置",
+        "project.tool_alias.cleared" => "已删除 {tool} 的覆盖设置",
+        "project.explain.overrides" => "工具覆盖：",
+        "project.explain.override_line" => "  {tool}：{overrides}（{source}）",
+        "command.approvals.about" => "审阅等待批准的工具调用（需要守护进程运行）。",
+        "command.approvals.list.about" => "列出等待批准的工具调用。",
+        "command.approvals.approve.about" => "允许等待中的工具调用执行。",
+        "command.approvals.deny.about" => "拒绝等待中的工具调用。",
+        "args.approvals.id" => "`mcp-center approvals list` 显示的编号。",
+        "args.approvals.reason" => "返回给智能体的拒绝理由。",
+        "approvals.none" => "没有等待批准的工具调用。",
+        "approvals.header" => "等待批准的工具调用：",
+        "approvals.line" => "  #{id} {tool}（{server}）来自 {project}，{expires} 过期",
+        "approvals.approved" => "已批准 #{id}（{tool}）。",
+        "approvals.denied" => "已拒绝 #{id}（{tool}）。",
+        "command.cache.about" => "查看或清除缓存的工具结果。",
+        "command.cache.stats.about" => "按 MCP 服务器显示缓存的工具结果。",
+        "command.cache.clear.about" => "删除缓存的工具结果。",
+        "args.cache.server" => "只处理此服务器（ID）的缓存。",
+        "cache.empty" => "没有缓存的工具结果。",
+        "cache.header.server" => "MCP 服务器",
+        "cache.header.entries" => "条目",
+        "cache.header.size" => "大小",
+        "cache.header.newest" => "最近写入",
+        "cache.cleared" => "已删除 {count} 条缓存结果，共 {size}。",
+        "project.explain.switches" => "注解开关：",
+        "project.explain.switch_line" => "  {switch}：{state}（{source}）",
+        "project.explain.reason.annotation" => "被 {switch} 模式隐藏（{source}）",
+        "project.effective.switch_line" => "  {switch}（{source}）隐藏了 {count} 个工具",
+        "project.explain.descriptions" => "工具描述：",
+        "project.explain.description_line" => "  {tool} — {source}",
+        "project.explain.source.rule" => "规则“{pattern}”",
+        "project.explain.source.parent" => "父项目 {path}",
+        "project.explain.source.project" => "项目记录",
+        "project.explain.source.repo_file" => "仓库配置 {path}",
+        "command.project.effective.about" => {
+            "模拟智能体在项目中实际看到的工具列表（需要守护进程运行）。"
+        }
+        "args.project_effective.path" => "项目路径（默认为当前目录）。",
+        "project.effective.summary" => "智能体可见 {allowed}/{total} 个工具：",
+        "project.effective.tool_line" => "  {tool}（{server}）：{verdict} — {reason}",
+        "project.effective.description" => "      {description}",
+        "project.effective.custom_description" => "      {description} [自定义，来自{source}]",
+        "project.explain.reason.repo_config" => "被仓库配置 {path} 排除",
+        "project.explain.repo_rule" => "    仓库配置进一步限制（允许：{allow}；禁止：{deny}）",
+        "project.explain.warnings" => "仓库配置警告：",
+        "project.explain.warning.not_installed" => {
+            "  .mcp-center.toml 引用了未安装的服务器“{server}”"
+        }
+        "project.explain.warning.not_allowed" => {
+            "  .mcp-center.toml 需要服务器“{server}”，但本项目未允许使用"
+        }
+        "project.explain.warning.invalid" => "  已忽略 .mcp-center.toml：{error}",
+        "args.project_approve.servers" => "批准时授予的服务器 ID（默认沿用项目现有权限）。",
+        "args.project_approve.all" => "批准未授予任何服务器的项目，允许其使用所有服务器。",
+        "project.list.pending" => "[待审批]",
+        "project.list.pending_hint" => {
+            "待审批项目只拥有默认权限；运行 'mcp-center project approve <PATH>' 进行批准。"
+        }
+        "project.approve.done" => "已批准项目 {path}",
+        "project.approve.already" => "项目 {path} 已经批准",
+        "project.approve.unrestricted" => "未显式授予任何服务器，该项目现在可以使用所有服务器。",
         "logs.tail.following" => {
             "正在跟踪 MCP 服务器“{server}”的日志（文件：{file}），按 Ctrl+C 退出。"
         }
@@ -1006,6 +2091,9 @@ fn zh_hant_text(key: &str) -> Option<&'static str> {
         "core.parse_toml_failed" => "解析 {path} 的 TOML 伺服器設定失敗：{error}",
         "core.serialise_toml_failed" => "序列化伺服器定義到 TOML 失敗：{error}",
         "core.remove_file_failed" => "刪除 {path} 失敗：{error}",
+        "core.settings_parse_failed" => "解析設定檔 {path} 失敗：{error}",
+        "core.settings_serialise_failed" => "序列化工作區設定失敗：{error}",
+        "core.settings_write_failed" => "寫入設定檔 {path} 失敗：{error}",
         "core.home_dir_unknown" => "無法判定使用者家目錄（MCP_CENTER_ROOT）",
         "logs.list.no_files" => "尚未找到任何日誌檔案。",
         "logs.list.no_files_for" => "MCP 伺服器「{server}」沒有日誌檔案。",
@@ -1181,6 +2269,11 @@ fn ja_text(key: &str) -> Option<&'static str> {
             "サーバー定義を TOML にシリアライズできませんでした: {error}"
         }
         "core.remove_file_failed" => "{path} の削除に失敗しました: {error}",
+        "core.settings_parse_failed" => "設定ファイル {path} の解析に失敗しました: {error}",
+        "core.settings_serialise_failed" => {
+            "ワークスペース設定のシリアライズに失敗しました: {error}"
+        }
+        "core.settings_write_failed" => "設定ファイル {path} の書き込みに失敗しました: {error}",
         "core.home_dir_unknown" => {
             "ユーザーのホームディレクトリ（MCP_CENTER_ROOT）を判別できません"
         }
@@ -0,0 +1,106 @@
+//! Result caching for the tools of one server.
+//!
+//! ```toml
+//! [mcp_server.cache]
+//! ttl_secs = 3600
+//! max_size_mb = 32
+//! tools = ["get_library_docs"]
+//! ```
+//!
+//! Results are keyed on the tool name and the canonical form of its arguments and kept under
+//! `state/cache/<server id>/` (see `daemon::cache`). Only tools annotated `readOnlyHint`
+//! are cached unless `require_read_only` is turned off.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::CoreError;
+
+pub const DEFAULT_TTL_SECS: u64 = 300;
+pub const DEFAULT_MAX_SIZE_MB: u64 = 16;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CacheConfig {
+    /// Seconds a cached result is served.
+    #[serde(default = "default_ttl_secs")]
+    pub ttl_secs: u64,
+    /// Size of the server's cache; the oldest results go first.
+    #[serde(default = "default_max_size_mb")]
+    pub max_size_mb: u64,
+    /// Tools to cache (empty = every eligible tool of the server).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<String>,
+    /// Only cache tools annotated `readOnlyHint`.
+    #[serde(default = "default_require_read_only")]
+    pub require_read_only: bool,
+}
+
+fn default_ttl_secs() -> u64 {
+    DEFAULT_TTL_SECS
+}
+
+fn default_max_size_mb() -> u64 {
+    DEFAULT_MAX_SIZE_MB
+}
+
+fn default_require_read_only() -> bool {
+    true
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl_secs: DEFAULT_TTL_SECS,
+            max_size_mb: DEFAULT_MAX_SIZE_MB,
+            tools: Vec::new(),
+            require_read_only: true,
+        }
+    }
+}
+
+impl CacheConfig {
+    pub fn max_bytes(&self) -> u64 {
+        self.max_size_mb.saturating_mul(1024 * 1024)
+    }
+
+    /// Whether results of `tool_name` may be cached; `read_only` is its `readOnlyHint`.
+    pub fn caches(&self, tool_name: &str, read_only: bool) -> bool {
+        (read_only || !self.require_read_only)
+            && (self.tools.is_empty() || self.tools.iter().any(|tool| tool == tool_name))
+    }
+
+    pub fn validate(&self, id: Option<String>) -> Result<(), CoreError> {
+        let invalid = |reason: &str| CoreError::CacheConfigInvalid {
+            id: id.clone(),
+            reason: reason.to_string(),
+        };
+        if self.ttl_secs == 0 {
+            return Err(invalid("ttl_secs must be positive"));
+        }
+        if self.max_size_mb == 0 {
+            return Err(invalid("max_size_mb must be positive"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caches_read_only_tools_by_default() {
+        let config: CacheConfig = toml_edit::de::from_str("").unwrap();
+        assert_eq!(config, CacheConfig::default());
+        assert!(config.caches("get_docs", true));
+        assert!(!config.caches("create_issue", false));
+
+        let config = CacheConfig {
+            tools: vec!["fetch".into()],
+            require_read_only: false,
+            ..CacheConfig::default()
+        };
+        assert!(config.caches("fetch", false));
+        assert!(!config.caches("get_docs", true));
+        assert!(CacheConfig { ttl_secs: 0, ..config }.validate(None).is_err());
+    }
+}
@@ -0,0 +1,144 @@
+//! Tool-call hooks: external executables run before and after a call reaches its server.
+//!
+//! ```toml
+//! [[mcp_server.hooks]]
+//! name = "redact-emails"
+//! command = "/usr/local/bin/redact-emails"
+//! stages = ["post"]
+//! tools = ["search_issues"]
+//! ```
+//!
+//! Hooks are set on a server config or on a project (its record or a `[[project_rules]]`
+//! entry). The server's hooks run first, then the project's, each in the order listed. A
+//! hook receives one JSON request on stdin and answers with one JSON object on stdout (see
+//! `daemon::hooks`); an empty answer leaves the call as it is.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::CoreError;
+
+/// Seconds a hook may run unless it sets `timeout_secs`.
+pub const DEFAULT_HOOK_TIMEOUT_SECS: u64 = 10;
+
+/// When a hook runs.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HookStage {
+    /// Before the call, with its arguments.
+    Pre,
+    /// After the server answered, with its result.
+    Post,
+}
+
+impl HookStage {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HookStage::Pre => "pre",
+            HookStage::Post => "post",
+        }
+    }
+}
+
+/// What happens to the call when a hook fails, times out or answers garbage.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HookFailure {
+    /// Answer the call with an error.
+    #[default]
+    Deny,
+    /// Carry on as if the hook was not there.
+    Ignore,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ToolHook {
+    /// Label in logs and errors (default: the command).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Stages the hook runs in (default: both).
+    #[serde(default = "all_stages")]
+    pub stages: Vec<HookStage>,
+    /// Upstream tool names the hook applies to (empty = every tool).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub on_failure: HookFailure,
+}
+
+fn all_stages() -> Vec<HookStage> {
+    vec![HookStage::Pre, HookStage::Post]
+}
+
+impl ToolHook {
+    pub fn label(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.command)
+    }
+
+    pub fn timeout_secs(&self) -> u64 {
+        self.timeout_secs.unwrap_or(DEFAULT_HOOK_TIMEOUT_SECS)
+    }
+
+    /// Whether the hook runs for `tool_name` in `stage`.
+    pub fn applies_to(&self, tool_name: &str, stage: HookStage) -> bool {
+        self.stages.contains(&stage)
+            && (self.tools.is_empty() || self.tools.iter().any(|tool| tool == tool_name))
+    }
+
+    fn validate(&self, id: Option<String>) -> Result<(), CoreError> {
+        let invalid = |reason: &str| CoreError::ToolHookInvalid {
+            id: id.clone(),
+            hook: self.label().to_string(),
+            reason: reason.to_string(),
+        };
+        if self.command.trim().is_empty() {
+            return Err(invalid("command cannot be empty"));
+        }
+        if self.stages.is_empty() {
+            return Err(invalid("stages cannot be empty"));
+        }
+        if self.timeout_secs == Some(0) {
+            return Err(invalid("timeout_secs must be positive"));
+        }
+        Ok(())
+    }
+}
+
+/// Check the hooks configured on server `id`.
+pub fn validate_hooks(id: Option<String>, hooks: &[ToolHook]) -> Result<(), CoreError> {
+    hooks.iter().try_for_each(|hook| hook.validate(id.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hooks_default_to_both_stages_and_every_tool() {
+        let hook: ToolHook = toml_edit::de::from_str("command = \"audit\"").unwrap();
+        assert_eq!(hook.label(), "audit");
+        assert_eq!(hook.on_failure, HookFailure::Deny);
+        assert!(hook.applies_to("anything", HookStage::Pre));
+        assert!(hook.applies_to("anything", HookStage::Post));
+
+        let hook: ToolHook = toml_edit::de::from_str(
+            "name = \"redact\"\ncommand = \"redact\"\nstages = [\"post\"]\ntools = [\"search\"]",
+        )
+        .unwrap();
+        assert!(hook.applies_to("search", HookStage::Post));
+        assert!(!hook.applies_to("search", HookStage::Pre));
+        assert!(!hook.applies_to("other", HookStage::Post));
+
+        assert!(validate_hooks(None, std::slice::from_ref(&hook)).is_ok());
+        let empty = ToolHook { command: " ".into(), ..hook };
+        assert!(validate_hooks(None, &[empty]).is_err());
+    }
+}
@@ -0,0 +1,116 @@
+//! Size limits on tool results, set on a server or a project.
+//!
+//! ```toml
+//! [mcp_server.output_limits]
+//! max_text_chars = 40000
+//! max_items = 20
+//! max_image_bytes = 1048576
+//! spill = true
+//! ```
+//!
+//! When a server and the calling project both set a limit the smaller one applies. Results
+//! over a limit are cut down with a marker saying what was left out (see `daemon::limits`);
+//! with `spill`, the full result is kept as a paged resource the agent can read.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::CoreError;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct OutputLimits {
+    /// Characters of text content, and the size of structured content.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_text_chars: Option<usize>,
+    /// Number of content items.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_items: Option<usize>,
+    /// Bytes of (base64) image data across the result.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_image_bytes: Option<usize>,
+    /// Keep the full result of a truncated call as a paged resource.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spill: Option<bool>,
+}
+
+impl OutputLimits {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether any result could be cut down.
+    pub fn limits_anything(&self) -> bool {
+        self.max_text_chars.is_some() || self.max_items.is_some() || self.max_image_bytes.is_some()
+    }
+
+    pub fn spills(&self) -> bool {
+        self.spill.unwrap_or(false)
+    }
+
+    /// The stricter of both limits, field by field; spilling if either asks for it.
+    pub fn tightest(self, other: Self) -> Self {
+        fn min(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+            match (a, b) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            }
+        }
+        Self {
+            max_text_chars: min(self.max_text_chars, other.max_text_chars),
+            max_items: min(self.max_items, other.max_items),
+            max_image_bytes: min(self.max_image_bytes, other.max_image_bytes),
+            spill: match (self.spill, other.spill) {
+                (None, None) => None,
+                (a, b) => Some(a.unwrap_or(false) || b.unwrap_or(false)),
+            },
+        }
+    }
+
+    pub fn validate(&self, id: Option<String>) -> Result<(), CoreError> {
+        let zero = [
+            ("max_text_chars", self.max_text_chars),
+            ("max_items", self.max_items),
+            ("max_image_bytes", self.max_image_bytes),
+        ]
+        .into_iter()
+        .find(|(_, value)| *value == Some(0));
+        match zero {
+            Some((name, _)) => Err(CoreError::OutputLimitsInvalid {
+                id,
+                reason: format!("{name} must be positive"),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tightest_takes_the_smaller_limit_of_each_kind() {
+        let server = OutputLimits {
+            max_text_chars: Some(10_000),
+            max_items: Some(5),
+            ..OutputLimits::default()
+        };
+        let project = OutputLimits {
+            max_text_chars: Some(2_000),
+            max_image_bytes: Some(1024),
+            spill: Some(true),
+            ..OutputLimits::default()
+        };
+        assert_eq!(
+            server.tightest(project),
+            OutputLimits {
+                max_text_chars: Some(2_000),
+                max_items: Some(5),
+                max_image_bytes: Some(1024),
+                spill: Some(true),
+            }
+        );
+        assert_eq!(server.tightest(OutputLimits::default()), server);
+        assert!(OutputLimits { max_items: Some(0), ..server }.validate(None).is_err());
+    }
+}
@@ -1,6 +1,12 @@
 //! Configuration handling utilities.
 
+pub mod cache;
+pub mod hooks;
 pub mod id_generator;
+pub mod limits;
+pub mod script;
 pub mod server;
+pub mod settings;
 
 pub use server::{ServerConfig, ServerDefinition, ServerProtocol};
+pub use settings::WorkspaceSettings;
@@ -0,0 +1,254 @@
+//! Tools of `script` servers, declared in the server config instead of served by a process.
+//!
+//! ```toml
+//! [mcp_server]
+//! name = "ops"
+//! protocol = "script"
+//! enabled = true
+//!
+//! [[mcp_server.tools]]
+//! name = "disk_usage"
+//! description = "Disk usage of a directory"
+//! input_schema = { type = "object", properties = { path = { type = "string" } } }
+//! command = "du"
+//! args = ["-sh", "{{path}}"]
+//!
+//! [[mcp_server.tools]]
+//! name = "triage"
+//! steps = [{ tool = "search_issues", arguments = { query = "{{query}}" } }]
+//! ```
+//!
+//! A tool either runs `command` — directly, never through a shell — or calls `steps`, other
+//! aggregated tools, one after another. `{{argument}}` placeholders in `args` and step
+//! arguments are replaced with the call's arguments.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use crate::{error::CoreError, project::schema::is_valid_tool_name};
+
+/// Seconds a command, or all steps of a composite, may run unless the tool sets
+/// `timeout_secs`.
+pub const DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScriptTool {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// JSON schema of the arguments (default: any object).
+    #[serde(default = "default_input_schema")]
+    pub input_schema: Value,
+    /// Advertise the tool as read-only and non-destructive.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub read_only: bool,
+    /// Executable run for each call.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Arguments of `command`; an argument that is only a placeholder is dropped when the
+    /// call leaves that argument out.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// Limit for the command, or for all steps of a composite tool together.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// Tool calls made in order instead of running a command.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<CompositeStep>,
+}
+
+/// One call of a composite tool.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CompositeStep {
+    /// Name of the tool as the session sees it.
+    pub tool: String,
+    /// Arguments, with placeholders filled from the composite call.
+    #[serde(default = "empty_object")]
+    pub arguments: Value,
+}
+
+fn default_input_schema() -> Value {
+    json!({ "type": "object" })
+}
+
+fn empty_object() -> Value {
+    Value::Object(Map::new())
+}
+
+impl ScriptTool {
+    pub fn is_composite(&self) -> bool {
+        !self.steps.is_empty()
+    }
+
+    pub fn timeout_secs(&self) -> u64 {
+        self.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS)
+    }
+
+    fn validate(&self, id: Option<String>) -> Result<(), CoreError> {
+        let invalid = |reason: &str| CoreError::ScriptToolInvalid {
+            id: id.clone(),
+            tool: self.name.clone(),
+            reason: reason.to_string(),
+        };
+        if !is_valid_tool_name(&self.name) {
+            return Err(invalid("names use 1-64 letters, digits, '_' or '-'"));
+        }
+        if !self.input_schema.is_object() {
+            return Err(invalid("input_schema must be an object"));
+        }
+        match (self.command.as_deref().map(str::trim), self.steps.is_empty()) {
+            (Some(""), _) => Err(invalid("command cannot be empty")),
+            (Some(_), false) => Err(invalid("set either command or steps, not both")),
+            (None, true) => Err(invalid("set command or steps")),
+            (None, false) if !self.args.is_empty() => Err(invalid("args only apply to commands")),
+            _ if self.timeout_secs == Some(0) => Err(invalid("timeout_secs must be positive")),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Check the tools of a `script` server: at least one, valid and uniquely named.
+pub fn validate_tools(id: Option<String>, tools: &[ScriptTool]) -> Result<(), CoreError> {
+    if tools.is_empty() {
+        return Err(CoreError::ScriptToolInvalid {
+            id,
+            tool: String::new(),
+            reason: "script servers need at least one tool".to_string(),
+        });
+    }
+    let mut names = HashSet::new();
+    for tool in tools {
+        tool.validate(id.clone())?;
+        if !names.insert(tool.name.as_str()) {
+            return Err(CoreError::ScriptToolInvalid {
+                id,
+                tool: tool.name.clone(),
+                reason: "defined more than once".to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// `template` with every `{{name}}` replaced by the argument's text; placeholders of
+/// missing arguments become empty.
+pub fn render_text(template: &str, arguments: &Map<String, Value>) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}").map(|end| start + end) else {
+            break;
+        };
+        rendered.push_str(&rest[..start]);
+        if let Some(value) = arguments.get(rest[start + 2..end].trim()) {
+            rendered.push_str(&value_text(value));
+        }
+        rest = &rest[end + 2..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// `template` with placeholders filled in. A string that is a single placeholder takes the
+/// argument's JSON value as is, or disappears from its object or array when the argument
+/// is missing; `None` if `template` itself disappears.
+pub fn render_value(template: &Value, arguments: &Map<String, Value>) -> Option<Value> {
+    match template {
+        Value::String(text) => match sole_placeholder(text) {
+            Some(name) => arguments.get(name).cloned(),
+            None => Some(Value::String(render_text(text, arguments))),
+        },
+        Value::Array(items) => Some(Value::Array(
+            items.iter().filter_map(|item| render_value(item, arguments)).collect(),
+        )),
+        Value::Object(fields) => Some(Value::Object(
+            fields
+                .iter()
+                .filter_map(|(key, value)| Some((key.clone(), render_value(value, arguments)?)))
+                .collect(),
+        )),
+        other => Some(other.clone()),
+    }
+}
+
+/// Arguments of `command`, rendered the same way.
+pub fn render_args(args: &[String], arguments: &Map<String, Value>) -> Vec<String> {
+    args.iter()
+        .filter_map(|arg| match sole_placeholder(arg) {
+            Some(name) => arguments.get(name).map(value_text),
+            None => Some(render_text(arg, arguments)),
+        })
+        .collect()
+}
+
+fn sole_placeholder(text: &str) -> Option<&str> {
+    let name = text.trim().strip_prefix("{{")?.strip_suffix("}}")?.trim();
+    (!name.contains("{{") && !name.contains("}}")).then_some(name)
+}
+
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command_tool(name: &str) -> ScriptTool {
+        ScriptTool {
+            name: name.to_string(),
+            title: None,
+            description: None,
+            input_schema: default_input_schema(),
+            read_only: false,
+            command: Some("echo".into()),
+            args: Vec::new(),
+            timeout_secs: None,
+            steps: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn renders_placeholders_by_position() {
+        let arguments = json!({ "path": "/tmp", "depth": 2, "query": "bug" });
+        let arguments = arguments.as_object().unwrap();
+        assert_eq!(
+            render_args(
+                &["-d{{depth}}".into(), "{{ path }}".into(), "{{missing}}".into()],
+                arguments
+            ),
+            ["-d2", "/tmp"]
+        );
+        assert_eq!(
+            render_value(
+                &json!({ "q": "is:{{query}}", "n": "{{depth}}", "skip": "{{missing}}" }),
+                arguments
+            ),
+            Some(json!({ "q": "is:bug", "n": 2 }))
+        );
+    }
+
+    #[test]
+    fn validates_tool_definitions() {
+        assert!(validate_tools(None, &[command_tool("a")]).is_ok());
+        assert!(validate_tools(None, &[]).is_err());
+        assert!(validate_tools(None, &[command_tool("a"), command_tool("a")]).is_err());
+        assert!(validate_tools(None, &[command_tool("bad name")]).is_err());
+
+        let mut both = command_tool("both");
+        both.steps = vec![CompositeStep { tool: "x".into(), arguments: empty_object() }];
+        assert!(validate_tools(None, &[both.clone()]).is_err());
+        both.command = None;
+        both.timeout_secs = Some(5);
+        assert!(validate_tools(None, &[both.clone()]).is_ok());
+        both.args = vec!["-v".into()];
+        assert!(validate_tools(None, &[both]).is_err());
+    }
+}
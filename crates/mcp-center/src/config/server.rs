@@ -9,7 +9,13 @@ use serde::{Deserialize, Serialize};
 use specta::Type;
 use url::Url;
 
-use super::id_generator::generate_id;
+use super::{
+    cache::CacheConfig,
+    hooks::{self, ToolHook},
+    id_generator::generate_id,
+    limits::OutputLimits,
+    script::{self, ScriptTool},
+};
 use crate::error::CoreError;
 
 /// Supported MCP server protocols.
@@ -24,6 +30,8 @@ pub enum ServerProtocol {
     Sse,
     #[serde(alias = "http")]
     Http,
+    /// Tools declared in the config and run by the daemon itself.
+    Script,
     #[serde(other)]
     Unknown,
 }
@@ -58,6 +66,50 @@ pub struct ServerDefinition {
     /// Whether the server is currently enabled.
     #[serde(default)]
     pub enabled: bool,
+    /// Tools of a `script` server.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<ScriptTool>,
+    /// Hooks run around every call to this server's tools.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hooks: Vec<ToolHook>,
+    /// Result caching for this server's tools (off when absent).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache: Option<CacheConfig>,
+    /// Size limits on this server's tool results (none when absent).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_limits: Option<OutputLimits>,
+    /// Check tool arguments (and structured results) against the tools' JSON Schemas.
+    #[serde(default = "default_validate_schemas", skip_serializing_if = "is_true")]
+    pub validate_schemas: bool,
+}
+
+fn default_validate_schemas() -> bool {
+    true
+}
+
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
+impl Default for ServerDefinition {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            name: None,
+            protocol: ServerProtocol::default(),
+            command: String::new(),
+            args: Vec::new(),
+            env: BTreeMap::new(),
+            endpoint: None,
+            headers: BTreeMap::new(),
+            enabled: false,
+            tools: Vec::new(),
+            hooks: Vec::new(),
+            cache: None,
+            output_limits: None,
+            validate_schemas: default_validate_schemas(),
+        }
+    }
 }
 
 impl ServerDefinition {
@@ -81,6 +133,17 @@ impl ServerDefinition {
             }
             .into());
         }
+        if matches!(self.protocol, ServerProtocol::Script) {
+            let id = (!self.id.trim().is_empty()).then(|| self.id.clone());
+            script::validate_tools(id, &self.tools)?;
+        }
+        hooks::validate_hooks((!self.id.trim().is_empty()).then(|| self.id.clone()), &self.hooks)?;
+        if let Some(cache) = &self.cache {
+            cache.validate((!self.id.trim().is_empty()).then(|| self.id.clone()))?;
+        }
+        if let Some(limits) = &self.output_limits {
+            limits.validate((!self.id.trim().is_empty()).then(|| self.id.clone()))?;
+        }
         if matches!(self.protocol, ServerProtocol::Sse | ServerProtocol::Http) {
             let id = (!self.id.trim().is_empty()).then(|| self.id.clone());
             let endpoint = self
@@ -290,13 +353,10 @@ endpoint = "not a url"
             id: "deepwiki".into(),
             name: Some("DeepWiki".into()),
             protocol: ServerProtocol::Sse,
-            command: String::new(),
-            args: Vec::new(),
-            env: BTreeMap::new(),
             endpoint: Some("https://mcp.deepwiki.com/sse".into()),
-            headers: BTreeMap::new(),
-            enabled: false,
+            ..Default::default()
         };
+        assert!(definition.validate_schemas, "schema checks are on unless turned off");
         assert!(ServerConfig::new(definition).is_ok());
     }
 }
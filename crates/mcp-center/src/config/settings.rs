@@ -0,0 +1,477 @@
+//! Workspace-wide settings stored in `config/settings.toml`.
+
+use std::{collections::BTreeMap, fs, path::Path, time::Duration};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use toml_edit::{DocumentMut, Item, Table, TableLike};
+use tracing::warn;
+
+use crate::{
+    Layout,
+    error::CoreError,
+    project::{ProjectRecord, ProjectStatus, ToolPermission, policy::ProjectRule},
+};
+
+/// Top-level settings document. Every section falls back to defaults when omitted.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct WorkspaceSettings {
+    pub project_detection: ProjectDetectionSettings,
+    /// Named session scopes selectable with `mcp-center connect --profile <name>`.
+    pub profiles: BTreeMap<String, SessionProfile>,
+    pub multi_root: MultiRootSettings,
+    /// Permissions given to projects the daemon sees for the first time.
+    pub new_projects: NewProjectSettings,
+    /// Named permission sets usable as `new_projects.template`.
+    pub permission_templates: BTreeMap<String, PermissionTemplate>,
+    /// Glob rules applying permissions to every matching project path.
+    pub project_rules: Vec<ProjectRule>,
+    pub approvals: ApprovalSettings,
+    pub management: ManagementSettings,
+}
+
+impl WorkspaceSettings {
+    /// Load settings from the workspace, returning defaults when the file does not exist.
+    pub fn load(layout: &Layout) -> Result<Self> {
+        Self::from_file(&layout.settings_path())
+    }
+
+    /// Load settings from an explicit path, returning defaults when the file does not exist.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)
+            .map_err(|source| CoreError::ReadConfig { path: path.to_path_buf(), source })?;
+        let settings = toml_edit::de::from_str(&content)
+            .map_err(|source| CoreError::SettingsParse { path: path.to_path_buf(), source })?;
+        Ok(settings)
+    }
+
+    /// Put a freshly detected project into the pending state with the configured
+    /// default permissions. `server_ids` lists every configured server.
+    pub fn seed_new_project(&self, record: &mut ProjectRecord, server_ids: Vec<String>) {
+        record.status = ProjectStatus::Pending;
+        record.allowed_server_ids.clear();
+        record.allowed_server_tools.clear();
+        match self.new_projects.policy {
+            NewProjectPolicy::AllowAll => record.allowed_server_ids = server_ids,
+            NewProjectPolicy::DenyAll => {}
+            NewProjectPolicy::Template => {
+                let name = self.new_projects.template.as_deref().unwrap_or_default();
+                match self.permission_templates.get(name) {
+                    Some(template) => {
+                        record.allowed_server_ids = template.servers.clone();
+                        record.allowed_server_tools = template.tools.clone().into_iter().collect();
+                    }
+                    None => warn!(
+                        template = name,
+                        "unknown permission template for new projects, denying all servers"
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Persist settings to the workspace. An existing file keeps its comments and layout:
+    /// only the values that differ from what it held are rewritten.
+    pub fn store(&self, layout: &Layout) -> Result<()> {
+        let path = layout.settings_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|source| CoreError::CreateDirectory {
+                path: parent.to_path_buf(),
+                source,
+            })?;
+        }
+        let updated = toml_edit::ser::to_document(self)
+            .map_err(|source| CoreError::SettingsSerialise { source })?;
+        let existing = fs::read_to_string(&path).ok().and_then(|content| {
+            let document = content.parse::<DocumentMut>().ok()?;
+            let previous = toml_edit::de::from_str::<Self>(&content).ok()?;
+            Some((document, previous))
+        });
+        let content = match existing {
+            Some((mut document, previous)) => {
+                let previous = toml_edit::ser::to_document(&previous)
+                    .map_err(|source| CoreError::SettingsSerialise { source })?;
+                apply_changes(document.as_table_mut(), previous.as_table(), updated.as_table());
+                document.to_string()
+            }
+            None => toml_edit::ser::to_string_pretty(self)
+                .map_err(|source| CoreError::SettingsSerialise { source })?,
+        };
+        fs::write(&path, content)
+            .map_err(|source| CoreError::SettingsWrite { path: path.clone(), source })?;
+        Ok(())
+    }
+}
+
+/// Bring `target` from `previous` to `updated`, touching only the keys that changed and
+/// keeping the comments around the values it replaces.
+fn apply_changes(target: &mut dyn TableLike, previous: &dyn TableLike, updated: &dyn TableLike) {
+    for (key, item) in updated.iter() {
+        let before = previous.get(key);
+        if let (Some(before), Some(after)) =
+            (before.and_then(Item::as_table_like), item.as_table_like())
+        {
+            let created = !target.contains_key(key);
+            if created {
+                target.insert(key, Item::Table(Table::new()));
+            }
+            if let Some(section) = target.get_mut(key).and_then(Item::as_table_like_mut) {
+                apply_changes(section, before, after);
+                // Sections the file left out stay out unless something in them changed
+                if created && section.is_empty() {
+                    target.remove(key);
+                }
+                continue;
+            }
+        }
+        if before.is_some_and(|before| before.to_string() == item.to_string()) {
+            continue;
+        }
+        let mut item = item.clone();
+        if let (Some(value), Some(old)) =
+            (item.as_value_mut(), target.get(key).and_then(Item::as_value))
+        {
+            *value.decor_mut() = old.decor().clone();
+        }
+        target.insert(key, item);
+    }
+    let removed = previous
+        .iter()
+        .map(|(key, _)| key)
+        .filter(|key| !updated.contains_key(key))
+        .collect::<Vec<_>>();
+    for key in removed {
+        target.remove(key);
+    }
+}
+
+/// `[profiles.<name>]` section.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct SessionProfile {
+    /// Server ids or names visible to the session (empty = every allowed server).
+    pub servers: Vec<String>,
+    /// Only expose tools annotated as read-only.
+    pub read_only: bool,
+}
+
+/// `[multi_root]` section.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct MultiRootSettings {
+    /// How permissions of the projects behind each workspace root are combined.
+    pub permission_merge: RootMergePolicy,
+}
+
+/// Combination rule for sessions bound to several projects.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RootMergePolicy {
+    /// A tool is visible if any bound project allows it.
+    Union,
+    /// A tool is visible only if every bound project allows it, so adding a root never
+    /// widens access.
+    #[default]
+    Intersection,
+}
+
+/// `[approvals]` section.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ApprovalSettings {
+    /// Seconds a call requiring approval waits before it is rejected.
+    pub timeout_secs: u64,
+}
+
+impl Default for ApprovalSettings {
+    fn default() -> Self {
+        Self { timeout_secs: 300 }
+    }
+}
+
+impl ApprovalSettings {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs)
+    }
+}
+
+/// `[management]` section.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ManagementSettings {
+    /// Offer the built-in `mcp-center` server, whose tools manage the center itself.
+    /// Projects still need permission for it like for any other server.
+    pub enabled: bool,
+}
+
+/// `[new_projects]` section.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct NewProjectSettings {
+    pub policy: NewProjectPolicy,
+    /// Entry of `[permission_templates]` used when `policy = "template"`.
+    pub template: Option<String>,
+}
+
+/// Starting permissions of a newly seen project while it waits for approval.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum NewProjectPolicy {
+    /// Every configured server.
+    AllowAll,
+    /// Nothing until the project is approved.
+    #[default]
+    DenyAll,
+    /// The permission template named by `new_projects.template`.
+    Template,
+}
+
+/// `[permission_templates.<name>]` section.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct PermissionTemplate {
+    /// Server ids granted in full.
+    pub servers: Vec<String>,
+    /// Tool-level permissions per server id.
+    pub tools: BTreeMap<String, ToolPermission>,
+}
+
+/// Strategies the bridge tries, in order, to find the project root.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DetectionStrategy {
+    /// Walk up from the working directory looking for editor/IDE marker files.
+    Markers,
+    /// Use the enclosing git repository root.
+    Git,
+}
+
+/// How to treat sub-packages inside a repository.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MonorepoMode {
+    /// Use the root found by the matching strategy.
+    #[default]
+    RepoRoot,
+    /// Narrow down to the nearest directory containing a package marker.
+    NearestPackage,
+}
+
+/// `[project_detection]` section.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ProjectDetectionSettings {
+    /// Strategies in priority order; the first one that matches wins.
+    pub strategies: Vec<DetectionStrategy>,
+    /// Files or directories (relative to a candidate directory) marking a project root.
+    pub markers: Vec<String>,
+    /// Whether to stop at the repository root or the nearest sub-package.
+    pub monorepo: MonorepoMode,
+    /// Files marking a package root when `monorepo = "nearest_package"`.
+    pub package_markers: Vec<String>,
+}
+
+impl Default for ProjectDetectionSettings {
+    fn default() -> Self {
+        Self {
+            strategies: vec![DetectionStrategy::Markers, DetectionStrategy::Git],
+            markers: [
+                ".cursor/settings.json",
+                "cursor.json",
+                ".windsurfrc",
+                ".cursor",
+                ".windsurf",
+                ".vscode",
+                ".idea",
+                ".zed",
+            ]
+            .iter()
+            .map(|marker| marker.to_string())
+            .collect(),
+            monorepo: MonorepoMode::RepoRoot,
+            package_markers: ["package.json", "Cargo.toml", "pyproject.toml", "go.mod"]
+                .iter()
+                .map(|marker| marker.to_string())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_file_yields_defaults() {
+        let temp = tempfile::tempdir().unwrap();
+        let layout = Layout::new(temp.path().to_path_buf());
+        assert_eq!(WorkspaceSettings::load(&layout).unwrap(), WorkspaceSettings::default());
+        assert_eq!(
+            WorkspaceSettings::default().multi_root.permission_merge,
+            RootMergePolicy::Intersection
+        );
+    }
+
+    #[test]
+    fn partial_section_keeps_other_defaults() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("settings.toml");
+        fs::write(
+            &path,
+            r#"
+[project_detection]
+strategies = ["git"]
+monorepo = "nearest_package"
+"#,
+        )
+        .unwrap();
+
+        let settings = WorkspaceSettings::from_file(&path).unwrap();
+        let detection = settings.project_detection;
+        assert_eq!(detection.strategies, vec![DetectionStrategy::Git]);
+        assert_eq!(detection.monorepo, MonorepoMode::NearestPackage);
+        assert_eq!(detection.markers, ProjectDetectionSettings::default().markers);
+    }
+
+    #[test]
+    fn parses_profiles() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("settings.toml");
+        fs::write(
+            &path,
+            r#"
+[profiles.review]
+servers = ["github"]
+read_only = true
+"#,
+        )
+        .unwrap();
+
+        let settings = WorkspaceSettings::from_file(&path).unwrap();
+        let profile = settings.profiles.get("review").expect("profile parsed");
+        assert_eq!(profile.servers, vec!["github".to_string()]);
+        assert!(profile.read_only);
+    }
+
+    #[test]
+    fn parses_approval_timeout() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("settings.toml");
+        assert_eq!(WorkspaceSettings::default().approvals.timeout(), Duration::from_secs(300));
+        fs::write(&path, "[approvals]\ntimeout_secs = 45\n").unwrap();
+
+        let settings = WorkspaceSettings::from_file(&path).unwrap();
+        assert_eq!(settings.approvals.timeout(), Duration::from_secs(45));
+    }
+
+    #[test]
+    fn management_server_is_off_by_default() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("settings.toml");
+        assert!(!WorkspaceSettings::default().management.enabled);
+        fs::write(&path, "[management]\nenabled = true\n").unwrap();
+
+        let settings = WorkspaceSettings::from_file(&path).unwrap();
+        assert!(settings.management.enabled);
+    }
+
+    #[test]
+    fn new_projects_start_pending_with_policy_permissions() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("settings.toml");
+        fs::write(
+            &path,
+            r#"
+[new_projects]
+policy = "template"
+template = "docs"
+
+[permission_templates.docs]
+servers = ["context7"]
+tools = { github = { type = "AllowList", tools = ["search"] } }
+"#,
+        )
+        .unwrap();
+        let servers = vec!["context7".to_string(), "github".to_string()];
+        let new_record =
+            || ProjectRecord::new(crate::ProjectId::from_path(temp.path()), "/p".into());
+
+        let mut settings = WorkspaceSettings::from_file(&path).unwrap();
+        let mut record = new_record();
+        settings.seed_new_project(&mut record, servers.clone());
+        assert!(record.is_pending());
+        assert!(record.allows_tool("context7", "docs"));
+        assert!(record.allows_tool("github", "search"));
+        assert!(!record.allows_tool("github", "delete_repo"));
+
+        settings.new_projects.policy = NewProjectPolicy::DenyAll;
+        let mut record = new_record();
+        settings.seed_new_project(&mut record, servers.clone());
+        assert!(!record.allows_tool("context7", "docs"));
+        record.status = ProjectStatus::Active;
+        assert!(record.allows_tool("context7", "docs"), "approval lifts the pending restriction");
+
+        settings.new_projects.policy = NewProjectPolicy::AllowAll;
+        let mut record = new_record();
+        settings.seed_new_project(&mut record, servers);
+        assert!(record.allows_tool("github", "delete_repo"));
+    }
+
+    #[test]
+    fn round_trips_through_store() {
+        let temp = tempfile::tempdir().unwrap();
+        let layout = Layout::new(temp.path().to_path_buf());
+        let mut settings = WorkspaceSettings::default();
+        settings.project_detection.markers = vec![".fleet".into()];
+        settings.store(&layout).unwrap();
+        assert_eq!(WorkspaceSettings::load(&layout).unwrap(), settings);
+    }
+
+    #[test]
+    fn store_keeps_comments_and_untouched_keys() {
+        let temp = tempfile::tempdir().unwrap();
+        let layout = Layout::new(temp.path().to_path_buf());
+        fs::create_dir_all(layout.settings_path().parent().unwrap()).unwrap();
+        fs::write(
+            layout.settings_path(),
+            r#"# Team defaults
+[new_projects]
+policy = "deny_all" # reviewed in March
+
+[project_detection]
+# Ours first
+markers = [".fleet"]
+strategies = ["git"]
+"#,
+        )
+        .unwrap();
+
+        let mut settings = WorkspaceSettings::load(&layout).unwrap();
+        settings.new_projects.policy = NewProjectPolicy::AllowAll;
+        settings.approvals.timeout_secs = 60;
+        settings.store(&layout).unwrap();
+
+        let content = fs::read_to_string(layout.settings_path()).unwrap();
+        assert_eq!(
+            content,
+            r#"# Team defaults
+[new_projects]
+policy = "allow_all" # reviewed in March
+
+[project_detection]
+# Ours first
+markers = [".fleet"]
+strategies = ["git"]
+
+[approvals]
+timeout_secs = 60
+"#
+        );
+        assert_eq!(WorkspaceSettings::load(&layout).unwrap(), settings);
+    }
+}
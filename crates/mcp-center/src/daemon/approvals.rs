@@ -0,0 +1,223 @@
+//! Human-in-the-loop approval for sensitive tool calls.
+//!
+//! [`HostService`](crate::daemon::host::HostService) parks calls that require approval in the
+//! daemon-wide [`ApprovalQueue`] and waits until someone approves or denies them through the
+//! HTTP API or `mcp-center approvals`, or until the configured timeout lapses.
+//!
+//! Approval happens before the call reaches the server manager, so the person sees the
+//! arguments the agent sent (after pinned and hidden parameters are applied). Server and
+//! project pre hooks run afterwards and may still rewrite those arguments, which means the
+//! arguments forwarded upstream can differ from the ones that were approved.
+
+use std::{
+    collections::BTreeMap,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tokio::sync::{broadcast, oneshot};
+use tokio_stream::wrappers::BroadcastStream;
+use tracing::{debug, info};
+
+/// A tool call waiting for a person to confirm it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalRequest {
+    pub id: u64,
+    pub project_path: String,
+    pub server_id: String,
+    pub tool_name: String,
+    /// Call arguments as pretty-printed JSON.
+    pub arguments: Option<String>,
+    pub requested_at: u64,
+    pub expires_at: u64,
+}
+
+/// How a parked call ended.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum ApprovalOutcome {
+    Approved,
+    Denied { reason: Option<String> },
+    TimedOut,
+}
+
+/// Change notification for SSE subscribers.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ApprovalEvent {
+    Requested { request: ApprovalRequest },
+    Resolved { id: u64, outcome: ApprovalOutcome },
+}
+
+struct Pending {
+    request: ApprovalRequest,
+    responder: oneshot::Sender<ApprovalOutcome>,
+}
+
+/// Calls awaiting approval, shared by every session of the daemon.
+pub struct ApprovalQueue {
+    pending: Mutex<BTreeMap<u64, Pending>>,
+    next_id: AtomicU64,
+    events: broadcast::Sender<ApprovalEvent>,
+}
+
+impl Default for ApprovalQueue {
+    fn default() -> Self {
+        let (events, _) = broadcast::channel(64);
+        Self { pending: Mutex::new(BTreeMap::new()), next_id: AtomicU64::new(1), events }
+    }
+}
+
+impl std::fmt::Debug for ApprovalQueue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApprovalQueue").field("pending", &self.list().len()).finish()
+    }
+}
+
+impl ApprovalQueue {
+    /// Park a call until it is resolved or `timeout` lapses. The entry is withdrawn when the
+    /// returned future is dropped, e.g. because the agent cancelled the request.
+    pub async fn request(
+        &self,
+        project_path: String,
+        server_id: String,
+        tool_name: String,
+        arguments: Option<String>,
+        timeout: Duration,
+    ) -> ApprovalOutcome {
+        let requested_at = now();
+        let request = ApprovalRequest {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            project_path,
+            server_id,
+            tool_name,
+            arguments,
+            requested_at,
+            expires_at: requested_at.saturating_add(timeout.as_secs()),
+        };
+        let id = request.id;
+        let (responder, verdict) = oneshot::channel();
+        self.pending
+            .lock()
+            .unwrap()
+            .insert(id, Pending { request: request.clone(), responder });
+        info!(id, tool = %request.tool_name, server = %request.server_id, "tool call awaiting approval");
+        let _ = self.events.send(ApprovalEvent::Requested { request });
+
+        let _withdraw = Withdraw { queue: self, id };
+        match tokio::time::timeout(timeout, verdict).await {
+            Ok(Ok(outcome)) => outcome,
+            Ok(Err(_)) | Err(_) => {
+                self.finish(id, ApprovalOutcome::TimedOut);
+                ApprovalOutcome::TimedOut
+            }
+        }
+    }
+
+    /// Calls currently waiting, oldest first.
+    pub fn list(&self) -> Vec<ApprovalRequest> {
+        self.pending
+            .lock()
+            .unwrap()
+            .values()
+            .map(|pending| pending.request.clone())
+            .collect()
+    }
+
+    /// Approve or deny a waiting call; `None` when no call with `id` is waiting.
+    pub fn resolve(&self, id: u64, outcome: ApprovalOutcome) -> Option<ApprovalRequest> {
+        let pending = self.pending.lock().unwrap().remove(&id)?;
+        info!(id, ?outcome, "tool call approval resolved");
+        let _ = self.events.send(ApprovalEvent::Resolved { id, outcome: outcome.clone() });
+        let _ = pending.responder.send(outcome);
+        Some(pending.request)
+    }
+
+    pub fn subscribe(&self) -> BroadcastStream<ApprovalEvent> {
+        BroadcastStream::new(self.events.subscribe())
+    }
+
+    fn finish(&self, id: u64, outcome: ApprovalOutcome) {
+        if self.pending.lock().unwrap().remove(&id).is_some() {
+            debug!(id, ?outcome, "approval request withdrawn");
+            let _ = self.events.send(ApprovalEvent::Resolved { id, outcome });
+        }
+    }
+}
+
+/// Removes the request if the waiting call is dropped before it was resolved.
+struct Withdraw<'a> {
+    queue: &'a ApprovalQueue,
+    id: u64,
+}
+
+impl Drop for Withdraw<'_> {
+    fn drop(&mut self) {
+        self.queue
+            .finish(self.id, ApprovalOutcome::Denied { reason: Some("cancelled".into()) });
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tokio_stream::StreamExt;
+
+    use super::*;
+
+    async fn park(
+        queue: &Arc<ApprovalQueue>,
+        timeout: Duration,
+    ) -> tokio::task::JoinHandle<ApprovalOutcome> {
+        let parked = queue.clone();
+        let handle = tokio::spawn(async move {
+            parked.request("/p".into(), "db".into(), "drop".into(), None, timeout).await
+        });
+        while !handle.is_finished() && queue.list().is_empty() {
+            tokio::task::yield_now().await;
+        }
+        handle
+    }
+
+    #[tokio::test]
+    async fn resolve_wakes_the_waiting_call() {
+        let queue = Arc::new(ApprovalQueue::default());
+        let mut events = queue.subscribe();
+
+        let call = park(&queue, Duration::from_secs(60)).await;
+        let waiting = queue.list();
+        assert_eq!(waiting.len(), 1);
+        assert_eq!(waiting[0].tool_name, "drop");
+        assert!(matches!(events.next().await, Some(Ok(ApprovalEvent::Requested { .. }))));
+
+        let denied = ApprovalOutcome::Denied { reason: Some("not today".into()) };
+        assert!(queue.resolve(waiting[0].id, denied.clone()).is_some());
+        assert_eq!(call.await.unwrap(), denied);
+        assert!(queue.list().is_empty());
+        assert!(queue.resolve(waiting[0].id, ApprovalOutcome::Approved).is_none());
+    }
+
+    #[tokio::test]
+    async fn unanswered_calls_time_out_and_cancelled_calls_are_withdrawn() {
+        let queue = Arc::new(ApprovalQueue::default());
+        let call = park(&queue, Duration::from_millis(20)).await;
+        assert_eq!(call.await.unwrap(), ApprovalOutcome::TimedOut);
+        assert!(queue.list().is_empty());
+
+        let call = park(&queue, Duration::from_secs(60)).await;
+        call.abort();
+        let _ = call.await;
+        assert!(queue.list().is_empty());
+    }
+}
@@ -0,0 +1,267 @@
+//! Peer authentication for the daemon's local sockets (`daemon.sock`, `daemon.rpc.sock`).
+//!
+//! Two layers are applied:
+//! - On Unix the socket files are created with mode `0600` and every accepted connection
+//!   is checked via `SO_PEERCRED` / `getpeereid` against the daemon's effective uid.
+//! - Optionally a random handshake token is written to `state_dir/daemon.token` (mode `0600`);
+//!   when present, clients must echo it back before the daemon serves them.
+
+use std::{fs, io, path::Path, sync::Arc};
+
+use anyhow::{Context, Result};
+use interprocess::local_socket::{ListenerOptions, tokio::prelude::LocalSocketStream};
+use rand::RngCore;
+use tracing::{info, warn};
+
+use crate::Layout;
+
+/// Permissions applied to socket and token files on Unix.
+#[cfg(unix)]
+pub const OWNER_ONLY_MODE: u32 = 0o600;
+
+/// Apply owner-only permissions to listener options (no-op outside Unix).
+pub fn restrict_listener(options: ListenerOptions<'_>) -> ListenerOptions<'_> {
+    #[cfg(unix)]
+    {
+        use interprocess::os::unix::local_socket::ListenerOptionsExt as _;
+        options.mode(OWNER_ONLY_MODE as libc::mode_t)
+    }
+    #[cfg(not(unix))]
+    {
+        options
+    }
+}
+
+/// Re-apply owner-only permissions to a socket file after binding.
+///
+/// Some platforms ignore the mode passed at bind time, so this is done unconditionally.
+pub fn restrict_socket_file(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(OWNER_ONLY_MODE))
+            .with_context(|| format!("failed to restrict permissions of {}", path.display()))?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// Credentials of the process on the other end of a local socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerCredentials {
+    pub uid: u32,
+    pub pid: Option<i32>,
+}
+
+/// Verify that the connecting process runs as the same user as the daemon.
+///
+/// Rejected peers are logged and dropped; the returned stream is the (unchanged) accepted
+/// connection. Outside Unix the check is skipped and the stream is returned as is.
+pub fn verify_peer(stream: LocalSocketStream, channel: &'static str) -> Result<LocalSocketStream> {
+    #[cfg(unix)]
+    {
+        use interprocess::local_socket::{
+            tokio::{RecvHalf, Stream},
+            traits::tokio::Stream as _,
+        };
+
+        let (recv, send) = stream.split();
+        let credentials = match &recv {
+            RecvHalf::UdSocket(half) => peer_credentials(std::os::fd::AsFd::as_fd(half)),
+        };
+        let stream = Stream::reunite(recv, send)
+            .map_err(|err| anyhow::anyhow!("failed to reunite socket halves: {err}"))?;
+
+        let expected = unsafe { libc::geteuid() };
+        match credentials {
+            Ok(peer) if peer.uid == expected => Ok(stream),
+            Ok(peer) => {
+                warn!(
+                    channel,
+                    peer_uid = peer.uid,
+                    peer_pid = peer.pid,
+                    daemon_uid = expected,
+                    "rejected local socket peer owned by another user"
+                );
+                anyhow::bail!("peer uid {} does not match daemon uid {expected}", peer.uid)
+            }
+            Err(err) => {
+                warn!(channel, error = ?err, "rejected local socket peer: credentials unavailable");
+                Err(anyhow::Error::new(err).context("failed to read peer credentials"))
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = channel;
+        Ok(stream)
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn peer_credentials(fd: std::os::fd::BorrowedFd<'_>) -> io::Result<PeerCredentials> {
+    use std::os::fd::AsRawFd;
+
+    let mut cred: libc::ucred = unsafe { std::mem::zeroed() };
+    let mut len = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+    let ret = unsafe {
+        libc::getsockopt(
+            fd.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PEERCRED,
+            (&mut cred as *mut libc::ucred).cast(),
+            &mut len,
+        )
+    };
+    if ret != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(PeerCredentials { uid: cred.uid, pid: Some(cred.pid) })
+}
+
+#[cfg(all(unix, not(any(target_os = "linux", target_os = "android"))))]
+fn peer_credentials(fd: std::os::fd::BorrowedFd<'_>) -> io::Result<PeerCredentials> {
+    use std::os::fd::AsRawFd;
+
+    let mut uid: libc::uid_t = 0;
+    let mut gid: libc::gid_t = 0;
+    let ret = unsafe { libc::getpeereid(fd.as_raw_fd(), &mut uid, &mut gid) };
+    if ret != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(PeerCredentials { uid, pid: None })
+}
+
+/// Handshake token required from socket clients, if enabled.
+#[derive(Debug, Clone, Default)]
+pub struct SocketToken {
+    expected: Option<Arc<str>>,
+}
+
+impl SocketToken {
+    /// A token that accepts every client (token authentication disabled).
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Load the token from `state_dir/daemon.token`.
+    ///
+    /// When `require` is set and no token exists yet, a fresh one is generated. Without
+    /// `require`, an existing token file still enables the check.
+    pub fn load_or_create(layout: &Layout, require: bool) -> Result<Self> {
+        let path = layout.daemon_token_path();
+        if let Some(token) = read_token_file(&path)? {
+            info!(path = %path.display(), "socket handshake token enabled");
+            return Ok(Self { expected: Some(token.into()) });
+        }
+        if !require {
+            return Ok(Self::disabled());
+        }
+
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let token: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+        write_token_file(&path, &token)?;
+        info!(path = %path.display(), "generated socket handshake token");
+        Ok(Self { expected: Some(token.into()) })
+    }
+
+    pub fn is_required(&self) -> bool {
+        self.expected.is_some()
+    }
+
+    /// Check a token presented by a client. Always succeeds when the token is disabled.
+    pub fn verify(&self, presented: Option<&str>) -> bool {
+        match (&self.expected, presented) {
+            (None, _) => true,
+            (Some(expected), Some(presented)) => constant_time_eq(expected, presented),
+            (Some(_), None) => false,
+        }
+    }
+}
+
+/// Read the handshake token a client should present, if the daemon enabled one.
+pub fn client_token(layout: &Layout) -> Option<String> {
+    match read_token_file(&layout.daemon_token_path()) {
+        Ok(token) => token,
+        Err(err) => {
+            warn!(error = ?err, "failed to read socket handshake token");
+            None
+        }
+    }
+}
+
+fn read_token_file(path: &Path) -> Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(raw) => {
+            let token = raw.trim();
+            Ok((!token.is_empty()).then(|| token.to_string()))
+        }
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => {
+            Err(err).with_context(|| format!("failed to read token file {}", path.display()))
+        }
+    }
+}
+
+fn write_token_file(path: &Path, token: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create directory {}", parent.display()))?;
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(OWNER_ONLY_MODE);
+    }
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("failed to create token file {}", path.display()))?;
+    io::Write::write_all(&mut file, token.as_bytes())
+        .with_context(|| format!("failed to write token file {}", path.display()))?;
+    restrict_socket_file(path)
+}
+
+fn constant_time_eq(left: &str, right: &str) -> bool {
+    let (left, right) = (left.as_bytes(), right.as_bytes());
+    if left.len() != right.len() {
+        return false;
+    }
+    left.iter().zip(right).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_is_generated_on_demand_and_reused() {
+        let temp = tempfile::tempdir().unwrap();
+        let layout = Layout::new(temp.path().to_path_buf());
+
+        let disabled = SocketToken::load_or_create(&layout, false).unwrap();
+        assert!(!disabled.is_required());
+        assert!(disabled.verify(None));
+
+        let token = SocketToken::load_or_create(&layout, true).unwrap();
+        assert!(token.is_required());
+        let presented = client_token(&layout).expect("token file written");
+        assert!(token.verify(Some(&presented)));
+        assert!(!token.verify(Some("nope")));
+        assert!(!token.verify(None));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(layout.daemon_token_path()).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, OWNER_ONLY_MODE);
+        }
+
+        // An existing token file keeps the check enabled across restarts.
+        let reloaded = SocketToken::load_or_create(&layout, false).unwrap();
+        assert!(reloaded.verify(Some(&presented)));
+    }
+}
@@ -0,0 +1,317 @@
+//! On-disk tool result cache (see [`crate::config::cache`]).
+//!
+//! Every result is one JSON file in `state/cache/<server id>/`, named after the hash of the
+//! tool name and its canonical arguments, so the CLI can report on and clear the cache
+//! without a running daemon. The daemon keeps a running total of each cache's size and only
+//! scans the directory when a new entry pushes it over the limit.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use rmcp::model::{CallToolResult, JsonObject, Tool};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{Layout, config::cache::CacheConfig};
+
+const ENTRY_EXTENSION: &str = "json";
+
+/// The cache of one server.
+#[derive(Debug, Clone)]
+pub struct ToolCache {
+    dir: PathBuf,
+    config: CacheConfig,
+    /// Bytes on disk as of the last scan plus what was written since; `None` before the
+    /// first scan.
+    bytes: Arc<Mutex<Option<u64>>>,
+}
+
+/// A stored result and how long ago it was stored.
+#[derive(Debug)]
+pub struct CacheHit {
+    pub result: CallToolResult,
+    pub age: Duration,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheEntry {
+    tool: String,
+    stored_at: u64,
+    result: CallToolResult,
+}
+
+impl ToolCache {
+    pub fn new(layout: &Layout, server_id: &str, config: CacheConfig) -> Self {
+        Self { dir: layout.server_cache_dir(server_id), config, bytes: Arc::default() }
+    }
+
+    /// Whether results of `tool` are cached.
+    pub fn caches(&self, tool: &Tool) -> bool {
+        let read_only = tool.annotations.as_ref().and_then(|hints| hints.read_only_hint);
+        self.config.caches(&tool.name, read_only.unwrap_or(false))
+    }
+
+    /// Key of a call: the same for arguments that only differ in key order.
+    pub fn key(tool_name: &str, arguments: Option<&JsonObject>) -> String {
+        let arguments = arguments.map(|arguments| canonical(&Value::Object(arguments.clone())));
+        let arguments = arguments.unwrap_or_else(|| Value::Object(JsonObject::new()));
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(tool_name.as_bytes());
+        hasher.update(&[0]);
+        hasher.update(arguments.to_string().as_bytes());
+        hasher.finalize().to_hex().to_string()
+    }
+
+    /// The stored result for `key` if it is still fresh; stale entries are dropped.
+    pub async fn get(&self, key: &str) -> Option<CacheHit> {
+        let path = self.entry_path(key);
+        let entry = tokio::fs::read(&path).await.ok()?;
+        let fresh = serde_json::from_slice::<CacheEntry>(&entry).ok().and_then(|stored| {
+            let age = Duration::from_secs(now().saturating_sub(stored.stored_at));
+            (age.as_secs() < self.config.ttl_secs)
+                .then_some(CacheHit { result: stored.result, age })
+        });
+        if fresh.is_none() && tokio::fs::remove_file(&path).await.is_ok() {
+            self.account(-(entry.len() as i64));
+        }
+        fresh
+    }
+
+    /// Store `result` under `key`, evicting the oldest entries once the size limit is
+    /// exceeded.
+    pub async fn put(&self, key: &str, tool_name: &str, result: &CallToolResult) -> Result<()> {
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .with_context(|| format!("failed to create cache dir {}", self.dir.display()))?;
+        let entry =
+            CacheEntry { tool: tool_name.to_string(), stored_at: now(), result: result.clone() };
+        let bytes = serde_json::to_vec(&entry).context("failed to serialise cache entry")?;
+        if bytes.len() as u64 > self.config.max_bytes() {
+            return Ok(());
+        }
+        // Write then rename so a concurrent read never sees half an entry
+        let path = self.entry_path(key);
+        let partial = path.with_extension("partial");
+        let replaced = tokio::fs::metadata(&path).await.map_or(0, |metadata| metadata.len());
+        let written = bytes.len() as u64;
+        tokio::fs::write(&partial, bytes)
+            .await
+            .with_context(|| format!("failed to write cache entry {}", partial.display()))?;
+        tokio::fs::rename(&partial, &path)
+            .await
+            .with_context(|| format!("failed to write cache entry {}", path.display()))?;
+
+        if self
+            .account(written as i64 - replaced as i64)
+            .is_some_and(|total| total <= self.config.max_bytes())
+        {
+            return Ok(());
+        }
+        let (dir, config) = (self.dir.clone(), self.config.clone());
+        let total = tokio::task::spawn_blocking(move || evict(&dir, &config))
+            .await
+            .context("cache eviction panicked")??;
+        *self.bytes.lock().unwrap() = Some(total);
+        Ok(())
+    }
+
+    /// Add `delta` to the running total; `None` until the directory was scanned once.
+    fn account(&self, delta: i64) -> Option<u64> {
+        let mut bytes = self.bytes.lock().unwrap();
+        let total = bytes.as_mut()?;
+        *total = total.saturating_add_signed(delta);
+        Some(*total)
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(key).with_extension(ENTRY_EXTENSION)
+    }
+}
+
+/// Drop expired entries in `dir`, then the oldest ones until the rest fit the size limit;
+/// returns the bytes left.
+fn evict(dir: &Path, config: &CacheConfig) -> Result<u64> {
+    let ttl = Duration::from_secs(config.ttl_secs);
+    let mut entries = list_entries(dir)?;
+    entries.retain(|entry| {
+        let expired = entry.modified.elapsed().is_ok_and(|age| age >= ttl);
+        if expired {
+            let _ = fs::remove_file(&entry.path);
+        }
+        !expired
+    });
+    entries.sort_by_key(|entry| entry.modified);
+    let mut total = entries.iter().map(|entry| entry.size).sum::<u64>();
+    for entry in entries {
+        if total <= config.max_bytes() {
+            break;
+        }
+        fs::remove_file(&entry.path)
+            .with_context(|| format!("failed to remove {}", entry.path.display()))?;
+        total -= entry.size;
+    }
+    Ok(total)
+}
+
+/// Entries and size of one server's cache.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub server_id: String,
+    pub entries: usize,
+    pub bytes: u64,
+    /// When the newest entry was stored.
+    pub newest: Option<SystemTime>,
+}
+
+/// Stats of every server cache, by server id.
+pub fn stats(layout: &Layout) -> Result<Vec<CacheStats>> {
+    let mut stats = Vec::new();
+    for server_id in cached_servers(layout)? {
+        let entries = list_entries(&layout.server_cache_dir(&server_id))?;
+        stats.push(CacheStats {
+            entries: entries.len(),
+            bytes: entries.iter().map(|entry| entry.size).sum(),
+            newest: entries.iter().map(|entry| entry.modified).max(),
+            server_id,
+        });
+    }
+    Ok(stats)
+}
+
+/// Remove the cached results of `server_id`, or of every server; returns what was removed.
+pub fn clear(layout: &Layout, server_id: Option<&str>) -> Result<Vec<CacheStats>> {
+    let removed = stats(layout)?
+        .into_iter()
+        .filter(|stats| server_id.is_none_or(|id| stats.server_id == id))
+        .collect::<Vec<_>>();
+    for stats in &removed {
+        let dir = layout.server_cache_dir(&stats.server_id);
+        fs::remove_dir_all(&dir)
+            .with_context(|| format!("failed to remove cache dir {}", dir.display()))?;
+    }
+    Ok(removed)
+}
+
+fn cached_servers(layout: &Layout) -> Result<Vec<String>> {
+    let dir = layout.cache_dir();
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read cache dir {}", dir.display()));
+        }
+    };
+    let mut servers = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .collect::<Vec<_>>();
+    servers.sort();
+    Ok(servers)
+}
+
+struct EntryFile {
+    path: PathBuf,
+    size: u64,
+    modified: SystemTime,
+}
+
+fn list_entries(dir: &Path) -> Result<Vec<EntryFile>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read cache dir {}", dir.display()));
+        }
+    };
+    Ok(entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some(ENTRY_EXTENSION))
+        .filter_map(|path| {
+            let metadata = fs::metadata(&path).ok()?;
+            let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+            Some(EntryFile { path, size: metadata.len(), modified })
+        })
+        .collect())
+}
+
+/// `value` with the keys of every object sorted.
+fn canonical(value: &Value) -> Value {
+    match value {
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| (key.clone(), canonical(value)))
+                .collect::<BTreeMap<_, _>>()
+                .into_iter()
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(canonical).collect()),
+        value => value.clone(),
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn keys_ignore_argument_order() {
+        let a = json!({ "q": "rust", "opts": { "a": 1, "b": 2 } });
+        let b = json!({ "opts": { "b": 2, "a": 1 }, "q": "rust" });
+        assert_eq!(
+            ToolCache::key("search", a.as_object()),
+            ToolCache::key("search", b.as_object())
+        );
+        assert_ne!(ToolCache::key("search", a.as_object()), ToolCache::key("fetch", a.as_object()));
+        assert_eq!(
+            ToolCache::key("search", None),
+            ToolCache::key("search", Some(&JsonObject::new()))
+        );
+    }
+
+    #[tokio::test]
+    async fn stores_evicts_and_clears_results() {
+        let tmp = tempdir().unwrap();
+        let layout = Layout::new(tmp.path().to_path_buf());
+        let config = CacheConfig { max_size_mb: 1, ..CacheConfig::default() };
+        let cache = ToolCache::new(&layout, "docs", config);
+
+        let small = CallToolResult::structured(json!({ "page": "x" }));
+        assert!(cache.get("one").await.is_none());
+        cache.put("one", "fetch", &small).await.unwrap();
+        let hit = cache.get("one").await.unwrap();
+        assert_eq!(hit.result.structured_content, small.structured_content);
+
+        // Results of ~600 KiB (text plus structured copy): two do not fit in 1 MiB
+        let large = CallToolResult::structured(json!({ "page": "x".repeat(300 * 1024) }));
+        for key in ["two", "three"] {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            cache.put(key, "fetch", &large).await.unwrap();
+        }
+        assert!(cache.get("one").await.is_none());
+        assert!(cache.get("two").await.is_none());
+        assert!(cache.get("three").await.is_some());
+        let listed = stats(&layout).unwrap();
+        assert_eq!((listed[0].server_id.as_str(), listed[0].entries), ("docs", 1));
+
+        assert!(clear(&layout, Some("other")).unwrap().is_empty());
+        assert_eq!(clear(&layout, None).unwrap()[0].entries, 1);
+        assert!(stats(&layout).unwrap().is_empty());
+    }
+}
@@ -1,9 +1,10 @@
-use std::{
-    path::PathBuf,
-    sync::{Arc, RwLock},
-};
+use std::{path::PathBuf, sync::Arc};
 
-use crate::{Layout, ProjectId, ProjectRecord, ProjectRegistry};
+use crate::{
+    Layout, ProjectId, ProjectRecord, ProjectRegistry,
+    config::{ServerDefinition, WorkspaceSettings},
+    project::{identity, policy::EffectivePolicy},
+};
 use anyhow::{Context, Result};
 use interprocess::local_socket::traits::tokio::Listener as _;
 use interprocess::local_socket::{
@@ -11,89 +12,48 @@ use interprocess::local_socket::{
     tokio::prelude::{LocalSocketListener, LocalSocketStream},
 };
 use rmcp::{ServiceExt as _, service::RoleServer, transport::async_rw::AsyncRwTransport};
-use serde::{Deserialize, Serialize};
 use serde_json::{self, Value};
 use tokio::{
     io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    sync::Notify,
     task::JoinHandle,
 };
 use tracing::{debug, error, info, warn};
 
-use crate::daemon::{host::HostService, server_manager::ServerManager};
-
-/// Message sent by `mcp-center-bridge` upon establishing a control connection.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BridgeHello {
-    pub project_path: PathBuf,
-    #[serde(default)]
-    pub agent: Option<String>,
-    #[serde(default)]
-    pub bridge_pid: Option<u32>,
-    #[serde(default)]
-    pub metadata: Value,
-}
-
-/// Response returned by the daemon after registering the bridge.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BridgeReady {
-    pub project_id: String,
-    pub allowed_server_ids: Vec<String>,
-    #[serde(default)]
-    pub project_path: PathBuf,
-}
-
-/// Line-oriented control protocol messages.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type", rename_all = "snake_case")]
-pub enum ControlMessage {
-    BridgeHello(BridgeHello),
-    BridgeReady(BridgeReady),
-    Error { message: String },
-}
-
-impl ControlMessage {
-    #[allow(dead_code)]
-    pub fn hello(
-        project_path: PathBuf,
-        agent: Option<String>,
-        bridge_pid: Option<u32>,
-        metadata: Value,
-    ) -> Self {
-        ControlMessage::BridgeHello(BridgeHello { project_path, agent, bridge_pid, metadata })
-    }
-
-    pub fn ready(
-        project_id: String,
-        project_path: PathBuf,
-        allowed_server_ids: Vec<String>,
-    ) -> Self {
-        ControlMessage::BridgeReady(BridgeReady { project_id, allowed_server_ids, project_path })
-    }
-
-    pub fn error(message: impl Into<String>) -> Self {
-        ControlMessage::Error { message: message.into() }
-    }
-}
+use crate::bridge::control::{BridgeHello, ControlMessage, MIN_SUPPORTED_PROTOCOL_VERSION};
+use crate::daemon::{
+    auth::{self, SocketToken},
+    host::HostService,
+    permissions,
+    server_manager::ServerManager,
+    session::{self, ProjectBinding, SessionFilter},
+};
 
 pub struct ControlServerHandle {
     task: JoinHandle<()>,
     socket_path: PathBuf,
+    shutdown: Arc<Notify>,
 }
 
 impl ControlServerHandle {
+    /// Resolves once a bridge asked the daemon to exit via [`ControlMessage::Shutdown`].
+    pub async fn shutdown_requested(&self) {
+        self.shutdown.notified().await;
+    }
+
     pub async fn shutdown(self) {
         self.task.abort();
         let _ = self.task.await;
         #[cfg(unix)]
         {
-            if let Err(err) = tokio::fs::remove_file(&self.socket_path).await {
-                if err.kind() != std::io::ErrorKind::NotFound {
-                    warn!(
-                        error = ?err,
-                        path = %self.socket_path.display(),
-                        "failed to remove control socket"
-                    );
-                }
+            if let Err(err) = tokio::fs::remove_file(&self.socket_path).await
+                && err.kind() != std::io::ErrorKind::NotFound
+            {
+                warn!(
+                    error = ?err,
+                    path = %self.socket_path.display(),
+                    "failed to remove control socket"
+                );
             }
         }
     }
@@ -103,6 +63,7 @@ pub async fn spawn_control_server(
     layout: Layout,
     registry: ProjectRegistry,
     manager: Arc<ServerManager>,
+    token: SocketToken,
 ) -> Result<ControlServerHandle> {
     let socket_path = layout.daemon_socket_path();
     #[cfg(unix)]
@@ -119,14 +80,17 @@ pub async fn spawn_control_server(
         .as_str()
         .to_fs_name::<GenericFilePath>()
         .with_context(|| format!("invalid control socket name {socket_display}"))?;
-    let listener = ListenerOptions::new()
-        .name(listener_name)
+    let listener = auth::restrict_listener(ListenerOptions::new().name(listener_name))
         .create_tokio()
         .with_context(|| format!("failed to bind control socket {socket_display}"))?;
+    #[cfg(unix)]
+    auth::restrict_socket_file(&socket_path)?;
     info!(path = %socket_display, "control socket listening");
 
-    let task = tokio::spawn(run_listener(listener, layout, registry, manager));
-    Ok(ControlServerHandle { task, socket_path })
+    let shutdown = Arc::new(Notify::new());
+    let task =
+        tokio::spawn(run_listener(listener, layout, registry, manager, token, shutdown.clone()));
+    Ok(ControlServerHandle { task, socket_path, shutdown })
 }
 
 async fn run_listener(
@@ -134,15 +98,24 @@ async fn run_listener(
     layout: Layout,
     registry: ProjectRegistry,
     manager: Arc<ServerManager>,
+    token: SocketToken,
+    shutdown: Arc<Notify>,
 ) {
     loop {
         match listener.accept().await {
             Ok(stream) => {
+                let Ok(stream) = auth::verify_peer(stream, "control") else {
+                    continue;
+                };
                 let layout = layout.clone();
                 let registry = registry.clone();
                 let manager = manager.clone();
+                let token = token.clone();
+                let shutdown = shutdown.clone();
                 tokio::spawn(async move {
-                    if let Err(err) = handle_stream(stream, layout, registry, manager).await {
+                    if let Err(err) =
+                        handle_stream(stream, layout, registry, manager, token, shutdown).await
+                    {
                         warn!(error = ?err, "control session ended with error");
                     }
                 });
@@ -160,6 +133,8 @@ async fn handle_stream(
     layout: Layout,
     registry: ProjectRegistry,
     manager: Arc<ServerManager>,
+    token: SocketToken,
+    shutdown: Arc<Notify>,
 ) -> Result<()> {
     let mut reader = BufReader::new(stream);
     let mut line = String::new();
@@ -174,21 +149,74 @@ async fn handle_stream(
     let message: ControlMessage =
         serde_json::from_str(line.trim()).context("invalid control message")?;
 
-    let BridgeHello { project_path: raw_path, agent, bridge_pid, metadata } = match message {
+    let hello = match message {
         ControlMessage::BridgeHello(hello) => hello,
+        ControlMessage::Shutdown { auth_token } => {
+            if !token.verify(auth_token.as_deref()) {
+                warn!("rejected shutdown request with missing or invalid handshake token");
+                write_message(reader.get_mut(), &ControlMessage::error("authentication failed"))
+                    .await?;
+                anyhow::bail!("shutdown request failed authentication");
+            }
+            info!("shutdown requested over control socket");
+            shutdown.notify_one();
+            return Ok(());
+        }
         other => {
             let err = ControlMessage::error("expected bridge_hello message");
-            let payload = serde_json::to_vec(&err)?;
-            reader.get_mut().write_all(&payload).await?;
-            reader.get_mut().write_all(b"\n").await?;
+            write_message(reader.get_mut(), &err).await?;
             anyhow::bail!("unexpected control message: {other:?}");
         }
     };
 
+    if !hello.is_supported() {
+        let err = ControlMessage::error(format!(
+            "bridge protocol version {} is no longer supported (daemon {} requires >= {}); upgrade mcp-center",
+            hello.protocol_version,
+            crate::bridge::control::binary_version(),
+            MIN_SUPPORTED_PROTOCOL_VERSION
+        ));
+        write_message(reader.get_mut(), &err).await?;
+        anyhow::bail!("rejected bridge with protocol version {}", hello.protocol_version);
+    }
+
+    if !token.verify(hello.auth_token.as_deref()) {
+        warn!(
+            bridge_pid = ?hello.bridge_pid,
+            project = %hello.project_path.display(),
+            "rejected bridge with missing or invalid handshake token"
+        );
+        write_message(reader.get_mut(), &ControlMessage::error("authentication failed")).await?;
+        anyhow::bail!("bridge handshake failed authentication");
+    }
+
+    let filter = match SessionFilter::resolve(&hello.scope, &layout) {
+        Ok(filter) => filter,
+        Err(err) => {
+            warn!(error = %err, scope = ?hello.scope, "rejected bridge session scope");
+            write_message(reader.get_mut(), &ControlMessage::error(err.to_string())).await?;
+            anyhow::bail!("invalid session scope: {err}");
+        }
+    };
+
+    let BridgeHello {
+        project_path: raw_path,
+        agent,
+        bridge_pid,
+        metadata,
+        protocol_version,
+        binary_version,
+        features,
+        ..
+    } = hello;
+
     debug!("=== DEBUG: Received BridgeHello ===");
     debug!("  raw_path: {}", raw_path.display());
     debug!("  agent: {:?}", agent);
     debug!("  bridge_pid: {:?}", bridge_pid);
+    debug!("  protocol_version: {protocol_version}");
+    debug!("  binary_version: {:?}", binary_version);
+    debug!("  features: {:?}", features);
     debug!(
         "  metadata: {}",
         serde_json::to_string_pretty(&metadata).unwrap_or_else(|_| "{}".to_string())
@@ -204,19 +232,58 @@ async fn handle_stream(
     let initial_project_id = ProjectId::from_path(&project_path);
     debug!("  initial project_id: {}", initial_project_id.as_str());
 
-    let mut record = match registry.load(&initial_project_id) {
+    let settings = WorkspaceSettings::load(&layout).unwrap_or_else(|err| {
+        warn!(error = ?err, "failed to load workspace settings, using defaults");
+        WorkspaceSettings::default()
+    });
+
+    let (mut record, is_new) = match registry.load(&initial_project_id) {
         Ok(record) => {
             // 已存在的项目，保留用户设置
-            record
+            (record, false)
         }
         Err(_) => {
-            // 新项目，默认允许所有服务器
             let mut new_record =
                 ProjectRecord::new(initial_project_id.clone(), project_path.clone());
-            new_record.allowed_server_ids = manager.list_server_ids();
-            new_record
+            let inherits =
+                EffectivePolicy::resolve(&registry, &settings.project_rules, &project_path)
+                    .has_inherited_layers();
+            if inherits {
+                // 命中项目规则或父项目：保持空权限以继承
+                info!(
+                    project = %initial_project_id.as_str(),
+                    path = %project_path.display(),
+                    "new project inherits permissions from rules or a parent project"
+                );
+            } else {
+                // 新项目：进入待审批状态，初始权限由 new_projects 策略决定
+                settings.seed_new_project(&mut new_record, manager.list_server_ids());
+                info!(
+                    project = %initial_project_id.as_str(),
+                    path = %project_path.display(),
+                    policy = ?settings.new_projects.policy,
+                    "new project is pending approval"
+                );
+            }
+            (new_record, true)
         }
     };
+    // Git lookups and the registry scan block, so keep them off the runtime
+    let (mut record, moved_from) = {
+        let registry = registry.clone();
+        tokio::task::spawn_blocking(move || {
+            let previous = identity::detect_moved_project(&registry, &mut record, is_new);
+            (record, previous)
+        })
+        .await
+        .context("project identity task failed")?
+    };
+    let mut notices = Vec::new();
+    if let Some(previous) = moved_from {
+        let hint = identity::rebind_hint(&previous, &project_path);
+        warn!("{hint}");
+        notices.push(hint);
+    }
     record.touch();
     if let Some(agent) = agent {
         record.set_agent(Some(agent.clone()));
@@ -225,6 +292,9 @@ async fn handle_stream(
     if let Some(pid) = bridge_pid {
         record.metadata.insert("bridge_pid".to_string(), pid.to_string());
     }
+    if let Some(version) = binary_version {
+        record.metadata.insert("bridge_version".to_string(), version);
+    }
     if let Value::Object(entries) = metadata {
         for (key, value) in entries {
             record.metadata.insert(format!("meta_{key}"), value.to_string());
@@ -235,26 +305,48 @@ async fn handle_stream(
     }
     registry.store(&record)?;
 
-    let ready = ControlMessage::ready(
-        record.id.clone(),
-        project_path.clone(),
-        record.allowed_server_ids.clone(),
-    );
-    let payload = serde_json::to_vec(&ready)?;
+    // 仓库内 .mcp-center.toml 引用的服务器问题：记录日志并随握手提示给客户端
+    let definitions = layout
+        .list_server_configs()
+        .map(|configs| configs.into_iter().map(|cfg| cfg.definition().clone()).collect())
+        .unwrap_or_else(|err| {
+            warn!(error = ?err, "failed to list server configs");
+            Vec::<ServerDefinition>::new()
+        });
+    let policy = EffectivePolicy::resolve(&registry, &settings.project_rules, &project_path)
+        .with_repo_config(&definitions);
+    for warning in &policy.repo_warnings {
+        warn!(project = %record.id, "{warning}");
+        notices.push(warning.to_string());
+    }
+
+    let mut allowed_server_ids = record.allowed_server_ids.clone();
+    if let Some(scoped) = filter.server_ids.as_ref() {
+        if allowed_server_ids.is_empty() && !record.is_pending() {
+            allowed_server_ids = scoped.iter().cloned().collect();
+            allowed_server_ids.sort();
+        } else {
+            allowed_server_ids.retain(|id| scoped.contains(id));
+        }
+    }
+    let ready =
+        ControlMessage::ready(record.id.clone(), project_path.clone(), allowed_server_ids, notices);
     let mut stream = reader.into_inner();
-    stream.write_all(&payload).await?;
-    stream.write_all(b"\n").await?;
+    write_message(&mut stream, &ready).await?;
 
     let (read_half, write_half) = tokio::io::split(stream);
     let transport = AsyncRwTransport::<RoleServer, _, _>::new_server(read_half, write_half);
 
-    // 使用 Arc<RwLock> 包装 project_id，允许后续更新
-    let project_id_lock = Arc::new(RwLock::new(initial_project_id.clone()));
+    // 会话绑定：初始为 BridgeHello 的项目，收到 roots/list 后按每个 root 重新绑定
+    let merge = settings.multi_root.permission_merge;
+    let binding =
+        Arc::new(ProjectBinding::new(initial_project_id.clone(), project_path.clone(), merge));
     let host_service = HostService::new(
         manager.clone(),
         layout.clone(),
-        project_id_lock.clone(),
+        binding.clone(),
         registry.clone(),
+        filter,
     );
     let project_id_str = record.id.clone();
 
@@ -263,110 +355,39 @@ async fn handle_stream(
             let project = project_id_str.clone();
             let peer = running.peer().clone();
 
-            // IMPORTANT: Synchronously fetch roots before spawn to determine the real project_id
+            // IMPORTANT: Synchronously fetch roots before spawn to determine the real project ids
             debug!("=== DEBUG: Attempting to list roots from client ===");
             match peer.list_roots().await {
                 Ok(roots_result) => {
-                    debug!("  Successfully got roots from client!");
                     debug!("  Number of roots: {}", roots_result.roots.len());
-                    if let Some(root) = roots_result.roots.first() {
-                        debug!("  Root URI: {}", root.uri);
-                        debug!("  Root name: {:?}", root.name);
-
-                        // 解析file:// URI
-                        if let Some(real_path) = parse_file_uri(&root.uri) {
-                            debug!("  Parsed real path: {}", real_path.display());
-
-                            // 基于真实路径重新计算 project_id
-                            let real_project_id = ProjectId::from_path(&real_path);
-                            debug!("  Real project_id: {}", real_project_id.as_str());
-
-                            // Update the project_id in HostService
-                            {
-                                let mut pid = project_id_lock.write().unwrap();
-                                *pid = real_project_id.clone();
-                                info!(
-                                    "Updated HostService project_id: {} -> {}",
-                                    initial_project_id.as_str(),
-                                    real_project_id.as_str()
-                                );
-                            }
-
-                            // 如果真实 ID 和初始 ID 不同，需要迁移记录
-                            if real_project_id.as_str() != initial_project_id.as_str() {
-                                info!(
-                                    "Project ID mismatch: initial={}, real={}. Migrating...",
-                                    initial_project_id.as_str(),
-                                    real_project_id.as_str()
-                                );
-
-                                // 检查真实 ID 是否已存在
-                                match registry.load(&real_project_id) {
-                                    Ok(mut existing_record) => {
-                                        // 真实 ID 已存在，更新它
-                                        info!("Real project ID already exists, updating it");
-                                        existing_record.path = real_path.clone();
-                                        existing_record.touch();
-                                        // 保留现有的服务器权限配置
-                                        if let Err(e) = registry.store(&existing_record) {
-                                            warn!("Failed to update real project record: {}", e);
-                                        } else {
-                                            info!("Updated real project record");
-                                        }
-                                    }
-                                    Err(_) => {
-                                        // 真实 ID 不存在，创建新记录
-                                        info!("Creating new record with real project ID");
-                                        let mut new_record = ProjectRecord::new(
-                                            real_project_id.clone(),
-                                            real_path.clone(),
-                                        );
-                                        // 继承初始记录的服务器权限配置
-                                        new_record.allowed_server_ids =
-                                            record.allowed_server_ids.clone();
-                                        new_record.agent = record.agent.clone();
-                                        new_record.metadata = record.metadata.clone();
-                                        new_record.touch();
-                                        if let Err(e) = registry.store(&new_record) {
-                                            warn!("Failed to create real project record: {}", e);
-                                        } else {
-                                            info!("Created real project record");
-                                        }
-                                    }
-                                }
-
-                                // 删除错误的初始记录（如果它是因为路径检测失败创建的）
-                                // 只在初始路径是父目录时删除
-                                if initial_project_id.as_str() != real_project_id.as_str()
-                                    && real_path.starts_with(&project_path)
-                                {
-                                    info!(
-                                        "Deleting incorrect initial record {}",
-                                        initial_project_id.as_str()
-                                    );
-                                    if let Err(e) = registry.delete(&initial_project_id) {
-                                        warn!("Failed to delete incorrect record: {}", e);
-                                    } else {
-                                        info!("Deleted incorrect initial record");
-                                    }
-                                }
-                            } else {
-                                // ID 相同，只需要更新路径（如果不同）
-                                if let Ok(mut record) = registry.load(&real_project_id) {
-                                    if record.path != real_path {
-                                        let old_path = record.path.clone();
-                                        record.path = real_path.clone();
-                                        if let Err(e) = registry.store(&record) {
-                                            warn!("Failed to update project path: {}", e);
-                                        } else {
-                                            info!(
-                                                "Updated project path: {} -> {}",
-                                                old_path.display(),
-                                                record.path.display()
-                                            );
-                                        }
-                                    }
-                                }
+                    let roots = session::root_paths(&roots_result.roots);
+                    if !roots.is_empty() {
+                        let bound = {
+                            let (registry, binding, roots) =
+                                (registry.clone(), binding.clone(), roots.clone());
+                            let (template, rules) =
+                                (record.clone(), settings.project_rules.clone());
+                            tokio::task::spawn_blocking(move || {
+                                session::bind_roots(&registry, &binding, &roots, &template, &rules)
+                            })
+                            .await
+                            .unwrap_or_else(|err| {
+                                warn!(error = ?err, "binding workspace roots failed");
+                                Vec::new()
+                            })
+                        };
+
+                        // 删除错误的初始记录（如果它是因为路径检测失败创建的）
+                        // 只在初始路径是某个 root 的父目录时删除
+                        if !bound.contains(&initial_project_id)
+                            && session::is_parent_of_any(&project_path, &roots)
+                        {
+                            info!(
+                                "Deleting incorrect initial record {}",
+                                initial_project_id.as_str()
+                            );
+                            if let Err(e) = registry.delete(&initial_project_id) {
+                                warn!("Failed to delete incorrect record: {}", e);
                             }
                         }
                     }
@@ -378,6 +399,14 @@ async fn handle_stream(
             }
             debug!("=== DEBUG: Roots listing complete ===");
 
+            // 临时授权到期时通知客户端刷新工具列表
+            tokio::spawn(permissions::notify_grant_expiry(
+                layout.clone(),
+                registry.clone(),
+                binding.clone(),
+                peer.clone(),
+            ));
+
             // 现在spawn session处理
             tokio::spawn(async move {
                 match running.waiting().await {
@@ -396,6 +425,13 @@ async fn handle_stream(
     Ok(())
 }
 
+async fn write_message(stream: &mut LocalSocketStream, message: &ControlMessage) -> Result<()> {
+    let mut payload = serde_json::to_vec(message)?;
+    payload.push(b'\n');
+    stream.write_all(&payload).await?;
+    Ok(())
+}
+
 async fn normalize_project_path(path: &PathBuf) -> Option<PathBuf> {
     match tokio::fs::canonicalize(path).await {
         Ok(canonical) => Some(canonical),
@@ -406,23 +442,39 @@ async fn normalize_project_path(path: &PathBuf) -> Option<PathBuf> {
     }
 }
 
-/// Parse a file:// URI to a local filesystem path
-/// Examples:
-///   file:///Users/wangnov/project -> /Users/wangnov/project
-///   file://localhost/Users/wangnov/project -> /Users/wangnov/project
-fn parse_file_uri(uri: &str) -> Option<PathBuf> {
-    let uri = uri.trim();
-
-    // Handle file:// scheme
-    if let Some(stripped) = uri.strip_prefix("file://") {
-        // Remove optional localhost
-        let path_part = stripped.strip_prefix("localhost").unwrap_or(stripped);
-
-        // The path should start with /
-        if path_part.starts_with('/') {
-            return Some(PathBuf::from(path_part));
-        }
+#[cfg(all(test, unix))]
+mod tests {
+    use interprocess::local_socket::traits::tokio::Stream as _;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn rejects_bridges_below_the_minimum_protocol_version() {
+        let tmp = tempfile::tempdir().unwrap();
+        let layout = Layout::new(tmp.path().to_path_buf());
+        layout.ensure().unwrap();
+        let registry = ProjectRegistry::new(&layout);
+        let manager = Arc::new(ServerManager::start(layout.clone()).await.unwrap());
+        let server =
+            spawn_control_server(layout.clone(), registry, manager, SocketToken::disabled())
+                .await
+                .unwrap();
+
+        let socket = layout.daemon_socket_path().to_string_lossy().into_owned();
+        let stream =
+            LocalSocketStream::connect(socket.as_str().to_fs_name::<GenericFilePath>().unwrap())
+                .await
+                .unwrap();
+        let mut stream = BufReader::new(stream);
+        let legacy =
+            format!(r#"{{"type":"bridge_hello","project_path":"{}"}}"#, tmp.path().display());
+        stream.get_mut().write_all(format!("{legacy}\n").as_bytes()).await.unwrap();
+        let mut reply = String::new();
+        stream.read_line(&mut reply).await.unwrap();
+        let ControlMessage::Error { message } = serde_json::from_str(&reply).unwrap() else {
+            panic!("expected an error, got {reply}");
+        };
+        assert!(message.contains("protocol version 0 is no longer supported"), "{message}");
+        server.shutdown().await;
     }
-
-    None
 }
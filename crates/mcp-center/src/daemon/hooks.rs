@@ -0,0 +1,292 @@
+//! Running tool-call hooks (see [`crate::config::hooks`]).
+//!
+//! A hook gets one JSON object on stdin:
+//!
+//! ```json
+//! { "stage": "pre", "server": "github", "tool": "create_issue", "project": "/work/app",
+//!   "arguments": { "title": "Crash on start" } }
+//! ```
+//!
+//! Post hooks also get `result`, the `CallToolResult` of the call. The hook answers with one
+//! JSON object on stdout, every field optional:
+//!
+//! - `arguments` replaces the arguments of the call (pre hooks only);
+//! - `result` answers the call with this result: a pre hook skips the server, a post hook
+//!   replaces what the server returned;
+//! - `deny` rejects the call with this reason.
+//!
+//! Empty output leaves the call as it is. A hook that exits non-zero, times out or prints
+//! anything else has failed, and its `on_failure` decides whether the call goes on. Every
+//! run is recorded in the server's tool log with its duration.
+
+use std::{path::Path, process::Stdio, time::Duration};
+
+use rmcp::model::{CallToolResult, Content, JsonObject};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::{io::AsyncWriteExt, time::Instant};
+use tracing::warn;
+
+use crate::{
+    config::hooks::{HookFailure, HookStage, ToolHook},
+    daemon::logging::{Redaction, ServerLogHandle},
+};
+
+/// Where the pre hooks left a call.
+#[derive(Debug)]
+pub enum PreOutcome {
+    /// Call the server with these arguments.
+    Call(Option<JsonObject>),
+    /// Answer with this result without calling the server.
+    Answer(CallToolResult),
+}
+
+/// Answer of a hook; see the module docs.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct HookReply {
+    arguments: Option<JsonObject>,
+    result: Option<CallToolResult>,
+    deny: Option<String>,
+}
+
+impl HookReply {
+    fn action(&self, stage: HookStage) -> &'static str {
+        match (stage, self) {
+            (_, HookReply { deny: Some(_), .. }) => "deny",
+            (HookStage::Pre, HookReply { result: Some(_), .. }) => "answer",
+            (HookStage::Post, HookReply { result: Some(_), .. }) => "replace",
+            (HookStage::Pre, HookReply { arguments: Some(_), .. }) => "rewrite",
+            _ => "continue",
+        }
+    }
+}
+
+/// One tool call passing through the hooks of its server and project.
+pub struct HookCall<'a> {
+    pub server_id: &'a str,
+    pub tool_name: &'a str,
+    pub project: Option<&'a Path>,
+    pub call_id: &'a str,
+    pub log: &'a ServerLogHandle,
+    pub redaction: &'a Redaction,
+}
+
+impl HookCall<'_> {
+    /// Run the pre hooks in order on `arguments`.
+    pub async fn run_pre(
+        &self,
+        hooks: &[&ToolHook],
+        mut arguments: Option<JsonObject>,
+    ) -> PreOutcome {
+        for hook in hooks.iter().filter(|hook| hook.applies_to(self.tool_name, HookStage::Pre)) {
+            let payload = self.payload(HookStage::Pre, arguments.as_ref(), None);
+            let reply = match self.run(hook, HookStage::Pre, &payload).await {
+                Ok(reply) => reply,
+                Err(answer) => return PreOutcome::Answer(answer),
+            };
+            if let Some(reason) = reply.deny {
+                return PreOutcome::Answer(denied(hook, &reason));
+            }
+            if let Some(result) = reply.result {
+                return PreOutcome::Answer(result);
+            }
+            if let Some(rewritten) = reply.arguments {
+                arguments = Some(rewritten);
+            }
+        }
+        PreOutcome::Call(arguments)
+    }
+
+    /// Run the post hooks in order on the `result` of a call made with `arguments`.
+    pub async fn run_post(
+        &self,
+        hooks: &[&ToolHook],
+        arguments: Option<&JsonObject>,
+        mut result: CallToolResult,
+    ) -> CallToolResult {
+        for hook in hooks.iter().filter(|hook| hook.applies_to(self.tool_name, HookStage::Post)) {
+            let payload = self.payload(HookStage::Post, arguments, Some(&result));
+            let reply = match self.run(hook, HookStage::Post, &payload).await {
+                Ok(reply) => reply,
+                Err(answer) => return answer,
+            };
+            if let Some(reason) = reply.deny {
+                return denied(hook, &reason);
+            }
+            if let Some(replaced) = reply.result {
+                result = replaced;
+            }
+        }
+        result
+    }
+
+    fn payload(
+        &self,
+        stage: HookStage,
+        arguments: Option<&JsonObject>,
+        result: Option<&CallToolResult>,
+    ) -> Value {
+        let mut payload = json!({
+            "stage": stage.as_str(),
+            "server": self.server_id,
+            "tool": self.tool_name,
+            "project": self.project,
+            "arguments": arguments,
+        });
+        if let Some(result) = result {
+            payload["result"] = serde_json::to_value(result).unwrap_or(Value::Null);
+        }
+        payload
+    }
+
+    /// Run one hook and log it. `Err` is the answer to the call when a failing hook denies.
+    async fn run(
+        &self,
+        hook: &ToolHook,
+        stage: HookStage,
+        payload: &Value,
+    ) -> Result<HookReply, CallToolResult> {
+        let start = Instant::now();
+        let outcome = execute(hook, payload).await.map_err(|err| self.redaction.text(err));
+        let logged = match &outcome {
+            Ok(reply) => Ok(reply.action(stage)),
+            Err(err) => Err(err.as_str()),
+        };
+        if let Err(err) = self
+            .log
+            .log_hook(self.call_id, self.tool_name, hook.label(), stage, start.elapsed(), logged)
+            .await
+        {
+            warn!(error = ?err, "failed to record hook log entry");
+        }
+        match outcome {
+            Ok(reply) => Ok(reply),
+            Err(err) => {
+                warn!(hook = hook.label(), tool = self.tool_name, "hook failed: {err}");
+                match hook.on_failure {
+                    HookFailure::Ignore => Ok(HookReply::default()),
+                    HookFailure::Deny => Err(CallToolResult::error(vec![Content::text(format!(
+                        "hook '{}' failed: {err}",
+                        hook.label()
+                    ))])),
+                }
+            }
+        }
+    }
+}
+
+fn denied(hook: &ToolHook, reason: &str) -> CallToolResult {
+    CallToolResult::error(vec![Content::text(format!(
+        "call denied by hook '{}': {reason}",
+        hook.label()
+    ))])
+}
+
+/// Start the hook, hand it `payload` and read its answer.
+async fn execute(hook: &ToolHook, payload: &Value) -> Result<HookReply, String> {
+    let mut child = tokio::process::Command::new(&hook.command)
+        .args(&hook.args)
+        .envs(&hook.env)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|err| format!("failed to start '{}': {err}", hook.command))?;
+    let mut stdin = child.stdin.take().ok_or("stdin unavailable")?;
+    let input = payload.to_string();
+    // Feed stdin while collecting output so a chatty hook cannot block on a full pipe; a
+    // hook that never reads its input just closes the pipe early
+    let write = async move {
+        let _ = stdin.write_all(input.as_bytes()).await;
+    };
+    let timeout = Duration::from_secs(hook.timeout_secs());
+    let (_, output) =
+        tokio::time::timeout(timeout, async { tokio::join!(write, child.wait_with_output()) })
+            .await
+            .map_err(|_| format!("did not finish within {} seconds", timeout.as_secs()))?;
+    let output = output.map_err(|err| format!("failed to run: {err}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(match stderr.trim() {
+            "" => format!("exited with {}", output.status),
+            stderr => format!("exited with {}: {stderr}", output.status),
+        });
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if stdout.trim().is_empty() {
+        return Ok(HookReply::default());
+    }
+    serde_json::from_str(stdout.trim()).map_err(|err| format!("invalid answer: {err}"))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn sh(script: &str) -> ToolHook {
+        ToolHook {
+            name: None,
+            command: "sh".into(),
+            args: vec!["-c".into(), script.into()],
+            env: Default::default(),
+            stages: vec![HookStage::Pre, HookStage::Post],
+            tools: Vec::new(),
+            timeout_secs: Some(5),
+            on_failure: HookFailure::Deny,
+        }
+    }
+
+    fn text(result: &CallToolResult) -> String {
+        result.content[0].as_text().map(|text| text.text.clone()).unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn hooks_rewrite_answer_deny_and_fail() {
+        let dir = tempdir().unwrap();
+        let log = ServerLogHandle::new("demo".into(), "Demo".into(), dir.path().into())
+            .await
+            .unwrap();
+        let redaction = Redaction::default();
+        let call = HookCall {
+            server_id: "demo",
+            tool_name: "search",
+            project: None,
+            call_id: "call-1",
+            log: &log,
+            redaction: &redaction,
+        };
+        let arguments = json!({ "query": "bug" }).as_object().cloned();
+
+        let rewrite = sh(r#"grep -q '"tool":"search"' && echo '{"arguments":{"query":"x"}}'"#);
+        let passthrough = sh("cat > /dev/null");
+        let outcome = call.run_pre(&[&rewrite, &passthrough], arguments.clone()).await;
+        assert!(matches!(outcome, PreOutcome::Call(Some(args)) if args["query"] == "x"));
+
+        let deny = sh(r#"echo '{"deny":"read-only hours"}'"#);
+        let PreOutcome::Answer(result) = call.run_pre(&[&deny, &rewrite], arguments.clone()).await
+        else {
+            panic!("deny should answer the call");
+        };
+        assert_eq!(result.is_error, Some(true));
+        assert_eq!(text(&result), "call denied by hook 'sh': read-only hours");
+
+        let replace = sh(r#"grep -q '"result"' && echo '{"result":{"content":[],"isError":false,
+            "structuredContent":{"redacted":true}}}'"#);
+        let result = call
+            .run_post(&[&replace], arguments.as_ref(), CallToolResult::structured(json!({})))
+            .await;
+        assert_eq!(result.structured_content, Some(json!({ "redacted": true })));
+
+        let broken = sh("echo boom >&2; exit 2");
+        let result = call.run_post(&[&broken], None, CallToolResult::success(vec![])).await;
+        assert_eq!(result.is_error, Some(true));
+        assert!(text(&result).contains("boom"));
+        let ignored = ToolHook { on_failure: HookFailure::Ignore, ..broken };
+        let outcome = call.run_pre(&[&ignored], arguments.clone()).await;
+        assert!(matches!(outcome, PreOutcome::Call(args) if args == arguments));
+    }
+}
@@ -1,96 +1,140 @@
-use std::sync::{Arc, RwLock};
+use std::{
+    collections::HashSet,
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use crate::project::ToolPermission;
-use crate::{Layout, ProjectId, ProjectRegistry};
+use crate::{
+    Layout, ProjectRecord, ProjectRegistry,
+    config::{
+        WorkspaceSettings,
+        script::{self, CompositeStep, ScriptTool},
+        settings::ApprovalSettings,
+    },
+    project::{annotations::ToolHints, arguments, schema},
+};
 use rmcp::{
     ErrorData as McpError,
     model::{
-        CallToolRequest, CallToolRequestParam, ClientRequest, CompleteRequestMethod,
-        GetPromptRequestMethod, InitializeResult, ListPromptsRequestMethod,
-        ListResourceTemplatesRequestMethod, ListResourcesRequestMethod, ListToolsResult,
-        ProtocolVersion, ReadResourceRequestMethod, ServerCapabilities, ServerResult,
+        CallToolRequest, CallToolRequestParam, CallToolResult, ClientNotification, ClientRequest,
+        CompleteRequestMethod, Content, GetPromptRequest, InitializeResult, ListPromptsResult,
+        ListResourceTemplatesResult, ListResourcesResult, ListToolsResult, ProtocolVersion,
+        ReadResourceRequest, ReadResourceResult, ServerCapabilities, ServerResult,
         SetLevelRequestMethod, SubscribeRequestMethod, Tool, UnsubscribeRequestMethod,
     },
-    service::{NotificationContext, RequestContext, RoleServer, Service},
+    service::{NotificationContext, RequestContext, RoleServer, Service, ServiceError},
 };
 use tracing::{debug, warn};
+use uuid::Uuid;
 
-use crate::daemon::server_manager::ServerManager;
+use crate::daemon::{
+    approvals::ApprovalOutcome,
+    library::Library,
+    limits,
+    logging::Redaction,
+    management::{self, ManagementContext},
+    permissions::{PermissionSnapshot, SessionPermissions},
+    server_manager::{ServerManager, ToolEntry},
+    session::{self, ProjectBinding, SessionFilter},
+    tool_search::{self, MetaCall},
+};
 
 pub struct HostService {
     manager: Arc<ServerManager>,
     layout: Layout,
-    project_id: Arc<RwLock<ProjectId>>,
+    binding: Arc<ProjectBinding>,
     registry: ProjectRegistry,
+    filter: SessionFilter,
+    permissions: SessionPermissions,
 }
 
 impl HostService {
     pub fn new(
         manager: Arc<ServerManager>,
         layout: Layout,
-        project_id: Arc<RwLock<ProjectId>>,
+        binding: Arc<ProjectBinding>,
         registry: ProjectRegistry,
+        filter: SessionFilter,
     ) -> Self {
-        Self { manager, layout, project_id, registry }
+        Self {
+            manager,
+            layout,
+            binding,
+            registry,
+            filter,
+            permissions: SessionPermissions::default(),
+        }
     }
 
-    /// Check if a specific tool is allowed for the current project
-    /// Priority: allowed_server_tools > allowed_server_ids
-    fn is_tool_allowed(&self, tool_name: &str, server_id: &str) -> bool {
-        let project_id = self.project_id.read().unwrap();
-        match self.registry.load(&project_id) {
-            Ok(record) => {
-                // 1. Check tool-level permissions first (highest priority)
-                if let Some(permission) = record.allowed_server_tools.get(server_id) {
-                    let allowed = match permission {
-                        ToolPermission::All => true,
-                        ToolPermission::AllowList { tools } => {
-                            tools.contains(&tool_name.to_string())
-                        }
-                        ToolPermission::DenyList { tools } => {
-                            !tools.contains(&tool_name.to_string())
-                        }
-                    };
-                    debug!(
-                        "Tool-level permission check: tool='{}', server='{}', permission={:?}, allowed={}",
-                        tool_name, server_id, permission, allowed
-                    );
-                    return allowed;
-                }
+    /// Compiled policies of every project bound to this session, primary first, narrowed by
+    /// each root's `.mcp-center.toml`; recompiled only after a change notification.
+    fn session_permissions(&self) -> Arc<PermissionSnapshot> {
+        self.permissions.current(&self.layout, &self.registry, self.binding.roots())
+    }
 
-                // 2. Fallback to server-level permissions
-                if record.allowed_server_ids.is_empty() {
-                    // Empty list = allow all
-                    true
-                } else {
-                    record.allowed_server_ids.contains(&server_id.to_string())
-                }
-            }
-            Err(_) => {
-                // No project record = allow all
-                true
-            }
-        }
+    /// Prompts and resources of the workspace and the primary project, read off the async
+    /// runtime.
+    async fn library(&self) -> std::result::Result<Library, McpError> {
+        let layout = self.layout.clone();
+        let project = self.binding.primary_path();
+        tokio::task::spawn_blocking(move || Library::load(&layout, Some(&project)))
+            .await
+            .map_err(|err| McpError::internal_error(err.to_string(), None))
     }
 
-    /// Get custom description for a tool if configured
-    fn get_custom_description(&self, tool_name: &str) -> Option<String> {
-        let project_id = self.project_id.read().unwrap();
-        match self.registry.load(&project_id) {
-            Ok(record) => {
-                for customization in &record.tool_customizations {
-                    if customization.tool_name == tool_name {
-                        return customization.description.clone();
-                    }
-                }
-                None
-            }
-            Err(_) => None,
-        }
+    /// Read one library resource without loading the rest of the library.
+    async fn read_resource(
+        &self,
+        uri: String,
+    ) -> std::result::Result<ReadResourceResult, McpError> {
+        let layout = self.layout.clone();
+        let project = self.binding.primary_path();
+        tokio::task::spawn_blocking(move || Library::read_resource(&layout, Some(&project), &uri))
+            .await
+            .map_err(|err| McpError::internal_error(err.to_string(), None))?
+    }
+
+    /// Check if a specific tool is allowed for the projects bound to this session
+    /// Each project: rules < parent projects < own record, then its annotation switches
+    /// against `tool`'s hints; results merged per policy
+    fn is_tool_allowed(
+        &self,
+        permissions: &PermissionSnapshot,
+        tool: &Tool,
+        server_id: &str,
+    ) -> bool {
+        let tool_name = tool.name.as_ref();
+        let hints = ToolHints::from_annotations(tool.annotations.as_ref());
+        let allowed = self.binding.merge(
+            permissions
+                .policies()
+                .iter()
+                .map(|policy| policy.allows_annotated_tool(server_id, tool_name, hints)),
+        );
+        debug!(
+            "Permission check: tool='{}', server='{}', policy={:?}, allowed={}",
+            tool_name,
+            server_id,
+            self.binding.merge_policy(),
+            allowed
+        );
+        allowed
+    }
+
+    /// Whether the session may see `server_id` at all: the built-in management server only
+    /// exists for sessions while `[management]` is enabled.
+    fn is_server_offered(&self, permissions: &PermissionSnapshot, server_id: &str) -> bool {
+        server_id != management::SERVER_ID || permissions.management_enabled()
     }
 
     fn server_info(&self) -> InitializeResult {
-        let capabilities = ServerCapabilities::builder().enable_tools().build();
+        let capabilities = ServerCapabilities::builder()
+            .enable_tools()
+            .enable_tool_list_changed()
+            .enable_prompts()
+            .enable_resources()
+            .build();
 
         let instructions = {
             let servers = self.manager.list_server_names();
@@ -107,6 +151,17 @@ impl HostService {
                 )
             }
         };
+        let instructions = if self.session_permissions().uses_tool_search() {
+            format!(
+                "{instructions} Tools are not listed directly: find them with {}, read their \
+                 parameters with {} and call them with {}.",
+                tool_search::SEARCH_TOOLS,
+                tool_search::DESCRIBE_TOOL,
+                tool_search::INVOKE_TOOL
+            )
+        } else {
+            instructions
+        };
 
         InitializeResult {
             protocol_version: ProtocolVersion::default(),
@@ -122,7 +177,18 @@ impl HostService {
         }
     }
 
+    /// Advertised tools: the meta-tools when the primary project uses tool search,
+    /// otherwise every visible tool.
     async fn list_tools(&self) -> std::result::Result<Vec<Tool>, McpError> {
+        if self.session_permissions().uses_tool_search() {
+            debug!("Tool search mode: advertising meta-tools only");
+            return Ok(tool_search::meta_tools());
+        }
+        self.visible_tools().await
+    }
+
+    /// Tools this session may use, as the agent sees them (renamed, retitled, reshaped).
+    async fn visible_tools(&self) -> std::result::Result<Vec<Tool>, McpError> {
         let entries = self
             .manager
             .list_tools()
@@ -132,17 +198,59 @@ impl HostService {
         // Permission control: Use tool-level permission check
         debug!("Filtering tools with tool-level permissions");
 
+        let permissions = self.session_permissions();
+        let upstream_names =
+            entries.iter().map(|entry| entry.tool.name.to_string()).collect::<HashSet<_>>();
         let mut filtered: Vec<Tool> = Vec::new();
         for entry in entries {
-            if self.is_tool_allowed(&entry.tool.name, &entry.server_id) {
+            if self.is_server_offered(&permissions, &entry.server_id)
+                && self.filter.allows_tool(&entry.server_id, &entry.tool)
+                && self.is_tool_allowed(&permissions, &entry.tool, &entry.server_id)
+            {
                 let mut tool = entry.tool;
 
-                // Apply custom description if configured
-                if let Some(custom_desc) = self.get_custom_description(&tool.name) {
+                // Apply custom description if configured (primary project)
+                if let Some(custom_desc) = permissions.description_for(&tool.name) {
                     debug!("Applying custom description for tool '{}'", tool.name);
                     tool.description = Some(custom_desc.into());
                 }
 
+                // Pinned arguments are filled in by the daemon, so the agent never sees them
+                let presets = permissions.presets_for(&tool.name);
+                if !presets.is_empty() {
+                    tool.input_schema = Arc::new(arguments::strip_schema_properties(
+                        &tool.input_schema,
+                        presets.keys().map(String::as_str),
+                    ));
+                }
+
+                // Alias, title and schema overrides (primary project)
+                if let Some(customization) = permissions.customization_for(&tool.name) {
+                    if let Some(title) = customization.title {
+                        tool.title = Some(title);
+                    }
+                    if customization.schema_patch.is_some()
+                        || !customization.hidden_parameters.is_empty()
+                    {
+                        tool.input_schema = Arc::new(schema::rewrite_input_schema(
+                            &tool.input_schema,
+                            customization.schema_patch.as_ref(),
+                            &customization.hidden_parameters,
+                        ));
+                    }
+                    if let Some(alias) = customization.alias {
+                        if upstream_names.contains(&alias) {
+                            warn!(
+                                "Alias '{}' for tool '{}' shadows an upstream tool; ignored",
+                                alias, tool.name
+                            );
+                        } else {
+                            debug!("Advertising tool '{}' as '{}'", tool.name, alias);
+                            tool.name = alias.into();
+                        }
+                    }
+                }
+
                 filtered.push(tool);
             } else {
                 debug!(
@@ -156,9 +264,187 @@ impl HostService {
         Ok(filtered)
     }
 
+    /// Evaluate the argument constraints of every bound project that allows the tool,
+    /// merged like tool visibility; rejected calls are recorded in the server's log.
+    async fn check_arguments(
+        &self,
+        permissions: &PermissionSnapshot,
+        params: &CallToolRequestParam,
+        entry: &ToolEntry,
+        redaction: &Redaction,
+    ) -> std::result::Result<(), McpError> {
+        let hints = ToolHints::from_annotations(entry.tool.annotations.as_ref());
+        let arguments = params.arguments.as_ref();
+        let checks = permissions
+            .policies()
+            .iter()
+            .filter(|policy| policy.allows_annotated_tool(&entry.server_id, &params.name, hints))
+            .map(|policy| policy.check_arguments(&params.name, arguments))
+            .collect::<Vec<_>>();
+        if self.binding.merge(checks.iter().map(Vec::is_empty)) {
+            return Ok(());
+        }
+
+        let mut violations = checks.into_iter().flatten().collect::<Vec<_>>();
+        violations.dedup();
+        warn!(
+            "Tool '{}' from server '{}' rejected by argument rules: {:?}",
+            params.name, entry.server_id, violations
+        );
+        if let Some(log) = self.manager.get_log_handle(&entry.server_id)
+            && let Err(err) = log
+                .log_policy_violation(
+                    &Uuid::new_v4().to_string(),
+                    &params.name,
+                    arguments,
+                    &violations,
+                    redaction,
+                )
+                .await
+        {
+            warn!(error = ?err, "failed to record argument policy violation");
+        }
+        let details = violations.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ");
+        let details = redaction.text(details);
+        Err(McpError::invalid_params(
+            format!("Arguments for tool '{}' violate project rules: {details}", params.name),
+            serde_json::to_value(&violations)
+                .ok()
+                .map(|violations| redaction.value(serde_json::json!({ "violations": violations }))),
+        ))
+    }
+
+    /// Park the call in the approval queue until someone approves it.
+    async fn await_approval(
+        &self,
+        params: &CallToolRequestParam,
+        server_id: &str,
+        redaction: &Redaction,
+    ) -> std::result::Result<(), McpError> {
+        let layout = self.layout.clone();
+        let timeout = tokio::task::spawn_blocking(move || WorkspaceSettings::load(&layout))
+            .await
+            .ok()
+            .and_then(Result::ok)
+            .map(|settings| settings.approvals.timeout())
+            .unwrap_or_else(|| ApprovalSettings::default().timeout());
+        let arguments = redaction
+            .arguments(params.arguments.as_ref())
+            .and_then(|args| serde_json::to_string_pretty(&args).ok());
+        let outcome = self
+            .manager
+            .approvals()
+            .request(
+                self.binding.primary_path().display().to_string(),
+                server_id.to_string(),
+                params.name.to_string(),
+                arguments,
+                timeout,
+            )
+            .await;
+        match outcome {
+            ApprovalOutcome::Approved => Ok(()),
+            ApprovalOutcome::Denied { reason } => {
+                let mut message = format!("Call to tool '{}' was denied by the user", params.name);
+                if let Some(reason) = reason {
+                    message.push_str(&format!(": {reason}"));
+                }
+                Err(McpError::invalid_request(message, None))
+            }
+            ApprovalOutcome::TimedOut => Err(McpError::invalid_request(
+                format!(
+                    "Call to tool '{}' was not approved within {} seconds",
+                    params.name,
+                    timeout.as_secs()
+                ),
+                None,
+            )),
+        }
+    }
+
+    /// Drop hidden parameters and fill in the arguments the primary project pins for this
+    /// tool, replacing whatever the agent sent; the secret ones are collected for log
+    /// redaction.
+    fn prepare_arguments(
+        &self,
+        permissions: &PermissionSnapshot,
+        params: &mut CallToolRequestParam,
+    ) -> Redaction {
+        let mut redaction = Redaction::default();
+        if let Some(customization) = permissions.customization_for(&params.name)
+            && let Some(arguments) = params.arguments.as_mut()
+        {
+            for hidden in &customization.hidden_parameters {
+                if arguments.remove(hidden).is_some() {
+                    debug!("  dropped hidden argument: {}", hidden);
+                }
+            }
+        }
+        let presets = permissions.presets_for(&params.name);
+        if presets.is_empty() {
+            return redaction;
+        }
+        let arguments = params.arguments.get_or_insert_with(Default::default);
+        for (name, preset) in presets {
+            debug!("  pinned argument: {}", name);
+            if preset.secret {
+                redaction.insert(&name, &preset.value);
+            }
+            arguments.insert(name, preset.value);
+        }
+        redaction
+    }
+
     async fn call_tool(
         &self,
         params: CallToolRequestParam,
+    ) -> std::result::Result<ServerResult, McpError> {
+        if self.session_permissions().uses_tool_search()
+            && let Some(call) = MetaCall::parse(&params)
+        {
+            return self.call_meta_tool(call?).await;
+        }
+        self.call_upstream_tool(params, None).await
+    }
+
+    /// Answer a meta-tool call from the visible tools; `invoke_tool` takes the same path
+    /// as a direct call.
+    async fn call_meta_tool(&self, call: MetaCall) -> std::result::Result<ServerResult, McpError> {
+        debug!("Meta-tool call: {:?}", call);
+        let result = match call {
+            MetaCall::Search { query, limit } => {
+                let tools = self.visible_tools().await?;
+                let matches = tool_search::rank(&query, &tools, limit)
+                    .into_iter()
+                    .map(tool_search::summary)
+                    .collect::<Vec<_>>();
+                CallToolResult::structured(serde_json::json!({ "tools": matches }))
+            }
+            MetaCall::Describe { name } => {
+                let tools = self.visible_tools().await?;
+                let tool = tools.iter().find(|tool| tool.name == name).ok_or_else(|| {
+                    McpError::invalid_params(
+                        format!("Tool '{name}' is not available in this session"),
+                        None,
+                    )
+                })?;
+                CallToolResult::structured(tool_search::definition(tool))
+            }
+            MetaCall::Invoke { name, arguments } => {
+                return self
+                    .call_upstream_tool(CallToolRequestParam { name: name.into(), arguments }, None)
+                    .await;
+            }
+        };
+        Ok(ServerResult::CallToolResult(result))
+    }
+
+    /// Route a call to the server of the tool. `composite` names the composite tool whose
+    /// step this call is, if any.
+    async fn call_upstream_tool(
+        &self,
+        mut params: CallToolRequestParam,
+        composite: Option<&str>,
     ) -> std::result::Result<ServerResult, McpError> {
         debug!("=== DEBUG: Tool Call Received ===");
         debug!("  tool_name: {}", params.name);
@@ -167,34 +453,310 @@ impl HostService {
             serde_json::to_string_pretty(&params.arguments).unwrap_or_else(|_| "{}".to_string())
         );
 
+        // Session scope (connect --servers/--profile/--read-only) narrows further
+        let permissions = self.session_permissions();
+        let mut entries = self
+            .manager
+            .list_tools()
+            .await
+            .map_err(|err| McpError::internal_error(err.to_string(), None))?;
+        // A renamed tool is only reachable under its alias, unless the alias was ignored
+        // for shadowing another upstream tool
+        if let Some(alias) =
+            permissions.customization_for(&params.name).and_then(|custom| custom.alias)
+            && !entries.iter().any(|entry| entry.tool.name == alias)
+        {
+            warn!("Tool '{}' called by its upstream name instead of '{}'", params.name, alias);
+            return Err(McpError::invalid_params(
+                format!("Tool '{}' is not available in this project", params.name),
+                None,
+            ));
+        }
+        entries.retain(|entry| self.is_server_offered(&permissions, &entry.server_id));
+        let mut entry = entries.iter().find(|entry| entry.tool.name == params.name);
+        // Aliased tools are advertised under the project's name; route to the upstream one
+        if entry.is_none()
+            && let Some(upstream) = permissions.upstream_for_alias(&params.name)
+        {
+            debug!("  alias '{}' resolves to '{}'", params.name, upstream);
+            entry = entries.iter().find(|entry| entry.tool.name == upstream);
+            params.name = upstream.into();
+        }
+        if let Some(entry) = entry
+            && !self.filter.allows_tool(&entry.server_id, &entry.tool)
+        {
+            warn!(
+                "Tool '{}' from server '{}' is outside the session scope",
+                params.name, entry.server_id
+            );
+            return Err(McpError::invalid_params(
+                format!(
+                    "Tool '{}' from server '{}' is not available in this session",
+                    params.name, entry.server_id
+                ),
+                None,
+            ));
+        }
+
         // Permission control: Use tool-level permission check
-        if let Ok(tool_server_id) = self.manager.get_server_for_tool(&params.name).await {
-            if !self.is_tool_allowed(&params.name, &tool_server_id) {
+        let mut redaction = Redaction::default();
+        if let Some(entry) = entry {
+            if !self.is_tool_allowed(&permissions, &entry.tool, &entry.server_id) {
                 warn!(
                     "Tool '{}' from server '{}' not allowed for this project",
-                    params.name, tool_server_id
+                    params.name, entry.server_id
                 );
                 return Err(McpError::invalid_params(
                     format!(
                         "Tool '{}' from server '{}' is not allowed for this project",
-                        params.name, tool_server_id
+                        params.name, entry.server_id
                     ),
                     None,
                 ));
             }
-            debug!("  server: {} (allowed)", tool_server_id);
+            debug!("  server: {} (allowed)", entry.server_id);
+
+            redaction = self.prepare_arguments(&permissions, &mut params);
+            self.check_arguments(&permissions, &params, entry, &redaction).await?;
+            self.check_input_schema(&params, entry, &redaction)?;
+
+            if permissions
+                .policies()
+                .iter()
+                .any(|policy| policy.requires_approval(&params.name))
+            {
+                self.await_approval(&params, &entry.server_id, &redaction).await?;
+            }
         }
 
-        let result = self.manager.call_tool(params).await?;
+        let composite_tool = entry.and_then(|entry| {
+            self.manager
+                .composite_tool(&entry.server_id, &params.name)
+                .map(|tool| (entry, tool))
+        });
+        if let (Some(parent), Some(_)) = (composite, &composite_tool) {
+            return Err(McpError::invalid_params(
+                format!("composite tool '{parent}' cannot call composite tool '{}'", params.name),
+                None,
+            ));
+        }
+
+        let result = match (entry, composite_tool) {
+            (_, Some((entry, tool))) => {
+                let call = self.run_composite(&params, tool);
+                self.call_in_process(&entry.server_id, &params, &redaction, call).await?
+            }
+            (Some(entry), None) if entry.server_id == management::SERVER_ID => {
+                self.call_management_tool(&permissions, &params, &redaction).await?
+            }
+            _ => {
+                let project = permissions.project_call();
+                self.manager.call_tool(params, &redaction, project).await?
+            }
+        };
+
+        if let Some(entry) = entry {
+            self.check_output_schema(entry, &result);
+        }
 
         debug!(
             "  result: {}",
-            serde_json::to_string(&result).unwrap_or_else(|_| "error".to_string())
+            serde_json::to_string(&result)
+                .map(|result| redaction.text(result))
+                .unwrap_or_else(|_| "error".to_string())
         );
         debug!("=== DEBUG: Tool Call Complete ===");
 
         Ok(ServerResult::CallToolResult(result))
     }
+
+    /// Reject arguments that do not match the tool's input schema, naming every failing path.
+    fn check_input_schema(
+        &self,
+        params: &CallToolRequestParam,
+        entry: &ToolEntry,
+        redaction: &Redaction,
+    ) -> std::result::Result<(), McpError> {
+        if !self.manager.validates_schemas(&entry.server_id) {
+            return Ok(());
+        }
+        let violations = self.manager.schemas().check_arguments(
+            &entry.server_id,
+            &entry.tool,
+            params.arguments.as_ref(),
+        );
+        if violations.is_empty() {
+            return Ok(());
+        }
+        let details = violations.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ");
+        let details = redaction.text(details);
+        warn!(
+            "Tool '{}' from server '{}' called with invalid arguments: {details}",
+            params.name, entry.server_id
+        );
+        Err(McpError::invalid_params(
+            format!(
+                "Arguments for tool '{}' do not match its input schema: {details}",
+                params.name
+            ),
+            serde_json::to_value(&violations)
+                .ok()
+                .map(|violations| redaction.value(serde_json::json!({ "violations": violations }))),
+        ))
+    }
+
+    /// Warn when structured content does not match the tool's output schema.
+    fn check_output_schema(&self, entry: &ToolEntry, result: &CallToolResult) {
+        let Some(structured) = &result.structured_content else {
+            return;
+        };
+        if result.is_error == Some(true) || !self.manager.validates_schemas(&entry.server_id) {
+            return;
+        }
+        let violations =
+            self.manager.schemas().check_output(&entry.server_id, &entry.tool, structured);
+        if !violations.is_empty() {
+            let details = violations.iter().map(ToString::to_string).collect::<Vec<_>>();
+            warn!(
+                "Structured result of tool '{}' from server '{}' does not match its output \
+                 schema: {}",
+                entry.tool.name,
+                entry.server_id,
+                details.join("; ")
+            );
+        }
+    }
+
+    /// Answer a built-in management tool in-process, logged like an upstream call.
+    async fn call_management_tool(
+        &self,
+        permissions: &PermissionSnapshot,
+        params: &CallToolRequestParam,
+        redaction: &Redaction,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let project = self.binding.primary_path();
+        let cx = ManagementContext {
+            manager: &self.manager,
+            layout: &self.layout,
+            registry: &self.registry,
+            project: &project,
+            permissions,
+        };
+        let result = self
+            .call_in_process(
+                management::SERVER_ID,
+                params,
+                redaction,
+                management::call(&cx, params),
+            )
+            .await;
+        // A project granted itself access; sessions must see the new grant
+        if params.name == management::REQUEST_TOOL_ACCESS && result.is_ok() {
+            self.registry.notify_changed();
+        }
+        result
+    }
+
+    /// Call each step of a composite tool in turn, as this session, stopping at the first
+    /// failure or when the tool's timeout runs out. The result lists every step that ran.
+    async fn run_composite(
+        &self,
+        params: &CallToolRequestParam,
+        tool: ScriptTool,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let timeout = Duration::from_secs(tool.timeout_secs());
+        match tokio::time::timeout(timeout, self.run_steps(params, tool.steps)).await {
+            Ok(result) => result,
+            Err(_) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "'{}' did not finish within {} seconds",
+                tool.name,
+                timeout.as_secs()
+            ))])),
+        }
+    }
+
+    async fn run_steps(
+        &self,
+        params: &CallToolRequestParam,
+        steps: Vec<CompositeStep>,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let arguments = params.arguments.clone().unwrap_or_default();
+        let mut results = Vec::with_capacity(steps.len());
+        for step in steps {
+            let step_arguments = match script::render_value(&step.arguments, &arguments) {
+                Some(serde_json::Value::Object(step_arguments)) => Some(step_arguments),
+                _ => None,
+            };
+            let call =
+                CallToolRequestParam { name: step.tool.clone().into(), arguments: step_arguments };
+            // Boxed: a step goes through call_upstream_tool again
+            let outcome = Box::pin(self.call_upstream_tool(call, Some(&params.name))).await;
+            let (result, failed) = match outcome {
+                Ok(ServerResult::CallToolResult(result)) => {
+                    let failed = result.is_error == Some(true);
+                    (serde_json::to_value(&result).unwrap_or_default(), failed)
+                }
+                Ok(other) => (serde_json::to_value(&other).unwrap_or_default(), false),
+                Err(err) => (serde_json::json!({ "error": err.message }), true),
+            };
+            results.push(serde_json::json!({ "tool": step.tool, "result": result }));
+            if failed {
+                return Ok(CallToolResult::structured_error(serde_json::json!({
+                    "steps": results
+                })));
+            }
+        }
+        Ok(CallToolResult::structured(serde_json::json!({ "steps": results })))
+    }
+
+    /// Run a tool answered by the daemon itself, logged to `server_id` like an upstream call.
+    async fn call_in_process(
+        &self,
+        server_id: &str,
+        params: &CallToolRequestParam,
+        redaction: &Redaction,
+        call: impl Future<Output = std::result::Result<CallToolResult, McpError>>,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let log = self.manager.get_log_handle(server_id);
+        let call_id = Uuid::new_v4().to_string();
+        if let Some(log) = &log
+            && let Err(err) = log
+                .log_tool_request(&call_id, &params.name, params.arguments.as_ref(), redaction)
+                .await
+        {
+            warn!(error = ?err, "failed to record tool request log entry");
+        }
+        let start = Instant::now();
+        let result = call.await;
+        if let Some(log) = &log {
+            let logged = match &result {
+                Ok(result) => {
+                    log.log_tool_response(
+                        &call_id,
+                        &params.name,
+                        start.elapsed(),
+                        result,
+                        redaction,
+                    )
+                    .await
+                }
+                Err(err) => {
+                    log.log_tool_error(
+                        &call_id,
+                        &params.name,
+                        start.elapsed(),
+                        &ServiceError::McpError(err.clone()),
+                        redaction,
+                    )
+                    .await
+                }
+            };
+            if let Err(err) = logged {
+                warn!(error = ?err, "failed to record tool result log entry");
+            }
+        }
+        result
+    }
 }
 
 impl Service<RoleServer> for HostService {
@@ -221,20 +783,29 @@ impl Service<RoleServer> for HostService {
             ClientRequest::SetLevelRequest(_) => {
                 Err(McpError::method_not_found::<SetLevelRequestMethod>())
             }
-            ClientRequest::GetPromptRequest(_) => {
-                Err(McpError::method_not_found::<GetPromptRequestMethod>())
-            }
-            ClientRequest::ListPromptsRequest(_) => {
-                Err(McpError::method_not_found::<ListPromptsRequestMethod>())
-            }
-            ClientRequest::ListResourcesRequest(_) => {
-                Err(McpError::method_not_found::<ListResourcesRequestMethod>())
+            ClientRequest::GetPromptRequest(GetPromptRequest { params, .. }) => {
+                let result =
+                    self.library().await?.get_prompt(&params.name, params.arguments.as_ref())?;
+                Ok(ServerResult::GetPromptResult(result))
             }
+            ClientRequest::ListPromptsRequest(_) => Ok(ServerResult::ListPromptsResult(
+                ListPromptsResult::with_all_items(self.library().await?.prompts()),
+            )),
+            ClientRequest::ListResourcesRequest(_) => Ok(ServerResult::ListResourcesResult(
+                ListResourcesResult::with_all_items(self.library().await?.resources()),
+            )),
             ClientRequest::ListResourceTemplatesRequest(_) => {
-                Err(McpError::method_not_found::<ListResourceTemplatesRequestMethod>())
+                Ok(ServerResult::ListResourceTemplatesResult(
+                    ListResourceTemplatesResult::with_all_items(Vec::new()),
+                ))
             }
-            ClientRequest::ReadResourceRequest(_) => {
-                Err(McpError::method_not_found::<ReadResourceRequestMethod>())
+            ClientRequest::ReadResourceRequest(ReadResourceRequest { params, .. }) => {
+                let result = if params.uri.starts_with(limits::OUTPUT_URI_PREFIX) {
+                    limits::read_page(&self.layout.outputs_dir(), &params.uri).await?
+                } else {
+                    self.read_resource(params.uri).await?
+                };
+                Ok(ServerResult::ReadResourceResult(result))
             }
             ClientRequest::SubscribeRequest(_) => {
                 Err(McpError::method_not_found::<SubscribeRequestMethod>())
@@ -247,9 +818,46 @@ impl Service<RoleServer> for HostService {
 
     async fn handle_notification(
         &self,
-        _notification: <RoleServer as rmcp::service::ServiceRole>::PeerNot,
-        _context: NotificationContext<RoleServer>,
+        notification: <RoleServer as rmcp::service::ServiceRole>::PeerNot,
+        context: NotificationContext<RoleServer>,
     ) -> Result<(), McpError> {
+        if let ClientNotification::RootsListChangedNotification(_) = notification {
+            // 不能在通知处理中直接等待 roots/list 的响应，放到独立任务里重新绑定
+            let peer = context.peer;
+            let registry = self.registry.clone();
+            let binding = self.binding.clone();
+            let layout = self.layout.clone();
+            tokio::spawn(async move {
+                let roots = match peer.list_roots().await {
+                    Ok(result) => session::root_paths(&result.roots),
+                    Err(err) => {
+                        warn!(error = ?err, "failed to refresh workspace roots");
+                        return;
+                    }
+                };
+                if roots.is_empty() {
+                    return;
+                }
+                let primary = binding.primary();
+                let template = registry
+                    .load(&primary)
+                    .unwrap_or_else(|_| ProjectRecord::new(primary, roots[0].clone()));
+                let rules = WorkspaceSettings::load(&layout)
+                    .map(|settings| settings.project_rules)
+                    .unwrap_or_default();
+                let bound = tokio::task::spawn_blocking(move || {
+                    session::bind_roots(&registry, &binding, &roots, &template, &rules);
+                })
+                .await;
+                if let Err(err) = bound {
+                    warn!(error = ?err, "binding workspace roots failed");
+                    return;
+                }
+                if let Err(err) = peer.notify_tool_list_changed().await {
+                    warn!(error = ?err, "failed to notify tool list change after roots update");
+                }
+            });
+        }
         Ok(())
     }
 
@@ -257,3 +865,123 @@ impl Service<RoleServer> for HostService {
         self.server_info()
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::fs;
+
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::{ProjectId, config::settings::RootMergePolicy};
+
+    const OPS: &str = r#"
+[mcp_server]
+id = "ops"
+name = "Ops"
+protocol = "script"
+enabled = true
+
+[[mcp_server.tools]]
+name = "greet"
+input_schema = { type = "object", properties = { name = { type = "string" } } }
+command = "echo"
+args = ["hello", "{{name}}"]
+"#;
+
+    /// A session bound to a fresh project that may use the `script` server in `config`.
+    async fn session(
+        config: &str,
+        customize: impl FnOnce(&mut ProjectRecord),
+    ) -> (tempfile::TempDir, HostService) {
+        let temp = tempfile::tempdir().unwrap();
+        let layout = Layout::new(temp.path().join("center"));
+        layout.ensure().unwrap();
+        fs::write(layout.server_config_path("ops"), config).unwrap();
+        let registry = ProjectRegistry::new(&layout);
+        registry.ensure().unwrap();
+
+        let root = temp.path().join("project");
+        fs::create_dir_all(&root).unwrap();
+        let id = ProjectId::from_path(&root);
+        let mut record = ProjectRecord::new(id.clone(), root.clone());
+        record.allowed_server_ids = vec!["ops".into()];
+        customize(&mut record);
+        registry.store(&record).unwrap();
+
+        let manager = Arc::new(ServerManager::start(layout.clone()).await.unwrap());
+        let binding = Arc::new(ProjectBinding::new(id, root, RootMergePolicy::default()));
+        let host = HostService::new(manager, layout, binding, registry, SessionFilter::default());
+        (temp, host)
+    }
+
+    async fn call(
+        host: &HostService,
+        name: &'static str,
+        arguments: Value,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        let params =
+            CallToolRequestParam { name: name.into(), arguments: arguments.as_object().cloned() };
+        match host.call_upstream_tool(params, None).await? {
+            ServerResult::CallToolResult(result) => Ok(result),
+            other => panic!("unexpected result {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn aliased_tools_are_only_reachable_by_their_alias() {
+        let (_temp, host) = session(OPS, |record| {
+            record.customization_mut("greet").alias = Some("say_hello".into());
+        })
+        .await;
+
+        let result = call(&host, "say_hello", json!({ "name": "ada" })).await.unwrap();
+        assert_eq!(result.structured_content.unwrap()["stdout"], json!("hello ada\n"));
+        let err = call(&host, "greet", json!({ "name": "ada" })).await.unwrap_err();
+        assert!(err.message.contains("not available"), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn calls_must_match_the_input_schema() {
+        let (_temp, host) = session(OPS, |_| {}).await;
+
+        let err = call(&host, "greet", json!({ "name": 7 })).await.unwrap_err();
+        assert!(err.message.contains("do not match its input schema"), "{err:?}");
+        assert_eq!(err.data.unwrap()["violations"][0]["path"], json!("/name"));
+        assert!(call(&host, "greet", json!({ "name": "ada" })).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn schema_checks_can_be_turned_off_per_server() {
+        let config = OPS.replace("enabled = true", "enabled = true\nvalidate_schemas = false");
+        let (_temp, host) = session(&config, |_| {}).await;
+
+        let result = call(&host, "greet", json!({ "name": 7 })).await.unwrap();
+        assert_eq!(result.structured_content.unwrap()["stdout"], json!("hello 7\n"));
+    }
+
+    #[tokio::test]
+    async fn composite_tools_stop_at_their_timeout() {
+        let config = format!(
+            r#"{OPS}
+[[mcp_server.tools]]
+name = "stall"
+command = "sleep"
+args = ["30"]
+
+[[mcp_server.tools]]
+name = "greet_slowly"
+timeout_secs = 1
+steps = [{{ tool = "greet" }}, {{ tool = "stall" }}]
+"#
+        );
+        let (_temp, host) = session(&config, |_| {}).await;
+
+        let started = Instant::now();
+        let result = call(&host, "greet_slowly", json!({})).await.unwrap();
+        assert_eq!(result.is_error, Some(true));
+        assert!(started.elapsed() < Duration::from_secs(10));
+        let message = serde_json::to_value(&result).unwrap()["content"][0]["text"].clone();
+        assert_eq!(message, json!("'greet_slowly' did not finish within 1 seconds"));
+    }
+}
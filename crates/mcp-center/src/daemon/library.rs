@@ -0,0 +1,517 @@
+//! Prompt templates and reference documents served by mcp-center itself.
+//!
+//! Prompts live in `config/prompts/` and resources in `config/resources/`; a project can
+//! add its own under `<root>/.mcp-center/prompts/` and `<root>/.mcp-center/resources/`,
+//! which win over workspace entries of the same name. Files are read on every request, so
+//! edits show up without restarting the daemon.
+//!
+//! A prompt is either `<name>.toml` with a `template` key, or `<name>.md` whose body is the
+//! template, optionally preceded by TOML front matter between `+++` lines. Both may set
+//! `title`, `description` and `[[arguments]]`; `{{argument}}` placeholders are filled in
+//! when the prompt is fetched. Without declared arguments every placeholder is a required
+//! argument. Every file below `resources/` is a resource named by its relative path.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result, bail};
+use rmcp::{
+    ErrorData as McpError,
+    model::{
+        AnnotateAble, GetPromptResult, JsonObject, Prompt, PromptArgument, PromptMessage,
+        PromptMessageRole, RawResource, ReadResourceResult, Resource, ResourceContents,
+    },
+};
+use serde::Deserialize;
+use serde_json::Value;
+use tracing::warn;
+
+use crate::Layout;
+
+/// Directory below a project root holding the project's own prompts and resources.
+pub const PROJECT_LIBRARY_DIR: &str = ".mcp-center";
+
+/// URI prefix of every resource served from the library.
+pub const RESOURCE_URI_PREFIX: &str = "mcp-center://resources/";
+
+/// Largest resource file that is served.
+pub const MAX_RESOURCE_BYTES: u64 = 1024 * 1024;
+
+const FRONT_MATTER_DELIMITER: &str = "+++";
+
+/// Prompts and resources visible to one session.
+#[derive(Debug, Default)]
+pub struct Library {
+    prompts: BTreeMap<String, PromptTemplate>,
+    resources: BTreeMap<String, ResourceFile>,
+}
+
+impl Library {
+    /// Workspace entries overlaid with those of `project`, if any. Unreadable entries are
+    /// skipped with a warning.
+    pub fn load(layout: &Layout, project: Option<&Path>) -> Self {
+        let mut library = Self::default();
+        library.add_dirs(layout.prompts_dir(), layout.resources_dir());
+        if let Some(root) = project {
+            let dir = root.join(PROJECT_LIBRARY_DIR);
+            library.add_dirs(&dir.join("prompts"), &dir.join("resources"));
+        }
+        library
+    }
+
+    fn add_dirs(&mut self, prompts_dir: &Path, resources_dir: &Path) {
+        for path in read_files(prompts_dir) {
+            match PromptTemplate::from_file(&path) {
+                Ok(Some(prompt)) => {
+                    self.prompts.insert(prompt.name.clone(), prompt);
+                }
+                Ok(None) => {}
+                Err(err) => warn!(path = %path.display(), error = ?err, "skipping prompt"),
+            }
+        }
+        let mut pending = vec![resources_dir.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let Ok(file_type) = entry.file_type() else {
+                    continue;
+                };
+                if is_hidden(&path) {
+                    continue;
+                }
+                if file_type.is_dir() {
+                    pending.push(path);
+                } else if file_type.is_file()
+                    && let Some(resource) = ResourceFile::new(resources_dir, path)
+                {
+                    self.resources.insert(resource.name.clone(), resource);
+                }
+            }
+        }
+    }
+
+    pub fn prompts(&self) -> Vec<Prompt> {
+        self.prompts.values().map(PromptTemplate::to_prompt).collect()
+    }
+
+    /// Render the prompt `name` with `arguments`.
+    pub fn get_prompt(
+        &self,
+        name: &str,
+        arguments: Option<&JsonObject>,
+    ) -> Result<GetPromptResult, McpError> {
+        let prompt = self
+            .prompts
+            .get(name)
+            .ok_or_else(|| McpError::invalid_params(format!("Prompt '{name}' not found"), None))?;
+        let text = prompt
+            .render(arguments)
+            .map_err(|err| McpError::invalid_params(format!("Prompt '{name}': {err}"), None))?;
+        Ok(GetPromptResult {
+            description: prompt.description.clone(),
+            messages: vec![PromptMessage::new_text(PromptMessageRole::User, text)],
+        })
+    }
+
+    pub fn resources(&self) -> Vec<Resource> {
+        self.resources.values().map(ResourceFile::to_resource).collect()
+    }
+
+    /// Read the resource behind `uri`, looking up only that file: the project entry if
+    /// there is one, otherwise the workspace entry.
+    pub fn read_resource(
+        layout: &Layout,
+        project: Option<&Path>,
+        uri: &str,
+    ) -> Result<ReadResourceResult, McpError> {
+        let project_dir = project.map(|root| root.join(PROJECT_LIBRARY_DIR).join("resources"));
+        let resource = uri
+            .strip_prefix(RESOURCE_URI_PREFIX)
+            .and_then(|name| {
+                project_dir
+                    .iter()
+                    .map(PathBuf::as_path)
+                    .chain([layout.resources_dir()])
+                    .find_map(|dir| ResourceFile::find(dir, name))
+            })
+            .ok_or_else(|| {
+                McpError::resource_not_found(format!("Resource '{uri}' not found"), None)
+            })?;
+        let text = resource
+            .read()
+            .map_err(|err| McpError::internal_error(format!("Resource '{uri}': {err}"), None))?;
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::TextResourceContents {
+                uri: uri.to_string(),
+                mime_type: Some(resource.mime_type().to_string()),
+                text,
+                meta: None,
+            }],
+        })
+    }
+}
+
+/// Regular, non-hidden files directly inside `dir`, sorted by path.
+fn read_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_file()))
+        .map(|entry| entry.path())
+        .filter(|path| !is_hidden(path))
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('.'))
+}
+
+/// Keys shared by `.toml` prompts and Markdown front matter.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct PromptDocument {
+    title: Option<String>,
+    description: Option<String>,
+    arguments: Vec<ArgumentSpec>,
+    /// Only in `.toml` prompts; the body of a Markdown prompt is its template.
+    template: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+struct ArgumentSpec {
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    required: bool,
+}
+
+/// A prompt file ready to be listed and rendered.
+#[derive(Debug, Clone)]
+pub struct PromptTemplate {
+    name: String,
+    title: Option<String>,
+    description: Option<String>,
+    arguments: Vec<ArgumentSpec>,
+    template: String,
+}
+
+impl PromptTemplate {
+    /// Parse a `.toml` or `.md` prompt; `None` for any other file.
+    pub fn from_file(path: &Path) -> Result<Option<Self>> {
+        let (Some(name), Some(extension)) = (
+            path.file_stem().and_then(|stem| stem.to_str()),
+            path.extension().and_then(|extension| extension.to_str()),
+        ) else {
+            return Ok(None);
+        };
+        let parse = match extension {
+            "toml" => Self::from_toml,
+            "md" => Self::from_markdown,
+            _ => return Ok(None),
+        };
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        parse(name, &content).map(Some)
+    }
+
+    fn from_toml(name: &str, content: &str) -> Result<Self> {
+        let mut document: PromptDocument = toml_edit::de::from_str(content)?;
+        let Some(template) = document.template.take() else {
+            bail!("missing 'template'");
+        };
+        Ok(Self::new(name, document, template))
+    }
+
+    fn from_markdown(name: &str, content: &str) -> Result<Self> {
+        let Some((front_matter, body)) = split_front_matter(content) else {
+            return Ok(Self::new(name, PromptDocument::default(), content.to_string()));
+        };
+        let document: PromptDocument = toml_edit::de::from_str(front_matter)?;
+        if document.template.is_some() {
+            bail!("'template' is not allowed in front matter; the body is the template");
+        }
+        Ok(Self::new(name, document, body.to_string()))
+    }
+
+    fn new(name: &str, document: PromptDocument, template: String) -> Self {
+        let arguments = if document.arguments.is_empty() {
+            placeholders(&template)
+                .into_iter()
+                .map(|name| ArgumentSpec { name, description: None, required: true })
+                .collect()
+        } else {
+            document.arguments
+        };
+        Self {
+            name: name.to_string(),
+            title: document.title,
+            description: document.description,
+            arguments,
+            template: template.trim().to_string(),
+        }
+    }
+
+    pub fn to_prompt(&self) -> Prompt {
+        Prompt {
+            name: self.name.clone(),
+            title: self.title.clone(),
+            description: self.description.clone(),
+            arguments: (!self.arguments.is_empty()).then(|| {
+                self.arguments
+                    .iter()
+                    .map(|argument| PromptArgument {
+                        name: argument.name.clone(),
+                        title: None,
+                        description: argument.description.clone(),
+                        required: Some(argument.required),
+                    })
+                    .collect()
+            }),
+            icons: None,
+        }
+    }
+
+    /// The template with declared arguments substituted; placeholders naming no argument
+    /// are left as they are.
+    pub fn render(&self, arguments: Option<&JsonObject>) -> Result<String, String> {
+        let value = |name: &str| {
+            arguments.and_then(|arguments| arguments.get(name)).map(|value| match value {
+                Value::String(text) => text.clone(),
+                other => other.to_string(),
+            })
+        };
+        if let Some(missing) = self
+            .arguments
+            .iter()
+            .find(|argument| argument.required && value(&argument.name).is_none())
+        {
+            return Err(format!("missing required argument '{}'", missing.name));
+        }
+
+        let mut rendered = String::with_capacity(self.template.len());
+        let mut rest = self.template.as_str();
+        while let Some(start) = rest.find("{{") {
+            let Some(end) = rest[start..].find("}}").map(|end| start + end) else {
+                break;
+            };
+            let key = rest[start + 2..end].trim();
+            rendered.push_str(&rest[..start]);
+            if self.arguments.iter().any(|argument| argument.name == key) {
+                rendered.push_str(&value(key).unwrap_or_default());
+            } else {
+                rendered.push_str(&rest[start..end + 2]);
+            }
+            rest = &rest[end + 2..];
+        }
+        rendered.push_str(rest);
+        Ok(rendered)
+    }
+}
+
+/// `(front matter, body)` of a Markdown prompt starting with a `+++` line.
+fn split_front_matter(content: &str) -> Option<(&str, &str)> {
+    let rest = content.strip_prefix(FRONT_MATTER_DELIMITER)?;
+    let rest = rest.strip_prefix("\r\n").or_else(|| rest.strip_prefix('\n'))?;
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == FRONT_MATTER_DELIMITER {
+            return Some((&rest[..offset], &rest[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Distinct `{{name}}` placeholders of `template` in order of appearance.
+fn placeholders(template: &str) -> Vec<String> {
+    let mut seen = BTreeSet::new();
+    let mut names = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}").map(|end| start + end) else {
+            break;
+        };
+        let key = rest[start + 2..end].trim();
+        if !key.is_empty()
+            && key.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+            && seen.insert(key.to_string())
+        {
+            names.push(key.to_string());
+        }
+        rest = &rest[end + 2..];
+    }
+    names
+}
+
+/// A file below a `resources/` directory.
+#[derive(Debug, Clone)]
+struct ResourceFile {
+    /// Path relative to the `resources/` directory, `/`-separated.
+    name: String,
+    path: PathBuf,
+    size: u64,
+}
+
+impl ResourceFile {
+    fn new(base: &Path, path: PathBuf) -> Option<Self> {
+        let relative = path.strip_prefix(base).ok()?;
+        let name = relative
+            .components()
+            .map(|component| component.as_os_str().to_str())
+            .collect::<Option<Vec<_>>>()?
+            .join("/");
+        let size = fs::metadata(&path).ok()?.len();
+        Some(Self { name, path, size })
+    }
+
+    /// The resource `name` below `base`, following the same rules as the directory walk:
+    /// no hidden components and no symbolic links.
+    fn find(base: &Path, name: &str) -> Option<Self> {
+        let mut path = base.to_path_buf();
+        for component in name.split('/') {
+            if component.is_empty() || component.starts_with('.') || component.contains('\\') {
+                return None;
+            }
+            if path != base && !fs::symlink_metadata(&path).ok()?.is_dir() {
+                return None;
+            }
+            path.push(component);
+        }
+        if !fs::symlink_metadata(&path).ok()?.is_file() {
+            return None;
+        }
+        Self::new(base, path)
+    }
+
+    fn uri(&self) -> String {
+        format!("{RESOURCE_URI_PREFIX}{}", self.name)
+    }
+
+    fn mime_type(&self) -> &'static str {
+        match self.path.extension().and_then(|extension| extension.to_str()) {
+            Some("md" | "markdown") => "text/markdown",
+            Some("json") => "application/json",
+            Some("toml") => "application/toml",
+            Some("yaml" | "yml") => "application/yaml",
+            Some("html" | "htm") => "text/html",
+            Some("csv") => "text/csv",
+            _ => "text/plain",
+        }
+    }
+
+    fn to_resource(&self) -> Resource {
+        let mut resource = RawResource::new(self.uri(), self.name.clone());
+        resource.mime_type = Some(self.mime_type().to_string());
+        resource.size = u32::try_from(self.size).ok();
+        resource.no_annotation()
+    }
+
+    fn read(&self) -> Result<String> {
+        if self.size > MAX_RESOURCE_BYTES {
+            bail!("larger than {MAX_RESOURCE_BYTES} bytes");
+        }
+        let bytes = fs::read(&self.path)?;
+        String::from_utf8(bytes).context("not UTF-8 text")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn markdown_prompts_take_front_matter_and_infer_arguments() {
+        let prompt = PromptTemplate::from_markdown(
+            "review",
+            "+++\ndescription = \"Review a file\"\n\n[[arguments]]\nname = \"file\"\n\
+             required = true\n\n[[arguments]]\nname = \"focus\"\n+++\n\
+             Review {{ file }} focusing on {{focus}}. Keep {{literal}}.\n",
+        )
+        .unwrap();
+        assert_eq!(prompt.description.as_deref(), Some("Review a file"));
+        let arguments = json!({ "file": "main.rs" });
+        assert_eq!(
+            prompt.render(arguments.as_object()).unwrap(),
+            "Review main.rs focusing on . Keep {{literal}}."
+        );
+        assert!(prompt.render(None).unwrap_err().contains("'file'"));
+
+        let inferred = PromptTemplate::from_markdown("plain", "Explain {{topic}} to {{who}}.")
+            .unwrap()
+            .to_prompt();
+        let names = inferred.arguments.unwrap().into_iter().map(|a| a.name).collect::<Vec<_>>();
+        assert_eq!(names, ["topic", "who"]);
+
+        assert!(PromptTemplate::from_toml("empty", "description = \"x\"").is_err());
+    }
+
+    #[test]
+    fn project_entries_override_workspace_entries() {
+        let temp = tempfile::tempdir().unwrap();
+        let layout = Layout::new(temp.path().join("root"));
+        layout.ensure().unwrap();
+        fs::write(layout.prompts_dir().join("greet.toml"), "template = \"Hello {{name}}\"")
+            .unwrap();
+        fs::write(layout.prompts_dir().join("notes.txt"), "ignored").unwrap();
+        fs::create_dir_all(layout.resources_dir().join("guides")).unwrap();
+        fs::write(layout.resources_dir().join("guides/style.md"), "# Workspace style").unwrap();
+
+        let project = temp.path().join("project");
+        let project_dir = project.join(PROJECT_LIBRARY_DIR);
+        fs::create_dir_all(project_dir.join("prompts")).unwrap();
+        fs::create_dir_all(project_dir.join("resources/guides")).unwrap();
+        fs::write(project_dir.join("prompts/greet.md"), "Hi {{name}}!").unwrap();
+        fs::write(project_dir.join("resources/guides/style.md"), "# Project style").unwrap();
+
+        let workspace = Library::load(&layout, None);
+        assert_eq!(workspace.prompts().len(), 1);
+        let arguments = json!({ "name": "Ada" });
+        let rendered = workspace.get_prompt("greet", arguments.as_object()).unwrap();
+        assert_eq!(
+            rendered.messages,
+            vec![PromptMessage::new_text(PromptMessageRole::User, "Hello Ada")]
+        );
+
+        let library = Library::load(&layout, Some(&project));
+        let rendered = library.get_prompt("greet", arguments.as_object()).unwrap();
+        assert_eq!(
+            rendered.messages,
+            vec![PromptMessage::new_text(PromptMessageRole::User, "Hi Ada!")]
+        );
+        let resources = library.resources();
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].uri, "mcp-center://resources/guides/style.md");
+        let read = Library::read_resource(&layout, Some(&project), &resources[0].uri).unwrap();
+        assert_eq!(
+            read.contents,
+            vec![ResourceContents::TextResourceContents {
+                uri: resources[0].uri.clone(),
+                mime_type: Some("text/markdown".into()),
+                text: "# Project style".into(),
+                meta: None,
+            }]
+        );
+        let read = |uri| Library::read_resource(&layout, Some(&project), uri);
+        assert!(read("mcp-center://resources/missing.md").is_err());
+        assert!(read("mcp-center://resources/guides/../guides/style.md").is_err());
+        fs::write(layout.resources_dir().join("shared.md"), "# Shared").unwrap();
+        assert!(read("mcp-center://resources/shared.md").is_ok());
+        assert!(library.get_prompt("missing", None).is_err());
+    }
+}
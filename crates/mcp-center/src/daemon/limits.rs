@@ -0,0 +1,327 @@
+//! Cutting tool results down to their [`OutputLimits`].
+//!
+//! Text is cut at the character budget, content items past the item limit are dropped,
+//! images that do not fit the image budget are left out, and structured content larger than
+//! the text limit is dropped. A text item at the end says what was left out. When the limits
+//! ask for it, the untouched result is written to `state/outputs/<id>/` as pages of its JSON
+//! form, served as `mcp-center://outputs/<id>/<page>` resources.
+
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{Context, Result};
+use rmcp::{
+    ErrorData as McpError,
+    model::{
+        Annotated, CallToolResult, Content, Meta, RawContent, RawTextContent, ReadResourceResult,
+        ResourceContents,
+    },
+};
+use serde_json::json;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::config::limits::OutputLimits;
+
+/// Prefix of the resources holding spilled results.
+pub const OUTPUT_URI_PREFIX: &str = "mcp-center://outputs/";
+
+/// Characters per page of a spilled result, unless the text limit is smaller.
+const PAGE_CHARS: usize = 50_000;
+const MIN_PAGE_CHARS: usize = 4_000;
+/// How long spilled results are kept.
+const SPILL_RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// What truncation left out of a result.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Cut {
+    text_chars: usize,
+    shown_chars: usize,
+    dropped_items: usize,
+    omitted_images: usize,
+    dropped_structured: bool,
+}
+
+impl Cut {
+    fn is_empty(&self) -> bool {
+        self.shown_chars == self.text_chars
+            && self.dropped_items == 0
+            && self.omitted_images == 0
+            && !self.dropped_structured
+    }
+
+    fn describe(&self) -> Vec<String> {
+        let mut parts = Vec::new();
+        if self.shown_chars < self.text_chars {
+            parts.push(format!("showing {} of {} characters", self.shown_chars, self.text_chars));
+        }
+        if self.dropped_items > 0 {
+            parts.push(format!("{} content item(s) dropped", self.dropped_items));
+        }
+        if self.omitted_images > 0 {
+            parts.push(format!("{} image(s) omitted", self.omitted_images));
+        }
+        if self.dropped_structured {
+            parts.push("structured content dropped".to_string());
+        }
+        parts
+    }
+}
+
+/// `result` within `limits`, spilling the full result under `outputs_dir` if asked to.
+pub async fn enforce(
+    limits: &OutputLimits,
+    result: CallToolResult,
+    outputs_dir: &Path,
+) -> CallToolResult {
+    if !limits.limits_anything() {
+        return result;
+    }
+    let (mut truncated, cut) = truncate(limits, &result);
+    if cut.is_empty() {
+        return result;
+    }
+    let mut marker = format!("[output truncated by mcp-center: {}", cut.describe().join(", "));
+    if limits.spills() {
+        let page_chars = limits
+            .max_text_chars
+            .map_or(PAGE_CHARS, |max| max.clamp(MIN_PAGE_CHARS, PAGE_CHARS));
+        let outputs_dir = outputs_dir.to_path_buf();
+        let spilled = tokio::task::spawn_blocking(move || spill(&outputs_dir, &result, page_chars))
+            .await
+            .context("spilling the tool result panicked");
+        match spilled.and_then(|spilled| spilled) {
+            Ok((id, pages)) => marker.push_str(&format!(
+                ". Full result in {pages} page(s): {OUTPUT_URI_PREFIX}{id}/1 to \
+                 {OUTPUT_URI_PREFIX}{id}/{pages}, read them with resources/read"
+            )),
+            Err(err) => warn!(error = ?err, "failed to keep the full tool result"),
+        }
+    }
+    marker.push(']');
+    truncated.content.push(Content::text(marker));
+    truncated
+}
+
+fn truncate(limits: &OutputLimits, result: &CallToolResult) -> (CallToolResult, Cut) {
+    let mut text_left = limits.max_text_chars.unwrap_or(usize::MAX);
+    let mut image_left = limits.max_image_bytes.unwrap_or(usize::MAX);
+    let max_items = limits.max_items.unwrap_or(usize::MAX);
+    let mut cut = Cut::default();
+    let mut content = Vec::new();
+    for item in &result.content {
+        if let RawContent::Text(text) = &item.raw {
+            let chars = text.text.chars().count();
+            cut.text_chars += chars;
+            if content.len() >= max_items || text_left == 0 {
+                cut.dropped_items += 1;
+                continue;
+            }
+            let shown = chars.min(text_left);
+            text_left -= shown;
+            cut.shown_chars += shown;
+            if shown == chars {
+                content.push(item.clone());
+            } else {
+                let raw = RawTextContent {
+                    text: text.text.chars().take(shown).collect(),
+                    meta: text.meta.clone(),
+                };
+                content.push(Annotated {
+                    raw: RawContent::Text(raw),
+                    annotations: item.annotations.clone(),
+                });
+            }
+            continue;
+        }
+        if content.len() >= max_items {
+            cut.dropped_items += 1;
+            continue;
+        }
+        if let RawContent::Image(image) = &item.raw {
+            if image.data.len() > image_left {
+                cut.omitted_images += 1;
+                continue;
+            }
+            image_left -= image.data.len();
+        }
+        content.push(item.clone());
+    }
+
+    let mut structured_content = result.structured_content.clone();
+    if let (Some(structured), Some(max)) = (&structured_content, limits.max_text_chars)
+        && structured.to_string().chars().count() > max
+    {
+        structured_content = None;
+        cut.dropped_structured = true;
+    }
+    let truncated = CallToolResult {
+        content,
+        structured_content,
+        is_error: result.is_error,
+        meta: result.meta.clone(),
+    };
+    (truncated, cut)
+}
+
+/// Write the JSON form of `result` as pages; returns the id and number of pages.
+fn spill(
+    outputs_dir: &Path,
+    result: &CallToolResult,
+    page_chars: usize,
+) -> Result<(String, usize)> {
+    prune(outputs_dir);
+    let id = Uuid::new_v4().to_string();
+    let dir = outputs_dir.join(&id);
+    fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let text = serde_json::to_string_pretty(result).context("failed to serialise tool result")?;
+    let chars = text.chars().collect::<Vec<_>>();
+    let pages = chars.chunks(page_chars).collect::<Vec<_>>();
+    for (index, page) in pages.iter().enumerate() {
+        let path = dir.join(format!("{}.txt", index + 1));
+        fs::write(&path, page.iter().collect::<String>())
+            .with_context(|| format!("failed to write {}", path.display()))?;
+    }
+    Ok((id, pages.len()))
+}
+
+/// Remove spilled results older than the retention period.
+fn prune(outputs_dir: &Path) {
+    let Ok(entries) = fs::read_dir(outputs_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let expired = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age > SPILL_RETENTION);
+        if expired && let Err(err) = fs::remove_dir_all(entry.path()) {
+            warn!(error = ?err, path = %entry.path().display(), "failed to remove old tool result");
+        }
+    }
+}
+
+/// Serve one page of a spilled result.
+pub async fn read_page(outputs_dir: &Path, uri: &str) -> Result<ReadResourceResult, McpError> {
+    let not_found = || McpError::resource_not_found(format!("Resource '{uri}' not found"), None);
+    let (id, page) = uri
+        .strip_prefix(OUTPUT_URI_PREFIX)
+        .and_then(|rest| rest.split_once('/'))
+        .ok_or_else(not_found)?;
+    let valid_id = !id.is_empty() && id.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+    let page = page.parse::<usize>().ok().filter(|page| *page > 0 && valid_id);
+    let page = page.ok_or_else(not_found)?;
+    let dir = outputs_dir.join(id);
+    let text = tokio::fs::read_to_string(dir.join(format!("{page}.txt")))
+        .await
+        .map_err(|_| not_found())?;
+    let pages = count_pages(&dir).await.unwrap_or(page);
+    let meta = json!({ "page": page, "pages": pages });
+    Ok(ReadResourceResult {
+        contents: vec![ResourceContents::TextResourceContents {
+            uri: uri.to_string(),
+            mime_type: Some("text/plain".to_string()),
+            text,
+            meta: meta.as_object().cloned().map(Meta),
+        }],
+    })
+}
+
+async fn count_pages(dir: &Path) -> std::io::Result<usize> {
+    let mut entries = tokio::fs::read_dir(dir).await?;
+    let mut pages = 0;
+    while entries.next_entry().await?.is_some() {
+        pages += 1;
+    }
+    Ok(pages)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn texts(result: &CallToolResult) -> Vec<String> {
+        result
+            .content
+            .iter()
+            .filter_map(|item| item.as_text())
+            .map(|t| t.text.clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn truncates_text_items_and_images() {
+        let tmp = tempdir().unwrap();
+        let result = CallToolResult::success(vec![
+            Content::text("a".repeat(6)),
+            Content::image("x".repeat(10), "image/png"),
+            Content::text("b".repeat(6)),
+            Content::text("c"),
+        ]);
+        let limits = OutputLimits {
+            max_text_chars: Some(8),
+            max_image_bytes: Some(4),
+            ..OutputLimits::default()
+        };
+        let truncated = enforce(&limits, result.clone(), tmp.path()).await;
+        assert_eq!(
+            texts(&truncated),
+            [
+                "aaaaaa",
+                "bb",
+                "[output truncated by mcp-center: showing 8 of 13 characters, \
+                 1 content item(s) dropped, 1 image(s) omitted]"
+            ]
+        );
+        assert_eq!(truncated.is_error, Some(false));
+
+        let roomy = OutputLimits { max_items: Some(10), ..OutputLimits::default() };
+        assert_eq!(enforce(&roomy, result.clone(), tmp.path()).await, result);
+    }
+
+    #[tokio::test]
+    async fn spills_the_full_result_in_pages() {
+        let tmp = tempdir().unwrap();
+        let result = CallToolResult::structured(json!({ "rows": "r".repeat(10_000) }));
+        let limits = OutputLimits {
+            max_text_chars: Some(1_000),
+            spill: Some(true),
+            ..OutputLimits::default()
+        };
+        let truncated = enforce(&limits, result.clone(), tmp.path()).await;
+        assert!(truncated.structured_content.is_none());
+        let marker = texts(&truncated).pop().unwrap();
+        let uri = marker
+            .split_whitespace()
+            .find(|word| word.starts_with(OUTPUT_URI_PREFIX))
+            .unwrap()
+            .to_string();
+
+        let mut full = String::new();
+        let mut page = 1;
+        loop {
+            let page_uri = format!("{}/{page}", uri.rsplit_once('/').unwrap().0);
+            let Ok(read) = read_page(tmp.path(), &page_uri).await else {
+                break;
+            };
+            let ResourceContents::TextResourceContents { text, meta, .. } = &read.contents[0]
+            else {
+                panic!("pages are text");
+            };
+            assert!(text.chars().count() <= MIN_PAGE_CHARS);
+            assert_eq!(meta.as_ref().unwrap().0["page"], json!(page));
+            full.push_str(text);
+            page += 1;
+        }
+        assert!(page > 2, "the result spans several pages");
+        assert_eq!(serde_json::from_str::<CallToolResult>(&full).unwrap(), result);
+        assert!(read_page(tmp.path(), &format!("{OUTPUT_URI_PREFIX}../1")).await.is_err());
+    }
+}
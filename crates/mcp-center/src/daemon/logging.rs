@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeSet,
     path::{Path, PathBuf},
     sync::Arc,
     time::Duration,
@@ -20,7 +21,7 @@ use tokio::{
 };
 use tokio_stream::wrappers::BroadcastStream;
 
-use crate::Layout;
+use crate::{Layout, config::hooks::HookStage, project::arguments::ArgumentViolation};
 
 /// Append-only JSONL log sink with simple daily file rotation.
 #[derive(Clone)]
@@ -69,6 +70,71 @@ impl LogSink {
     }
 }
 
+/// Placeholder written in place of secret values.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Secret argument values to keep out of tool logs.
+///
+/// Top-level arguments named in `arguments` are masked outright, and any string in the
+/// logged payload has occurrences of the secret string values replaced, which also
+/// catches servers that echo a key back in a result or error.
+#[derive(Debug, Clone, Default)]
+pub struct Redaction {
+    arguments: BTreeSet<String>,
+    values: Vec<String>,
+}
+
+impl Redaction {
+    /// Mask the argument `name`, whose pinned value is `value`.
+    pub fn insert(&mut self, name: &str, value: &Value) {
+        self.arguments.insert(name.to_string());
+        match value {
+            Value::String(text) if !text.is_empty() => self.values.push(text.clone()),
+            Value::String(_) => {}
+            value => self.values.push(value.to_string()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.arguments.is_empty()
+    }
+
+    /// Call arguments with the secret ones masked.
+    pub fn arguments(&self, arguments: Option<&JsonObject>) -> Option<Value> {
+        let mut arguments = arguments?.clone();
+        for (name, value) in arguments.iter_mut() {
+            if self.arguments.contains(name) {
+                *value = Value::String(REDACTED.to_string());
+            }
+        }
+        Some(self.value(Value::Object(arguments)))
+    }
+
+    /// `value` with every occurrence of a secret string masked.
+    pub fn value(&self, value: Value) -> Value {
+        if self.is_empty() {
+            return value;
+        }
+        match value {
+            Value::String(text) => Value::String(self.text(text)),
+            Value::Array(items) => Value::Array(items.into_iter().map(|v| self.value(v)).collect()),
+            Value::Object(map) => {
+                Value::Object(map.into_iter().map(|(k, v)| (k, self.value(v))).collect())
+            }
+            value => value,
+        }
+    }
+
+    pub fn text(&self, mut text: String) -> String {
+        for secret in &self.values {
+            if text.contains(secret.as_str()) {
+                text = text.replace(secret.as_str(), REDACTED);
+            }
+        }
+        text
+    }
+}
+
 #[derive(Clone)]
 pub struct ServerLogHandle {
     inner: Arc<ServerLogHandleInner>,
@@ -137,6 +203,7 @@ impl ServerLogHandle {
         call_id: &str,
         tool_name: &str,
         arguments: Option<&JsonObject>,
+        redaction: &Redaction,
     ) -> Result<()> {
         self.record(LogEntry {
             timestamp: now_timestamp(),
@@ -146,7 +213,7 @@ impl ServerLogHandle {
             server: Some(ServerContext::new(self.server_id(), self.server_name())),
             tool: Some(ToolContext::new(tool_name, call_id)),
             duration_ms: None,
-            details: arguments.map(|args| Value::Object(args.clone())),
+            details: redaction.arguments(arguments),
         })
         .await
     }
@@ -157,6 +224,7 @@ impl ServerLogHandle {
         tool_name: &str,
         duration: Duration,
         result: &CallToolResult,
+        redaction: &Redaction,
     ) -> Result<()> {
         let is_error = result.is_error.unwrap_or(false);
         self.record(LogEntry {
@@ -179,7 +247,96 @@ impl ServerLogHandle {
             server: Some(ServerContext::new(self.server_id(), self.server_name())),
             tool: Some(ToolContext::new(tool_name, call_id)),
             duration_ms: Some(duration.as_millis()),
-            details: serde_json::to_value(result).ok(),
+            details: serde_json::to_value(result).ok().map(|value| redaction.value(value)),
+        })
+        .await
+    }
+
+    /// Record a call answered from the result cache instead of the server.
+    pub async fn log_cache_hit(
+        &self,
+        call_id: &str,
+        tool_name: &str,
+        age: Duration,
+        result: &CallToolResult,
+        redaction: &Redaction,
+    ) -> Result<()> {
+        let mut details = json!({ "ageSecs": age.as_secs() });
+        if let Ok(result) = serde_json::to_value(result) {
+            details["result"] = redaction.value(result);
+        }
+        self.record(LogEntry {
+            timestamp: now_timestamp(),
+            level: LogLevel::Info,
+            category: LogCategory::CacheHit,
+            message: format!("tool call served from cache: {tool_name}"),
+            server: Some(ServerContext::new(self.server_id(), self.server_name())),
+            tool: Some(ToolContext::new(tool_name, call_id)),
+            duration_ms: None,
+            details: Some(details),
+        })
+        .await
+    }
+
+    /// Record a call rejected before it reached the server because its arguments broke the
+    /// project's argument constraints.
+    pub async fn log_policy_violation(
+        &self,
+        call_id: &str,
+        tool_name: &str,
+        arguments: Option<&JsonObject>,
+        violations: &[ArgumentViolation],
+        redaction: &Redaction,
+    ) -> Result<()> {
+        self.record(LogEntry {
+            timestamp: now_timestamp(),
+            level: LogLevel::Warn,
+            category: LogCategory::PolicyViolation,
+            message: format!("tool call rejected by argument rules: {tool_name}"),
+            server: Some(ServerContext::new(self.server_id(), self.server_name())),
+            tool: Some(ToolContext::new(tool_name, call_id)),
+            duration_ms: None,
+            details: Some(redaction.value(json!({
+                "arguments": redaction.arguments(arguments),
+                "violations": violations,
+            }))),
+        })
+        .await
+    }
+
+    /// Record one hook run of a call: what it did, or why it failed. `outcome` must already
+    /// be redacted.
+    pub async fn log_hook(
+        &self,
+        call_id: &str,
+        tool_name: &str,
+        hook: &str,
+        stage: HookStage,
+        duration: Duration,
+        outcome: Result<&str, &str>,
+    ) -> Result<()> {
+        let stage = stage.as_str();
+        let (level, message, details) = match outcome {
+            Ok(action) => (
+                LogLevel::Info,
+                format!("{stage} hook '{hook}' ran: {tool_name}"),
+                json!({ "hook": hook, "stage": stage, "action": action }),
+            ),
+            Err(error) => (
+                LogLevel::Warn,
+                format!("{stage} hook '{hook}' failed: {tool_name}"),
+                json!({ "hook": hook, "stage": stage, "error": error }),
+            ),
+        };
+        self.record(LogEntry {
+            timestamp: now_timestamp(),
+            level,
+            category: LogCategory::Hook,
+            message,
+            server: Some(ServerContext::new(self.server_id(), self.server_name())),
+            tool: Some(ToolContext::new(tool_name, call_id)),
+            duration_ms: Some(duration.as_millis()),
+            details: Some(details),
         })
         .await
     }
@@ -190,6 +347,7 @@ impl ServerLogHandle {
         tool_name: &str,
         duration: Duration,
         error: &ServiceError,
+        redaction: &Redaction,
     ) -> Result<()> {
         self.record(LogEntry {
             timestamp: now_timestamp(),
@@ -200,7 +358,7 @@ impl ServerLogHandle {
             tool: Some(ToolContext::new(tool_name, call_id)),
             duration_ms: Some(duration.as_millis()),
             details: Some(json!({
-                "error": format!("{error:?}")
+                "error": redaction.text(format!("{error:?}"))
             })),
         })
         .await
@@ -224,6 +382,9 @@ pub enum LogCategory {
     ToolRequest,
     ToolResponse,
     ToolError,
+    PolicyViolation,
+    Hook,
+    CacheHit,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Type)]
@@ -454,3 +615,24 @@ fn map_logging_level(level: LoggingLevel) -> LogLevel {
         | LoggingLevel::Emergency => LogLevel::Error,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redaction_masks_secret_arguments_and_echoes() {
+        let mut redaction = Redaction::default();
+        redaction.insert("token", &json!("sk-123"));
+        let arguments = json!({ "token": "sk-123", "query": "issues" });
+        assert_eq!(
+            redaction.arguments(arguments.as_object()),
+            Some(json!({ "token": REDACTED, "query": "issues" }))
+        );
+        assert_eq!(
+            redaction.value(json!({ "content": [{ "text": "bad key sk-123" }] })),
+            json!({ "content": [{ "text": "bad key [REDACTED]" }] })
+        );
+        assert_eq!(Redaction::default().value(json!("sk-123")), json!("sk-123"));
+    }
+}
@@ -0,0 +1,452 @@
+//! Built-in `mcp-center` server whose tools manage the center itself.
+//!
+//! The server is never launched: [`ServerManager`] lists its tools next to the upstream
+//! ones and the session answers calls to them in-process. Sessions only see it when
+//! `[management] enabled = true`, and projects need permission for the `mcp-center` server
+//! like for any other, so an agent can inspect and change the center only where allowed.
+
+use std::{path::Path, sync::Arc, time::Duration};
+
+use rmcp::{
+    ErrorData as McpError,
+    model::{CallToolRequestParam, CallToolResult, JsonObject, Tool, ToolAnnotations},
+};
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+
+use crate::{
+    Layout, ProjectRecord, ProjectRegistry,
+    config::{WorkspaceSettings, settings::ApprovalSettings},
+    daemon::{
+        approvals::ApprovalOutcome,
+        permissions::PermissionSnapshot,
+        server_manager::{ServerManager, ToolEntry},
+    },
+    project::{annotations::ToolHints, grant::TemporaryGrant, policy::DecisionReason},
+};
+
+/// Id and display name of the built-in server in permissions, logs and tool listings.
+pub const SERVER_ID: &str = "mcp-center";
+pub const SERVER_NAME: &str = "MCP Center";
+
+pub const LIST_SERVERS: &str = "list_servers";
+pub const ENABLE_SERVER: &str = "enable_server";
+pub const LIST_PROJECT_PERMISSIONS: &str = "list_project_permissions";
+pub const REQUEST_TOOL_ACCESS: &str = "request_tool_access";
+
+/// Grant length of `request_tool_access` unless the agent asks for another one.
+pub const DEFAULT_GRANT_MINUTES: u64 = 60;
+pub const MAX_GRANT_MINUTES: u64 = 24 * 60;
+
+/// What a management tool may act on: the daemon's state and the session's primary
+/// project.
+pub struct ManagementContext<'a> {
+    pub manager: &'a ServerManager,
+    pub layout: &'a Layout,
+    pub registry: &'a ProjectRegistry,
+    pub project: &'a Path,
+    pub permissions: &'a PermissionSnapshot,
+}
+
+/// The built-in tools as entries of the shared tool cache.
+pub fn tool_entries() -> Vec<ToolEntry> {
+    tools()
+        .into_iter()
+        .map(|tool| ToolEntry {
+            server_id: SERVER_ID.to_string(),
+            server_name: SERVER_NAME.to_string(),
+            tool,
+        })
+        .collect()
+}
+
+pub fn tools() -> Vec<Tool> {
+    vec![
+        Tool::new(
+            LIST_SERVERS,
+            "List the MCP servers configured in MCP Center with whether each is enabled and \
+             running.",
+            schema(json!({ "type": "object", "properties": {} })),
+        )
+        .annotate(ToolAnnotations::new().read_only(true).open_world(false)),
+        Tool::new(
+            ENABLE_SERVER,
+            "Enable and start, or disable and stop, a configured MCP server.",
+            schema(json!({
+                "type": "object",
+                "properties": {
+                    "server_id": { "type": "string", "description": "Id from list_servers." },
+                    "enabled": {
+                        "type": "boolean",
+                        "description": "false disables the server (default true).",
+                    },
+                },
+                "required": ["server_id"],
+            })),
+        )
+        .annotate(ToolAnnotations::new().read_only(false).destructive(false).open_world(false)),
+        Tool::new(
+            LIST_PROJECT_PERMISSIONS,
+            "List every tool with whether the current project may use it and why.",
+            schema(json!({ "type": "object", "properties": {} })),
+        )
+        .annotate(ToolAnnotations::new().read_only(true).open_world(false)),
+        Tool::new(
+            REQUEST_TOOL_ACCESS,
+            "Ask the user for temporary access to a server's tools. Waits until the request is \
+             approved or denied.",
+            schema(json!({
+                "type": "object",
+                "properties": {
+                    "server_id": { "type": "string", "description": "Id from list_servers." },
+                    "tools": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Tools to unlock; omit for the whole server.",
+                    },
+                    "reason": { "type": "string", "description": "Why access is needed." },
+                    "minutes": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_GRANT_MINUTES,
+                        "description": format!(
+                            "How long the access lasts (default {DEFAULT_GRANT_MINUTES})."
+                        ),
+                    },
+                },
+                "required": ["server_id"],
+            })),
+        )
+        .annotate(ToolAnnotations::new().read_only(false).destructive(false).open_world(false)),
+    ]
+}
+
+fn schema(value: Value) -> Arc<JsonObject> {
+    match value {
+        Value::Object(object) => Arc::new(object),
+        _ => Arc::default(),
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct EnableServerArgs {
+    server_id: String,
+    #[serde(default = "enabled_by_default")]
+    enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct RequestToolAccessArgs {
+    server_id: String,
+    #[serde(default)]
+    tools: Vec<String>,
+    #[serde(default)]
+    reason: Option<String>,
+    #[serde(default)]
+    minutes: Option<u64>,
+}
+
+fn parse<T: DeserializeOwned>(params: &CallToolRequestParam) -> Result<T, McpError> {
+    let arguments = Value::Object(params.arguments.clone().unwrap_or_default());
+    serde_json::from_value(arguments).map_err(|err| {
+        McpError::invalid_params(format!("Invalid arguments for '{}': {err}", params.name), None)
+    })
+}
+
+/// Answer a call to one of the built-in tools.
+pub async fn call(
+    cx: &ManagementContext<'_>,
+    params: &CallToolRequestParam,
+) -> Result<CallToolResult, McpError> {
+    let value = match params.name.as_ref() {
+        LIST_SERVERS => list_servers(cx).await?,
+        ENABLE_SERVER => enable_server(cx, parse(params)?).await?,
+        LIST_PROJECT_PERMISSIONS => list_project_permissions(cx).await?,
+        REQUEST_TOOL_ACCESS => request_tool_access(cx, parse(params)?).await?,
+        other => {
+            return Err(McpError::invalid_params(
+                format!("Unknown {SERVER_NAME} tool '{other}'"),
+                None,
+            ));
+        }
+    };
+    Ok(CallToolResult::structured(value))
+}
+
+fn internal(err: impl ToString) -> McpError {
+    McpError::internal_error(err.to_string(), None)
+}
+
+/// Run blocking file access off the async runtime.
+async fn blocking<T: Send + 'static>(
+    task: impl FnOnce() -> anyhow::Result<T> + Send + 'static,
+) -> Result<T, McpError> {
+    tokio::task::spawn_blocking(task).await.map_err(internal)?.map_err(internal)
+}
+
+async fn list_servers(cx: &ManagementContext<'_>) -> Result<Value, McpError> {
+    let layout = cx.layout.clone();
+    let configs = blocking(move || layout.list_server_configs()).await?;
+    let mut servers = Vec::with_capacity(configs.len());
+    for config in configs {
+        let definition = config.definition();
+        let tool_count = cx.manager.tool_count_for(&definition.id).await;
+        servers.push(json!({
+            "id": definition.id,
+            "name": definition.name.clone().unwrap_or_else(|| definition.id.clone()),
+            "protocol": definition.protocol,
+            "enabled": definition.enabled,
+            "running": tool_count.is_some(),
+            "toolCount": tool_count.unwrap_or_default(),
+        }));
+    }
+    Ok(json!({ "servers": servers }))
+}
+
+async fn enable_server(
+    cx: &ManagementContext<'_>,
+    args: EnableServerArgs,
+) -> Result<Value, McpError> {
+    ensure_configured(cx, &args.server_id).await?;
+    let (config, warning) = cx
+        .manager
+        .set_server_enabled(&args.server_id, args.enabled)
+        .await
+        .map_err(internal)?;
+    Ok(json!({
+        "id": config.definition().id,
+        "enabled": config.definition().enabled,
+        "running": cx.manager.tool_count_for(&args.server_id).await.is_some(),
+        "warning": warning,
+    }))
+}
+
+async fn list_project_permissions(cx: &ManagementContext<'_>) -> Result<Value, McpError> {
+    let Some(policy) = cx.permissions.policies().first() else {
+        return Ok(json!({ "project": cx.project, "tools": [] }));
+    };
+    let entries = cx.manager.list_tools().await.map_err(internal)?;
+    let tools = policy
+        .simulate(entries.into_iter().map(|entry| {
+            let hints = ToolHints::from_annotations(entry.tool.annotations.as_ref());
+            (entry.server_id, entry.tool.name.into_owned(), None, hints)
+        }))
+        .into_iter()
+        .map(|tool| {
+            let (reason, source) = describe_reason(&tool.decision.reason);
+            json!({
+                "name": tool.name,
+                "serverId": tool.server_id,
+                "allowed": tool.decision.allowed,
+                "reason": reason,
+                "source": source,
+            })
+        })
+        .collect::<Vec<_>>();
+    Ok(json!({ "project": cx.project, "tools": tools }))
+}
+
+fn describe_reason(reason: &DecisionReason) -> (&'static str, Option<String>) {
+    match reason {
+        DecisionReason::TemporaryGrant { source, .. } => {
+            ("temporary_grant", Some(source.to_string()))
+        }
+        DecisionReason::ToolPermission { source } => ("tool_permission", Some(source.to_string())),
+        DecisionReason::ServerList { source } => ("server_list", Some(source.to_string())),
+        DecisionReason::RepoConfig { path } => ("repo_config", Some(path.display().to_string())),
+        DecisionReason::Annotation { source, .. } => ("annotation", Some(source.to_string())),
+        DecisionReason::PendingApproval => ("pending_approval", None),
+        DecisionReason::Unrestricted => ("unrestricted", None),
+    }
+}
+
+/// Queue the request for the user and, once approved, add a temporary grant to the
+/// primary project. Only registered projects can receive grants: creating a record here
+/// would leave the project active with every server allowed.
+async fn request_tool_access(
+    cx: &ManagementContext<'_>,
+    args: RequestToolAccessArgs,
+) -> Result<Value, McpError> {
+    ensure_configured(cx, &args.server_id).await?;
+    registered_project(cx).await?;
+    let minutes = args.minutes.unwrap_or(DEFAULT_GRANT_MINUTES).clamp(1, MAX_GRANT_MINUTES);
+    let tools = args
+        .tools
+        .iter()
+        .map(|tool| tool.trim().to_string())
+        .filter(|tool| !tool.is_empty())
+        .collect::<Vec<_>>();
+
+    let layout = cx.layout.clone();
+    let timeout = blocking(move || WorkspaceSettings::load(&layout))
+        .await
+        .map(|settings| settings.approvals.timeout())
+        .unwrap_or_else(|_| ApprovalSettings::default().timeout());
+    let summary = json!({
+        "server_id": args.server_id,
+        "tools": tools,
+        "reason": args.reason,
+        "minutes": minutes,
+    });
+    let outcome = cx
+        .manager
+        .approvals()
+        .request(
+            cx.project.display().to_string(),
+            SERVER_ID.to_string(),
+            REQUEST_TOOL_ACCESS.to_string(),
+            serde_json::to_string_pretty(&summary).ok(),
+            timeout,
+        )
+        .await;
+    match outcome {
+        ApprovalOutcome::Approved => {}
+        ApprovalOutcome::Denied { reason } => {
+            let mut message = format!("Access to server '{}' was denied", args.server_id);
+            if let Some(reason) = reason {
+                message.push_str(&format!(": {reason}"));
+            }
+            return Err(McpError::invalid_request(message, None));
+        }
+        ApprovalOutcome::TimedOut => {
+            return Err(McpError::invalid_request(
+                format!(
+                    "Access to server '{}' was not approved within {} seconds",
+                    args.server_id,
+                    timeout.as_secs()
+                ),
+                None,
+            ));
+        }
+    }
+
+    let mut record = registered_project(cx).await?;
+    let grant = TemporaryGrant::new(
+        args.server_id.clone(),
+        tools.clone(),
+        Duration::from_secs(minutes * 60),
+    );
+    let expires_at = grant.expires_at;
+    record.grants.push(grant);
+    record.touch();
+    let registry = cx.registry.clone();
+    blocking(move || registry.store(&record)).await?;
+
+    Ok(json!({
+        "serverId": args.server_id,
+        "tools": tools,
+        "expiresAt": expires_at,
+    }))
+}
+
+async fn ensure_configured(cx: &ManagementContext<'_>, server_id: &str) -> Result<(), McpError> {
+    let layout = cx.layout.clone();
+    let configs = blocking(move || layout.list_server_configs()).await?;
+    if configs.iter().any(|config| config.definition().id == server_id) {
+        Ok(())
+    } else {
+        Err(McpError::invalid_params(format!("Unknown server id '{server_id}'"), None))
+    }
+}
+
+async fn registered_project(cx: &ManagementContext<'_>) -> Result<ProjectRecord, McpError> {
+    let registry = cx.registry.clone();
+    let project = cx.project.to_path_buf();
+    blocking(move || registry.find_by_path(&project)).await?.ok_or_else(|| {
+        McpError::invalid_request(
+            format!("Project '{}' is not registered with {SERVER_NAME}", cx.project.display()),
+            None,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(name: &'static str, arguments: Value) -> CallToolRequestParam {
+        CallToolRequestParam { name: name.into(), arguments: arguments.as_object().cloned() }
+    }
+
+    #[test]
+    fn builtin_tools_are_listed_under_the_reserved_server() {
+        let entries = tool_entries();
+        let names = entries.iter().map(|entry| entry.tool.name.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [LIST_SERVERS, ENABLE_SERVER, LIST_PROJECT_PERMISSIONS, REQUEST_TOOL_ACCESS]
+        );
+        assert!(entries.iter().all(|entry| entry.server_id == SERVER_ID));
+        let hints = ToolHints::from_annotations(entries[0].tool.annotations.as_ref());
+        assert!(hints.read_only);
+    }
+
+    #[test]
+    fn parses_tool_arguments() {
+        let args: EnableServerArgs = parse(&params(ENABLE_SERVER, json!({ "server_id": "db" })))
+            .expect("enable_server args");
+        assert_eq!(args, EnableServerArgs { server_id: "db".into(), enabled: true });
+
+        let args: RequestToolAccessArgs = parse(&params(
+            REQUEST_TOOL_ACCESS,
+            json!({ "server_id": "db", "tools": ["query"], "minutes": 15 }),
+        ))
+        .expect("request_tool_access args");
+        assert_eq!(args.tools, ["query"]);
+        assert_eq!(args.minutes, Some(15));
+
+        assert!(parse::<EnableServerArgs>(&params(ENABLE_SERVER, json!({}))).is_err());
+        assert!(
+            parse::<RequestToolAccessArgs>(&params(
+                REQUEST_TOOL_ACCESS,
+                json!({ "server_id": "db", "tools": "query" })
+            ))
+            .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn unregistered_projects_cannot_request_access() {
+        let temp = tempfile::tempdir().unwrap();
+        let layout = Layout::new(temp.path().join("center"));
+        layout.ensure().unwrap();
+        std::fs::write(
+            layout.server_config_path("ops"),
+            r#"
+[mcp_server]
+id = "ops"
+name = "Ops"
+protocol = "script"
+enabled = true
+
+[[mcp_server.tools]]
+name = "disk_usage"
+command = "du"
+"#,
+        )
+        .unwrap();
+        let registry = ProjectRegistry::new(&layout);
+        registry.ensure().unwrap();
+        let project = temp.path().join("project");
+        let manager = ServerManager::start(layout.clone()).await.unwrap();
+        let permissions = PermissionSnapshot::compile(&layout, &registry, vec![project.clone()]);
+        let cx = ManagementContext {
+            manager: &manager,
+            layout: &layout,
+            registry: &registry,
+            project: &project,
+            permissions: &permissions,
+        };
+
+        let err = call(&cx, &params(REQUEST_TOOL_ACCESS, json!({ "server_id": "ops" })))
+            .await
+            .unwrap_err();
+        assert!(err.message.contains("not registered"), "{err:?}");
+        assert!(manager.approvals().list().is_empty());
+        assert!(registry.find_by_path(&project).unwrap().is_none());
+    }
+}
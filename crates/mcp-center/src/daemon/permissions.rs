@@ -0,0 +1,362 @@
+//! Compiled permission view per session.
+//!
+//! Resolving an [`EffectivePolicy`] touches the settings file, every server config, the
+//! project registry (which stats the whole projects directory) and each root's
+//! `.mcp-center.toml`. A session compiles that once and reuses it for `tools/list` and
+//! `tools/call` until [`ProjectRegistry::generation`] moves or the bound roots change.
+//! In-process writers bump the generation directly; [`spawn_policy_watcher`] covers edits
+//! made by other processes, and a snapshot also goes stale when a temporary grant lapses.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
+};
+
+use rmcp::service::{Peer, RoleServer};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+use crate::{
+    Layout, ProjectRegistry,
+    config::{ServerDefinition, WorkspaceSettings},
+    daemon::{server_manager::ProjectCall, session::ProjectBinding},
+    project::{
+        ToolCustomization, arguments::ArgumentPreset, grant, policy::EffectivePolicy,
+        repo_config::REPO_CONFIG_FILE,
+    },
+};
+
+/// How often the watcher polls the filesystem for out-of-process edits.
+pub const POLICY_WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Effective policies of a set of roots, valid for one registry generation.
+#[derive(Debug)]
+pub struct PermissionSnapshot {
+    generation: u64,
+    roots: Vec<PathBuf>,
+    policies: Vec<EffectivePolicy>,
+    /// Whether `[management]` offers the built-in server to sessions.
+    management_enabled: bool,
+    /// Earliest expiry of a grant that was active at compile time.
+    next_expiry: Option<u64>,
+}
+
+impl PermissionSnapshot {
+    /// Resolve the policy of every root, loading settings and server configs once.
+    pub fn compile(layout: &Layout, registry: &ProjectRegistry, roots: Vec<PathBuf>) -> Self {
+        if let Err(err) = registry.poll_changes() {
+            warn!(error = ?err, "failed to refresh project registry");
+        }
+        let generation = registry.generation();
+        let settings = WorkspaceSettings::load(layout).unwrap_or_else(|err| {
+            warn!(error = ?err, "failed to load workspace settings, ignoring project rules");
+            WorkspaceSettings::default()
+        });
+        let rules = settings.project_rules;
+        let definitions: Vec<ServerDefinition> = layout
+            .list_server_configs()
+            .map(|configs| configs.into_iter().map(|cfg| cfg.definition().clone()).collect())
+            .unwrap_or_else(|err| {
+                warn!(error = ?err, "failed to list server configs, ignoring repository config");
+                Vec::new()
+            });
+        let policies = roots
+            .iter()
+            .map(|root| {
+                EffectivePolicy::resolve(registry, &rules, root).with_repo_config(&definitions)
+            })
+            .collect::<Vec<_>>();
+        let now = grant::now();
+        let next_expiry = policies.iter().filter_map(|policy| policy.next_grant_expiry(now)).min();
+        Self {
+            generation,
+            roots,
+            policies,
+            management_enabled: settings.management.enabled,
+            next_expiry,
+        }
+    }
+
+    pub fn is_current(&self, generation: u64, roots: &[PathBuf]) -> bool {
+        self.generation == generation
+            && self.roots == roots
+            && self.next_expiry.is_none_or(|expires_at| grant::now() < expires_at)
+    }
+
+    /// When the next temporary grant of these projects lapses.
+    pub fn next_expiry(&self) -> Option<u64> {
+        self.next_expiry
+    }
+
+    /// One policy per root, primary first.
+    pub fn policies(&self) -> &[EffectivePolicy] {
+        &self.policies
+    }
+
+    /// Arguments the primary project pins for `tool_name`.
+    pub fn presets_for(&self, tool_name: &str) -> BTreeMap<String, ArgumentPreset> {
+        self.policies
+            .first()
+            .map(|policy| {
+                policy
+                    .argument_presets(tool_name)
+                    .into_iter()
+                    .map(|(argument, preset)| (argument.to_string(), preset.value.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Whether sessions may see the built-in management server at all; projects still
+    /// need permission for it.
+    pub fn management_enabled(&self) -> bool {
+        self.management_enabled
+    }
+
+    /// Whether the primary project replaces the tool list with the search meta-tools.
+    pub fn uses_tool_search(&self) -> bool {
+        self.policies.first().is_some_and(EffectivePolicy::uses_tool_search)
+    }
+
+    /// Hooks and output limits the primary project applies to its tool calls.
+    pub fn project_call(&self) -> Option<ProjectCall<'_>> {
+        let policy = self.policies.first()?;
+        Some(ProjectCall {
+            path: &policy.path,
+            hooks: policy.hooks().map(|hooks| hooks.value).unwrap_or_default(),
+            output_limits: policy.output_limits(),
+        })
+    }
+
+    /// Customization of `tool_name` in the primary project.
+    pub fn customization_for(&self, tool_name: &str) -> Option<ToolCustomization> {
+        self.policies
+            .first()
+            .and_then(|policy| policy.customization(tool_name))
+            .map(|customization| customization.value.clone())
+    }
+
+    /// Upstream name of the tool the primary project advertises as `alias`.
+    pub fn upstream_for_alias(&self, alias: &str) -> Option<String> {
+        self.policies.first().and_then(|policy| policy.upstream_for_alias(alias))
+    }
+
+    /// Custom description from the primary project.
+    pub fn description_for(&self, tool_name: &str) -> Option<String> {
+        self.policies.first().and_then(|policy| policy.description_for(tool_name))
+    }
+}
+
+/// Cached [`PermissionSnapshot`] of one session.
+#[derive(Debug, Default)]
+pub struct SessionPermissions {
+    snapshot: RwLock<Option<Arc<PermissionSnapshot>>>,
+}
+
+impl SessionPermissions {
+    /// Return the cached snapshot, recompiling it when the registry generation or the
+    /// bound roots changed since it was built.
+    pub fn current(
+        &self,
+        layout: &Layout,
+        registry: &ProjectRegistry,
+        roots: Vec<PathBuf>,
+    ) -> Arc<PermissionSnapshot> {
+        let generation = registry.generation();
+        if let Some(snapshot) = self.snapshot.read().unwrap().as_ref()
+            && snapshot.is_current(generation, &roots)
+        {
+            return snapshot.clone();
+        }
+        let snapshot = Arc::new(PermissionSnapshot::compile(layout, registry, roots));
+        debug!(generation = snapshot.generation, "compiled session permission snapshot");
+        *self.snapshot.write().unwrap() = Some(snapshot.clone());
+        snapshot
+    }
+}
+
+/// Send `notifications/tools/list_changed` whenever a temporary grant of the session's
+/// projects lapses. Returns once the session transport is closed.
+pub async fn notify_grant_expiry(
+    layout: Layout,
+    registry: ProjectRegistry,
+    binding: Arc<ProjectBinding>,
+    peer: Peer<RoleServer>,
+) {
+    let permissions = SessionPermissions::default();
+    while !peer.is_transport_closed() {
+        let snapshot = permissions.current(&layout, &registry, binding.roots());
+        let wait = snapshot
+            .next_expiry()
+            .map(|expires_at| Duration::from_secs(expires_at.saturating_sub(grant::now())))
+            .unwrap_or(POLICY_WATCH_INTERVAL)
+            .clamp(Duration::from_millis(100), POLICY_WATCH_INTERVAL);
+        tokio::time::sleep(wait).await;
+
+        if let Some(expires_at) = snapshot.next_expiry()
+            && grant::now() >= expires_at
+        {
+            info!(roots = ?binding.roots(), "temporary grant expired, notifying client");
+            if let Err(err) = peer.notify_tool_list_changed().await {
+                debug!(error = ?err, "failed to send tools/list_changed, stopping grant watcher");
+                break;
+            }
+        }
+    }
+}
+
+/// Poll settings, server configs, project records and repository config files, bumping
+/// the registry generation when any of them changed on disk.
+pub fn spawn_policy_watcher(
+    layout: Layout,
+    registry: ProjectRegistry,
+    interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        let mut last = None;
+        loop {
+            ticker.tick().await;
+            let layout = layout.clone();
+            let registry = registry.clone();
+            let previous = last.take();
+            let fingerprint = tokio::task::spawn_blocking(move || {
+                let fingerprint = policy_inputs_fingerprint(&layout, &registry);
+                if previous.as_ref().is_some_and(|previous| previous != &fingerprint) {
+                    debug!("permission inputs changed on disk");
+                    registry.notify_changed();
+                }
+                fingerprint
+            })
+            .await;
+            match fingerprint {
+                Ok(fingerprint) => last = Some(fingerprint),
+                Err(err) => warn!(error = ?err, "policy watcher task failed"),
+            }
+        }
+    })
+}
+
+type InputsFingerprint = BTreeMap<PathBuf, Option<(SystemTime, u64)>>;
+
+/// Modification time and size of every file permissions are derived from, apart from the
+/// project records themselves (picked up through [`ProjectRegistry::poll_changes`]).
+fn policy_inputs_fingerprint(layout: &Layout, registry: &ProjectRegistry) -> InputsFingerprint {
+    if let Err(err) = registry.poll_changes() {
+        warn!(error = ?err, "failed to refresh project registry");
+    }
+    let mut fingerprint = BTreeMap::new();
+    let mut record = |path: PathBuf| {
+        let stamp = file_stamp(&path);
+        fingerprint.insert(path, stamp);
+    };
+    record(layout.settings_path());
+    if let Ok(entries) = fs::read_dir(layout.servers_dir()) {
+        for entry in entries.flatten() {
+            record(entry.path());
+        }
+    }
+    for project in registry.list().unwrap_or_default() {
+        record(project.path.join(REPO_CONFIG_FILE));
+    }
+    fingerprint
+}
+
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ProjectId, ProjectRecord};
+
+    #[test]
+    fn snapshot_is_reused_until_generation_or_roots_change() {
+        let temp = tempfile::tempdir().unwrap();
+        let layout = Layout::new(temp.path().to_path_buf());
+        let registry = ProjectRegistry::new(&layout);
+        registry.ensure().unwrap();
+
+        let root = temp.path().join("workspace");
+        let mut record = ProjectRecord::new(ProjectId::from_path(&root), root.clone());
+        record.allowed_server_ids = vec!["github".into()];
+        registry.store(&record).unwrap();
+
+        let permissions = SessionPermissions::default();
+        let first = permissions.current(&layout, &registry, vec![root.clone()]);
+        let again = permissions.current(&layout, &registry, vec![root.clone()]);
+        assert!(Arc::ptr_eq(&first, &again));
+        assert!(!first.policies()[0].allows_tool("db", "query"));
+
+        record.allowed_server_ids.push("db".into());
+        registry.store(&record).unwrap();
+        let updated = permissions.current(&layout, &registry, vec![root.clone()]);
+        assert!(!Arc::ptr_eq(&first, &updated));
+        assert!(updated.policies()[0].allows_tool("db", "query"));
+
+        let other = temp.path().join("other");
+        let rebound = permissions.current(&layout, &registry, vec![root, other]);
+        assert_eq!(rebound.policies().len(), 2);
+    }
+
+    #[test]
+    fn snapshot_goes_stale_when_a_grant_lapses() {
+        let temp = tempfile::tempdir().unwrap();
+        let layout = Layout::new(temp.path().to_path_buf());
+        let registry = ProjectRegistry::new(&layout);
+        registry.ensure().unwrap();
+
+        let root = temp.path().join("workspace");
+        let mut record = ProjectRecord::new(ProjectId::from_path(&root), root.clone());
+        record.allowed_server_ids = vec!["github".into()];
+        record
+            .grants
+            .push(grant::TemporaryGrant::new("db", Vec::new(), Duration::from_secs(3600)));
+        record.grants.push(grant::TemporaryGrant {
+            server_id: "slack".into(),
+            tools: Vec::new(),
+            granted_at: 0,
+            expires_at: 1,
+        });
+        registry.store(&record).unwrap();
+
+        let snapshot = PermissionSnapshot::compile(&layout, &registry, vec![root.clone()]);
+        assert!(snapshot.policies()[0].allows_tool("db", "write"));
+        assert!(!snapshot.policies()[0].allows_tool("slack", "post"));
+        assert_eq!(snapshot.next_expiry(), Some(record.grants[0].expires_at));
+        assert!(snapshot.is_current(registry.generation(), std::slice::from_ref(&root)));
+
+        let lapsed = PermissionSnapshot { next_expiry: Some(1), ..snapshot };
+        assert!(!lapsed.is_current(registry.generation(), &[root]));
+    }
+
+    #[test]
+    fn watcher_fingerprint_tracks_settings_and_repo_files() {
+        let temp = tempfile::tempdir().unwrap();
+        let layout = Layout::new(temp.path().join("center"));
+        layout.ensure().unwrap();
+        let registry = ProjectRegistry::new(&layout);
+        registry.ensure().unwrap();
+
+        let root = temp.path().join("workspace");
+        fs::create_dir_all(&root).unwrap();
+        registry
+            .store(&ProjectRecord::new(ProjectId::from_path(&root), root.clone()))
+            .unwrap();
+
+        let before = policy_inputs_fingerprint(&layout, &registry);
+        assert_eq!(before, policy_inputs_fingerprint(&layout, &registry));
+
+        fs::write(root.join(REPO_CONFIG_FILE), "servers = [\"github\"]\n").unwrap();
+        let after_repo = policy_inputs_fingerprint(&layout, &registry);
+        assert_ne!(before, after_repo);
+
+        fs::write(layout.settings_path(), "[multi_root]\n").unwrap();
+        assert_ne!(after_repo, policy_inputs_fingerprint(&layout, &registry));
+    }
+}
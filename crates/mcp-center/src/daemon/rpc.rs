@@ -1,16 +1,21 @@
 //! Daemon RPC interface for CLI communication via Unix Socket
 
+use rmcp::model::ToolAnnotations;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use interprocess::local_socket::traits::tokio::Listener as _;
 use interprocess::local_socket::{
     GenericFilePath, ListenerOptions, ToFsName, tokio::prelude::LocalSocketStream,
 };
 
-use crate::daemon::server_manager::ServerManager;
+use crate::daemon::{
+    approvals::{ApprovalOutcome, ApprovalRequest},
+    auth::{self, SocketToken},
+    server_manager::ServerManager,
+};
 
 /// RPC request from CLI to daemon
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,8 +25,19 @@ pub enum DaemonRequest {
     ListTools { server_name: Option<String> },
     /// Get detailed info about a specific tool
     GetToolInfo { tool_name: String },
+    /// List tool calls waiting for approval
+    ListApprovals,
+    /// Approve or deny a waiting tool call
+    ResolveApproval {
+        id: u64,
+        approve: bool,
+        reason: Option<String>,
+    },
     /// Ping to check if daemon is alive
     Ping,
+    /// Present the handshake token from `state_dir/daemon.token`; must precede other requests
+    /// when the daemon requires one
+    Authenticate { token: String },
 }
 
 /// RPC response from daemon to CLI
@@ -41,6 +57,8 @@ pub enum ResponseData {
     ToolList(Vec<ToolInfo>),
     ToolInfo(ToolInfo),
     Pong(String),
+    Approvals { approvals: Vec<ApprovalRequest> },
+    Approval { approval: ApprovalRequest },
 }
 
 /// Tool information for CLI display
@@ -49,17 +67,26 @@ pub struct ToolInfo {
     pub name: String,
     pub description: String,
     pub server_name: String,
+    #[serde(default)]
+    pub server_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<ToolAnnotations>,
 }
 
 /// RPC server that listens on Unix Socket
 pub struct RpcServer {
     manager: Arc<ServerManager>,
     socket_path: std::path::PathBuf,
+    token: SocketToken,
 }
 
 impl RpcServer {
-    pub fn new(manager: Arc<ServerManager>, socket_path: std::path::PathBuf) -> Self {
-        Self { manager, socket_path }
+    pub fn new(
+        manager: Arc<ServerManager>,
+        socket_path: std::path::PathBuf,
+        token: SocketToken,
+    ) -> Self {
+        Self { manager, socket_path, token }
     }
 
     /// Start the RPC server
@@ -79,15 +106,22 @@ impl RpcServer {
 
         let socket_display = self.socket_path.to_string_lossy().into_owned();
         let listener_name = socket_display.as_str().to_fs_name::<GenericFilePath>()?;
-        let listener = ListenerOptions::new().name(listener_name).create_tokio()?;
+        let listener =
+            auth::restrict_listener(ListenerOptions::new().name(listener_name)).create_tokio()?;
+        #[cfg(unix)]
+        auth::restrict_socket_file(&self.socket_path)?;
         info!("RPC server listening on {}", socket_display);
 
         loop {
             match listener.accept().await {
                 Ok(stream) => {
+                    let Ok(stream) = auth::verify_peer(stream, "rpc") else {
+                        continue;
+                    };
                     let manager = self.manager.clone();
+                    let token = self.token.clone();
                     tokio::spawn(async move {
-                        if let Err(e) = handle_connection(stream, manager).await {
+                        if let Err(e) = handle_connection(stream, manager, token).await {
                             error!("Error handling RPC connection: {}", e);
                         }
                     });
@@ -104,10 +138,12 @@ impl RpcServer {
 async fn handle_connection(
     stream: LocalSocketStream,
     manager: Arc<ServerManager>,
+    token: SocketToken,
 ) -> anyhow::Result<()> {
     let (reader, mut writer) = tokio::io::split(stream);
     let mut reader = BufReader::new(reader);
     let mut line = String::new();
+    let mut authenticated = !token.is_required();
 
     while reader.read_line(&mut line).await? > 0 {
         let request: DaemonRequest = match serde_json::from_str(line.trim()) {
@@ -121,9 +157,27 @@ async fn handle_connection(
             }
         };
 
-        debug!("Received RPC request: {:?}", request);
-
-        let response = handle_request(request, &manager).await;
+        let response = match request {
+            DaemonRequest::Authenticate { token: presented } => {
+                if token.verify(Some(&presented)) {
+                    authenticated = true;
+                    DaemonResponse::Success {
+                        data: ResponseData::Pong("authenticated".to_string()),
+                    }
+                } else {
+                    warn!("rejected RPC client with invalid handshake token");
+                    DaemonResponse::Error { message: "authentication failed".to_string() }
+                }
+            }
+            request if !authenticated => {
+                warn!(?request, "rejected unauthenticated RPC request");
+                DaemonResponse::Error { message: "authentication required".to_string() }
+            }
+            request => {
+                debug!("Received RPC request: {:?}", request);
+                handle_request(request, &manager).await
+            }
+        };
         let response_json = serde_json::to_string(&response)? + "\n";
         writer.write_all(response_json.as_bytes()).await?;
 
@@ -148,9 +202,30 @@ async fn handle_request(request: DaemonRequest, manager: &ServerManager) -> Daem
                 }
             }
         }
+        DaemonRequest::ListApprovals => DaemonResponse::Success {
+            data: ResponseData::Approvals { approvals: manager.approvals().list() },
+        },
+        DaemonRequest::ResolveApproval { id, approve, reason } => {
+            let outcome = if approve {
+                ApprovalOutcome::Approved
+            } else {
+                ApprovalOutcome::Denied { reason }
+            };
+            match manager.approvals().resolve(id, outcome) {
+                Some(approval) => {
+                    DaemonResponse::Success { data: ResponseData::Approval { approval } }
+                }
+                None => {
+                    DaemonResponse::Error { message: format!("No pending approval with id {id}") }
+                }
+            }
+        }
         DaemonRequest::Ping => {
             DaemonResponse::Success { data: ResponseData::Pong("pong".to_string()) }
         }
+        DaemonRequest::Authenticate { .. } => {
+            DaemonResponse::Success { data: ResponseData::Pong("authenticated".to_string()) }
+        }
     }
 }
 
@@ -164,16 +239,18 @@ async fn list_tools(
     let mut tools = Vec::new();
     for entry in entries {
         // Filter by server name if specified
-        if let Some(ref name) = server_name {
-            if entry.server_name != *name {
-                continue;
-            }
+        if let Some(ref name) = server_name
+            && entry.server_name != *name
+        {
+            continue;
         }
 
         tools.push(ToolInfo {
             name: entry.tool.name.to_string(),
             description: entry.tool.description.clone().unwrap_or_default().to_string(),
             server_name: entry.server_name.clone(),
+            server_id: entry.server_id.clone(),
+            annotations: entry.tool.annotations.clone(),
         });
     }
 
@@ -190,6 +267,8 @@ async fn get_tool_info(manager: &ServerManager, tool_name: &str) -> anyhow::Resu
                 name: entry.tool.name.to_string(),
                 description: entry.tool.description.clone().unwrap_or_default().to_string(),
                 server_name: entry.server_name.clone(),
+                server_id: entry.server_id.clone(),
+                annotations: entry.tool.annotations.clone(),
             });
         }
     }
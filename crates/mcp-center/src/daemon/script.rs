@@ -0,0 +1,132 @@
+//! Running the tools of `script` servers.
+//!
+//! Command tools run here, inside the server manager, so they share the server's log and
+//! honour the tool's timeout. Composite tools are run by the session instead: every step
+//! is a regular tool call with its own permission checks (see `HostService`).
+
+use std::{process::Stdio, sync::Arc, time::Duration};
+
+use rmcp::model::{CallToolResult, Content, JsonObject, Tool, ToolAnnotations};
+use serde_json::{Value, json};
+
+use crate::config::{ServerDefinition, script::ScriptTool};
+
+/// The tool as advertised to sessions.
+pub fn tool(script: &ScriptTool) -> Tool {
+    let schema = match &script.input_schema {
+        Value::Object(schema) => schema.clone(),
+        _ => JsonObject::new(),
+    };
+    let mut tool = Tool::new(script.name.clone(), "", Arc::new(schema));
+    tool.title = script.title.clone();
+    tool.description = script.description.clone().map(Into::into);
+    if script.read_only {
+        tool = tool.annotate(ToolAnnotations::new().read_only(true).destructive(false));
+    }
+    tool
+}
+
+/// Run the command of `script` with the call's arguments. Output is returned as
+/// `{ exitCode, stdout, stderr }`; a non-zero exit, a failure to start or a timeout is an
+/// error result.
+pub async fn run_command(
+    server: &ServerDefinition,
+    script: &ScriptTool,
+    arguments: Option<&JsonObject>,
+) -> CallToolResult {
+    let program = script.command.as_deref().unwrap_or_default();
+    let args =
+        crate::config::script::render_args(&script.args, &arguments.cloned().unwrap_or_default());
+    let mut command = tokio::process::Command::new(program);
+    command
+        .args(&args)
+        .envs(&server.env)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let child = match command.spawn() {
+        Ok(child) => child,
+        Err(err) => {
+            return CallToolResult::error(vec![Content::text(format!(
+                "failed to start '{program}': {err}"
+            ))]);
+        }
+    };
+
+    let timeout = Duration::from_secs(script.timeout_secs());
+    match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(Ok(output)) => {
+            let result = json!({
+                "exitCode": output.status.code(),
+                "stdout": String::from_utf8_lossy(&output.stdout),
+                "stderr": String::from_utf8_lossy(&output.stderr),
+            });
+            if output.status.success() {
+                CallToolResult::structured(result)
+            } else {
+                CallToolResult::structured_error(result)
+            }
+        }
+        Ok(Err(err)) => {
+            CallToolResult::error(vec![Content::text(format!("failed to run '{program}': {err}"))])
+        }
+        // Dropping the child on timeout kills it
+        Err(_) => CallToolResult::error(vec![Content::text(format!(
+            "'{}' did not finish within {} seconds",
+            script.name,
+            timeout.as_secs()
+        ))]),
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::config::ServerProtocol;
+
+    fn server(tool: ScriptTool) -> ServerDefinition {
+        ServerDefinition {
+            id: "ops".into(),
+            name: Some("ops".into()),
+            protocol: ServerProtocol::Script,
+            env: BTreeMap::from([("GREETING".to_string(), "hello".to_string())]),
+            enabled: true,
+            tools: vec![tool],
+            ..Default::default()
+        }
+    }
+
+    fn sh(name: &str, script: &str, timeout_secs: Option<u64>) -> ScriptTool {
+        toml_edit::de::from_str(&format!(
+            "name = \"{name}\"\ncommand = \"sh\"\n\
+             args = [\"-c\", {script:?}, \"sh\", \"{{{{who}}}}\"]\n"
+        ))
+        .map(|tool: ScriptTool| ScriptTool { timeout_secs, ..tool })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn captures_output_exit_code_and_timeouts() {
+        let greet = sh("greet", "echo \"$GREETING $1\"; echo oops >&2", None);
+        let arguments = json!({ "who": "world" });
+        let result = run_command(&server(greet.clone()), &greet, arguments.as_object()).await;
+        assert_eq!(result.is_error, Some(false));
+        assert_eq!(
+            result.structured_content,
+            Some(json!({ "exitCode": 0, "stdout": "hello world\n", "stderr": "oops\n" }))
+        );
+
+        let fail = sh("fail", "exit 3", None);
+        let result = run_command(&server(fail.clone()), &fail, None).await;
+        assert_eq!(result.is_error, Some(true));
+        assert_eq!(result.structured_content.unwrap()["exitCode"], json!(3));
+
+        let slow = sh("slow", "sleep 5", Some(1));
+        let result = run_command(&server(slow.clone()), &slow, None).await;
+        assert_eq!(result.is_error, Some(true));
+        assert!(result.structured_content.is_none());
+    }
+}
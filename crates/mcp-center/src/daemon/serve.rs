@@ -11,7 +11,9 @@ use tracing_appender::{non_blocking::WorkerGuard, rolling};
 use tracing_subscriber::{EnvFilter, fmt, prelude::*};
 
 use crate::{
-    daemon::{control, rpc::RpcServer, server_manager::ServerManager},
+    daemon::{
+        auth::SocketToken, control, permissions, rpc::RpcServer, server_manager::ServerManager,
+    },
     web::http::{self, HttpState},
 };
 
@@ -26,6 +28,10 @@ pub struct ServeArgs {
     /// Authentication token required for HTTP API (fallback env MCP_CENTER_HTTP_TOKEN).
     #[arg(long, value_name = "TOKEN")]
     pub http_auth_token: Option<String>,
+    /// Require local socket clients to present the token stored in `state/daemon.token`
+    /// (generated on first use; an existing token file always enables the check).
+    #[arg(long)]
+    pub require_socket_token: bool,
 }
 
 pub async fn run(mut args: ServeArgs) -> Result<()> {
@@ -42,7 +48,7 @@ pub async fn run(mut args: ServeArgs) -> Result<()> {
 }
 
 async fn run_impl(layout: Layout, args: &mut ServeArgs) -> Result<()> {
-    let ServeArgs { http_bind, http_auth_token, .. } = args;
+    let ServeArgs { http_bind, http_auth_token, require_socket_token, .. } = args;
     let http_bind = *http_bind;
     let mut http_auth_token = http_auth_token.take();
     if http_auth_token.is_none() {
@@ -52,13 +58,26 @@ async fn run_impl(layout: Layout, args: &mut ServeArgs) -> Result<()> {
     let registry = ProjectRegistry::new(&layout);
     registry.ensure()?;
 
+    let socket_token = SocketToken::load_or_create(&layout, *require_socket_token)?;
+
     let manager = Arc::new(ServerManager::start(layout.clone()).await?);
-    let control_handle =
-        control::spawn_control_server(layout.clone(), registry.clone(), manager.clone()).await?;
+    // 文件系统变更（其他进程修改设置、服务器配置、项目记录）使会话权限快照失效
+    let policy_watcher = permissions::spawn_policy_watcher(
+        layout.clone(),
+        registry.clone(),
+        permissions::POLICY_WATCH_INTERVAL,
+    );
+    let control_handle = control::spawn_control_server(
+        layout.clone(),
+        registry.clone(),
+        manager.clone(),
+        socket_token.clone(),
+    )
+    .await?;
 
     // Start RPC server for CLI communication
     let rpc_socket_path = layout.daemon_rpc_socket_path();
-    let rpc_server = RpcServer::new(manager.clone(), rpc_socket_path.clone());
+    let rpc_server = RpcServer::new(manager.clone(), rpc_socket_path.clone(), socket_token);
     let rpc_handle = tokio::spawn(async move {
         if let Err(e) = rpc_server.start().await {
             error!("RPC server error: {}", e);
@@ -85,22 +104,24 @@ async fn run_impl(layout: Layout, args: &mut ServeArgs) -> Result<()> {
     );
 
     // 在 bridge 模式下,daemon 只监听 control socket,不在 stdin/stdout 上建立会话
-    // 等待 Ctrl+C 信号来优雅关闭
+    // 等待 Ctrl+C 信号或 bridge 发来的 shutdown 请求来优雅关闭
     let ctrl_c = signal::ctrl_c();
     tokio::pin!(ctrl_c);
 
-    match (&mut ctrl_c).await {
-        Ok(()) => {
-            info!("received Ctrl+C, shutting down daemon");
-        }
-        Err(err) => {
-            warn!(error = ?err, "failed to listen for Ctrl+C");
+    tokio::select! {
+        result = &mut ctrl_c => match result {
+            Ok(()) => info!("received Ctrl+C, shutting down daemon"),
+            Err(err) => warn!(error = ?err, "failed to listen for Ctrl+C"),
+        },
+        _ = control_handle.shutdown_requested() => {
+            info!("bridge requested shutdown, shutting down daemon");
         }
     }
 
     manager.shutdown().await;
     control_handle.shutdown().await;
     rpc_handle.abort();
+    policy_watcher.abort();
 
     if let Some(handle) = http_handle {
         handle.shutdown();
@@ -125,29 +146,29 @@ fn resolve_layout(root_override: Option<PathBuf>) -> Result<Layout> {
 }
 
 fn expand_tilde(path: PathBuf) -> Result<PathBuf> {
-    if let Some(str_path) = path.to_str() {
-        if let Some(stripped) = str_path.strip_prefix('~') {
-            let home = dirs_home().context("cannot expand '~', HOME unset")?;
-            if stripped.is_empty() {
-                return Ok(home);
-            }
-            let stripped = stripped.strip_prefix('/').unwrap_or(stripped);
-            return Ok(home.join(stripped));
+    if let Some(str_path) = path.to_str()
+        && let Some(stripped) = str_path.strip_prefix('~')
+    {
+        let home = dirs_home().context("cannot expand '~', HOME unset")?;
+        if stripped.is_empty() {
+            return Ok(home);
         }
+        let stripped = stripped.strip_prefix('/').unwrap_or(stripped);
+        return Ok(home.join(stripped));
     }
     Ok(path)
 }
 
 fn dirs_home() -> Option<PathBuf> {
-    if let Ok(home) = std::env::var("HOME") {
-        if !home.is_empty() {
-            return Some(PathBuf::from(home));
-        }
+    if let Ok(home) = std::env::var("HOME")
+        && !home.is_empty()
+    {
+        return Some(PathBuf::from(home));
     }
-    if let Ok(profile) = std::env::var("USERPROFILE") {
-        if !profile.is_empty() {
-            return Some(PathBuf::from(profile));
-        }
+    if let Ok(profile) = std::env::var("USERPROFILE")
+        && !profile.is_empty()
+    {
+        return Some(PathBuf::from(profile));
     }
     None
 }
@@ -1,15 +1,26 @@
 use std::{
     borrow::Cow,
     collections::HashMap,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{
         Arc, RwLock as SyncRwLock,
         atomic::{AtomicBool, Ordering},
     },
 };
 
-use super::logging::ServerLogHandle;
-use crate::{Layout, ServerDefinition, ServerProtocol};
+use super::{
+    approvals::ApprovalQueue,
+    cache::ToolCache,
+    hooks::{HookCall, PreOutcome},
+    limits,
+    logging::{Redaction, ServerLogHandle},
+    management, script,
+    validation::SchemaValidators,
+};
+use crate::{
+    Layout, ServerConfig, ServerDefinition, ServerProtocol,
+    config::{hooks::ToolHook, limits::OutputLimits, script::ScriptTool},
+};
 use anyhow::{Context, Result, anyhow};
 use rmcp::{
     ErrorData as McpError,
@@ -75,6 +86,16 @@ impl ToolEntry {
 enum ManagedServerKind {
     LocalProcess,
     Remote,
+    /// Tools declared in the config; nothing to connect to.
+    Script,
+}
+
+/// What the calling project adds to a tool call.
+#[derive(Debug, Clone, Copy)]
+pub struct ProjectCall<'a> {
+    pub path: &'a Path,
+    pub hooks: &'a [ToolHook],
+    pub output_limits: OutputLimits,
 }
 
 pub struct ServerManager {
@@ -82,6 +103,10 @@ pub struct ServerManager {
     servers: SyncRwLock<HashMap<String, Arc<ManagedServer>>>,
     tool_cache: RwLock<Vec<ToolEntry>>,
     tool_index: RwLock<HashMap<String, String>>,
+    schemas: SchemaValidators,
+    approvals: ApprovalQueue,
+    /// Log of the built-in management server, which has no process of its own.
+    management_log: ServerLogHandle,
 }
 
 #[derive(Debug, Clone, Serialize, Type)]
@@ -115,11 +140,20 @@ impl ServerManager {
             }
         }
 
+        let management_log = ServerLogHandle::new(
+            management::SERVER_ID.to_string(),
+            management::SERVER_NAME.to_string(),
+            layout.server_log_dir(management::SERVER_ID),
+        )
+        .await?;
         let manager = Self {
             layout,
             servers: SyncRwLock::new(servers),
             tool_cache: RwLock::new(Vec::new()),
             tool_index: RwLock::new(HashMap::new()),
+            schemas: SchemaValidators::default(),
+            approvals: ApprovalQueue::default(),
+            management_log,
         };
 
         // Refresh tool cache (will be empty if no servers)
@@ -136,6 +170,11 @@ impl ServerManager {
         self.servers.read().unwrap().keys().cloned().collect()
     }
 
+    /// Tool calls waiting for approval, shared by every session.
+    pub fn approvals(&self) -> &ApprovalQueue {
+        &self.approvals
+    }
+
     pub fn list_server_names(&self) -> Vec<String> {
         self.servers
             .read()
@@ -146,6 +185,9 @@ impl ServerManager {
     }
 
     pub fn get_log_handle(&self, server_id: &str) -> Option<ServerLogHandle> {
+        if server_id == management::SERVER_ID {
+            return Some(self.management_log.clone());
+        }
         self.servers.read().unwrap().get(server_id).map(|server| server.log.clone())
     }
 
@@ -207,6 +249,58 @@ impl ServerManager {
         }
     }
 
+    /// Persist `enabled` in the server's config, then start or stop the server to match.
+    /// The config change stands even if starting or stopping fails; that failure is
+    /// returned as a warning.
+    pub async fn set_server_enabled(
+        &self,
+        server_id: &str,
+        enabled: bool,
+    ) -> Result<(ServerConfig, Option<String>)> {
+        let mut config = self.layout.load_server_config(server_id)?;
+        config.definition_mut().enabled = enabled;
+        let path = config
+            .source()
+            .ok_or_else(|| anyhow!("server config path is unknown"))?
+            .to_path_buf();
+        let toml = config.to_toml_string()?;
+        fs::write(&path, toml).await.context("failed to write server config")?;
+
+        let mut warning = None;
+        if enabled {
+            if let Err(err) = self.ensure_server_running(server_id).await {
+                warn!(server_id, error = ?err, "failed to start MCP server after enabling");
+                warning = Some(format!("failed to start MCP server: {err}"));
+            }
+        } else if let Err(err) = self.disable_server(server_id).await {
+            warn!(server_id, error = ?err, "failed to stop MCP server after disabling");
+            warning = Some(format!("failed to stop MCP server: {err}"));
+        }
+        Ok((config, warning))
+    }
+
+    /// `tool_name` if it is a composite tool of the script server `server_id`.
+    pub fn composite_tool(&self, server_id: &str, tool_name: &str) -> Option<ScriptTool> {
+        let server = self.servers.read().unwrap().get(server_id).cloned()?;
+        server
+            .definition
+            .tools
+            .iter()
+            .find(|tool| tool.name == tool_name && tool.is_composite())
+            .cloned()
+    }
+
+    /// Whether calls to `server_id` are checked against the tools' schemas.
+    pub fn validates_schemas(&self, server_id: &str) -> bool {
+        let server = self.servers.read().unwrap().get(server_id).cloned();
+        server.is_none_or(|server| server.definition.validate_schemas)
+    }
+
+    /// Compiled tool schemas, valid until the next tool list refresh.
+    pub fn schemas(&self) -> &SchemaValidators {
+        &self.schemas
+    }
+
     pub async fn tool_count_for(&self, server_id: &str) -> Option<usize> {
         let server = {
             let guard = self.servers.read().unwrap();
@@ -220,9 +314,13 @@ impl ServerManager {
         }
     }
 
+    /// Call a tool on the server that owns it, through the server's hooks and then those of
+    /// `project`; `redaction` masks secrets in its log.
     pub async fn call_tool(
         &self,
         params: CallToolRequestParam,
+        redaction: &Redaction,
+        project: Option<ProjectCall<'_>>,
     ) -> Result<CallToolResult, McpError> {
         self.ensure_tool_cache()
             .await
@@ -240,6 +338,11 @@ impl ServerManager {
             debug!("  tool not found in index");
             return Err(McpError::method_not_found::<CallToolRequestMethod>());
         };
+        if server_id == management::SERVER_ID {
+            // Sessions answer built-in tools themselves; reaching here means it is not offered
+            debug!("  built-in tool not offered to this session");
+            return Err(McpError::method_not_found::<CallToolRequestMethod>());
+        }
 
         debug!("  routed to server_id: {}", server_id);
 
@@ -254,9 +357,9 @@ impl ServerManager {
         debug!("  server_name: {}", server.display_name());
 
         server
-            .call_tool(params)
+            .call_tool(params, redaction, project)
             .await
-            .map_err(|err| McpError::internal_error(err.to_string(), None))
+            .map_err(|err| McpError::internal_error(redaction.text(err.to_string()), None))
     }
 
     /// 获取指定tool所属的server名称（用于权限检查）
@@ -334,8 +437,24 @@ impl ServerManager {
             }
         }
 
+        // Built-in tools never displace an upstream tool of the same name
+        for entry in management::tool_entries() {
+            let tool_name = entry.tool.name.to_string();
+            if let Some(existing) = new_index.get(&tool_name) {
+                warn!(
+                    tool = %tool_name,
+                    server = %existing,
+                    "upstream tool shadows a built-in management tool"
+                );
+                continue;
+            }
+            new_index.insert(tool_name, entry.server_id.clone());
+            new_entries.push(entry);
+        }
+
         *self.tool_index.write().await = new_index;
         *self.tool_cache.write().await = new_entries;
+        self.schemas.clear();
 
         Ok(())
     }
@@ -347,6 +466,8 @@ struct ManagedServer {
     tools: RwLock<Vec<Tool>>,
     needs_refresh: Arc<AtomicBool>,
     log: ServerLogHandle,
+    cache: Option<ToolCache>,
+    outputs_dir: PathBuf,
 }
 
 struct ServerRuntime {
@@ -362,6 +483,11 @@ impl ManagedServer {
         let log_dir = layout.server_log_dir(&definition.id);
         let log = ServerLogHandle::new(definition.id.clone(), server_name, log_dir).await?;
         let adapter = ServerAdapter::new(log.clone(), needs_refresh.clone());
+        let cache = definition
+            .cache
+            .clone()
+            .map(|config| ToolCache::new(layout, &definition.id, config));
+        let outputs_dir = layout.outputs_dir();
 
         match definition.protocol {
             ServerProtocol::StdIo => {
@@ -377,16 +503,18 @@ impl ManagedServer {
                     tools: RwLock::new(Vec::new()),
                     needs_refresh,
                     log,
+                    cache,
+                    outputs_dir,
                 });
                 Ok(server)
             }
             ServerProtocol::Sse | ServerProtocol::Http => {
                 let client = Self::connect_remote(&definition, adapter.clone()).await?;
                 let pid_path = layout.server_pid_path(&definition.id);
-                if let Err(err) = fs::remove_file(&pid_path).await {
-                    if err.kind() != std::io::ErrorKind::NotFound {
-                        warn!(error = ?err, path = %pid_path.display(), "failed to clean remote pid file");
-                    }
+                if let Err(err) = fs::remove_file(&pid_path).await
+                    && err.kind() != std::io::ErrorKind::NotFound
+                {
+                    warn!(error = ?err, path = %pid_path.display(), "failed to clean remote pid file");
                 }
                 let server = Arc::new(Self {
                     definition,
@@ -398,9 +526,24 @@ impl ManagedServer {
                     tools: RwLock::new(Vec::new()),
                     needs_refresh,
                     log,
+                    cache,
+                    outputs_dir,
                 });
                 Ok(server)
             }
+            ServerProtocol::Script => Ok(Arc::new(Self {
+                definition,
+                runtime: Mutex::new(ServerRuntime {
+                    client: None,
+                    pid_path: None,
+                    kind: ManagedServerKind::Script,
+                }),
+                tools: RwLock::new(Vec::new()),
+                needs_refresh,
+                log,
+                cache,
+                outputs_dir,
+            })),
             ServerProtocol::Unknown => Err(anyhow!("unsupported protocol: unknown")),
         }
     }
@@ -504,7 +647,17 @@ impl ManagedServer {
         self.needs_refresh.load(Ordering::SeqCst)
     }
 
+    fn is_script(&self) -> bool {
+        self.definition.protocol == ServerProtocol::Script
+    }
+
     async fn refresh_tools(&self) -> Result<Vec<Tool>> {
+        if self.is_script() {
+            let tools = self.definition.tools.iter().map(script::tool).collect::<Vec<_>>();
+            *self.tools.write().await = tools.clone();
+            self.needs_refresh.store(false, Ordering::SeqCst);
+            return Ok(tools);
+        }
         let peer = {
             let runtime = self.runtime.lock().await;
             runtime
@@ -528,22 +681,27 @@ impl ManagedServer {
 
     async fn call_tool(
         &self,
-        params: CallToolRequestParam,
+        mut params: CallToolRequestParam,
+        redaction: &Redaction,
+        project: Option<ProjectCall<'_>>,
     ) -> Result<CallToolResult, ServiceError> {
         let call_id = Uuid::new_v4().to_string();
         let tool_name = params.name.clone().into_owned();
         let arguments_snapshot = params.arguments.clone();
-        let peer = {
+        let peer = if self.is_script() {
+            None
+        } else {
             let runtime = self.runtime.lock().await;
-            runtime
+            let peer = runtime
                 .client
                 .as_ref()
                 .map(|client| client.peer().clone())
-                .ok_or_else(|| ServiceError::TransportClosed)?
+                .ok_or_else(|| ServiceError::TransportClosed)?;
+            Some(peer)
         };
         if let Err(err) = self
             .log
-            .log_tool_request(&call_id, &tool_name, arguments_snapshot.as_ref())
+            .log_tool_request(&call_id, &tool_name, arguments_snapshot.as_ref(), redaction)
             .await
         {
             warn!(
@@ -554,11 +712,82 @@ impl ManagedServer {
             );
         }
         let start = Instant::now();
-        match peer.call_tool(params).await {
-            Ok(result) => {
-                if let Err(err) =
-                    self.log.log_tool_response(&call_id, &tool_name, start.elapsed(), &result).await
+        let hooks = self
+            .definition
+            .hooks
+            .iter()
+            .chain(project.map(|project| project.hooks).unwrap_or_default())
+            .collect::<Vec<_>>();
+        let hook_call = HookCall {
+            server_id: &self.definition.id,
+            tool_name: &tool_name,
+            project: project.map(|project| project.path),
+            call_id: &call_id,
+            log: &self.log,
+            redaction,
+        };
+        let mut cache_age = None;
+        let outcome = match hook_call.run_pre(&hooks, params.arguments.take()).await {
+            PreOutcome::Call(arguments) => {
+                params.arguments = arguments;
+                let arguments = params.arguments.clone();
+                let cache_key = self.cache_key(&params).await;
+                let cached = cache_key.as_deref().zip(self.cache.as_ref());
+                let hit = match cached {
+                    Some((key, cache)) => cache.get(key).await,
+                    None => None,
+                };
+                let outcome = match (hit, peer) {
+                    (Some(hit), _) => {
+                        cache_age = Some(hit.age);
+                        Ok(hit.result)
+                    }
+                    (None, Some(peer)) => peer.call_tool(params).await,
+                    (None, None) => self.call_script_tool(&params).await,
+                };
+                if cache_age.is_none()
+                    && let (Some((key, cache)), Ok(result)) = (cached, &outcome)
+                    && result.is_error != Some(true)
+                    && let Err(err) = cache.put(key, &tool_name, result).await
                 {
+                    warn!(error = ?err, server_id = %self.definition.id, "failed to cache result");
+                }
+                match outcome {
+                    Ok(result) => Ok(hook_call.run_post(&hooks, arguments.as_ref(), result).await),
+                    Err(err) => Err(err),
+                }
+            }
+            PreOutcome::Answer(result) => Ok(result),
+        };
+        // Limit before logging so the log holds what the client received
+        let output_limits = self.definition.output_limits.unwrap_or_default();
+        let output_limits = match project {
+            Some(project) => output_limits.tightest(project.output_limits),
+            None => output_limits,
+        };
+        let outcome = match outcome {
+            Ok(result) => Ok(limits::enforce(&output_limits, result, &self.outputs_dir).await),
+            Err(err) => Err(err),
+        };
+        match outcome {
+            Ok(result) => {
+                let logged = match cache_age {
+                    Some(age) => {
+                        self.log.log_cache_hit(&call_id, &tool_name, age, &result, redaction).await
+                    }
+                    None => {
+                        self.log
+                            .log_tool_response(
+                                &call_id,
+                                &tool_name,
+                                start.elapsed(),
+                                &result,
+                                redaction,
+                            )
+                            .await
+                    }
+                };
+                if let Err(err) = logged {
                     warn!(
                         error = ?err,
                         server_id = %self.definition.id,
@@ -569,8 +798,10 @@ impl ManagedServer {
                 Ok(result)
             }
             Err(err) => {
-                if let Err(log_err) =
-                    self.log.log_tool_error(&call_id, &tool_name, start.elapsed(), &err).await
+                if let Err(log_err) = self
+                    .log
+                    .log_tool_error(&call_id, &tool_name, start.elapsed(), &err, redaction)
+                    .await
                 {
                     warn!(
                         error = ?log_err,
@@ -584,22 +815,47 @@ impl ManagedServer {
         }
     }
 
+    /// Cache key of the call when this server caches the tool's results.
+    async fn cache_key(&self, params: &CallToolRequestParam) -> Option<String> {
+        let cache = self.cache.as_ref()?;
+        let tools = self.tools.read().await;
+        let tool = tools.iter().find(|tool| tool.name == params.name)?;
+        cache
+            .caches(tool)
+            .then(|| ToolCache::key(&params.name, params.arguments.as_ref()))
+    }
+
+    async fn call_script_tool(
+        &self,
+        params: &CallToolRequestParam,
+    ) -> Result<CallToolResult, ServiceError> {
+        let tool = self.definition.tools.iter().find(|tool| tool.name == params.name).ok_or_else(
+            || ServiceError::McpError(McpError::method_not_found::<CallToolRequestMethod>()),
+        )?;
+        if tool.is_composite() {
+            // Steps need the calling session's permissions, so only a session can run them
+            return Err(ServiceError::McpError(McpError::invalid_request(
+                format!("composite tool '{}' can only be called from a session", tool.name),
+                None,
+            )));
+        }
+        Ok(script::run_command(&self.definition, tool, params.arguments.as_ref()).await)
+    }
+
     async fn shutdown(&self) -> Result<()> {
         let mut runtime = self.runtime.lock().await;
-        if let Some(client) = runtime.client.take() {
-            if let Err(err) = client.cancel().await {
-                warn!(error = ?err, server_id = %self.definition.id, "error while cancelling MCP connection");
-            }
+        if let Some(client) = runtime.client.take()
+            && let Err(err) = client.cancel().await
+        {
+            warn!(error = ?err, server_id = %self.definition.id, "error while cancelling MCP connection");
         }
 
         if let (ManagedServerKind::LocalProcess, Some(pid_path)) =
             (runtime.kind, runtime.pid_path.clone())
+            && let Err(err) = fs::remove_file(&pid_path).await
+            && err.kind() != std::io::ErrorKind::NotFound
         {
-            if let Err(err) = fs::remove_file(&pid_path).await {
-                if err.kind() != std::io::ErrorKind::NotFound {
-                    warn!(error = ?err, path = %pid_path.display(), "failed to remove pid file");
-                }
-            }
+            warn!(error = ?err, path = %pid_path.display(), "failed to remove pid file");
         }
         Ok(())
     }
@@ -0,0 +1,355 @@
+//! Per-session restrictions negotiated during the bridge handshake.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::RwLock,
+};
+
+use anyhow::{Result, bail};
+use rmcp::model::{Root, Tool};
+use tracing::{info, warn};
+
+use crate::{
+    Layout, ProjectId, ProjectRecord, ProjectRegistry,
+    bridge::control::SessionScope,
+    config::{WorkspaceSettings, settings::RootMergePolicy},
+    project::{
+        identity,
+        policy::{EffectivePolicy, ProjectRule},
+    },
+};
+
+/// Resolved form of a [`SessionScope`]: server ids instead of names, profile merged in.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionFilter {
+    /// Server ids the session may use (`None` = no restriction).
+    pub server_ids: Option<HashSet<String>>,
+    /// Only tools annotated with `readOnlyHint: true` are visible.
+    pub read_only: bool,
+}
+
+impl SessionFilter {
+    /// Resolve a scope requested by the bridge against the workspace configuration.
+    ///
+    /// Unknown profiles or servers are rejected so a typo never silently widens a session.
+    pub fn resolve(scope: &SessionScope, layout: &Layout) -> Result<Self> {
+        if scope.is_unrestricted() {
+            return Ok(Self::default());
+        }
+
+        let configs = layout.list_server_configs()?;
+        let resolve_server = |name: &str| -> Result<String> {
+            configs
+                .iter()
+                .map(|config| config.definition())
+                .find(|definition| {
+                    definition.id == name || definition.name.as_deref() == Some(name)
+                })
+                .map(|definition| definition.id.clone())
+                .ok_or_else(|| anyhow::anyhow!("unknown MCP server '{name}' in session scope"))
+        };
+
+        let mut filter = Self { server_ids: None, read_only: scope.read_only };
+
+        if let Some(profile_name) = scope.profile.as_deref() {
+            let settings = WorkspaceSettings::load(layout)?;
+            let Some(profile) = settings.profiles.get(profile_name) else {
+                bail!("unknown profile '{profile_name}'");
+            };
+            filter.read_only |= profile.read_only;
+            if !profile.servers.is_empty() {
+                let ids = profile
+                    .servers
+                    .iter()
+                    .map(|name| resolve_server(name))
+                    .collect::<Result<HashSet<_>>>()?;
+                filter.server_ids = Some(ids);
+            }
+        }
+
+        if let Some(servers) = scope.servers.as_ref() {
+            let ids = servers
+                .iter()
+                .map(|name| resolve_server(name))
+                .collect::<Result<HashSet<_>>>()?;
+            filter.server_ids = Some(match filter.server_ids.take() {
+                Some(existing) => existing.intersection(&ids).cloned().collect(),
+                None => ids,
+            });
+        }
+
+        Ok(filter)
+    }
+
+    pub fn allows_server(&self, server_id: &str) -> bool {
+        self.server_ids.as_ref().map(|ids| ids.contains(server_id)).unwrap_or(true)
+    }
+
+    pub fn allows_tool(&self, server_id: &str, tool: &Tool) -> bool {
+        if !self.allows_server(server_id) {
+            return false;
+        }
+        if self.read_only {
+            return tool.annotations.as_ref().and_then(|a| a.read_only_hint).unwrap_or(false);
+        }
+        true
+    }
+}
+
+/// Projects a session is bound to, one per workspace root reported by the client.
+///
+/// Starts with the project from `BridgeHello` and is replaced whenever the client answers
+/// `roots/list` (initially and after `notifications/roots/list_changed`).
+#[derive(Debug)]
+pub struct ProjectBinding {
+    bound: RwLock<Vec<(ProjectId, PathBuf)>>,
+    merge: RootMergePolicy,
+}
+
+impl ProjectBinding {
+    pub fn new(project_id: ProjectId, path: PathBuf, merge: RootMergePolicy) -> Self {
+        Self { bound: RwLock::new(vec![(project_id, path)]), merge }
+    }
+
+    /// Project bound to the first root; used for per-project customisations.
+    pub fn primary(&self) -> ProjectId {
+        self.bound.read().unwrap()[0].0.clone()
+    }
+
+    pub fn primary_path(&self) -> PathBuf {
+        self.bound.read().unwrap()[0].1.clone()
+    }
+
+    pub fn project_ids(&self) -> Vec<ProjectId> {
+        self.bound.read().unwrap().iter().map(|(id, _)| id.clone()).collect()
+    }
+
+    pub fn roots(&self) -> Vec<PathBuf> {
+        self.bound.read().unwrap().iter().map(|(_, path)| path.clone()).collect()
+    }
+
+    pub fn merge_policy(&self) -> RootMergePolicy {
+        self.merge
+    }
+
+    /// Combine per-project verdicts according to the merge policy.
+    pub fn merge<I: IntoIterator<Item = bool>>(&self, verdicts: I) -> bool {
+        let mut verdicts = verdicts.into_iter();
+        match self.merge {
+            RootMergePolicy::Union => verdicts.any(|allowed| allowed),
+            RootMergePolicy::Intersection => verdicts.all(|allowed| allowed),
+        }
+    }
+
+    fn replace(&self, bound: Vec<(ProjectId, PathBuf)>) {
+        if !bound.is_empty() {
+            *self.bound.write().unwrap() = bound;
+        }
+    }
+}
+
+/// Extract local filesystem paths from a `roots/list` answer, skipping non-`file://` roots.
+pub fn root_paths(roots: &[Root]) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for root in roots {
+        match parse_file_uri(&root.uri) {
+            Some(path) if !paths.contains(&path) => paths.push(path),
+            Some(_) => {}
+            None => warn!(uri = %root.uri, "ignoring non-file workspace root"),
+        }
+    }
+    paths
+}
+
+/// Bind the session to one project per root, creating missing records from `template`.
+///
+/// Roots covered by a project rule or a parent project get an empty record so they
+/// inherit instead. Returns the ids now bound to the session. Runs git and reads the whole
+/// registry, so async callers go through `spawn_blocking`.
+pub fn bind_roots(
+    registry: &ProjectRegistry,
+    binding: &ProjectBinding,
+    roots: &[PathBuf],
+    template: &ProjectRecord,
+    rules: &[ProjectRule],
+) -> Vec<ProjectId> {
+    let mut bound = Vec::with_capacity(roots.len());
+    for root in roots {
+        let project_id = ProjectId::from_path(root);
+        match registry.load(&project_id) {
+            Ok(mut existing) => {
+                existing.path = root.clone();
+                identity::detect_moved_project(registry, &mut existing, false);
+                existing.touch();
+                if let Err(err) = registry.store(&existing) {
+                    warn!(error = ?err, path = %root.display(), "failed to update project record");
+                }
+            }
+            Err(_) => {
+                let mut record = ProjectRecord::new(project_id.clone(), root.clone());
+                if !EffectivePolicy::resolve(registry, rules, root).has_inherited_layers() {
+                    // 继承初始记录的服务器权限配置
+                    record.allowed_server_ids = template.allowed_server_ids.clone();
+                    record.allowed_server_tools = template.allowed_server_tools.clone();
+                    record.status = template.status;
+                }
+                record.agent = template.agent.clone();
+                record.metadata = template.metadata.clone();
+                if let Some(previous) = identity::detect_moved_project(registry, &mut record, true)
+                {
+                    warn!("{}", identity::rebind_hint(&previous, root));
+                }
+                if let Err(err) = registry.store(&record) {
+                    warn!(error = ?err, path = %root.display(), "failed to create project record");
+                } else {
+                    info!(project = %project_id.as_str(), path = %root.display(), "created project record for workspace root");
+                }
+            }
+        }
+        bound.push((project_id, root.clone()));
+    }
+
+    let ids = bound.iter().map(|(id, _)| id.clone()).collect();
+    binding.replace(bound);
+    info!(roots = ?binding.roots(), policy = ?binding.merge_policy(), "session bound to workspace roots");
+    ids
+}
+
+/// Parse a file:// URI to a local filesystem path
+/// Examples:
+///   file:///Users/wangnov/project -> /Users/wangnov/project
+///   file://localhost/Users/wangnov/project -> /Users/wangnov/project
+pub fn parse_file_uri(uri: &str) -> Option<PathBuf> {
+    let uri = uri.trim();
+
+    // Handle file:// scheme
+    if let Some(stripped) = uri.strip_prefix("file://") {
+        // Remove optional localhost
+        let path_part = stripped.strip_prefix("localhost").unwrap_or(stripped);
+
+        // The path should start with /
+        if path_part.starts_with('/') {
+            return Some(PathBuf::from(path_part));
+        }
+    }
+
+    None
+}
+
+/// Whether `path` is a parent of any of `roots` (used to drop records created from a guess).
+pub fn is_parent_of_any(path: &Path, roots: &[PathBuf]) -> bool {
+    roots.iter().any(|root| root != path && root.starts_with(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, sync::Arc};
+
+    use rmcp::model::ToolAnnotations;
+
+    use super::*;
+    use crate::{ServerConfig, ServerDefinition};
+
+    fn layout_with_servers(names: &[(&str, &str)]) -> (tempfile::TempDir, Layout) {
+        let temp = tempfile::tempdir().unwrap();
+        let layout = Layout::new(temp.path().to_path_buf());
+        layout.ensure().unwrap();
+        for (id, name) in names {
+            let config = ServerConfig::new(ServerDefinition {
+                id: id.to_string(),
+                name: Some(name.to_string()),
+                command: "echo".into(),
+                enabled: true,
+                ..Default::default()
+            })
+            .unwrap();
+            fs::write(layout.server_config_toml_path(id), config.to_toml_string().unwrap())
+                .unwrap();
+        }
+        (temp, layout)
+    }
+
+    fn tool(read_only: Option<bool>) -> Tool {
+        let mut tool = Tool::new("t", "tool", Arc::new(serde_json::Map::new()));
+        if let Some(flag) = read_only {
+            tool.annotations = Some(ToolAnnotations::new().read_only(flag));
+        }
+        tool
+    }
+
+    #[test]
+    fn profile_and_servers_intersect() {
+        let (_temp, layout) = layout_with_servers(&[("a1", "alpha"), ("b2", "beta")]);
+        fs::write(layout.settings_path(), "[profiles.dev]\nservers = [\"alpha\", \"b2\"]\n")
+            .unwrap();
+
+        let scope = SessionScope {
+            servers: Some(vec!["beta".into()]),
+            profile: Some("dev".into()),
+            read_only: false,
+        };
+        let filter = SessionFilter::resolve(&scope, &layout).unwrap();
+        assert!(filter.allows_server("b2"));
+        assert!(!filter.allows_server("a1"));
+    }
+
+    #[test]
+    fn rejects_unknown_names() {
+        let (_temp, layout) = layout_with_servers(&[("a1", "alpha")]);
+        let unknown_server =
+            SessionScope { servers: Some(vec!["gamma".into()]), ..SessionScope::default() };
+        assert!(SessionFilter::resolve(&unknown_server, &layout).is_err());
+        let unknown_profile =
+            SessionScope { profile: Some("missing".into()), ..SessionScope::default() };
+        assert!(SessionFilter::resolve(&unknown_profile, &layout).is_err());
+    }
+
+    #[test]
+    fn read_only_hides_unannotated_tools() {
+        let filter = SessionFilter { server_ids: None, read_only: true };
+        assert!(filter.allows_tool("a1", &tool(Some(true))));
+        assert!(!filter.allows_tool("a1", &tool(Some(false))));
+        assert!(!filter.allows_tool("a1", &tool(None)));
+    }
+
+    #[test]
+    fn binds_every_root_and_merges_permissions() {
+        let temp = tempfile::tempdir().unwrap();
+        let layout = Layout::new(temp.path().to_path_buf());
+        let registry = ProjectRegistry::new(&layout);
+        registry.ensure().unwrap();
+
+        let web = PathBuf::from("/work/web");
+        let api = PathBuf::from("/work/api");
+        let mut existing = ProjectRecord::new(ProjectId::from_path(&api), api.clone());
+        existing.allowed_server_ids = vec!["db".into()];
+        registry.store(&existing).unwrap();
+
+        let mut template = ProjectRecord::new(ProjectId::from_path(&web), web.clone());
+        template.allowed_server_ids = vec!["github".into()];
+
+        let roots = root_paths(&[
+            Root { uri: "file:///work/web".into(), name: None },
+            Root { uri: "file:///work/api".into(), name: None },
+            Root { uri: "https://example.test".into(), name: None },
+        ]);
+        assert_eq!(roots, vec![web.clone(), api.clone()]);
+
+        for (policy, expect_github) in
+            [(RootMergePolicy::Union, true), (RootMergePolicy::Intersection, false)]
+        {
+            let binding = ProjectBinding::new(ProjectId::from_path(&web), web.clone(), policy);
+            let bound = bind_roots(&registry, &binding, &roots, &template, &[]);
+            assert_eq!(bound.len(), 2);
+            assert_eq!(binding.primary(), ProjectId::from_path(&web));
+
+            let verdicts = binding
+                .project_ids()
+                .iter()
+                .map(|id| registry.load(id).unwrap().allows_tool("github", "search"))
+                .collect::<Vec<_>>();
+            assert_eq!(binding.merge(verdicts), expect_github);
+        }
+    }
+}
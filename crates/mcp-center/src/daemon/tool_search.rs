@@ -0,0 +1,249 @@
+//! Meta-tools that stand in for the full tool list.
+//!
+//! Projects with tool search enabled see only [`SEARCH_TOOLS`], [`DESCRIBE_TOOL`] and
+//! [`INVOKE_TOOL`] in `tools/list`. The agent finds tools by keyword, fetches the schema of
+//! the ones it needs and calls them through `invoke_tool`, which takes the same path as a
+//! direct call. Searching and describing only ever see the tools the session may list.
+
+use std::sync::Arc;
+
+use rmcp::{
+    ErrorData as McpError,
+    model::{CallToolRequestParam, JsonObject, Tool, ToolAnnotations},
+};
+use serde_json::{Value, json};
+
+pub const SEARCH_TOOLS: &str = "search_tools";
+pub const DESCRIBE_TOOL: &str = "describe_tool";
+pub const INVOKE_TOOL: &str = "invoke_tool";
+
+/// Matches returned by `search_tools` unless the agent asks for another amount.
+pub const DEFAULT_LIMIT: usize = 10;
+pub const MAX_LIMIT: usize = 50;
+
+/// A call to one of the meta-tools.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetaCall {
+    Search {
+        query: String,
+        limit: usize,
+    },
+    Describe {
+        name: String,
+    },
+    Invoke {
+        name: String,
+        arguments: Option<JsonObject>,
+    },
+}
+
+impl MetaCall {
+    /// Parse `params` if they address a meta-tool; `None` for any other tool.
+    pub fn parse(params: &CallToolRequestParam) -> Option<Result<Self, McpError>> {
+        let arguments = params.arguments.clone().unwrap_or_default();
+        let string = |key: &str| match arguments.get(key) {
+            Some(Value::String(value)) => Ok(value.clone()),
+            _ => Err(McpError::invalid_params(
+                format!("'{}' requires a string argument '{key}'", params.name),
+                None,
+            )),
+        };
+        let call = match params.name.as_ref() {
+            SEARCH_TOOLS => string("query").map(|query| MetaCall::Search {
+                query,
+                limit: arguments
+                    .get("limit")
+                    .and_then(Value::as_u64)
+                    .map_or(DEFAULT_LIMIT, |limit| (limit as usize).clamp(1, MAX_LIMIT)),
+            }),
+            DESCRIBE_TOOL => string("name").map(|name| MetaCall::Describe { name }),
+            INVOKE_TOOL => string("name").and_then(|name| match arguments.get("arguments") {
+                None | Some(Value::Null) => Ok(MetaCall::Invoke { name, arguments: None }),
+                Some(Value::Object(inner)) => {
+                    Ok(MetaCall::Invoke { name, arguments: Some(inner.clone()) })
+                }
+                Some(_) => Err(McpError::invalid_params(
+                    format!("'{INVOKE_TOOL}' expects 'arguments' to be an object"),
+                    None,
+                )),
+            }),
+            _ => return None,
+        };
+        Some(call)
+    }
+}
+
+/// The tools advertised in place of the real ones.
+pub fn meta_tools() -> Vec<Tool> {
+    vec![
+        Tool::new(
+            SEARCH_TOOLS,
+            "Search the available tools by keyword. Returns matching tool names with their \
+             descriptions; use describe_tool for a tool's parameters.",
+            schema(json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Keywords describing the task." },
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_LIMIT,
+                        "description": format!("Maximum matches (default {DEFAULT_LIMIT})."),
+                    },
+                },
+                "required": ["query"],
+            })),
+        )
+        .annotate(ToolAnnotations::new().read_only(true).open_world(false)),
+        Tool::new(
+            DESCRIBE_TOOL,
+            "Return the full definition of a tool, including its input schema.",
+            schema(json!({
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string", "description": "Tool name from search_tools." },
+                },
+                "required": ["name"],
+            })),
+        )
+        .annotate(ToolAnnotations::new().read_only(true).open_world(false)),
+        Tool::new(
+            INVOKE_TOOL,
+            "Call a tool found with search_tools, passing arguments that match its input schema.",
+            schema(json!({
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string", "description": "Tool name from search_tools." },
+                    "arguments": { "type": "object", "description": "Arguments for the tool." },
+                },
+                "required": ["name"],
+            })),
+        ),
+    ]
+}
+
+fn schema(value: Value) -> Arc<JsonObject> {
+    match value {
+        Value::Object(object) => Arc::new(object),
+        _ => Arc::default(),
+    }
+}
+
+/// The `limit` best keyword matches for `query`, best first. Every query term scores on
+/// the tool name (whole name, name word, substring) and, with less weight, on the title
+/// and description; tools matching nothing are left out.
+pub fn rank<'a>(query: &str, tools: &'a [Tool], limit: usize) -> Vec<&'a Tool> {
+    let terms = words(query);
+    let mut scored = tools
+        .iter()
+        .filter_map(|tool| {
+            let score = terms.iter().map(|term| score(term, tool)).sum::<usize>();
+            (score > 0).then_some((score, tool))
+        })
+        .collect::<Vec<_>>();
+    scored.sort_by(|(a, tool_a), (b, tool_b)| b.cmp(a).then_with(|| tool_a.name.cmp(&tool_b.name)));
+    scored.into_iter().take(limit).map(|(_, tool)| tool).collect()
+}
+
+fn score(term: &str, tool: &Tool) -> usize {
+    let name = tool.name.to_lowercase();
+    let mut score = 0;
+    if name == term {
+        score += 10;
+    }
+    if words(&name).iter().any(|word| word == term) {
+        score += 5;
+    } else if name.contains(term) {
+        score += 3;
+    }
+    let text = [tool.title.as_deref(), tool.description.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let text_words = words(&text);
+    if text_words.iter().any(|word| word == term) {
+        score += 2;
+    } else if text_words.iter().any(|word| word.starts_with(term)) {
+        score += 1;
+    }
+    score
+}
+
+/// Lowercase words split at anything that is not a letter or digit.
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Entry of a `search_tools` result.
+pub fn summary(tool: &Tool) -> Value {
+    json!({
+        "name": tool.name,
+        "title": tool.title,
+        "description": tool.description,
+    })
+}
+
+/// Result of `describe_tool`.
+pub fn definition(tool: &Tool) -> Value {
+    serde_json::to_value(tool).unwrap_or_else(|_| summary(tool))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(name: &'static str, description: &'static str) -> Tool {
+        Tool::new(name, description, Arc::new(JsonObject::new()))
+    }
+
+    #[test]
+    fn ranks_name_matches_above_description_matches() {
+        let tools = vec![
+            tool("create_issue", "Open a new issue in a repository"),
+            tool("search_code", "Search code across repositories"),
+            tool("list_commits", "List commits; mentions issue numbers"),
+            tool("read_file", "Read a file from disk"),
+        ];
+        let names = |query: &str| {
+            rank(query, &tools, DEFAULT_LIMIT)
+                .into_iter()
+                .map(|tool| tool.name.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names("issue"), ["create_issue", "list_commits"]);
+        assert_eq!(names("Search repo"), ["search_code", "create_issue"]);
+        assert!(names("kubernetes").is_empty());
+        assert_eq!(rank("issue", &tools, 1).len(), 1);
+    }
+
+    #[test]
+    fn parses_meta_calls() {
+        let params = |name: &'static str, arguments: Value| CallToolRequestParam {
+            name: name.into(),
+            arguments: arguments.as_object().cloned(),
+        };
+        assert_eq!(
+            MetaCall::parse(&params(SEARCH_TOOLS, json!({ "query": "db", "limit": 500 })))
+                .unwrap()
+                .unwrap(),
+            MetaCall::Search { query: "db".into(), limit: MAX_LIMIT }
+        );
+        assert_eq!(
+            MetaCall::parse(&params(INVOKE_TOOL, json!({ "name": "query" })))
+                .unwrap()
+                .unwrap(),
+            MetaCall::Invoke { name: "query".into(), arguments: None }
+        );
+        assert!(MetaCall::parse(&params(DESCRIBE_TOOL, json!({}))).unwrap().is_err());
+        assert!(
+            MetaCall::parse(&params(INVOKE_TOOL, json!({ "name": "q", "arguments": [1] })))
+                .unwrap()
+                .is_err()
+        );
+        assert!(MetaCall::parse(&params("read_file", json!({}))).is_none());
+    }
+}
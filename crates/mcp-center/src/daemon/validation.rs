@@ -0,0 +1,187 @@
+//! Checking tool arguments and structured results against the JSON Schemas a tool declares.
+//!
+//! Schemas come from the upstream server, so one that does not compile is not held against
+//! the caller: it is reported in the daemon log once and the value passes unchecked.
+//! Compiled schemas are kept per server and tool until the next tool list refresh.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, RwLock},
+};
+
+use jsonschema::Validator;
+use rmcp::model::{JsonObject, Tool};
+use serde::Serialize;
+use serde_json::Value;
+use tracing::warn;
+
+/// One place where a value does not match its schema.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// JSON pointer to the offending value; empty for the value itself.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.path.as_str() {
+            "" => write!(f, "(root): {}", self.message),
+            path => write!(f, "{path}: {}", self.message),
+        }
+    }
+}
+
+/// Which of a tool's schemas a value is checked against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum SchemaKind {
+    Input,
+    Output,
+}
+
+/// Compiled schemas by server, tool and kind; `None` for schemas that do not compile.
+type Compiled = HashMap<(String, String, SchemaKind), Option<Arc<Validator>>>;
+
+/// Compiled tool schemas, shared by every session.
+#[derive(Default)]
+pub struct SchemaValidators {
+    compiled: RwLock<Compiled>,
+}
+
+impl SchemaValidators {
+    /// Where `arguments` (absent meaning `{}`) do not match the input schema of `tool`.
+    pub fn check_arguments(
+        &self,
+        server_id: &str,
+        tool: &Tool,
+        arguments: Option<&JsonObject>,
+    ) -> Vec<SchemaViolation> {
+        let arguments = Value::Object(arguments.cloned().unwrap_or_default());
+        self.check(server_id, tool, SchemaKind::Input, &arguments)
+    }
+
+    /// Where `structured` content does not match the output schema of `tool`, if it has one.
+    pub fn check_output(
+        &self,
+        server_id: &str,
+        tool: &Tool,
+        structured: &Value,
+    ) -> Vec<SchemaViolation> {
+        self.check(server_id, tool, SchemaKind::Output, structured)
+    }
+
+    /// Forget every compiled schema, for when the tool lists were fetched again.
+    pub fn clear(&self) {
+        self.compiled.write().unwrap().clear();
+    }
+
+    fn check(
+        &self,
+        server_id: &str,
+        tool: &Tool,
+        kind: SchemaKind,
+        value: &Value,
+    ) -> Vec<SchemaViolation> {
+        match self.validator(server_id, tool, kind) {
+            Some(validator) => violations(&validator, value),
+            None => Vec::new(),
+        }
+    }
+
+    fn validator(&self, server_id: &str, tool: &Tool, kind: SchemaKind) -> Option<Arc<Validator>> {
+        let key = (server_id.to_string(), tool.name.to_string(), kind);
+        if let Some(compiled) = self.compiled.read().unwrap().get(&key) {
+            return compiled.clone();
+        }
+        // Compile under the write lock so a broken schema is reported only once
+        let mut cache = self.compiled.write().unwrap();
+        if let Some(compiled) = cache.get(&key) {
+            return compiled.clone();
+        }
+        let schema = match kind {
+            SchemaKind::Input => Some(&tool.input_schema),
+            SchemaKind::Output => tool.output_schema.as_ref(),
+        };
+        let compiled = schema.and_then(|schema| {
+            match jsonschema::validator_for(&Value::Object(schema.as_ref().clone())) {
+                Ok(validator) => Some(Arc::new(validator)),
+                Err(err) => {
+                    warn!(
+                        server_id,
+                        tool = %tool.name,
+                        error = %err,
+                        "ignoring {kind:?} schema that does not compile"
+                    );
+                    None
+                }
+            }
+        });
+        cache.insert(key, compiled.clone());
+        compiled
+    }
+}
+
+/// Where `value` does not match `validator`, by path.
+fn violations(validator: &Validator, value: &Value) -> Vec<SchemaViolation> {
+    let mut violations = validator
+        .iter_errors(value)
+        .map(|error| SchemaViolation {
+            path: error.instance_path().to_string(),
+            message: error.to_string(),
+        })
+        .collect::<Vec<_>>();
+    violations.sort_by(|a, b| a.path.cmp(&b.path));
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn tool(input_schema: Value) -> Tool {
+        Tool::new("create_issue", "", Arc::new(input_schema.as_object().unwrap().clone()))
+    }
+
+    #[test]
+    fn reports_every_failing_path() {
+        let tool = tool(json!({
+            "type": "object",
+            "properties": {
+                "title": { "type": "string" },
+                "labels": { "type": "array", "items": { "type": "string" } }
+            },
+            "required": ["title"]
+        }));
+        let validators = SchemaValidators::default();
+        let arguments = json!({ "labels": ["bug", 7] });
+        let violations = validators.check_arguments("github", &tool, arguments.as_object());
+        let paths = violations.iter().map(|violation| violation.path.as_str()).collect::<Vec<_>>();
+        assert_eq!(paths, ["", "/labels/1"]);
+        assert!(violations[0].to_string().starts_with("(root): "));
+        assert!(violations[0].message.contains("title"));
+
+        let valid = json!({ "title": "Crash" });
+        assert!(validators.check_arguments("github", &tool, valid.as_object()).is_empty());
+        assert_eq!(validators.check_arguments("github", &tool, None).len(), 1);
+        // No output schema: anything goes
+        assert!(validators.check_output("github", &tool, &json!(1)).is_empty());
+    }
+
+    #[test]
+    fn schemas_compile_once_until_cleared() {
+        let validators = SchemaValidators::default();
+        let broken = tool(json!({ "type": "no such type" }));
+        assert!(validators.check_arguments("github", &broken, None).is_empty());
+        assert!(validators.check_arguments("github", &broken, None).is_empty());
+        let compiled = validators.compiled.read().unwrap().values().cloned().collect::<Vec<_>>();
+        assert!(matches!(compiled.as_slice(), [None]));
+
+        // A refresh may bring a fixed schema under the same name
+        validators.clear();
+        let fixed = tool(json!({ "type": "object", "required": ["title"] }));
+        assert_eq!(validators.check_arguments("github", &fixed, None).len(), 1);
+    }
+}
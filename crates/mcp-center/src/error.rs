@@ -30,6 +30,26 @@ pub enum CoreError {
         source: UrlParseError,
     },
 
+    #[error("invalid script tool '{tool}': {reason}")]
+    ScriptToolInvalid {
+        id: Option<String>,
+        tool: String,
+        reason: String,
+    },
+
+    #[error("invalid hook '{hook}': {reason}")]
+    ToolHookInvalid {
+        id: Option<String>,
+        hook: String,
+        reason: String,
+    },
+
+    #[error("invalid cache settings: {reason}")]
+    CacheConfigInvalid { id: Option<String>, reason: String },
+
+    #[error("invalid output limits: {reason}")]
+    OutputLimitsInvalid { id: Option<String>, reason: String },
+
     #[error("server configuration '{id}' not found")]
     ServerConfigNotFound { id: String },
 
@@ -80,6 +100,9 @@ pub enum CoreError {
     #[error("project configuration '{id}' not found")]
     ProjectConfigNotFound { id: String },
 
+    #[error("approving project {path} without granting any server would allow every server")]
+    ProjectApprovalUnrestricted { path: PathBuf },
+
     #[error("failed to read project config file {path}")]
     ProjectRead {
         path: PathBuf,
@@ -107,6 +130,26 @@ pub enum CoreError {
         source: io::Error,
     },
 
+    #[error("failed to parse settings file {path}")]
+    SettingsParse {
+        path: PathBuf,
+        #[source]
+        source: TomlDeError,
+    },
+
+    #[error("failed to serialise workspace settings to TOML")]
+    SettingsSerialise {
+        #[source]
+        source: TomlSerError,
+    },
+
+    #[error("failed to write settings file {path}")]
+    SettingsWrite {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+
     #[error("failed to remove {path}")]
     RemoveFile {
         path: PathBuf,
@@ -116,6 +159,9 @@ pub enum CoreError {
 
     #[error("unable to determine user home directory for MCP_CENTER_ROOT")]
     HomeDirectoryUnknown,
+
+    #[error("invalid duration '{value}' (expected e.g. 30m, 2h, 1d or 1h30m)")]
+    InvalidDuration { value: String },
 }
 
 impl CoreError {
@@ -156,6 +202,10 @@ impl CoreError {
                     "core.server_endpoint_invalid"
                 }
             }
+            CoreError::ScriptToolInvalid { .. } => "core.script_tool_invalid",
+            CoreError::ToolHookInvalid { .. } => "core.tool_hook_invalid",
+            CoreError::CacheConfigInvalid { .. } => "core.cache_config_invalid",
+            CoreError::OutputLimitsInvalid { .. } => "core.output_limits_invalid",
             CoreError::ServerConfigNotFound { .. } => "core.server_config_not_found",
             CoreError::ServerConfigNotFoundByName { .. } => "core.server_config_not_found_name",
             CoreError::CreateDirectory { .. } => "core.create_dir_failed",
@@ -165,12 +215,17 @@ impl CoreError {
             CoreError::ParseToml { .. } => "core.parse_toml_failed",
             CoreError::SerialiseToml { .. } => "core.serialise_toml_failed",
             CoreError::ProjectConfigNotFound { .. } => "core.project_config_not_found",
+            CoreError::ProjectApprovalUnrestricted { .. } => "core.project_approval_unrestricted",
             CoreError::ProjectRead { .. } => "core.project_read_failed",
             CoreError::ProjectParse { .. } => "core.project_parse_failed",
             CoreError::ProjectSerialise { .. } => "core.project_serialise_failed",
             CoreError::ProjectWrite { .. } => "core.project_write_failed",
+            CoreError::SettingsParse { .. } => "core.settings_parse_failed",
+            CoreError::SettingsSerialise { .. } => "core.settings_serialise_failed",
+            CoreError::SettingsWrite { .. } => "core.settings_write_failed",
             CoreError::RemoveFile { .. } => "core.remove_file_failed",
             CoreError::HomeDirectoryUnknown => "core.home_dir_unknown",
+            CoreError::InvalidDuration { .. } => "core.invalid_duration",
         }
     }
 
@@ -194,6 +249,14 @@ impl CoreError {
                 placeholders.push(("error", source.to_string()));
                 placeholders
             }
+            CoreError::ScriptToolInvalid { tool, reason, .. } => {
+                vec![("tool", tool.clone()), ("reason", reason.clone())]
+            }
+            CoreError::ToolHookInvalid { hook, reason, .. } => {
+                vec![("hook", hook.clone()), ("reason", reason.clone())]
+            }
+            CoreError::CacheConfigInvalid { reason, .. }
+            | CoreError::OutputLimitsInvalid { reason, .. } => vec![("reason", reason.clone())],
             CoreError::ServerConfigNotFound { id } => vec![("id", id.clone())],
             CoreError::ServerConfigNotFoundByName { name } => {
                 vec![("name", name.clone())]
@@ -203,20 +266,27 @@ impl CoreError {
             | CoreError::ReadConfig { path, source }
             | CoreError::RemoveFile { path, source }
             | CoreError::ProjectRead { path, source }
-            | CoreError::ProjectWrite { path, source } => {
+            | CoreError::ProjectWrite { path, source }
+            | CoreError::SettingsWrite { path, source } => {
                 vec![("path", display_path(path)), ("error", source.to_string())]
             }
             CoreError::ParseJson { path, source } => {
                 vec![("path", display_path(path)), ("error", source.to_string())]
             }
-            CoreError::ParseToml { path, source } | CoreError::ProjectParse { path, source } => {
+            CoreError::ParseToml { path, source }
+            | CoreError::ProjectParse { path, source }
+            | CoreError::SettingsParse { path, source } => {
                 vec![("path", display_path(path)), ("error", source.to_string())]
             }
-            CoreError::SerialiseToml { source } | CoreError::ProjectSerialise { source } => {
+            CoreError::SerialiseToml { source }
+            | CoreError::ProjectSerialise { source }
+            | CoreError::SettingsSerialise { source } => {
                 vec![("error", source.to_string())]
             }
             CoreError::ProjectConfigNotFound { id } => vec![("id", id.clone())],
+            CoreError::ProjectApprovalUnrestricted { path } => vec![("path", display_path(path))],
             CoreError::HomeDirectoryUnknown => Vec::new(),
+            CoreError::InvalidDuration { value } => vec![("value", value.clone())],
         }
     }
 }
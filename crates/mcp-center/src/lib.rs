@@ -25,16 +25,40 @@ pub mod bridge {
 
 // Daemon 模块
 pub mod daemon {
+    #[path = "../daemon/approvals.rs"]
+    pub mod approvals;
+    #[path = "../daemon/auth.rs"]
+    pub mod auth;
+    #[path = "../daemon/cache.rs"]
+    pub mod cache;
     #[path = "../daemon/control.rs"]
     pub mod control;
+    #[path = "../daemon/hooks.rs"]
+    pub mod hooks;
     #[path = "../daemon/host.rs"]
     pub mod host;
+    #[path = "../daemon/library.rs"]
+    pub mod library;
+    #[path = "../daemon/limits.rs"]
+    pub mod limits;
     #[path = "../daemon/logging.rs"]
     pub mod logging;
+    #[path = "../daemon/management.rs"]
+    pub mod management;
+    #[path = "../daemon/permissions.rs"]
+    pub mod permissions;
     #[path = "../daemon/rpc.rs"]
     pub mod rpc;
+    #[path = "../daemon/script.rs"]
+    pub mod script;
     #[path = "../daemon/server_manager.rs"]
     pub mod server_manager;
+    #[path = "../daemon/session.rs"]
+    pub mod session;
+    #[path = "../daemon/tool_search.rs"]
+    pub mod tool_search;
+    #[path = "../daemon/validation.rs"]
+    pub mod validation;
 
     // Serve command (daemon entry point)
     pub mod serve;
@@ -24,6 +24,8 @@ pub struct Layout {
     root: PathBuf,
     config_dir: PathBuf,
     servers_dir: PathBuf,
+    prompts_dir: PathBuf,
+    resources_dir: PathBuf,
     logs_dir: PathBuf,
     server_logs_dir: PathBuf,
     state_dir: PathBuf,
@@ -35,6 +37,8 @@ impl Layout {
     pub fn new(root: PathBuf) -> Self {
         let config_dir = root.join("config");
         let servers_dir = config_dir.join("servers");
+        let prompts_dir = config_dir.join("prompts");
+        let resources_dir = config_dir.join("resources");
         let logs_dir = root.join("logs");
         let server_logs_dir = logs_dir.join("servers");
         let state_dir = root.join("state");
@@ -44,6 +48,8 @@ impl Layout {
             root,
             config_dir,
             servers_dir,
+            prompts_dir,
+            resources_dir,
             logs_dir,
             server_logs_dir,
             state_dir,
@@ -57,6 +63,8 @@ impl Layout {
             self.root(),
             self.config_dir(),
             self.servers_dir(),
+            self.prompts_dir(),
+            self.resources_dir(),
             self.logs_dir(),
             self.server_logs_dir(),
             self.state_dir(),
@@ -87,6 +95,16 @@ impl Layout {
         &self.servers_dir
     }
 
+    /// Directory of prompt templates served to every session.
+    pub fn prompts_dir(&self) -> &Path {
+        &self.prompts_dir
+    }
+
+    /// Directory of reference documents served to every session as resources.
+    pub fn resources_dir(&self) -> &Path {
+        &self.resources_dir
+    }
+
     /// Directory that stores aggregated logs.
     pub fn logs_dir(&self) -> &Path {
         &self.logs_dir
@@ -102,6 +120,21 @@ impl Layout {
         &self.state_dir
     }
 
+    /// Directory holding cached tool results, one subdirectory per server.
+    pub fn cache_dir(&self) -> PathBuf {
+        self.state_dir().join("cache")
+    }
+
+    /// Cached tool results of a specific server.
+    pub fn server_cache_dir(&self, id: &str) -> PathBuf {
+        self.cache_dir().join(id)
+    }
+
+    /// Full results of truncated tool calls, kept for paging.
+    pub fn outputs_dir(&self) -> PathBuf {
+        self.state_dir().join("outputs")
+    }
+
     /// Directory where project registry files are stored.
     pub fn projects_dir(&self) -> &Path {
         &self.projects_dir
@@ -129,6 +162,11 @@ impl Layout {
         self.server_log_dir(id).join(format!("{filename}.log"))
     }
 
+    /// Path to the workspace settings file.
+    pub fn settings_path(&self) -> PathBuf {
+        self.config_dir().join("settings.toml")
+    }
+
     /// Path to a pid file by server id.
     pub fn server_pid_path(&self, id: &str) -> PathBuf {
         self.state_dir().join(format!("{id}.pid"))
@@ -152,6 +190,11 @@ impl Layout {
         }
     }
 
+    /// Path to the optional socket handshake token shared by the daemon and its clients.
+    pub fn daemon_token_path(&self) -> PathBuf {
+        self.state_dir().join("daemon.token")
+    }
+
     /// Path to the daemon lock file (prevents concurrent startup).
     pub fn daemon_lock_path(&self) -> PathBuf {
         self.state_dir().join("daemon.lock")
@@ -273,10 +316,10 @@ impl Layout {
 
 /// Determine the default root directory for mcp-center.
 pub fn default_root() -> Result<PathBuf> {
-    if let Ok(value) = env::var(ROOT_ENV_KEY) {
-        if !value.trim().is_empty() {
-            return Ok(PathBuf::from(value));
-        }
+    if let Ok(value) = env::var(ROOT_ENV_KEY)
+        && !value.trim().is_empty()
+    {
+        return Ok(PathBuf::from(value));
     }
 
     let home = user_home_dir().ok_or(CoreError::HomeDirectoryUnknown)?;
@@ -284,16 +327,16 @@ pub fn default_root() -> Result<PathBuf> {
 }
 
 fn user_home_dir() -> Option<PathBuf> {
-    if let Ok(home) = env::var("HOME") {
-        if !home.is_empty() {
-            return Some(PathBuf::from(home));
-        }
+    if let Ok(home) = env::var("HOME")
+        && !home.is_empty()
+    {
+        return Some(PathBuf::from(home));
     }
 
-    if let Ok(profile) = env::var("USERPROFILE") {
-        if !profile.is_empty() {
-            return Some(PathBuf::from(profile));
-        }
+    if let Ok(profile) = env::var("USERPROFILE")
+        && !profile.is_empty()
+    {
+        return Some(PathBuf::from(profile));
     }
 
     None
@@ -0,0 +1,178 @@
+//! Policy switches evaluated from MCP tool annotations.
+//!
+//! Tools describe themselves with `readOnlyHint`, `destructiveHint` and `openWorldHint`.
+//! A project can hide whole classes of tools with [`AnnotationPolicy`] instead of listing
+//! names, and a [`ToolCustomization`](super::ToolCustomization) can correct the hints of a
+//! server that annotates its tools incorrectly.
+
+use std::fmt;
+
+use rmcp::model::ToolAnnotations;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Project-level switches; `None` inherits from rules and parent projects.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(default)]
+pub struct AnnotationPolicy {
+    /// Hide every tool not annotated `readOnlyHint: true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_only: Option<bool>,
+    /// Hide tools that may perform destructive updates.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_destructive: Option<bool>,
+    /// Hide tools that may interact with external entities.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_open_world: Option<bool>,
+}
+
+impl AnnotationPolicy {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn get(&self, switch: AnnotationSwitch) -> Option<bool> {
+        match switch {
+            AnnotationSwitch::ReadOnly => self.read_only,
+            AnnotationSwitch::NoDestructive => self.no_destructive,
+            AnnotationSwitch::NoOpenWorld => self.no_open_world,
+        }
+    }
+
+    pub fn set(&mut self, switch: AnnotationSwitch, value: Option<bool>) {
+        match switch {
+            AnnotationSwitch::ReadOnly => self.read_only = value,
+            AnnotationSwitch::NoDestructive => self.no_destructive = value,
+            AnnotationSwitch::NoOpenWorld => self.no_open_world = value,
+        }
+    }
+}
+
+/// One of the [`AnnotationPolicy`] switches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum AnnotationSwitch {
+    ReadOnly,
+    NoDestructive,
+    NoOpenWorld,
+}
+
+impl AnnotationSwitch {
+    pub const ALL: [AnnotationSwitch; 3] = [
+        AnnotationSwitch::ReadOnly,
+        AnnotationSwitch::NoDestructive,
+        AnnotationSwitch::NoOpenWorld,
+    ];
+
+    /// Whether the switch, when on, hides a tool with these hints.
+    pub fn hides(self, hints: &ToolHints) -> bool {
+        match self {
+            AnnotationSwitch::ReadOnly => !hints.read_only,
+            AnnotationSwitch::NoDestructive => hints.may_destroy(),
+            AnnotationSwitch::NoOpenWorld => hints.open_world,
+        }
+    }
+
+    /// Name used on the command line (`read-only`, `no-destructive`, `no-open-world`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AnnotationSwitch::ReadOnly => "read-only",
+            AnnotationSwitch::NoDestructive => "no-destructive",
+            AnnotationSwitch::NoOpenWorld => "no-open-world",
+        }
+    }
+}
+
+impl fmt::Display for AnnotationSwitch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Per-tool replacement for the hints reported by the server.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(default)]
+pub struct AnnotationOverride {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub read_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destructive: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub open_world: Option<bool>,
+}
+
+impl AnnotationOverride {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Resolved hints, with the MCP defaults applied to missing annotations: not read-only,
+/// destructive and open-world.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolHints {
+    pub read_only: bool,
+    pub destructive: bool,
+    pub open_world: bool,
+}
+
+impl Default for ToolHints {
+    fn default() -> Self {
+        Self { read_only: false, destructive: true, open_world: true }
+    }
+}
+
+impl ToolHints {
+    pub fn from_annotations(annotations: Option<&ToolAnnotations>) -> Self {
+        let defaults = Self::default();
+        let Some(annotations) = annotations else {
+            return defaults;
+        };
+        Self {
+            read_only: annotations.read_only_hint.unwrap_or(defaults.read_only),
+            destructive: annotations.destructive_hint.unwrap_or(defaults.destructive),
+            open_world: annotations.open_world_hint.unwrap_or(defaults.open_world),
+        }
+    }
+
+    pub fn with_override(self, patch: &AnnotationOverride) -> Self {
+        Self {
+            read_only: patch.read_only.unwrap_or(self.read_only),
+            destructive: patch.destructive.unwrap_or(self.destructive),
+            open_world: patch.open_world.unwrap_or(self.open_world),
+        }
+    }
+
+    /// `destructiveHint` only means something for tools that are not read-only.
+    pub fn may_destroy(&self) -> bool {
+        !self.read_only && self.destructive
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_annotations_use_spec_defaults() {
+        let hints = ToolHints::from_annotations(None);
+        assert!(AnnotationSwitch::ReadOnly.hides(&hints));
+        assert!(AnnotationSwitch::NoDestructive.hides(&hints));
+        assert!(AnnotationSwitch::NoOpenWorld.hides(&hints));
+
+        let reader = ToolHints::from_annotations(Some(&ToolAnnotations::new().read_only(true)));
+        assert!(!AnnotationSwitch::ReadOnly.hides(&reader));
+        // Read-only tools are never destructive, whatever destructiveHint says.
+        assert!(!AnnotationSwitch::NoDestructive.hides(&reader));
+        assert!(AnnotationSwitch::NoOpenWorld.hides(&reader));
+    }
+
+    #[test]
+    fn override_replaces_only_given_hints() {
+        let upstream = ToolHints::from_annotations(Some(&ToolAnnotations::new().read_only(true)));
+        let patched = upstream
+            .with_override(&AnnotationOverride { read_only: Some(false), ..Default::default() });
+        assert_eq!(patched, ToolHints { read_only: false, destructive: true, open_world: true });
+    }
+}
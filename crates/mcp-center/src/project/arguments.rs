@@ -0,0 +1,397 @@
+//! Constraints on the arguments of individual tool calls.
+//!
+//! Allowing a tool allows any arguments; an [`ArgumentConstraint`] attached to a
+//! [`ToolCustomization`](super::ToolCustomization) narrows that down. Each constraint
+//! addresses one value with a JSON pointer and can require it to stay under a directory,
+//! match or avoid regular expressions, or lie within numeric bounds. Array values are
+//! checked element by element.
+//!
+//! An [`ArgumentPreset`] pins a top-level argument instead: the daemon removes it from the
+//! tool's input schema and fills it in on every call, so the agent can neither see nor
+//! change it.
+//!
+//! Patterns are compiled when a record or rule is deserialized, so an invalid expression
+//! fails the load instead of surfacing as a violation on every call.
+
+use std::{
+    fmt,
+    path::{Component, Path, PathBuf},
+};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use specta::Type;
+
+/// Placeholder in [`ArgumentConstraint::within`] for the project root.
+pub const PROJECT_ROOT_VAR: &str = "${PROJECT_ROOT}";
+
+/// `[[tool_customizations.arguments]]` entry.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Type)]
+#[serde(default)]
+pub struct ArgumentConstraint {
+    /// JSON pointer into the call arguments, e.g. `/path` or `/options/depth`.
+    pub pointer: String,
+    /// Reject calls that omit the argument.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub required: bool,
+    /// Path values must resolve under this directory; relative paths are resolved against
+    /// the project root, and `${PROJECT_ROOT}` expands to it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub within: Option<String>,
+    /// String values must match at least one of these regular expressions.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[specta(type = Vec<String>)]
+    pub allow: Vec<Pattern>,
+    /// String values must not match any of these regular expressions.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[specta(type = Vec<String>)]
+    pub deny: Vec<Pattern>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+}
+
+/// A regular expression in an [`ArgumentConstraint`], stored as its source text.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Pattern(Regex);
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        Regex::new(pattern).map(Self)
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.0.is_match(text)
+    }
+}
+
+impl TryFrom<String> for Pattern {
+    type Error = String;
+
+    fn try_from(pattern: String) -> Result<Self, Self::Error> {
+        Self::new(&pattern).map_err(|err| format!("invalid pattern '{pattern}': {err}"))
+    }
+}
+
+impl From<Pattern> for String {
+    fn from(pattern: Pattern) -> Self {
+        pattern.0.as_str().to_string()
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// `[tool_customizations.presets.<argument>]` entry.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ArgumentPreset {
+    pub value: Value,
+    /// Redact the value from tool logs and never print it.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub secret: bool,
+}
+
+impl ArgumentPreset {
+    /// The value for display, masked when secret.
+    pub fn display_value(&self) -> String {
+        if self.secret {
+            "********".to_string()
+        } else {
+            self.value.to_string()
+        }
+    }
+}
+
+/// Copy of `schema` without the pinned `arguments` in `properties` and `required`.
+pub fn strip_schema_properties<'a>(
+    schema: &Map<String, Value>,
+    arguments: impl IntoIterator<Item = &'a str> + Clone,
+) -> Map<String, Value> {
+    let mut schema = schema.clone();
+    if let Some(Value::Object(properties)) = schema.get_mut("properties") {
+        for argument in arguments.clone() {
+            properties.remove(argument);
+        }
+    }
+    if let Some(Value::Array(required)) = schema.get_mut("required") {
+        required.retain(|name| {
+            !arguments.clone().into_iter().any(|argument| name.as_str() == Some(argument))
+        });
+    }
+    schema
+}
+
+/// A constraint an actual call did not satisfy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ArgumentViolation {
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for ArgumentViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.pointer, self.message)
+    }
+}
+
+/// Compact summary such as `required, within ${PROJECT_ROOT}, deny /\.env$/, max 5`.
+impl fmt::Display for ArgumentConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if self.required {
+            parts.push("required".to_string());
+        }
+        if let Some(within) = &self.within {
+            parts.push(format!("within {within}"));
+        }
+        parts.extend(self.allow.iter().map(|pattern| format!("allow /{pattern}/")));
+        parts.extend(self.deny.iter().map(|pattern| format!("deny /{pattern}/")));
+        if let Some(min) = self.min {
+            parts.push(format!("min {min}"));
+        }
+        if let Some(max) = self.max {
+            parts.push(format!("max {max}"));
+        }
+        f.write_str(&parts.join(", "))
+    }
+}
+
+impl ArgumentConstraint {
+    /// Whether the constraint restricts anything besides naming a pointer.
+    pub fn is_empty(&self) -> bool {
+        !self.required
+            && self.within.is_none()
+            && self.allow.is_empty()
+            && self.deny.is_empty()
+            && self.min.is_none()
+            && self.max.is_none()
+    }
+
+    /// Check `arguments` of a call made from the project at `project_root`.
+    pub fn check(
+        &self,
+        arguments: Option<&Map<String, Value>>,
+        project_root: &Path,
+    ) -> Vec<ArgumentViolation> {
+        let value = arguments.and_then(|arguments| lookup(arguments, &self.pointer));
+        let Some(value) = value.filter(|value| !value.is_null()) else {
+            return if self.required {
+                vec![self.violation("argument is required")]
+            } else {
+                Vec::new()
+            };
+        };
+        match value {
+            Value::Array(items) => {
+                items.iter().filter_map(|item| self.check_value(item, project_root)).collect()
+            }
+            value => self.check_value(value, project_root).into_iter().collect(),
+        }
+    }
+
+    fn check_value(&self, value: &Value, project_root: &Path) -> Option<ArgumentViolation> {
+        if self.min.is_some() || self.max.is_some() {
+            let Some(number) = value.as_f64() else {
+                return Some(self.violation("expected a number"));
+            };
+            if let Some(min) = self.min
+                && number < min
+            {
+                return Some(self.violation(format!("{number} is below the minimum {min}")));
+            }
+            if let Some(max) = self.max
+                && number > max
+            {
+                return Some(self.violation(format!("{number} is above the maximum {max}")));
+            }
+        }
+        if self.within.is_none() && self.allow.is_empty() && self.deny.is_empty() {
+            return None;
+        }
+        let Some(text) = value.as_str() else {
+            return Some(self.violation("expected a string"));
+        };
+        if let Some(within) = &self.within {
+            let root = resolve(
+                project_root,
+                &within.replace(PROJECT_ROOT_VAR, &project_root.to_string_lossy()),
+            );
+            if !resolve(project_root, text).starts_with(&root) {
+                return Some(self.violation(format!("'{text}' is outside {}", root.display())));
+            }
+        }
+        if let Some(pattern) = self.deny.iter().find(|pattern| pattern.is_match(text)) {
+            return Some(self.violation(format!("'{text}' matches denied pattern '{pattern}'")));
+        }
+        if !self.allow.is_empty() && !self.allow.iter().any(|pattern| pattern.is_match(text)) {
+            return Some(self.violation(format!("'{text}' matches no allowed pattern")));
+        }
+        None
+    }
+
+    fn violation(&self, message: impl Into<String>) -> ArgumentViolation {
+        ArgumentViolation { pointer: self.pointer.clone(), message: message.into() }
+    }
+}
+
+fn lookup<'a>(arguments: &'a Map<String, Value>, pointer: &str) -> Option<&'a Value> {
+    match pointer {
+        "" | "/" => None,
+        pointer => {
+            let (head, rest) = match pointer.trim_start_matches('/').split_once('/') {
+                Some((head, rest)) => (head, Some(rest)),
+                None => (pointer.trim_start_matches('/'), None),
+            };
+            let value = arguments.get(&head.replace("~1", "/").replace("~0", "~"))?;
+            match rest {
+                Some(rest) => value.pointer(&format!("/{rest}")),
+                None => Some(value),
+            }
+        }
+    }
+}
+
+/// Absolute, normalised form of `raw` relative to `base`. Symlinks are resolved for the
+/// longest existing prefix so a link inside the project cannot point outside of it.
+fn resolve(base: &Path, raw: &str) -> PathBuf {
+    let joined = base.join(raw);
+    let mut normal = PathBuf::new();
+    for component in joined.components() {
+        match component {
+            Component::ParentDir => {
+                normal.pop();
+            }
+            Component::CurDir => {}
+            component => normal.push(component),
+        }
+    }
+    let mut existing = normal.as_path();
+    let mut rest = Vec::new();
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            return rest.iter().rev().fold(canonical, |path, part| path.join(part));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name.to_os_string());
+                existing = parent;
+            }
+            _ => return normal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn args(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn paths_must_stay_under_the_project_root() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path().canonicalize().unwrap();
+        let constraint = ArgumentConstraint {
+            pointer: "/path".into(),
+            within: Some(PROJECT_ROOT_VAR.into()),
+            ..Default::default()
+        };
+
+        assert!(
+            constraint
+                .check(Some(&args(json!({ "path": "src/main.rs" }))), &root)
+                .is_empty()
+        );
+        let inside = root.join("notes.txt").to_string_lossy().into_owned();
+        assert!(constraint.check(Some(&args(json!({ "path": inside }))), &root).is_empty());
+        assert_eq!(constraint.check(Some(&args(json!({ "path": "../secrets" }))), &root).len(), 1);
+        assert_eq!(constraint.check(Some(&args(json!({ "path": "/etc/passwd" }))), &root).len(), 1);
+        // Absent and not required.
+        assert!(constraint.check(None, &root).is_empty());
+    }
+
+    #[test]
+    fn pinned_arguments_leave_the_schema() {
+        let schema = args(json!({
+            "type": "object",
+            "properties": { "repo": { "type": "string" }, "query": { "type": "string" } },
+            "required": ["repo", "query"],
+        }));
+        let stripped = strip_schema_properties(&schema, ["repo"]);
+        assert_eq!(stripped["properties"], json!({ "query": { "type": "string" } }));
+        assert_eq!(stripped["required"], json!(["query"]));
+
+        let secret = ArgumentPreset { value: json!("sk-123"), secret: true };
+        assert_eq!(secret.display_value(), "********");
+    }
+
+    #[test]
+    fn patterns_bounds_and_arrays() {
+        let root = Path::new("/work");
+        let command = ArgumentConstraint {
+            pointer: "/command".into(),
+            allow: vec![Pattern::new("^git ").unwrap(), Pattern::new("^ls").unwrap()],
+            deny: vec![Pattern::new("--force").unwrap()],
+            required: true,
+            ..Default::default()
+        };
+        assert!(command.check(Some(&args(json!({ "command": "git status" }))), root).is_empty());
+        assert_eq!(command.check(Some(&args(json!({ "command": "rm -rf /" }))), root).len(), 1);
+        let forced = command.check(Some(&args(json!({ "command": "git push --force" }))), root);
+        assert_eq!(forced[0].pointer, "/command");
+        assert_eq!(command.check(Some(&args(json!({}))), root)[0].message, "argument is required");
+
+        let depth = ArgumentConstraint {
+            pointer: "/options/depth".into(),
+            min: Some(1.0),
+            max: Some(5.0),
+            ..Default::default()
+        };
+        assert!(depth.check(Some(&args(json!({ "options": { "depth": 3 } }))), root).is_empty());
+        assert_eq!(depth.check(Some(&args(json!({ "options": { "depth": 9 } }))), root).len(), 1);
+
+        let files = ArgumentConstraint {
+            pointer: "/files".into(),
+            deny: vec![Pattern::new(r"\.env$").unwrap()],
+            ..Default::default()
+        };
+        let violations =
+            files.check(Some(&args(json!({ "files": ["a.rs", ".env", "b/.env"] }))), root);
+        assert_eq!(violations.len(), 2);
+    }
+
+    #[test]
+    fn invalid_patterns_fail_to_load() {
+        let err = serde_json::from_value::<ArgumentConstraint>(json!({
+            "pointer": "/command",
+            "deny": ["(unclosed"],
+        }))
+        .unwrap_err();
+        assert!(err.to_string().contains("invalid pattern '(unclosed'"), "{err}");
+
+        let constraint: ArgumentConstraint =
+            serde_json::from_value(json!({ "pointer": "/command", "allow": ["^git "] })).unwrap();
+        assert_eq!(serde_json::to_value(&constraint).unwrap()["allow"], json!(["^git "]));
+    }
+}
@@ -0,0 +1,246 @@
+//! Project root detection used by the bridge and `mcp-center project detect`.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::config::settings::{DetectionStrategy, MonorepoMode, ProjectDetectionSettings};
+
+/// Environment variable that pins the project path, bypassing every other rule.
+pub const PROJECT_PATH_ENV_KEY: &str = "MCP_CENTER_PROJECT_PATH";
+
+/// Result of project detection together with the rule that produced it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedProject {
+    pub path: PathBuf,
+    pub rule: DetectionRule,
+}
+
+/// Rule that decided the project root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DetectionRule {
+    /// `MCP_CENTER_PROJECT_PATH` was set.
+    EnvOverride,
+    /// A marker file or directory was found in the project root.
+    Marker { marker: String },
+    /// The enclosing git repository root.
+    Git,
+    /// Narrowed from a repository root to the nearest sub-package.
+    NearestPackage {
+        marker: String,
+        repo_root: PathBuf,
+        base: Box<DetectionRule>,
+    },
+    /// Nothing matched; the working directory is used as is.
+    CurrentDir,
+}
+
+/// Detect the project root for `cwd`, honouring `MCP_CENTER_PROJECT_PATH`.
+pub fn detect_project(cwd: &Path, settings: &ProjectDetectionSettings) -> DetectedProject {
+    if let Some(env_override) = env::var_os(PROJECT_PATH_ENV_KEY)
+        && !env_override.is_empty()
+    {
+        let path = PathBuf::from(env_override);
+        return DetectedProject {
+            path: canonicalize_best_effort(&path),
+            rule: DetectionRule::EnvOverride,
+        };
+    }
+    detect_project_from(cwd, settings)
+}
+
+/// Detect the project root for `cwd` using only the configured strategies.
+pub fn detect_project_from(cwd: &Path, settings: &ProjectDetectionSettings) -> DetectedProject {
+    let cwd = canonicalize_best_effort(cwd);
+    // Markers above the repository (e.g. `~/.cursor`) belong to the user, not the project
+    let git_root = git_toplevel(&cwd);
+
+    for strategy in &settings.strategies {
+        let found = match strategy {
+            DetectionStrategy::Markers => {
+                probe_markers(&cwd, &settings.markers, git_root.as_deref())
+                    .map(|(path, marker)| (path, DetectionRule::Marker { marker }))
+            }
+            DetectionStrategy::Git => git_root.clone().map(|path| (path, DetectionRule::Git)),
+        };
+        if let Some((root, rule)) = found {
+            return narrow_to_package(&cwd, root, rule, settings);
+        }
+    }
+
+    DetectedProject { path: cwd, rule: DetectionRule::CurrentDir }
+}
+
+fn narrow_to_package(
+    cwd: &Path,
+    root: PathBuf,
+    rule: DetectionRule,
+    settings: &ProjectDetectionSettings,
+) -> DetectedProject {
+    if settings.monorepo == MonorepoMode::NearestPackage && cwd.starts_with(&root) {
+        for ancestor in cwd.ancestors() {
+            if ancestor == root {
+                break;
+            }
+            if let Some(marker) =
+                settings.package_markers.iter().find(|marker| ancestor.join(marker).exists())
+            {
+                return DetectedProject {
+                    path: ancestor.to_path_buf(),
+                    rule: DetectionRule::NearestPackage {
+                        marker: marker.clone(),
+                        repo_root: root,
+                        base: Box::new(rule),
+                    },
+                };
+            }
+        }
+    }
+    DetectedProject { path: root, rule }
+}
+
+/// Nearest ancestor of `base` holding a marker, looking no further up than `boundary`.
+fn probe_markers(
+    base: &Path,
+    markers: &[String],
+    boundary: Option<&Path>,
+) -> Option<(PathBuf, String)> {
+    for ancestor in base.ancestors() {
+        for marker in markers {
+            if ancestor.join(marker).exists() {
+                return Some((canonicalize_best_effort(ancestor), marker.clone()));
+            }
+        }
+        if boundary == Some(ancestor) {
+            break;
+        }
+    }
+    None
+}
+
+fn git_toplevel(base: &Path) -> Option<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .current_dir(base)
+        .output();
+    match output {
+        Ok(result) if result.status.success() => {
+            let raw = String::from_utf8_lossy(&result.stdout).trim().to_string();
+            (!raw.is_empty()).then(|| canonicalize_best_effort(Path::new(&raw)))
+        }
+        _ => None,
+    }
+}
+
+fn canonicalize_best_effort(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(
+        strategies: Vec<DetectionStrategy>,
+        monorepo: MonorepoMode,
+    ) -> ProjectDetectionSettings {
+        ProjectDetectionSettings { strategies, monorepo, ..ProjectDetectionSettings::default() }
+    }
+
+    #[test]
+    fn nearest_marker_wins() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join("app/.vscode")).unwrap();
+        fs::create_dir_all(root.join("app/src/deep")).unwrap();
+
+        let detected = detect_project_from(
+            &root.join("app/src/deep"),
+            &settings(vec![DetectionStrategy::Markers], MonorepoMode::RepoRoot),
+        );
+        assert_eq!(detected.path, root.join("app"));
+        assert_eq!(detected.rule, DetectionRule::Marker { marker: ".vscode".into() });
+    }
+
+    #[test]
+    fn nearest_package_narrows_inside_root() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path().canonicalize().unwrap();
+        fs::create_dir_all(root.join(".idea")).unwrap();
+        fs::create_dir_all(root.join("packages/web/src")).unwrap();
+        fs::write(root.join("package.json"), "{}").unwrap();
+        fs::write(root.join("packages/web/package.json"), "{}").unwrap();
+        let cwd = root.join("packages/web/src");
+
+        let repo_root = detect_project_from(
+            &cwd,
+            &settings(vec![DetectionStrategy::Markers], MonorepoMode::RepoRoot),
+        );
+        assert_eq!(repo_root.path, root);
+
+        let nearest = detect_project_from(
+            &cwd,
+            &settings(vec![DetectionStrategy::Markers], MonorepoMode::NearestPackage),
+        );
+        assert_eq!(nearest.path, root.join("packages/web"));
+        assert!(matches!(
+            nearest.rule,
+            DetectionRule::NearestPackage { ref marker, .. } if marker == "package.json"
+        ));
+    }
+
+    #[test]
+    fn editor_directories_are_default_markers() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path().canonicalize().unwrap();
+        for marker in [".vscode", ".idea", ".zed"] {
+            let project = root.join(marker.trim_start_matches('.'));
+            fs::create_dir_all(project.join(marker)).unwrap();
+            fs::create_dir_all(project.join("src")).unwrap();
+
+            let detected =
+                detect_project_from(&project.join("src"), &ProjectDetectionSettings::default());
+            assert_eq!(detected.path, project);
+            assert_eq!(detected.rule, DetectionRule::Marker { marker: marker.into() });
+        }
+    }
+
+    #[test]
+    fn markers_above_the_git_root_are_ignored() {
+        let temp = tempfile::tempdir().unwrap();
+        let home = temp.path().canonicalize().unwrap();
+        fs::create_dir_all(home.join(".vscode")).unwrap();
+        fs::create_dir_all(home.join("code/app/src")).unwrap();
+        let status = Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(home.join("code/app"))
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let detected =
+            detect_project_from(&home.join("code/app/src"), &ProjectDetectionSettings::default());
+        assert_eq!(detected.path, home.join("code/app"));
+        assert_eq!(detected.rule, DetectionRule::Git);
+
+        // Outside any repository the marker still applies
+        fs::create_dir_all(home.join("notes")).unwrap();
+        let detected =
+            detect_project_from(&home.join("notes"), &ProjectDetectionSettings::default());
+        assert_eq!(detected.path, home);
+    }
+
+    #[test]
+    fn falls_back_to_current_dir() {
+        let temp = tempfile::tempdir().unwrap();
+        let cwd = temp.path().canonicalize().unwrap();
+        let mut config = settings(vec![DetectionStrategy::Markers], MonorepoMode::RepoRoot);
+        config.markers = vec!["definitely-not-present.marker".into()];
+
+        let detected = detect_project_from(&cwd, &config);
+        assert_eq!(detected.path, cwd);
+        assert_eq!(detected.rule, DetectionRule::CurrentDir);
+    }
+}
@@ -0,0 +1,123 @@
+//! Temporary permission grants that lapse on their own.
+//!
+//! A grant lets a project use a whole server, or a few of its tools, until `expires_at`,
+//! regardless of its permanent tool permissions and server list. Expired grants stay on the
+//! record so `project grants` can show what was handed out.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::current_timestamp;
+use crate::error::CoreError;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Type)]
+pub struct TemporaryGrant {
+    pub server_id: String,
+    /// Tools covered by the grant (empty = every tool of the server).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<String>,
+    pub granted_at: u64,
+    /// Unix timestamp (seconds) after which the grant no longer applies.
+    pub expires_at: u64,
+}
+
+impl TemporaryGrant {
+    /// Grant `tools` of `server_id` (all of them when empty) for `duration` from now.
+    pub fn new(server_id: impl Into<String>, tools: Vec<String>, duration: Duration) -> Self {
+        let granted_at = current_timestamp();
+        Self {
+            server_id: server_id.into(),
+            tools,
+            granted_at,
+            expires_at: granted_at.saturating_add(duration.as_secs()),
+        }
+    }
+
+    pub fn is_active_at(&self, now: u64) -> bool {
+        now < self.expires_at
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.is_active_at(current_timestamp())
+    }
+
+    pub fn covers(&self, server_id: &str, tool_name: &str) -> bool {
+        self.server_id == server_id
+            && (self.tools.is_empty() || self.tools.iter().any(|tool| tool == tool_name))
+    }
+}
+
+/// Current Unix timestamp in seconds, as used for `expires_at`.
+pub fn now() -> u64 {
+    current_timestamp()
+}
+
+/// Parse durations such as `90s`, `30m`, `2h`, `1d` or `1h30m`.
+pub fn parse_duration(value: &str) -> Result<Duration> {
+    let invalid = || CoreError::InvalidDuration { value: value.to_string() };
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return Err(invalid().into());
+    }
+
+    let mut total = 0u64;
+    let mut digits = String::new();
+    for ch in trimmed.chars() {
+        if ch.is_ascii_digit() {
+            digits.push(ch);
+            continue;
+        }
+        let unit = match ch.to_ascii_lowercase() {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 24 * 60 * 60,
+            'w' => 7 * 24 * 60 * 60,
+            _ => return Err(invalid().into()),
+        };
+        let amount: u64 = digits.parse().map_err(|_| invalid())?;
+        total = amount
+            .checked_mul(unit)
+            .and_then(|secs| total.checked_add(secs))
+            .ok_or_else(invalid)?;
+        digits.clear();
+    }
+    if !digits.is_empty() || total == 0 {
+        return Err(invalid().into());
+    }
+    Ok(Duration::from_secs(total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_compound_durations() {
+        assert_eq!(parse_duration("90s").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_duration("2h").unwrap(), Duration::from_secs(7200));
+        assert_eq!(parse_duration("1h30m").unwrap(), Duration::from_secs(5400));
+        assert_eq!(parse_duration("1d").unwrap(), Duration::from_secs(86400));
+        for invalid in ["", "2", "h", "0m", "2x", "-1h"] {
+            assert!(parse_duration(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn grant_covers_listed_tools_until_expiry() {
+        let grant = TemporaryGrant {
+            server_id: "db".into(),
+            tools: vec!["write".into()],
+            granted_at: 100,
+            expires_at: 200,
+        };
+        assert!(grant.covers("db", "write"));
+        assert!(!grant.covers("db", "drop"));
+        assert!(!grant.covers("other", "write"));
+        assert!(grant.is_active_at(199));
+        assert!(!grant.is_active_at(200));
+    }
+}
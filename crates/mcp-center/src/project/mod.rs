@@ -1,23 +1,40 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fs,
     path::{Path, PathBuf},
-    sync::{Arc, RwLock},
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
     time::{SystemTime, UNIX_EPOCH},
 };
 
 use anyhow::Result;
 use blake3::Hasher;
 use serde::{Deserialize, Serialize};
+use specta::Type;
 
 #[cfg(unix)]
 use std::os::unix::ffi::OsStrExt as _;
 #[cfg(windows)]
 use std::os::windows::ffi::OsStrExt as _;
 
-use crate::{error::CoreError, paths::Layout};
+use crate::{
+    config::{hooks::ToolHook, limits::OutputLimits},
+    error::CoreError,
+    paths::Layout,
+};
 use tracing::warn;
 
+pub mod annotations;
+pub mod arguments;
+pub mod detect;
+pub mod grant;
+pub mod identity;
+pub mod policy;
+pub mod repo_config;
+pub mod schema;
+
 const PROJECT_ID_HEX_LEN: usize = 16;
 const MAX_CACHE_REFRESH_ATTEMPTS: usize = 3;
 
@@ -85,13 +102,96 @@ pub enum ToolPermission {
 }
 
 /// Customization for a specific tool (e.g., custom description).
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ToolCustomization {
     /// Tool name (e.g., "mcp__context7__get-library-docs")
     pub tool_name: String,
     /// Custom description to override the original
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Corrected annotation hints for servers that annotate this tool incorrectly
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<annotations::AnnotationOverride>,
+    /// Park calls to this tool until someone approves them (`None` = project setting)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_approval: Option<bool>,
+    /// Constraints every call's arguments must satisfy
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub arguments: Vec<arguments::ArgumentConstraint>,
+    /// Arguments pinned by the project and hidden from the agent
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub presets: BTreeMap<String, arguments::ArgumentPreset>,
+    /// Name advertised instead of the upstream tool name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    /// Custom title to override the original
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// JSON merge patch for the upstream `inputSchema`, stored as JSON text
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "schema::json_text"
+    )]
+    pub schema_patch: Option<serde_json::Value>,
+    /// Parameters removed from the schema and dropped from calls
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hidden_parameters: Vec<String>,
+}
+
+impl ToolCustomization {
+    pub fn new(tool_name: impl Into<String>) -> Self {
+        Self {
+            tool_name: tool_name.into(),
+            description: None,
+            annotations: None,
+            require_approval: None,
+            arguments: Vec::new(),
+            presets: BTreeMap::new(),
+            alias: None,
+            title: None,
+            schema_patch: None,
+            hidden_parameters: Vec::new(),
+        }
+    }
+
+    /// Whether the entry no longer changes anything about the tool.
+    pub fn is_empty(&self) -> bool {
+        self.description.is_none()
+            && self.annotations.is_none_or(|patch| patch.is_empty())
+            && self.require_approval.is_none()
+            && self.arguments.is_empty()
+            && self.presets.is_empty()
+            && !self.has_overrides()
+    }
+
+    /// Whether the entry renames, retitles or reshapes the tool.
+    pub fn has_overrides(&self) -> bool {
+        self.alias.is_some()
+            || self.title.is_some()
+            || self.schema_patch.is_some()
+            || !self.hidden_parameters.is_empty()
+    }
+
+    /// Drop the alias, title, schema patch and hidden parameters.
+    pub fn clear_overrides(&mut self) {
+        self.alias = None;
+        self.title = None;
+        self.schema_patch = None;
+        self.hidden_parameters.clear();
+    }
+}
+
+/// Approval state of a project record.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectStatus {
+    /// Approved (or created explicitly by the user).
+    #[default]
+    Active,
+    /// Seen for the first time by the daemon and waiting for the user to approve it.
+    /// Only explicit grants apply while pending.
+    Pending,
 }
 
 /// Record persisted for each detected project path.
@@ -117,6 +217,32 @@ pub struct ProjectRecord {
     pub last_seen_at: u64,
     #[serde(default)]
     pub metadata: HashMap<String, String>,
+    /// Path-independent identity (see [`identity::project_identity`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity: Option<String>,
+    #[serde(default)]
+    pub status: ProjectStatus,
+    /// Time-limited grants (see [`grant::TemporaryGrant`]).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub grants: Vec<grant::TemporaryGrant>,
+    /// Switches hiding tools by their annotations (see [`annotations::AnnotationPolicy`]).
+    #[serde(
+        default,
+        skip_serializing_if = "annotations::AnnotationPolicy::is_empty"
+    )]
+    pub annotation_policy: annotations::AnnotationPolicy,
+    /// Park every tool call until someone approves it (`None` inherits).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_approval: Option<bool>,
+    /// Expose the search/describe/invoke meta-tools instead of every tool (`None` inherits).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_search: Option<bool>,
+    /// Hooks run around this project's tool calls, after the server's own (empty inherits).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hooks: Vec<ToolHook>,
+    /// Size limits on the tool results this project receives (unset limits inherit).
+    #[serde(default, skip_serializing_if = "OutputLimits::is_empty")]
+    pub output_limits: OutputLimits,
 }
 
 fn current_timestamp() -> u64 {
@@ -137,6 +263,14 @@ impl ProjectRecord {
             created_at: timestamp,
             last_seen_at: timestamp,
             metadata: HashMap::new(),
+            identity: None,
+            status: ProjectStatus::Active,
+            grants: Vec::new(),
+            annotation_policy: annotations::AnnotationPolicy::default(),
+            require_approval: None,
+            tool_search: None,
+            hooks: Vec::new(),
+            output_limits: OutputLimits::default(),
         }
     }
 
@@ -147,6 +281,72 @@ impl ProjectRecord {
     pub fn set_agent(&mut self, agent: Option<String>) {
         self.agent = agent;
     }
+
+    pub fn is_pending(&self) -> bool {
+        self.status == ProjectStatus::Pending
+    }
+
+    /// Activate the project. An empty allow list reads as "every server", so approving a
+    /// project that grants nothing (e.g. one seeded by `deny_all`) needs `allow_all`.
+    pub fn approve(&mut self, allow_all: bool) -> Result<(), CoreError> {
+        if !allow_all && self.allowed_server_ids.is_empty() && self.allowed_server_tools.is_empty()
+        {
+            return Err(CoreError::ProjectApprovalUnrestricted { path: self.path.clone() });
+        }
+        self.status = ProjectStatus::Active;
+        Ok(())
+    }
+
+    /// Customization entry for `tool_name`, created empty when missing.
+    pub fn customization_mut(&mut self, tool_name: &str) -> &mut ToolCustomization {
+        let index = match self.tool_customizations.iter().position(|c| c.tool_name == tool_name) {
+            Some(index) => index,
+            None => {
+                self.tool_customizations.push(ToolCustomization::new(tool_name));
+                self.tool_customizations.len() - 1
+            }
+        };
+        &mut self.tool_customizations[index]
+    }
+
+    /// Clear the custom description of `tool_name`, keeping other customizations.
+    /// Returns whether there was one.
+    pub fn reset_tool_description(&mut self, tool_name: &str) -> bool {
+        let Some(customization) = self
+            .tool_customizations
+            .iter_mut()
+            .find(|c| c.tool_name == tool_name && c.description.is_some())
+        else {
+            return false;
+        };
+        customization.description = None;
+        self.tool_customizations.retain(|c| !c.is_empty());
+        true
+    }
+
+    /// Whether this project may use `tool_name` from `server_id`.
+    /// Priority: active grants > allowed_server_tools > allowed_server_ids (empty = allow all,
+    /// unless pending).
+    pub fn allows_tool(&self, server_id: &str, tool_name: &str) -> bool {
+        if self
+            .grants
+            .iter()
+            .any(|grant| grant.is_active() && grant.covers(server_id, tool_name))
+        {
+            return true;
+        }
+        if let Some(permission) = self.allowed_server_tools.get(server_id) {
+            return match permission {
+                ToolPermission::All => true,
+                ToolPermission::AllowList { tools } => tools.iter().any(|tool| tool == tool_name),
+                ToolPermission::DenyList { tools } => !tools.iter().any(|tool| tool == tool_name),
+            };
+        }
+        if self.allowed_server_ids.is_empty() {
+            return !self.is_pending();
+        }
+        self.allowed_server_ids.iter().any(|id| id == server_id)
+    }
 }
 
 /// Helper to load and persist project records on disk.
@@ -159,6 +359,8 @@ pub struct ProjectRegistry {
 struct ProjectRegistryInner {
     root: PathBuf,
     cache: RwLock<ProjectCache>,
+    /// Bumped whenever records or other permission inputs change; see [`ProjectRegistry::generation`].
+    generation: AtomicU64,
 }
 
 /// Snapshot of files under the projects directory used to invalidate the in-memory cache.
@@ -249,6 +451,7 @@ impl ProjectRegistry {
             inner: Arc::new(ProjectRegistryInner {
                 root: layout.projects_dir().to_path_buf(),
                 cache: RwLock::new(ProjectCache::default()),
+                generation: AtomicU64::new(0),
             }),
         }
     }
@@ -263,6 +466,26 @@ impl ProjectRegistry {
         Ok(())
     }
 
+    /// Change counter shared by every clone of this registry.
+    ///
+    /// Advances on `store`/`delete`, when a refresh picks up edits made by another process,
+    /// and on [`Self::notify_changed`]. Anything derived from project permissions can cache
+    /// its result until the value moves.
+    pub fn generation(&self) -> u64 {
+        self.inner.generation.load(Ordering::Acquire)
+    }
+
+    /// Signal that an input of project permissions changed (settings, server configs, a
+    /// repository `.mcp-center.toml`).
+    pub fn notify_changed(&self) {
+        self.inner.generation.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Re-check the projects directory and pick up records changed by other processes.
+    pub fn poll_changes(&self) -> Result<()> {
+        self.ensure_cache_fresh()
+    }
+
     pub fn list(&self) -> Result<Vec<ProjectRecord>> {
         self.ensure_cache_fresh()?;
         let cache = self.cache();
@@ -303,6 +526,37 @@ impl ProjectRegistry {
         }
     }
 
+    /// Find the most recently seen record sharing `identity` that lives at another path.
+    pub fn find_by_identity(
+        &self,
+        identity: &str,
+        exclude_path: &Path,
+    ) -> Result<Option<ProjectRecord>> {
+        Ok(self
+            .list()?
+            .into_iter()
+            .filter(|record| {
+                record.identity.as_deref() == Some(identity) && record.path != exclude_path
+            })
+            .max_by_key(|record| record.last_seen_at))
+    }
+
+    /// Move `record` to `new_path`: store it under the id derived from the new path
+    /// (replacing whatever was stored there) and remove the old file.
+    pub fn rebind(&self, record: &ProjectRecord, new_path: &Path) -> Result<ProjectRecord> {
+        let new_id = ProjectId::from_path(new_path);
+        let mut moved = record.clone();
+        moved.id = new_id.clone().into();
+        moved.path = new_path.to_path_buf();
+        moved.identity = identity::project_identity(new_path).or(moved.identity);
+        moved.touch();
+        self.store(&moved)?;
+        if record.id != moved.id && self.path_for_raw(&record.id).exists() {
+            self.delete_by_id_str(&record.id)?;
+        }
+        Ok(moved)
+    }
+
     pub fn store(&self, record: &ProjectRecord) -> Result<()> {
         let path = self.path_for_raw(&record.id);
         let doc = toml_edit::ser::to_string_pretty(record)
@@ -365,6 +619,7 @@ impl ProjectRegistry {
     }
 
     fn refresh_cache(&self, fingerprint: DirectoryFingerprint) -> Result<()> {
+        self.notify_changed();
         if matches!(fingerprint, DirectoryFingerprint::Missing) {
             let mut cache = self.cache().write().expect("project cache poisoned");
             cache.clear_to_missing();
@@ -395,6 +650,7 @@ impl ProjectRegistry {
     }
 
     fn update_cache_after_store(&self, record: ProjectRecord) {
+        self.notify_changed();
         let file_path = self.path_for_raw(&record.id);
         let mut cache = self.cache().write().expect("project cache poisoned");
         if !cache.loaded {
@@ -421,6 +677,7 @@ impl ProjectRegistry {
     }
 
     fn update_cache_after_delete(&self, id: &str) {
+        self.notify_changed();
         let file_path = self.path_for_raw(id);
         let mut cache = self.cache().write().expect("project cache poisoned");
         if !cache.loaded {
@@ -0,0 +1,1128 @@
+//! Effective project permissions assembled from glob rules, parent projects and the
+//! project's own record.
+//!
+//! Layers are ordered from the most general to the most specific; for every setting the
+//! most specific layer that defines it wins, and empty settings inherit. A committed
+//! `.mcp-center.toml` can only narrow the result (see [`super::repo_config`]).
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    env, fmt,
+    path::{Component, Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::{
+    ProjectId, ProjectRecord, ProjectRegistry, ProjectStatus, ToolCustomization, ToolPermission,
+    annotations::{AnnotationPolicy, AnnotationSwitch, ToolHints},
+    arguments::{ArgumentConstraint, ArgumentPreset, ArgumentViolation},
+    grant::{self, TemporaryGrant},
+    repo_config::{RepoConfig, RepoLayer, RepoWarning},
+};
+use crate::config::{ServerDefinition, hooks::ToolHook, limits::OutputLimits};
+
+/// `[[project_rules]]` entry in the workspace settings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ProjectRule {
+    /// Glob over project paths: `*` and `?` match inside one path segment, `**` matches
+    /// any number of segments, and a leading `~` expands to the home directory.
+    pub pattern: String,
+    /// Server ids granted in full (empty = inherit).
+    pub servers: Vec<String>,
+    /// Tool-level permissions per server id.
+    pub tools: BTreeMap<String, ToolPermission>,
+    pub tool_customizations: Vec<ToolCustomization>,
+    /// Annotation switches for matching projects (unset switches inherit).
+    pub annotations: AnnotationPolicy,
+    /// Require approval for every tool call of matching projects.
+    pub require_approval: Option<bool>,
+    /// Expose only the tool search meta-tools to matching projects.
+    pub tool_search: Option<bool>,
+    /// Hooks run around the tool calls of matching projects (empty = inherit).
+    pub hooks: Vec<ToolHook>,
+    /// Size limits on the tool results of matching projects.
+    pub output_limits: OutputLimits,
+}
+
+impl ProjectRule {
+    pub fn matches(&self, path: &Path) -> bool {
+        glob_matches(&expand_home(&self.pattern), path)
+    }
+
+    /// Number of literal segments; rules with more literal segments are more specific.
+    fn specificity(&self) -> usize {
+        split_pattern(&expand_home(&self.pattern))
+            .iter()
+            .filter(|segment| !segment.contains(['*', '?']))
+            .count()
+    }
+}
+
+/// Where a layer of the effective policy comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicySource {
+    Rule { pattern: String },
+    Parent { id: String, path: PathBuf },
+    RepoFile { path: PathBuf },
+    Project { id: String, path: PathBuf },
+}
+
+impl fmt::Display for PolicySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicySource::Rule { pattern } => write!(f, "rule '{pattern}'"),
+            PolicySource::Parent { path, .. } => write!(f, "parent project {}", path.display()),
+            PolicySource::RepoFile { path } => write!(f, "{}", path.display()),
+            PolicySource::Project { path, .. } => write!(f, "project {}", path.display()),
+        }
+    }
+}
+
+/// Permissions contributed by a single rule or record.
+#[derive(Debug, Clone)]
+pub struct PolicyLayer {
+    pub source: PolicySource,
+    pub servers: Vec<String>,
+    pub tools: HashMap<String, ToolPermission>,
+    pub tool_customizations: Vec<ToolCustomization>,
+    pub grants: Vec<TemporaryGrant>,
+    pub annotations: AnnotationPolicy,
+    pub require_approval: Option<bool>,
+    pub tool_search: Option<bool>,
+    pub hooks: Vec<ToolHook>,
+    pub output_limits: OutputLimits,
+}
+
+impl PolicyLayer {
+    fn from_rule(rule: &ProjectRule) -> Self {
+        Self {
+            source: PolicySource::Rule { pattern: rule.pattern.clone() },
+            servers: rule.servers.clone(),
+            tools: rule.tools.clone().into_iter().collect(),
+            tool_customizations: rule.tool_customizations.clone(),
+            grants: Vec::new(),
+            annotations: rule.annotations,
+            require_approval: rule.require_approval,
+            tool_search: rule.tool_search,
+            hooks: rule.hooks.clone(),
+            output_limits: rule.output_limits,
+        }
+    }
+
+    fn from_record(record: &ProjectRecord, parent: bool) -> Self {
+        let (id, path) = (record.id.clone(), record.path.clone());
+        Self {
+            source: if parent {
+                PolicySource::Parent { id, path }
+            } else {
+                PolicySource::Project { id, path }
+            },
+            servers: record.allowed_server_ids.clone(),
+            tools: record.allowed_server_tools.clone(),
+            tool_customizations: record.tool_customizations.clone(),
+            grants: record.grants.clone(),
+            annotations: record.annotation_policy,
+            require_approval: record.require_approval,
+            tool_search: record.tool_search,
+            hooks: record.hooks.clone(),
+            output_limits: record.output_limits,
+        }
+    }
+}
+
+/// A value together with the layer that produced it.
+#[derive(Debug, Clone, Copy)]
+pub struct Sourced<'a, T: ?Sized> {
+    pub value: &'a T,
+    pub source: &'a PolicySource,
+}
+
+/// Why a tool is allowed or denied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecisionReason {
+    /// An unexpired temporary grant allowed it.
+    TemporaryGrant {
+        source: PolicySource,
+        expires_at: u64,
+    },
+    /// A per-server tool permission decided.
+    ToolPermission { source: PolicySource },
+    /// The granted server list decided.
+    ServerList { source: PolicySource },
+    /// Allowed locally but excluded by the repository's `.mcp-center.toml`.
+    RepoConfig { path: PathBuf },
+    /// Allowed by name but hidden by an annotation switch.
+    Annotation {
+        switch: AnnotationSwitch,
+        source: PolicySource,
+    },
+    /// Nothing is configured; unapproved projects get nothing.
+    PendingApproval,
+    /// Nothing is configured; everything is allowed.
+    Unrestricted,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decision {
+    pub allowed: bool,
+    pub reason: DecisionReason,
+}
+
+/// A tool as an agent bound to the project would see it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EffectiveTool {
+    pub server_id: String,
+    pub name: String,
+    pub decision: Decision,
+    /// Annotation hints after per-tool overrides.
+    pub hints: ToolHints,
+    /// Description exposed to the agent, custom when overridden.
+    pub description: Option<String>,
+    /// Layer that replaced the upstream description.
+    pub description_source: Option<PolicySource>,
+    /// Name advertised instead of `name`.
+    pub alias: Option<String>,
+}
+
+/// Permissions in effect for one project path.
+#[derive(Debug, Clone)]
+pub struct EffectivePolicy {
+    pub path: PathBuf,
+    pub status: ProjectStatus,
+    /// From the most general to the most specific.
+    pub layers: Vec<PolicyLayer>,
+    /// Restrictions from the project's `.mcp-center.toml`, when loaded.
+    pub repo: Option<RepoLayer>,
+    /// Problems found while loading the repository config.
+    pub repo_warnings: Vec<RepoWarning>,
+}
+
+impl EffectivePolicy {
+    /// Assemble the policy for `path` from matching rules, ancestor records and its own record.
+    ///
+    /// Ancestors still pending approval are skipped so they cannot hand out permissions.
+    pub fn resolve(registry: &ProjectRegistry, rules: &[ProjectRule], path: &Path) -> Self {
+        let mut matching = rules.iter().filter(|rule| rule.matches(path)).collect::<Vec<_>>();
+        matching.sort_by_key(|rule| rule.specificity());
+        let mut layers = matching.into_iter().map(PolicyLayer::from_rule).collect::<Vec<_>>();
+
+        let mut parents = path
+            .ancestors()
+            .skip(1)
+            .filter_map(|ancestor| registry.find_by_path(ancestor).ok().flatten())
+            .filter(|record| !record.is_pending())
+            .collect::<Vec<_>>();
+        parents.reverse();
+        layers.extend(parents.iter().map(|record| PolicyLayer::from_record(record, true)));
+
+        let own = registry.load(&ProjectId::from_path(path)).ok();
+        let status = own.as_ref().map(|record| record.status).unwrap_or_default();
+        if let Some(record) = own.as_ref() {
+            layers.push(PolicyLayer::from_record(record, false));
+        }
+
+        Self { path: path.to_path_buf(), status, layers, repo: None, repo_warnings: Vec::new() }
+    }
+
+    /// Apply the `.mcp-center.toml` at the project root, if any.
+    ///
+    /// Its tool rules narrow every decision and its descriptions sit between inherited
+    /// layers and the project's own record. Referenced servers that are not installed, or
+    /// required servers the local configuration does not allow, become warnings.
+    pub fn with_repo_config(mut self, servers: &[ServerDefinition]) -> Self {
+        let config = match RepoConfig::load(&self.path) {
+            Ok(Some(config)) => config,
+            Ok(None) => return self,
+            Err(err) => {
+                self.repo_warnings.push(RepoWarning::Invalid { error: format!("{err:#}") });
+                return self;
+            }
+        };
+        let repo = RepoLayer::new(&self.path, config, servers);
+        let at = self
+            .layers
+            .iter()
+            .position(|layer| matches!(layer.source, PolicySource::Project { .. }))
+            .unwrap_or(self.layers.len());
+        self.layers.insert(
+            at,
+            PolicyLayer {
+                source: PolicySource::RepoFile { path: repo.path.clone() },
+                servers: Vec::new(),
+                tools: HashMap::new(),
+                tool_customizations: repo.tool_customizations.clone(),
+                grants: Vec::new(),
+                annotations: AnnotationPolicy::default(),
+                require_approval: None,
+                tool_search: None,
+                // Hooks run commands, so a file committed to the repository cannot add any
+                hooks: Vec::new(),
+                output_limits: OutputLimits::default(),
+            },
+        );
+        self.repo_warnings.extend(
+            repo.missing_servers
+                .iter()
+                .map(|server| RepoWarning::NotInstalled { server: server.clone() }),
+        );
+        let not_allowed = repo
+            .required_servers
+            .iter()
+            .filter(|(_, id)| !self.allows_server(id))
+            .map(|(server, _)| RepoWarning::NotAllowed { server: server.clone() })
+            .collect::<Vec<_>>();
+        self.repo_warnings.extend(not_allowed);
+        self.repo = Some(repo);
+        self
+    }
+
+    /// Whether anything besides the project's own record and repository config contributes.
+    pub fn has_inherited_layers(&self) -> bool {
+        self.layers.iter().any(|layer| {
+            matches!(layer.source, PolicySource::Rule { .. } | PolicySource::Parent { .. })
+        })
+    }
+
+    /// Granted server list of the most specific layer that defines one.
+    pub fn servers(&self) -> Option<Sourced<'_, [String]>> {
+        self.layers
+            .iter()
+            .rev()
+            .find(|layer| !layer.servers.is_empty())
+            .map(|layer| Sourced { value: layer.servers.as_slice(), source: &layer.source })
+    }
+
+    /// Tool permission for `server_id` from the most specific layer that defines one.
+    pub fn tool_permission(&self, server_id: &str) -> Option<Sourced<'_, ToolPermission>> {
+        self.layers.iter().rev().find_map(|layer| {
+            layer.tools.get(server_id).map(|value| Sourced { value, source: &layer.source })
+        })
+    }
+
+    /// Every server with a tool permission, resolved per server.
+    pub fn tool_permissions(&self) -> Vec<(String, Sourced<'_, ToolPermission>)> {
+        let mut servers = self
+            .layers
+            .iter()
+            .flat_map(|layer| layer.tools.keys().cloned())
+            .collect::<Vec<_>>();
+        servers.sort();
+        servers.dedup();
+        servers
+            .into_iter()
+            .filter_map(|server| {
+                let permission = self.tool_permission(&server)?;
+                Some((server, permission))
+            })
+            .collect()
+    }
+
+    /// Customization for `tool_name` from the most specific layer that defines one.
+    pub fn customization(&self, tool_name: &str) -> Option<Sourced<'_, ToolCustomization>> {
+        self.layers.iter().rev().find_map(|layer| {
+            layer
+                .tool_customizations
+                .iter()
+                .find(|customization| customization.tool_name == tool_name)
+                .map(|value| Sourced { value, source: &layer.source })
+        })
+    }
+
+    /// Every customized tool, resolved per tool name.
+    pub fn customizations(&self) -> Vec<Sourced<'_, ToolCustomization>> {
+        let mut names = self
+            .layers
+            .iter()
+            .flat_map(|layer| layer.tool_customizations.iter().map(|c| c.tool_name.clone()))
+            .collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+        names.iter().filter_map(|name| self.customization(name)).collect()
+    }
+
+    /// Decide `tool_name` from `server_id`: active grants > tool permissions > server list >
+    /// default, then narrowed by the repository config.
+    pub fn decide(&self, server_id: &str, tool_name: &str) -> Decision {
+        let decision = self.decide_locally(server_id, tool_name);
+        match &self.repo {
+            Some(repo) if decision.allowed && !repo.allows(server_id, tool_name) => Decision {
+                allowed: false,
+                reason: DecisionReason::RepoConfig { path: repo.path.clone() },
+            },
+            _ => decision,
+        }
+    }
+
+    /// Like [`Self::decide`], then hidden when an enabled annotation switch matches the
+    /// tool's hints (`upstream` as reported by the server, corrected by customizations).
+    /// Temporary grants are explicit and bypass the switches.
+    pub fn decide_annotated(
+        &self,
+        server_id: &str,
+        tool_name: &str,
+        upstream: ToolHints,
+    ) -> Decision {
+        let decision = self.decide(server_id, tool_name);
+        if !decision.allowed || matches!(decision.reason, DecisionReason::TemporaryGrant { .. }) {
+            return decision;
+        }
+        let hints = self.hints_for(tool_name, upstream);
+        for switch in AnnotationSwitch::ALL {
+            if let Some(enabled) = self.annotation_switch(switch)
+                && *enabled.value
+                && switch.hides(&hints)
+            {
+                return Decision {
+                    allowed: false,
+                    reason: DecisionReason::Annotation { switch, source: enabled.source.clone() },
+                };
+            }
+        }
+        decision
+    }
+
+    pub fn allows_annotated_tool(
+        &self,
+        server_id: &str,
+        tool_name: &str,
+        hints: ToolHints,
+    ) -> bool {
+        self.decide_annotated(server_id, tool_name, hints).allowed
+    }
+
+    /// Setting of `switch` from the most specific layer that defines it.
+    pub fn annotation_switch(&self, switch: AnnotationSwitch) -> Option<Sourced<'_, bool>> {
+        self.layers.iter().rev().find_map(|layer| {
+            let value = match switch {
+                AnnotationSwitch::ReadOnly => layer.annotations.read_only.as_ref(),
+                AnnotationSwitch::NoDestructive => layer.annotations.no_destructive.as_ref(),
+                AnnotationSwitch::NoOpenWorld => layer.annotations.no_open_world.as_ref(),
+            }?;
+            Some(Sourced { value, source: &layer.source })
+        })
+    }
+
+    /// Whether calls to `tool_name` must be approved: a per-tool setting from the most
+    /// specific layer wins over project-wide settings.
+    pub fn approval_requirement(&self, tool_name: &str) -> Option<Sourced<'_, bool>> {
+        self.tool_approval(tool_name).or_else(|| self.project_approval())
+    }
+
+    /// Per-tool approval setting for `tool_name` from the most specific layer that has one.
+    pub fn tool_approval(&self, tool_name: &str) -> Option<Sourced<'_, bool>> {
+        self.layers.iter().rev().find_map(|layer| {
+            layer
+                .tool_customizations
+                .iter()
+                .find(|customization| customization.tool_name == tool_name)
+                .and_then(|customization| customization.require_approval.as_ref())
+                .map(|value| Sourced { value, source: &layer.source })
+        })
+    }
+
+    /// Project-wide approval setting from the most specific layer that has one.
+    pub fn project_approval(&self) -> Option<Sourced<'_, bool>> {
+        self.layers.iter().rev().find_map(|layer| {
+            layer
+                .require_approval
+                .as_ref()
+                .map(|value| Sourced { value, source: &layer.source })
+        })
+    }
+
+    /// Tool search setting from the most specific layer that has one.
+    pub fn tool_search(&self) -> Option<Sourced<'_, bool>> {
+        self.layers.iter().rev().find_map(|layer| {
+            layer.tool_search.as_ref().map(|value| Sourced { value, source: &layer.source })
+        })
+    }
+
+    pub fn uses_tool_search(&self) -> bool {
+        self.tool_search().is_some_and(|enabled| *enabled.value)
+    }
+
+    /// Output limits, each from the most specific layer that sets it.
+    pub fn output_limits(&self) -> OutputLimits {
+        let pick = |limit: fn(&OutputLimits) -> Option<usize>| {
+            self.layers.iter().rev().find_map(|layer| limit(&layer.output_limits))
+        };
+        OutputLimits {
+            max_text_chars: pick(|limits| limits.max_text_chars),
+            max_items: pick(|limits| limits.max_items),
+            max_image_bytes: pick(|limits| limits.max_image_bytes),
+            spill: self.layers.iter().rev().find_map(|layer| layer.output_limits.spill),
+        }
+    }
+
+    /// Hooks of the most specific layer that lists any.
+    pub fn hooks(&self) -> Option<Sourced<'_, [ToolHook]>> {
+        self.layers
+            .iter()
+            .rev()
+            .find(|layer| !layer.hooks.is_empty())
+            .map(|layer| Sourced { value: layer.hooks.as_slice(), source: &layer.source })
+    }
+
+    pub fn requires_approval(&self, tool_name: &str) -> bool {
+        self.approval_requirement(tool_name).is_some_and(|required| *required.value)
+    }
+
+    /// `upstream` hints with the most specific annotation override for `tool_name` applied.
+    pub fn hints_for(&self, tool_name: &str, upstream: ToolHints) -> ToolHints {
+        let patch = self.layers.iter().rev().find_map(|layer| {
+            layer
+                .tool_customizations
+                .iter()
+                .find(|customization| customization.tool_name == tool_name)
+                .and_then(|customization| customization.annotations)
+        });
+        match patch {
+            Some(patch) => upstream.with_override(&patch),
+            None => upstream,
+        }
+    }
+
+    /// Active grant covering `tool_name` of `server_id`, from the most specific layer.
+    pub fn active_grant(
+        &self,
+        server_id: &str,
+        tool_name: &str,
+        now: u64,
+    ) -> Option<Sourced<'_, TemporaryGrant>> {
+        self.layers.iter().rev().find_map(|layer| {
+            layer
+                .grants
+                .iter()
+                .find(|grant| grant.is_active_at(now) && grant.covers(server_id, tool_name))
+                .map(|value| Sourced { value, source: &layer.source })
+        })
+    }
+
+    /// Earliest `expires_at` among grants still active at `now`.
+    pub fn next_grant_expiry(&self, now: u64) -> Option<u64> {
+        self.layers
+            .iter()
+            .flat_map(|layer| &layer.grants)
+            .filter(|grant| grant.is_active_at(now))
+            .map(|grant| grant.expires_at)
+            .min()
+    }
+
+    /// Whether the local configuration lets any tool of `server_id` through.
+    fn allows_server(&self, server_id: &str) -> bool {
+        let now = grant::now();
+        if self.layers.iter().flat_map(|layer| &layer.grants).any(|grant| {
+            grant.is_active_at(now) && grant.server_id == server_id && grant.tools.is_empty()
+        }) {
+            return true;
+        }
+        if let Some(permission) = self.tool_permission(server_id) {
+            return !matches!(permission.value, ToolPermission::AllowList { tools } if tools.is_empty());
+        }
+        if let Some(servers) = self.servers() {
+            return servers.value.iter().any(|id| id == server_id);
+        }
+        self.status != ProjectStatus::Pending
+    }
+
+    fn decide_locally(&self, server_id: &str, tool_name: &str) -> Decision {
+        if let Some(grant) = self.active_grant(server_id, tool_name, grant::now()) {
+            return Decision {
+                allowed: true,
+                reason: DecisionReason::TemporaryGrant {
+                    source: grant.source.clone(),
+                    expires_at: grant.value.expires_at,
+                },
+            };
+        }
+        if let Some(permission) = self.tool_permission(server_id) {
+            let allowed = match permission.value {
+                ToolPermission::All => true,
+                ToolPermission::AllowList { tools } => tools.iter().any(|tool| tool == tool_name),
+                ToolPermission::DenyList { tools } => !tools.iter().any(|tool| tool == tool_name),
+            };
+            return Decision {
+                allowed,
+                reason: DecisionReason::ToolPermission { source: permission.source.clone() },
+            };
+        }
+        if let Some(servers) = self.servers() {
+            return Decision {
+                allowed: servers.value.iter().any(|id| id == server_id),
+                reason: DecisionReason::ServerList { source: servers.source.clone() },
+            };
+        }
+        if self.status == ProjectStatus::Pending {
+            Decision { allowed: false, reason: DecisionReason::PendingApproval }
+        } else {
+            Decision { allowed: true, reason: DecisionReason::Unrestricted }
+        }
+    }
+
+    pub fn allows_tool(&self, server_id: &str, tool_name: &str) -> bool {
+        self.decide(server_id, tool_name).allowed
+    }
+
+    /// Run every `(server_id, tool_name, description, hints)` through
+    /// [`Self::decide_annotated`] and apply custom descriptions, the same way the daemon
+    /// filters `tools/list`.
+    pub fn simulate(
+        &self,
+        tools: impl IntoIterator<Item = (String, String, Option<String>, ToolHints)>,
+    ) -> Vec<EffectiveTool> {
+        tools
+            .into_iter()
+            .map(|(server_id, name, description, upstream)| {
+                let decision = self.decide_annotated(&server_id, &name, upstream);
+                let hints = self.hints_for(&name, upstream);
+                let customization = self.customization(&name);
+                let alias = customization.as_ref().and_then(|c| c.value.alias.clone());
+                let custom = customization.and_then(|customization| {
+                    let description = customization.value.description.clone()?;
+                    Some((description, customization.source.clone()))
+                });
+                let (description, description_source) = match custom {
+                    Some((custom, source)) => (Some(custom), Some(source)),
+                    None => (description, None),
+                };
+                EffectiveTool {
+                    server_id,
+                    name,
+                    decision,
+                    hints,
+                    description,
+                    description_source,
+                    alias,
+                }
+            })
+            .collect()
+    }
+
+    /// Argument constraints for `tool_name` from every layer; unlike other settings they
+    /// accumulate, so a project cannot lift a constraint imposed by a rule or parent.
+    pub fn argument_constraints(&self, tool_name: &str) -> Vec<Sourced<'_, ArgumentConstraint>> {
+        self.layers
+            .iter()
+            .flat_map(|layer| {
+                layer
+                    .tool_customizations
+                    .iter()
+                    .filter(move |customization| customization.tool_name == tool_name)
+                    .flat_map(|customization| &customization.arguments)
+                    .map(|value| Sourced { value, source: &layer.source })
+            })
+            .collect()
+    }
+
+    /// Violations of the argument constraints for a call to `tool_name`, with
+    /// `${PROJECT_ROOT}` standing for this project's path.
+    pub fn check_arguments(
+        &self,
+        tool_name: &str,
+        arguments: Option<&Map<String, Value>>,
+    ) -> Vec<ArgumentViolation> {
+        self.argument_constraints(tool_name)
+            .into_iter()
+            .flat_map(|constraint| constraint.value.check(arguments, &self.path))
+            .collect()
+    }
+
+    /// Pinned arguments for `tool_name`; per argument the most specific layer wins.
+    pub fn argument_presets(&self, tool_name: &str) -> BTreeMap<&str, Sourced<'_, ArgumentPreset>> {
+        let mut presets = BTreeMap::new();
+        for layer in &self.layers {
+            for customization in &layer.tool_customizations {
+                if customization.tool_name != tool_name {
+                    continue;
+                }
+                for (argument, value) in &customization.presets {
+                    presets.insert(argument.as_str(), Sourced { value, source: &layer.source });
+                }
+            }
+        }
+        presets
+    }
+
+    /// Upstream name of the tool advertised as `alias`.
+    pub fn upstream_for_alias(&self, alias: &str) -> Option<String> {
+        self.customizations()
+            .into_iter()
+            .find(|customization| customization.value.alias.as_deref() == Some(alias))
+            .map(|customization| customization.value.tool_name.clone())
+    }
+
+    pub fn description_for(&self, tool_name: &str) -> Option<String> {
+        self.customization(tool_name)
+            .and_then(|customization| customization.value.description.clone())
+    }
+}
+
+fn expand_home(pattern: &str) -> String {
+    if let Some(rest) = pattern.strip_prefix('~')
+        && (rest.is_empty() || rest.starts_with(['/', '\\']))
+        && let Some(home) = env::var_os("HOME").or_else(|| env::var_os("USERPROFILE"))
+    {
+        return format!("{}{rest}", home.to_string_lossy());
+    }
+    pattern.to_string()
+}
+
+fn split_pattern(pattern: &str) -> Vec<&str> {
+    pattern.split(['/', '\\']).filter(|segment| !segment.is_empty()).collect()
+}
+
+fn path_segments(path: &Path) -> Vec<String> {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+            Component::Prefix(prefix) => Some(prefix.as_os_str().to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn glob_matches(pattern: &str, path: &Path) -> bool {
+    let pattern = split_pattern(pattern);
+    let segments = path_segments(path);
+    let segments = segments.iter().map(String::as_str).collect::<Vec<_>>();
+    match_segments(&pattern, &segments)
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| match_segments(rest, &path[skip..])),
+        Some((segment, rest)) => match path.split_first() {
+            Some((head, tail)) => match_segment(segment, head) && match_segments(rest, tail),
+            None => false,
+        },
+    }
+}
+
+fn match_segment(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Layout, project::arguments::Pattern};
+
+    fn rule(pattern: &str, servers: &[&str]) -> ProjectRule {
+        ProjectRule {
+            pattern: pattern.into(),
+            servers: servers.iter().map(|s| s.to_string()).collect(),
+            ..ProjectRule::default()
+        }
+    }
+
+    #[test]
+    fn glob_semantics() {
+        let path = Path::new("/repos/acme/web");
+        assert!(glob_matches("/repos/**", path));
+        assert!(glob_matches("/repos/acme/*", path));
+        assert!(glob_matches("/repos/*/w?b", path));
+        assert!(glob_matches("/repos/acme/web/**", path));
+        assert!(!glob_matches("/repos/*", path));
+        assert!(!glob_matches("/repos/acme/api*", path));
+    }
+
+    #[test]
+    fn nearest_layer_wins_and_explains_itself() {
+        let temp = tempfile::tempdir().unwrap();
+        let layout = Layout::new(temp.path().to_path_buf());
+        let registry = ProjectRegistry::new(&layout);
+        registry.ensure().unwrap();
+
+        let repo = PathBuf::from("/repos/acme");
+        let mut parent = ProjectRecord::new(ProjectId::from_path(&repo), repo.clone());
+        parent.allowed_server_tools.insert(
+            "github".into(),
+            ToolPermission::DenyList { tools: vec!["delete_repo".into()] },
+        );
+        parent.tool_customizations.push(ToolCustomization {
+            description: Some("from parent".into()),
+            ..ToolCustomization::new("search")
+        });
+        registry.store(&parent).unwrap();
+
+        let rules = vec![rule("/repos/**", &["github", "db"]), rule("/repos/acme/*", &["github"])];
+        let web = repo.join("web");
+        let policy = EffectivePolicy::resolve(&registry, &rules, &web);
+
+        assert!(policy.has_inherited_layers());
+        let servers = policy.servers().unwrap();
+        assert_eq!(servers.value, ["github".to_string()]);
+        assert_eq!(servers.source, &PolicySource::Rule { pattern: "/repos/acme/*".into() });
+
+        assert!(policy.allows_tool("github", "search"));
+        let denied = policy.decide("github", "delete_repo");
+        assert!(!denied.allowed);
+        assert!(matches!(
+            denied.reason,
+            DecisionReason::ToolPermission { source: PolicySource::Parent { .. } }
+        ));
+        assert!(!policy.allows_tool("db", "query"));
+        assert_eq!(policy.description_for("search").as_deref(), Some("from parent"));
+
+        // The nested project overrides what it inherits.
+        let mut own = ProjectRecord::new(ProjectId::from_path(&web), web.clone());
+        own.allowed_server_tools.insert("github".into(), ToolPermission::All);
+        registry.store(&own).unwrap();
+        let policy = EffectivePolicy::resolve(&registry, &rules, &web);
+        assert!(policy.allows_tool("github", "delete_repo"));
+    }
+
+    #[test]
+    fn repo_config_only_narrows() {
+        let temp = tempfile::tempdir().unwrap();
+        let layout = Layout::new(temp.path().join("root"));
+        let registry = ProjectRegistry::new(&layout);
+        registry.ensure().unwrap();
+
+        let project = temp.path().join("widgets");
+        std::fs::create_dir_all(&project).unwrap();
+        std::fs::write(
+            project.join(super::super::repo_config::REPO_CONFIG_FILE),
+            "servers = [\"db\"]\n[tools.github]\nallow = [\"search\", \"delete_repo\"]\n\n[descriptions]\nsearch = \"from repo\"\n",
+        )
+        .unwrap();
+        let mut record = ProjectRecord::new(ProjectId::from_path(&project), project.clone());
+        record.allowed_server_tools.insert(
+            "github".into(),
+            ToolPermission::DenyList { tools: vec!["delete_repo".into()] },
+        );
+        registry.store(&record).unwrap();
+
+        let servers = ["github", "db"].map(|id| ServerDefinition {
+            id: id.into(),
+            name: Some(id.into()),
+            command: "echo".into(),
+            enabled: true,
+            ..Default::default()
+        });
+        let policy = EffectivePolicy::resolve(&registry, &[], &project).with_repo_config(&servers);
+
+        assert!(!policy.has_inherited_layers());
+        assert!(policy.allows_tool("github", "search"));
+        // Denied locally; the repo allow list does not re-grant it.
+        assert!(!policy.allows_tool("github", "delete_repo"));
+        assert!(matches!(
+            policy.decide("github", "list_issues").reason,
+            DecisionReason::RepoConfig { .. }
+        ));
+        assert_eq!(policy.description_for("search").as_deref(), Some("from repo"));
+        assert!(policy.repo_warnings.is_empty());
+    }
+
+    #[test]
+    fn simulate_reports_reason_and_description_per_tool() {
+        let mut own = ProjectRecord::new(ProjectId::from_path(Path::new("/p")), "/p".into());
+        own.allowed_server_ids = vec!["db".into()];
+        own.allowed_server_tools
+            .insert("github".into(), ToolPermission::AllowList { tools: vec!["search".into()] });
+        own.tool_customizations.push(ToolCustomization {
+            description: Some("custom".into()),
+            ..ToolCustomization::new("search")
+        });
+        let policy = EffectivePolicy {
+            path: PathBuf::from("/p"),
+            status: ProjectStatus::Active,
+            layers: vec![PolicyLayer::from_record(&own, false)],
+            repo: None,
+            repo_warnings: Vec::new(),
+        };
+
+        let hints = ToolHints::default();
+        let tools = policy.simulate([
+            ("github".to_string(), "search".to_string(), Some("upstream".to_string()), hints),
+            ("github".to_string(), "delete_repo".to_string(), None, hints),
+            ("db".to_string(), "query".to_string(), Some("run sql".to_string()), hints),
+            ("slack".to_string(), "post".to_string(), None, hints),
+        ]);
+        let project = PolicySource::Project { id: own.id.clone(), path: "/p".into() };
+
+        assert!(tools[0].decision.allowed);
+        assert_eq!(tools[0].description.as_deref(), Some("custom"));
+        assert_eq!(tools[0].description_source.as_ref(), Some(&project));
+        assert!(!tools[1].decision.allowed);
+        assert_eq!(
+            tools[1].decision.reason,
+            DecisionReason::ToolPermission { source: project.clone() }
+        );
+        // Tool-level entries for github do not affect db, which follows the server list.
+        assert!(tools[2].decision.allowed);
+        assert_eq!(tools[2].decision.reason, DecisionReason::ServerList { source: project });
+        assert_eq!(tools[2].description.as_deref(), Some("run sql"));
+        assert!(!tools[3].decision.allowed);
+    }
+
+    #[test]
+    fn annotation_switches_inherit_and_respect_overrides() {
+        let temp = tempfile::tempdir().unwrap();
+        let layout = Layout::new(temp.path().to_path_buf());
+        let registry = ProjectRegistry::new(&layout);
+        registry.ensure().unwrap();
+
+        let rules = vec![ProjectRule {
+            pattern: "/repos/**".into(),
+            annotations: AnnotationPolicy { read_only: Some(true), ..Default::default() },
+            ..ProjectRule::default()
+        }];
+        let path = PathBuf::from("/repos/web");
+        let mut own = ProjectRecord::new(ProjectId::from_path(&path), path.clone());
+        own.annotation_policy.no_open_world = Some(true);
+        own.tool_customizations.push(ToolCustomization {
+            annotations: Some(super::super::annotations::AnnotationOverride {
+                read_only: Some(true),
+                open_world: Some(false),
+                ..Default::default()
+            }),
+            ..ToolCustomization::new("lookup")
+        });
+        registry.store(&own).unwrap();
+        let policy = EffectivePolicy::resolve(&registry, &rules, &path);
+
+        let writer = ToolHints::default();
+        let decision = policy.decide_annotated("db", "write", writer);
+        assert_eq!(
+            decision.reason,
+            DecisionReason::Annotation {
+                switch: AnnotationSwitch::ReadOnly,
+                source: PolicySource::Rule { pattern: "/repos/**".into() },
+            }
+        );
+        // Hinted read-only upstream but still open-world.
+        let reader = ToolHints { read_only: true, ..writer };
+        assert!(matches!(
+            policy.decide_annotated("db", "query", reader).reason,
+            DecisionReason::Annotation { switch: AnnotationSwitch::NoOpenWorld, .. }
+        ));
+        assert!(policy.allows_annotated_tool("db", "lookup", writer));
+        // Name-based checks are unaffected.
+        assert!(policy.allows_tool("db", "write"));
+
+        // The project turns read-only off again.
+        own.annotation_policy.read_only = Some(false);
+        own.annotation_policy.no_open_world = None;
+        registry.store(&own).unwrap();
+        let policy = EffectivePolicy::resolve(&registry, &rules, &path);
+        assert!(policy.allows_annotated_tool("db", "write", writer));
+    }
+
+    #[test]
+    fn argument_constraints_accumulate_across_layers() {
+        let temp = tempfile::tempdir().unwrap();
+        let layout = Layout::new(temp.path().to_path_buf());
+        let registry = ProjectRegistry::new(&layout);
+        registry.ensure().unwrap();
+
+        let mut inherited = ToolCustomization::new("write_file");
+        inherited.arguments.push(ArgumentConstraint {
+            pointer: "/path".into(),
+            within: Some("${PROJECT_ROOT}".into()),
+            ..Default::default()
+        });
+        let rules = vec![ProjectRule {
+            pattern: "/repos/**".into(),
+            tool_customizations: vec![inherited],
+            ..ProjectRule::default()
+        }];
+        let path = PathBuf::from("/repos/web");
+        let mut own = ProjectRecord::new(ProjectId::from_path(&path), path.clone());
+        own.customization_mut("write_file").arguments.push(ArgumentConstraint {
+            pointer: "/path".into(),
+            deny: vec![Pattern::new(r"\.env$").unwrap()],
+            ..Default::default()
+        });
+        registry.store(&own).unwrap();
+
+        let policy = EffectivePolicy::resolve(&registry, &rules, &path);
+        assert_eq!(policy.argument_constraints("write_file").len(), 2);
+        let call = |path: &str| {
+            let arguments = serde_json::json!({ "path": path });
+            policy.check_arguments("write_file", arguments.as_object()).len()
+        };
+        assert_eq!(call("src/lib.rs"), 0);
+        assert_eq!(call(".env"), 1);
+        assert_eq!(call("/etc/hosts"), 1);
+        assert!(policy.check_arguments("read_file", None).is_empty());
+    }
+
+    #[test]
+    fn argument_presets_merge_per_argument() {
+        let temp = tempfile::tempdir().unwrap();
+        let layout = Layout::new(temp.path().to_path_buf());
+        let registry = ProjectRegistry::new(&layout);
+        registry.ensure().unwrap();
+
+        let preset = |value: &str| ArgumentPreset { value: value.into(), secret: false };
+        let mut inherited = ToolCustomization::new("query");
+        inherited.presets.insert("database".into(), preset("shared"));
+        inherited.presets.insert("schema".into(), preset("public"));
+        let rules = vec![ProjectRule {
+            pattern: "/repos/**".into(),
+            tool_customizations: vec![inherited],
+            ..ProjectRule::default()
+        }];
+        let path = PathBuf::from("/repos/web");
+        let mut own = ProjectRecord::new(ProjectId::from_path(&path), path.clone());
+        own.customization_mut("query").presets.insert("database".into(), preset("web"));
+        registry.store(&own).unwrap();
+
+        let policy = EffectivePolicy::resolve(&registry, &rules, &path);
+        let presets = policy.argument_presets("query");
+        assert_eq!(presets["database"].value.value, "web");
+        assert_eq!(presets["schema"].value.value, "public");
+        assert!(policy.argument_presets("other").is_empty());
+    }
+
+    #[test]
+    fn aliases_resolve_to_upstream_tools() {
+        let mut own = ProjectRecord::new(ProjectId::from_path(Path::new("/p")), "/p".into());
+        own.customization_mut("search").alias = Some("issue_search".into());
+        let policy = EffectivePolicy {
+            path: PathBuf::from("/p"),
+            status: ProjectStatus::Active,
+            layers: vec![PolicyLayer::from_record(&own, false)],
+            repo: None,
+            repo_warnings: Vec::new(),
+        };
+
+        assert_eq!(policy.upstream_for_alias("issue_search").as_deref(), Some("search"));
+        assert_eq!(policy.upstream_for_alias("search"), None);
+        let tools = policy.simulate([(
+            "github".to_string(),
+            "search".to_string(),
+            None,
+            ToolHints::default(),
+        )]);
+        assert_eq!(tools[0].alias.as_deref(), Some("issue_search"));
+    }
+
+    #[test]
+    fn tool_search_follows_the_most_specific_layer() {
+        let temp = tempfile::tempdir().unwrap();
+        let layout = Layout::new(temp.path().to_path_buf());
+        let registry = ProjectRegistry::new(&layout);
+        registry.ensure().unwrap();
+
+        let rules = vec![ProjectRule {
+            pattern: "/repos/**".into(),
+            tool_search: Some(true),
+            ..ProjectRule::default()
+        }];
+        let path = PathBuf::from("/repos/web");
+        assert!(EffectivePolicy::resolve(&registry, &rules, &path).uses_tool_search());
+
+        let mut own = ProjectRecord::new(ProjectId::from_path(&path), path.clone());
+        own.tool_search = Some(false);
+        registry.store(&own).unwrap();
+        let policy = EffectivePolicy::resolve(&registry, &rules, &path);
+        assert!(!policy.uses_tool_search());
+        assert!(matches!(policy.tool_search().unwrap().source, PolicySource::Project { .. }));
+    }
+
+    #[test]
+    fn per_tool_approval_wins_over_project_wide_setting() {
+        let temp = tempfile::tempdir().unwrap();
+        let layout = Layout::new(temp.path().to_path_buf());
+        let registry = ProjectRegistry::new(&layout);
+        registry.ensure().unwrap();
+
+        let rules = vec![ProjectRule {
+            pattern: "/repos/**".into(),
+            require_approval: Some(true),
+            ..ProjectRule::default()
+        }];
+        let path = PathBuf::from("/repos/web");
+        let mut own = ProjectRecord::new(ProjectId::from_path(&path), path.clone());
+        own.customization_mut("search").require_approval = Some(false);
+        registry.store(&own).unwrap();
+
+        let policy = EffectivePolicy::resolve(&registry, &rules, &path);
+        assert!(policy.requires_approval("deploy"));
+        assert!(!policy.requires_approval("search"));
+        let required = policy.approval_requirement("deploy").unwrap();
+        assert_eq!(required.source, &PolicySource::Rule { pattern: "/repos/**".into() });
+
+        own.require_approval = Some(false);
+        registry.store(&own).unwrap();
+        let policy = EffectivePolicy::resolve(&registry, &rules, &path);
+        assert!(!policy.requires_approval("deploy"));
+    }
+
+    #[test]
+    fn output_limits_resolve_field_by_field() {
+        let temp = tempfile::tempdir().unwrap();
+        let layout = Layout::new(temp.path().to_path_buf());
+        let registry = ProjectRegistry::new(&layout);
+        registry.ensure().unwrap();
+
+        let rules = vec![ProjectRule {
+            pattern: "/repos/**".into(),
+            output_limits: OutputLimits {
+                max_text_chars: Some(20_000),
+                max_items: Some(10),
+                ..OutputLimits::default()
+            },
+            ..ProjectRule::default()
+        }];
+        let path = PathBuf::from("/repos/web");
+        let mut own = ProjectRecord::new(ProjectId::from_path(&path), path.clone());
+        own.output_limits = OutputLimits {
+            max_text_chars: Some(50_000),
+            spill: Some(true),
+            ..OutputLimits::default()
+        };
+        registry.store(&own).unwrap();
+
+        let policy = EffectivePolicy::resolve(&registry, &rules, &path);
+        assert_eq!(
+            policy.output_limits(),
+            OutputLimits {
+                max_text_chars: Some(50_000),
+                max_items: Some(10),
+                max_image_bytes: None,
+                spill: Some(true),
+            }
+        );
+    }
+
+    #[test]
+    fn pending_without_grants_denies() {
+        let policy = EffectivePolicy {
+            path: PathBuf::from("/p"),
+            status: ProjectStatus::Pending,
+            layers: Vec::new(),
+            repo: None,
+            repo_warnings: Vec::new(),
+        };
+        assert_eq!(
+            policy.decide("github", "search"),
+            Decision { allowed: false, reason: DecisionReason::PendingApproval }
+        );
+    }
+}
@@ -0,0 +1,206 @@
+//! Optional `.mcp-center.toml` committed at a project root.
+//!
+//! The file lets a team share which servers a repository expects, which tools it wants
+//! and how they are described. It never grants anything: tool rules only narrow what the
+//! local configuration already allows.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use super::ToolCustomization;
+use crate::config::ServerDefinition;
+
+/// File name looked up at the project root.
+pub const REPO_CONFIG_FILE: &str = ".mcp-center.toml";
+
+/// Parsed `.mcp-center.toml`. Servers are referenced by name or id.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct RepoConfig {
+    /// Servers the repository expects to be installed and allowed.
+    pub servers: Vec<String>,
+    /// Tool allow/deny lists per server.
+    pub tools: BTreeMap<String, RepoToolRule>,
+    /// Description overrides per tool name.
+    pub descriptions: BTreeMap<String, String>,
+}
+
+/// `[tools.<server>]` table.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct RepoToolRule {
+    /// Only these tools (empty = every tool).
+    pub allow: Vec<String>,
+    /// Never these tools.
+    pub deny: Vec<String>,
+}
+
+impl RepoToolRule {
+    pub fn allows(&self, tool_name: &str) -> bool {
+        (self.allow.is_empty() || self.allow.iter().any(|tool| tool == tool_name))
+            && !self.deny.iter().any(|tool| tool == tool_name)
+    }
+}
+
+impl RepoConfig {
+    /// Read `<root>/.mcp-center.toml`, returning `None` when the file does not exist.
+    pub fn load(root: &Path) -> Result<Option<Self>> {
+        let path = root.join(REPO_CONFIG_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let config = toml_edit::de::from_str(&content)
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+        Ok(Some(config))
+    }
+}
+
+/// Problem with a server referenced by the repository config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepoWarning {
+    /// No installed server has this name or id.
+    NotInstalled { server: String },
+    /// Installed, but the local configuration does not allow it.
+    NotAllowed { server: String },
+    /// The file exists but could not be read or parsed.
+    Invalid { error: String },
+}
+
+impl fmt::Display for RepoWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RepoWarning::NotInstalled { server } => {
+                write!(
+                    f,
+                    "{REPO_CONFIG_FILE} references MCP server '{server}' which is not installed"
+                )
+            }
+            RepoWarning::NotAllowed { server } => write!(
+                f,
+                "{REPO_CONFIG_FILE} requires MCP server '{server}' but it is not allowed for this project"
+            ),
+            RepoWarning::Invalid { error } => write!(f, "ignoring {REPO_CONFIG_FILE}: {error}"),
+        }
+    }
+}
+
+/// Repository config with server names resolved to ids.
+#[derive(Debug, Clone)]
+pub struct RepoLayer {
+    pub path: PathBuf,
+    /// `(reference, id)` for every required server that is installed.
+    pub required_servers: Vec<(String, String)>,
+    pub tools: HashMap<String, RepoToolRule>,
+    pub tool_customizations: Vec<ToolCustomization>,
+    /// References (required or in `[tools]`) that match no installed server.
+    pub missing_servers: Vec<String>,
+}
+
+impl RepoLayer {
+    pub fn new(root: &Path, config: RepoConfig, servers: &[ServerDefinition]) -> Self {
+        let lookup = |reference: &str| {
+            servers
+                .iter()
+                .find(|server| server.id == reference || server.name.as_deref() == Some(reference))
+                .map(|server| server.id.clone())
+        };
+
+        let mut missing_servers = Vec::new();
+        let mut required_servers = Vec::new();
+        for reference in &config.servers {
+            match lookup(reference) {
+                Some(id) => required_servers.push((reference.clone(), id)),
+                None => missing_servers.push(reference.clone()),
+            }
+        }
+        let mut tools = HashMap::new();
+        for (reference, rule) in config.tools {
+            match lookup(&reference) {
+                Some(id) => {
+                    tools.insert(id, rule);
+                }
+                None if !missing_servers.contains(&reference) => missing_servers.push(reference),
+                None => {}
+            }
+        }
+        let tool_customizations = config
+            .descriptions
+            .into_iter()
+            .map(|(tool_name, description)| ToolCustomization {
+                description: Some(description),
+                ..ToolCustomization::new(tool_name)
+            })
+            .collect();
+
+        Self {
+            path: root.join(REPO_CONFIG_FILE),
+            required_servers,
+            tools,
+            tool_customizations,
+            missing_servers,
+        }
+    }
+
+    /// Whether the repository config leaves `tool_name` of `server_id` available.
+    pub fn allows(&self, server_id: &str, tool_name: &str) -> bool {
+        self.tools.get(server_id).map(|rule| rule.allows(tool_name)).unwrap_or(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(id: &str, name: &str) -> ServerDefinition {
+        ServerDefinition {
+            id: id.into(),
+            name: Some(name.into()),
+            command: "echo".into(),
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_and_resolves_server_names() {
+        let temp = tempfile::tempdir().unwrap();
+        fs::write(
+            temp.path().join(REPO_CONFIG_FILE),
+            r#"
+servers = ["github", "jira"]
+
+[tools.github]
+allow = ["search_code", "get_issue"]
+deny = ["get_issue"]
+
+[descriptions]
+search_code = "Search this monorepo"
+"#,
+        )
+        .unwrap();
+
+        let config = RepoConfig::load(temp.path()).unwrap().expect("file present");
+        let layer = RepoLayer::new(temp.path(), config, &[server("gh01", "github")]);
+        assert_eq!(layer.required_servers, vec![("github".to_string(), "gh01".to_string())]);
+        assert_eq!(layer.missing_servers, vec!["jira".to_string()]);
+        assert!(layer.allows("gh01", "search_code"));
+        assert!(!layer.allows("gh01", "get_issue"));
+        assert!(!layer.allows("gh01", "delete_repo"));
+        assert!(layer.allows("other", "anything"));
+        assert_eq!(layer.tool_customizations[0].tool_name, "search_code");
+    }
+
+    #[test]
+    fn missing_file_is_not_an_error() {
+        let temp = tempfile::tempdir().unwrap();
+        assert!(RepoConfig::load(temp.path()).unwrap().is_none());
+    }
+}
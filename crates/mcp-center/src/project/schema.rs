@@ -0,0 +1,145 @@
+//! Per-project rewrites of a tool's advertised shape.
+//!
+//! A [`ToolCustomization`](super::ToolCustomization) can rename a tool, retitle it, patch
+//! its `inputSchema` with a JSON merge patch (RFC 7396) and hide individual parameters.
+//! Merge patches delete keys with `null`, which TOML cannot express, so records store the
+//! patch as JSON text (see [`json_text`]).
+
+use serde_json::{Map, Value};
+
+use super::arguments::strip_schema_properties;
+
+/// Longest tool name MCP clients reliably accept.
+pub const MAX_TOOL_NAME_LEN: usize = 64;
+
+/// Whether `name` can be advertised as a tool name: 1–64 ASCII letters, digits, `_` or `-`.
+pub fn is_valid_tool_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_TOOL_NAME_LEN
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Apply a JSON merge patch to `target` in place.
+pub fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+/// `schema` after the merge `patch` and with the `hidden` parameters removed.
+pub fn rewrite_input_schema(
+    schema: &Map<String, Value>,
+    patch: Option<&Value>,
+    hidden: &[String],
+) -> Map<String, Value> {
+    let mut schema = match patch {
+        Some(patch) => {
+            let mut value = Value::Object(schema.clone());
+            merge_patch(&mut value, patch);
+            match value {
+                Value::Object(schema) => schema,
+                _ => schema.clone(),
+            }
+        }
+        None => schema.clone(),
+    };
+    if !hidden.is_empty() {
+        schema = strip_schema_properties(&schema, hidden.iter().map(String::as_str));
+    }
+    schema
+}
+
+/// Parse a schema patch given as JSON text; it must be an object.
+pub fn parse_schema_patch(raw: &str) -> Result<Value, String> {
+    match serde_json::from_str(raw) {
+        Ok(Value::Object(patch)) => Ok(Value::Object(patch)),
+        Ok(_) => Err("schema patch must be a JSON object".to_string()),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Serde adapter storing an optional JSON value as JSON text.
+pub mod json_text {
+    use serde::{Deserialize, Deserializer, Serializer, de::Error as _};
+    use serde_json::Value;
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<Value>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_str(&value.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Value>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|text| serde_json::from_str(&text).map_err(D::Error::custom))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn merge_patch_follows_rfc_7396() {
+        let mut target = json!({ "a": "b", "c": { "d": "e", "f": "g" } });
+        merge_patch(&mut target, &json!({ "a": "z", "c": { "f": null } }));
+        assert_eq!(target, json!({ "a": "z", "c": { "d": "e" } }));
+
+        let mut target = json!({ "a": [1, 2] });
+        merge_patch(&mut target, &json!({ "a": [3], "b": { "c": null } }));
+        assert_eq!(target, json!({ "a": [3], "b": {} }));
+    }
+
+    #[test]
+    fn schema_rewrites_patch_then_hide() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "query": { "type": "string" },
+                "page": { "type": "integer" },
+                "debug": { "type": "boolean" },
+            },
+            "required": ["query", "debug"],
+        });
+        let patch = json!({ "properties": { "query": { "description": "Issue search" } } });
+        let rewritten =
+            rewrite_input_schema(schema.as_object().unwrap(), Some(&patch), &["debug".to_string()]);
+        assert_eq!(
+            Value::Object(rewritten),
+            json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Issue search" },
+                    "page": { "type": "integer" },
+                },
+                "required": ["query"],
+            })
+        );
+
+        assert!(parse_schema_patch("[1]").is_err());
+        assert!(is_valid_tool_name("issue_search-v2"));
+        assert!(!is_valid_tool_name("issue search"));
+    }
+}
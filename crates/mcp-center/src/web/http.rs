@@ -29,12 +29,24 @@ use url::form_urlencoded;
 
 use crate::{
     CoreError, Layout,
-    config::{ServerConfig, ServerDefinition, ServerProtocol},
+    config::{
+        ServerConfig, ServerDefinition, ServerProtocol, WorkspaceSettings,
+        settings::NewProjectPolicy,
+    },
     daemon::{
+        approvals::{ApprovalOutcome, ApprovalRequest},
         logging::{self, LogEntry},
+        management,
         server_manager::{ServerManager, ServerSnapshot},
     },
-    project::{ProjectId, ProjectRecord, ProjectRegistry, ToolCustomization, ToolPermission},
+    project::{
+        ProjectId, ProjectRecord, ProjectRegistry, ProjectStatus, ToolPermission,
+        annotations::{AnnotationOverride, AnnotationPolicy, AnnotationSwitch, ToolHints},
+        arguments::ArgumentConstraint,
+        grant::{self, TemporaryGrant},
+        policy::{DecisionReason, EffectivePolicy},
+        schema,
+    },
 };
 
 #[derive(Clone)]
@@ -180,18 +192,66 @@ pub struct ProjectSummary {
     pub display_name: Option<String>,
     pub agent: Option<String>,
     pub allowed_server_ids: Vec<String>,
+    /// Servers with tool-level permissions.
+    pub tool_permission_server_ids: Vec<String>,
+    pub status: ProjectStatus,
+    pub annotation_policy: AnnotationSwitches,
+    /// Project-wide approval requirement; `null` inherits from rules and parents.
+    pub require_approval: Option<bool>,
+    /// Tool search mode; `null` inherits from rules and parents.
+    pub tool_search: Option<bool>,
     pub created_at: u64,
     pub last_seen_at: u64,
 }
 
+/// Annotation switches of a project record; `null` inherits from rules and parents.
+#[derive(Serialize, Deserialize, Type, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnotationSwitches {
+    #[serde(default)]
+    pub read_only: Option<bool>,
+    #[serde(default)]
+    pub no_destructive: Option<bool>,
+    #[serde(default)]
+    pub no_open_world: Option<bool>,
+}
+
+impl From<AnnotationPolicy> for AnnotationSwitches {
+    fn from(policy: AnnotationPolicy) -> Self {
+        Self {
+            read_only: policy.read_only,
+            no_destructive: policy.no_destructive,
+            no_open_world: policy.no_open_world,
+        }
+    }
+}
+
+impl From<AnnotationSwitches> for AnnotationPolicy {
+    fn from(switches: AnnotationSwitches) -> Self {
+        Self {
+            read_only: switches.read_only,
+            no_destructive: switches.no_destructive,
+            no_open_world: switches.no_open_world,
+        }
+    }
+}
+
 impl From<ProjectRecord> for ProjectSummary {
     fn from(record: ProjectRecord) -> Self {
+        let mut tool_permission_server_ids =
+            record.allowed_server_tools.keys().cloned().collect::<Vec<_>>();
+        tool_permission_server_ids.sort();
         ProjectSummary {
             id: record.id,
             path: record.path.to_string_lossy().to_string(),
             display_name: record.display_name,
             agent: record.agent,
             allowed_server_ids: record.allowed_server_ids,
+            tool_permission_server_ids,
+            status: record.status,
+            annotation_policy: record.annotation_policy.into(),
+            require_approval: record.require_approval,
+            tool_search: record.tool_search,
             created_at: record.created_at,
             last_seen_at: record.last_seen_at,
         }
@@ -241,6 +301,9 @@ impl From<CoreError> for ApiError {
             CoreError::ProjectConfigNotFound { id } => {
                 ApiError::not_found(format!("project '{id}' not found"))
             }
+            CoreError::SettingsParse { .. } | CoreError::InvalidDuration { .. } => {
+                ApiError::bad_request(err.to_string())
+            }
             CoreError::ProjectRead { .. }
             | CoreError::ProjectParse { .. }
             | CoreError::ProjectSerialise { .. }
@@ -256,7 +319,14 @@ impl From<CoreError> for ApiError {
             CoreError::ServerNameEmpty { .. }
             | CoreError::UnsupportedProtocol { .. }
             | CoreError::ServerEndpointMissing { .. }
-            | CoreError::ServerEndpointInvalid { .. } => ApiError::bad_request(err.to_string()),
+            | CoreError::ServerEndpointInvalid { .. }
+            | CoreError::ScriptToolInvalid { .. }
+            | CoreError::ToolHookInvalid { .. }
+            | CoreError::CacheConfigInvalid { .. }
+            | CoreError::OutputLimitsInvalid { .. }
+            | CoreError::ProjectApprovalUnrestricted { .. } => {
+                ApiError::bad_request(err.to_string())
+            }
             other => ApiError::internal(other.to_string()),
         }
     }
@@ -394,10 +464,23 @@ pub fn build_router(state: HttpState) -> Router {
         .route("/api/project", get(list_projects))
         .route("/api/project/allow", post(project_allow))
         .route("/api/project/deny", post(project_deny))
+        .route("/api/project/approve", post(project_approve))
+        .route("/api/project/:id/effective", get(get_project_effective))
+        .route("/api/project/:id/grants", get(get_project_grants))
+        .route("/api/project/grant", post(project_grant))
         .route("/api/project/tools/allow", post(project_allow_tools))
         .route("/api/project/tools/deny", post(project_deny_tools))
         .route("/api/project/tool/description", post(project_set_tool_desc))
         .route("/api/project/tool/description/reset", post(project_reset_tool_desc))
+        .route("/api/project/tool/alias", post(project_set_tool_alias))
+        .route("/api/project/annotation-policy", post(project_set_annotation_policy))
+        .route("/api/project/tool/hints", post(project_set_tool_hints))
+        .route("/api/project/tool/arguments", post(project_set_tool_arguments))
+        .route("/api/project/require-approval", post(project_require_approval))
+        .route("/api/project/tool-search", post(project_tool_search))
+        .route("/api/approvals", get(list_approvals).post(resolve_approval))
+        .route("/api/approvals/stream", get(stream_approvals))
+        .route("/api/settings", get(get_settings).patch(update_settings))
         .route("/api/logs/servers", get(list_server_logs))
         .route("/api/logs/entries", get(get_log_entries))
         .route("/api/logs/tail/:server_id", get(tail_server_logs))
@@ -438,10 +521,10 @@ async fn list_server_logs(
             {
                 let file_type =
                     entry.file_type().await.map_err(|err| ApiError::internal(err.to_string()))?;
-                if file_type.is_dir() {
-                    if let Some(name) = entry.file_name().to_str() {
-                        server_ids.insert(name.to_string());
-                    }
+                if file_type.is_dir()
+                    && let Some(name) = entry.file_name().to_str()
+                {
+                    server_ids.insert(name.to_string());
                 }
             }
         }
@@ -475,10 +558,10 @@ async fn get_log_entries(
     State(state): State<HttpState>,
     Query(query): Query<LogEntriesQuery>,
 ) -> Result<Json<LogEntriesResponse>, ApiError> {
-    if let Some(file) = &query.file {
-        if file.contains('/') || file.contains('\\') {
-            return Err(ApiError::bad_request("invalid file name"));
-        }
+    if let Some(file) = &query.file
+        && (file.contains('/') || file.contains('\\'))
+    {
+        return Err(ApiError::bad_request("invalid file name"));
     }
 
     let files = logging::list_server_log_files(&state.layout, &query.server_id)
@@ -547,6 +630,65 @@ async fn tail_server_logs(
         .keep_alive(KeepAlive::new().interval(Duration::from_secs(15)).text("keep-alive")))
 }
 
+#[derive(Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ApprovalListResponse {
+    pub approvals: Vec<ApprovalRequest>,
+}
+
+#[derive(Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveApprovalRequest {
+    pub id: u64,
+    pub approve: bool,
+    /// Returned to the agent when the call is denied.
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+async fn list_approvals(State(state): State<HttpState>) -> Json<ApprovalListResponse> {
+    Json(ApprovalListResponse { approvals: state.manager.approvals().list() })
+}
+
+async fn resolve_approval(
+    State(state): State<HttpState>,
+    Json(body): Json<ResolveApprovalRequest>,
+) -> Result<Json<ApprovalRequest>, ApiError> {
+    let outcome = if body.approve {
+        ApprovalOutcome::Approved
+    } else {
+        let reason = body.reason.map(|reason| reason.trim().to_string());
+        ApprovalOutcome::Denied { reason: reason.filter(|reason| !reason.is_empty()) }
+    };
+    state
+        .manager
+        .approvals()
+        .resolve(body.id, outcome)
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("no pending approval with id {}", body.id)))
+}
+
+async fn stream_approvals(
+    State(state): State<HttpState>,
+) -> Sse<impl futures_core::stream::Stream<Item = Result<Event, Infallible>>> {
+    let stream = state.manager.approvals().subscribe().filter_map(|item| match item {
+        Ok(event) => match serde_json::to_string(&event) {
+            Ok(payload) => Some(Ok(Event::default().data(payload))),
+            Err(err) => {
+                warn!(error = ?err, "failed to serialise approval event for SSE");
+                None
+            }
+        },
+        Err(err) => {
+            warn!(error = ?err, "approval event stream lagged");
+            None
+        }
+    });
+
+    Sse::new(stream)
+        .keep_alive(KeepAlive::new().interval(Duration::from_secs(15)).text("keep-alive"))
+}
+
 pub async fn spawn_http_server(state: HttpState, addr: SocketAddr) -> Result<HttpServerHandle> {
     let router = build_router(state);
     let listener = TcpListener::bind(addr).await?;
@@ -625,15 +767,12 @@ async fn create_mcp(
     ensure_unique_server_name_from_configs(&configs, display_name, None)?;
 
     let mut definition = ServerDefinition {
-        id: String::new(),
         name: Some(display_name.to_string()),
         protocol: protocol.clone(),
-        command: String::new(),
-        args: Vec::new(),
         env: env.unwrap_or_default(),
-        endpoint: None,
         headers: headers.unwrap_or_default(),
         enabled: false,
+        ..Default::default()
     };
 
     match protocol {
@@ -657,6 +796,11 @@ async fn create_mcp(
                 .ok_or_else(|| ApiError::bad_request("endpoint is required for remote servers"))?;
             definition.endpoint = Some(endpoint.to_string());
         }
+        ServerProtocol::Script => {
+            return Err(ApiError::bad_request(
+                "script servers declare their tools in a config file",
+            ));
+        }
         ServerProtocol::Unknown => {
             return Err(ApiError::bad_request("unsupported server protocol"));
         }
@@ -682,6 +826,7 @@ async fn create_mcp(
     fs::write(&destination, toml)
         .await
         .map_err(|err| ApiError::internal(format!("failed to write server config: {err}")))?;
+    state.registry.notify_changed();
 
     // 获取刚创建的配置文件的时间戳
     let created_at = server_config_timestamp(Some(&destination)).await;
@@ -752,6 +897,7 @@ async fn delete_mcp(
     }
 
     state.layout.remove_server_config(&definition.id).map_err(ApiError::from)?;
+    state.registry.notify_changed();
 
     Ok(StatusCode::NO_CONTENT)
 }
@@ -769,15 +915,13 @@ fn ensure_unique_server_name_from_configs(
             .map(|value| value.trim())
             .filter(|value| !value.is_empty());
 
-        if let Some(existing) = existing {
-            if existing == candidate {
-                if skip_id.map(|id| id == config.definition().id).unwrap_or(false) {
-                    continue;
-                }
-                return Err(ApiError::bad_request(format!(
-                    "server name '{candidate}' already exists"
-                )));
+        if let Some(existing) = existing
+            && existing == candidate
+        {
+            if skip_id.map(|id| id == config.definition().id).unwrap_or(false) {
+                continue;
             }
+            return Err(ApiError::bad_request(format!("server name '{candidate}' already exists")));
         }
     }
     Ok(())
@@ -855,27 +999,11 @@ async fn update_mcp_enabled(
     Path(id): Path<String>,
     Json(body): Json<UpdateMcpEnabled>,
 ) -> Result<Json<ServerToggleResponse>, ApiError> {
-    let mut config = state.layout.load_server_config(&id).map_err(ApiError::from)?;
-    config.definition_mut().enabled = body.enabled;
-    let path = config
-        .source()
-        .ok_or_else(|| ApiError::internal("server config path is unknown"))?
-        .to_path_buf();
-    let toml = config.to_toml_string().map_err(ApiError::from)?;
-    fs::write(&path, toml)
+    let (config, warning) = state
+        .manager
+        .set_server_enabled(&id, body.enabled)
         .await
-        .map_err(|err| ApiError::internal(format!("failed to write server config: {err}")))?;
-
-    let mut warning = None;
-    if body.enabled {
-        if let Err(err) = state.manager.ensure_server_running(&id).await {
-            warn!(server_id = %id, error = ?err, "failed to start MCP server after enabling");
-            warning = Some(format!("failed to start MCP server: {err}"));
-        }
-    } else if let Err(err) = state.manager.disable_server(&id).await {
-        warn!(server_id = %id, error = ?err, "failed to stop MCP server after disabling");
-        warning = Some(format!("failed to stop MCP server: {err}"));
-    }
+        .map_err(ApiError::from)?;
 
     let tool_count = state.manager.tool_count_for(&id).await.unwrap_or_default();
     let created_at = server_config_timestamp(config.source()).await;
@@ -963,6 +1091,200 @@ async fn project_deny(
     Ok(Json(ProjectSummary::from(record)))
 }
 
+#[derive(Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectApproveRequest {
+    pub target: String,
+    #[serde(default)]
+    pub servers: Vec<String>,
+    /// Approve without granting servers, allowing every server.
+    #[serde(default)]
+    pub all: bool,
+}
+
+async fn project_approve(
+    State(state): State<HttpState>,
+    Json(body): Json<ProjectApproveRequest>,
+) -> Result<Json<ProjectSummary>, ApiError> {
+    state.registry.ensure().map_err(ApiError::from)?;
+
+    let (mut record, _) = load_existing_project_with_path(&state.registry, &body.target)?;
+
+    let servers: BTreeSet<String> = body
+        .servers
+        .iter()
+        .map(|server| server.trim().to_string())
+        .filter(|server| !server.is_empty())
+        .collect();
+    if !servers.is_empty() {
+        let configs = state.layout.list_server_configs().map_err(ApiError::from)?;
+        if let Some(unknown) =
+            servers.iter().find(|id| !configs.iter().any(|cfg| &cfg.definition().id == *id))
+        {
+            return Err(ApiError::bad_request(format!("unknown server id '{unknown}'")));
+        }
+        record.allowed_server_ids = servers.into_iter().collect();
+    }
+
+    record.approve(body.all).map_err(ApiError::from)?;
+    record.touch();
+    state.registry.store(&record).map_err(ApiError::from)?;
+
+    Ok(Json(ProjectSummary::from(record)))
+}
+
+/// What decided a tool's visibility; `source` on [`EffectiveToolSummary`] names the layer.
+#[derive(Serialize, Type, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EffectiveReason {
+    TemporaryGrant,
+    ToolPermission,
+    ServerList,
+    RepoConfig,
+    Annotation,
+    PendingApproval,
+    Unrestricted,
+}
+
+#[derive(Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveToolSummary {
+    pub name: String,
+    pub server_id: String,
+    pub server_name: String,
+    pub allowed: bool,
+    pub reason: EffectiveReason,
+    pub source: Option<String>,
+    /// Set when a temporary grant allowed the tool.
+    pub expires_at: Option<u64>,
+    /// Set when an annotation switch hid the tool.
+    pub hidden_by: Option<AnnotationSwitch>,
+    /// Annotation hints after per-tool overrides.
+    pub hints: ToolHints,
+    pub description: Option<String>,
+    pub description_source: Option<String>,
+    /// Name advertised to agents instead of `name`.
+    pub alias: Option<String>,
+}
+
+/// An enabled annotation switch and the tools it hides.
+#[derive(Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveSwitchSummary {
+    pub switch: AnnotationSwitch,
+    pub source: String,
+    pub hidden: Vec<String>,
+}
+
+#[derive(Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectEffectiveResponse {
+    pub project: ProjectSummary,
+    pub tools: Vec<EffectiveToolSummary>,
+    pub switches: Vec<EffectiveSwitchSummary>,
+    pub warnings: Vec<String>,
+}
+
+async fn get_project_effective(
+    State(state): State<HttpState>,
+    Path(id): Path<String>,
+) -> Result<Json<ProjectEffectiveResponse>, ApiError> {
+    state.registry.ensure().map_err(ApiError::from)?;
+    let (record, path) = load_existing_project_with_path(&state.registry, &id)?;
+
+    let settings = load_settings(&state.layout)?;
+    let definitions = state
+        .layout
+        .list_server_configs()
+        .map_err(ApiError::from)?
+        .into_iter()
+        .map(|cfg| cfg.definition().clone())
+        .collect::<Vec<_>>();
+    let policy = EffectivePolicy::resolve(&state.registry, &settings.project_rules, &path)
+        .with_repo_config(&definitions);
+
+    let mut entries = state.manager.list_tools().await.map_err(ApiError::from)?;
+    // The built-in management server only exists for sessions while it is enabled
+    entries.retain(|entry| settings.management.enabled || entry.server_id != management::SERVER_ID);
+    let server_names = entries
+        .iter()
+        .map(|entry| (entry.server_id.clone(), entry.server_name.clone()))
+        .collect::<HashMap<_, _>>();
+    let tools = policy
+        .simulate(entries.into_iter().map(|entry| {
+            let hints = ToolHints::from_annotations(entry.tool.annotations.as_ref());
+            let description = entry.tool.description.as_ref().map(|desc| desc.to_string());
+            (entry.server_id, entry.tool.name.into_owned(), description, hints)
+        }))
+        .into_iter()
+        .map(|tool| {
+            let expires_at = match &tool.decision.reason {
+                DecisionReason::TemporaryGrant { expires_at, .. } => Some(*expires_at),
+                _ => None,
+            };
+            let hidden_by = match &tool.decision.reason {
+                DecisionReason::Annotation { switch, .. } => Some(*switch),
+                _ => None,
+            };
+            let (reason, source) = match tool.decision.reason {
+                DecisionReason::TemporaryGrant { source, .. } => {
+                    (EffectiveReason::TemporaryGrant, Some(source.to_string()))
+                }
+                DecisionReason::ToolPermission { source } => {
+                    (EffectiveReason::ToolPermission, Some(source.to_string()))
+                }
+                DecisionReason::ServerList { source } => {
+                    (EffectiveReason::ServerList, Some(source.to_string()))
+                }
+                DecisionReason::RepoConfig { path } => {
+                    (EffectiveReason::RepoConfig, Some(path.display().to_string()))
+                }
+                DecisionReason::Annotation { source, .. } => {
+                    (EffectiveReason::Annotation, Some(source.to_string()))
+                }
+                DecisionReason::PendingApproval => (EffectiveReason::PendingApproval, None),
+                DecisionReason::Unrestricted => (EffectiveReason::Unrestricted, None),
+            };
+            EffectiveToolSummary {
+                server_name: server_names.get(&tool.server_id).cloned().unwrap_or_default(),
+                name: tool.name,
+                server_id: tool.server_id,
+                allowed: tool.decision.allowed,
+                reason,
+                source,
+                expires_at,
+                hidden_by,
+                hints: tool.hints,
+                description: tool.description,
+                description_source: tool.description_source.map(|source| source.to_string()),
+                alias: tool.alias,
+            }
+        })
+        .collect::<Vec<EffectiveToolSummary>>();
+    let switches = AnnotationSwitch::ALL
+        .into_iter()
+        .filter_map(|switch| {
+            let setting = policy.annotation_switch(switch).filter(|setting| *setting.value)?;
+            Some(EffectiveSwitchSummary {
+                switch,
+                source: setting.source.to_string(),
+                hidden: tools
+                    .iter()
+                    .filter(|tool| tool.hidden_by == Some(switch))
+                    .map(|tool| tool.name.clone())
+                    .collect(),
+            })
+        })
+        .collect();
+
+    Ok(Json(ProjectEffectiveResponse {
+        project: ProjectSummary::from(record),
+        tools,
+        switches,
+        warnings: policy.repo_warnings.iter().map(ToString::to_string).collect(),
+    }))
+}
+
 #[derive(Deserialize, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct ProjectToolsRequest {
@@ -996,6 +1318,108 @@ async fn project_allow_tools(
     Ok(Json(ProjectSummary::from(record)))
 }
 
+/// Temporary grant: whole `servers` and/or `server::tool` specs for `duration` (e.g. `2h`).
+#[derive(Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectGrantRequest {
+    pub target: String,
+    #[serde(default)]
+    pub servers: Vec<String>,
+    #[serde(default)]
+    pub tools: Vec<String>,
+    pub duration: String,
+}
+
+#[derive(Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct GrantSummary {
+    pub server_id: String,
+    pub tools: Vec<String>,
+    pub granted_at: u64,
+    pub expires_at: u64,
+    pub active: bool,
+}
+
+#[derive(Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectGrantsResponse {
+    pub project: ProjectSummary,
+    pub grants: Vec<GrantSummary>,
+}
+
+impl From<ProjectRecord> for ProjectGrantsResponse {
+    fn from(record: ProjectRecord) -> Self {
+        let now = grant::now();
+        let mut grants = record
+            .grants
+            .iter()
+            .map(|grant| GrantSummary {
+                server_id: grant.server_id.clone(),
+                tools: grant.tools.clone(),
+                granted_at: grant.granted_at,
+                expires_at: grant.expires_at,
+                active: grant.is_active_at(now),
+            })
+            .collect::<Vec<_>>();
+        grants.sort_by_key(|grant| std::cmp::Reverse(grant.expires_at));
+        ProjectGrantsResponse { project: ProjectSummary::from(record), grants }
+    }
+}
+
+async fn project_grant(
+    State(state): State<HttpState>,
+    Json(body): Json<ProjectGrantRequest>,
+) -> Result<Json<ProjectGrantsResponse>, ApiError> {
+    state.registry.ensure().map_err(ApiError::from)?;
+
+    let (mut record, _) = load_existing_project_with_path(&state.registry, &body.target)?;
+    let duration = grant::parse_duration(&body.duration).map_err(ApiError::from)?;
+
+    // Whole servers (empty tool list) plus individual tools of other servers
+    let whole: BTreeSet<String> = body
+        .servers
+        .iter()
+        .map(|server| server.trim().to_string())
+        .filter(|server| !server.is_empty())
+        .collect();
+    let mut server_tools: BTreeMap<String, Vec<String>> =
+        whole.iter().map(|server| (server.clone(), Vec::new())).collect();
+    for spec in &body.tools {
+        let (server, tool) = parse_tool_spec(spec)?;
+        if !whole.contains(&server) {
+            server_tools.entry(server).or_default().push(tool);
+        }
+    }
+    if server_tools.is_empty() {
+        return Err(ApiError::bad_request("no servers or tools to grant"));
+    }
+
+    let configs = state.layout.list_server_configs().map_err(ApiError::from)?;
+    if let Some(unknown) = server_tools
+        .keys()
+        .find(|id| !configs.iter().any(|cfg| &cfg.definition().id == *id))
+    {
+        return Err(ApiError::bad_request(format!("unknown server id '{unknown}'")));
+    }
+
+    for (server, tools) in server_tools {
+        record.grants.push(TemporaryGrant::new(server, tools, duration));
+    }
+    record.touch();
+    state.registry.store(&record).map_err(ApiError::from)?;
+
+    Ok(Json(ProjectGrantsResponse::from(record)))
+}
+
+async fn get_project_grants(
+    State(state): State<HttpState>,
+    Path(id): Path<String>,
+) -> Result<Json<ProjectGrantsResponse>, ApiError> {
+    state.registry.ensure().map_err(ApiError::from)?;
+    let (record, _) = load_existing_project_with_path(&state.registry, &id)?;
+    Ok(Json(ProjectGrantsResponse::from(record)))
+}
+
 async fn project_deny_tools(
     State(state): State<HttpState>,
     Json(body): Json<ProjectToolsRequest>,
@@ -1037,11 +1461,7 @@ async fn project_set_tool_desc(
     state.registry.ensure().map_err(ApiError::from)?;
 
     let (mut record, _) = load_existing_project_with_path(&state.registry, &body.target)?;
-    record.tool_customizations.retain(|c| c.tool_name != body.tool);
-    record.tool_customizations.push(ToolCustomization {
-        tool_name: body.tool.clone(),
-        description: Some(body.description.clone()),
-    });
+    record.customization_mut(&body.tool).description = Some(body.description.clone());
     record.touch();
     state.registry.store(&record).map_err(ApiError::from)?;
 
@@ -1062,9 +1482,7 @@ async fn project_reset_tool_desc(
     state.registry.ensure().map_err(ApiError::from)?;
 
     let (mut record, _) = load_existing_project_with_path(&state.registry, &body.target)?;
-    let before = record.tool_customizations.len();
-    record.tool_customizations.retain(|c| c.tool_name != body.tool);
-    if before == record.tool_customizations.len() {
+    if !record.reset_tool_description(&body.tool) {
         return Err(ApiError::not_found(format!("tool customization not found for {}", body.tool)));
     }
     record.touch();
@@ -1073,6 +1491,293 @@ async fn project_reset_tool_desc(
     Ok(Json(ProjectSummary::from(record)))
 }
 
+/// Replace the alias, title, schema patch and hidden parameters of one tool; `null` and
+/// empty values remove them.
+#[derive(Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectToolAliasRequest {
+    pub target: String,
+    pub tool: String,
+    #[serde(default)]
+    pub alias: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    /// JSON merge patch for the tool's `inputSchema`, as JSON text.
+    #[serde(default)]
+    pub schema_patch: Option<String>,
+    #[serde(default)]
+    pub hidden_parameters: Vec<String>,
+}
+
+async fn project_set_tool_alias(
+    State(state): State<HttpState>,
+    Json(body): Json<ProjectToolAliasRequest>,
+) -> Result<Json<ProjectSummary>, ApiError> {
+    state.registry.ensure().map_err(ApiError::from)?;
+
+    let alias = body.alias.filter(|alias| !alias.is_empty() && *alias != body.tool);
+    if let Some(alias) = &alias
+        && !schema::is_valid_tool_name(alias)
+    {
+        return Err(ApiError::bad_request(format!("'{alias}' is not a valid tool name")));
+    }
+    let schema_patch = body
+        .schema_patch
+        .filter(|patch| !patch.trim().is_empty())
+        .map(|patch| schema::parse_schema_patch(&patch))
+        .transpose()
+        .map_err(|err| ApiError::bad_request(format!("invalid schema patch: {err}")))?;
+
+    let (mut record, _) = load_existing_project_with_path(&state.registry, &body.target)?;
+    if let Some(alias) = &alias
+        && let Some(other) = record.tool_customizations.iter().find(|c| {
+            c.tool_name != body.tool && (c.alias.as_ref() == Some(alias) || c.tool_name == *alias)
+        })
+    {
+        return Err(ApiError::bad_request(format!(
+            "'{alias}' is already used by tool {}",
+            other.tool_name
+        )));
+    }
+    let customization = record.customization_mut(&body.tool);
+    customization.alias = alias;
+    customization.title = body.title.filter(|title| !title.is_empty());
+    customization.schema_patch = schema_patch;
+    customization.hidden_parameters = body.hidden_parameters;
+    record.tool_customizations.retain(|c| !c.is_empty());
+    record.touch();
+    state.registry.store(&record).map_err(ApiError::from)?;
+
+    Ok(Json(ProjectSummary::from(record)))
+}
+
+#[derive(Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectAnnotationPolicyRequest {
+    pub target: String,
+    /// Replaces all three switches of the project record.
+    pub switches: AnnotationSwitches,
+}
+
+async fn project_set_annotation_policy(
+    State(state): State<HttpState>,
+    Json(body): Json<ProjectAnnotationPolicyRequest>,
+) -> Result<Json<ProjectSummary>, ApiError> {
+    state.registry.ensure().map_err(ApiError::from)?;
+
+    let (mut record, _) = load_existing_project_with_path(&state.registry, &body.target)?;
+    record.annotation_policy = body.switches.into();
+    record.touch();
+    state.registry.store(&record).map_err(ApiError::from)?;
+
+    Ok(Json(ProjectSummary::from(record)))
+}
+
+/// Replace the argument constraints of one tool; an empty list removes them.
+#[derive(Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectToolArgumentsRequest {
+    pub target: String,
+    pub tool: String,
+    #[serde(default)]
+    pub arguments: Vec<ArgumentConstraint>,
+}
+
+async fn project_set_tool_arguments(
+    State(state): State<HttpState>,
+    Json(body): Json<ProjectToolArgumentsRequest>,
+) -> Result<Json<ProjectSummary>, ApiError> {
+    state.registry.ensure().map_err(ApiError::from)?;
+
+    for constraint in &body.arguments {
+        if !constraint.pointer.starts_with('/') {
+            return Err(ApiError::bad_request(format!(
+                "'{}' is not a JSON pointer",
+                constraint.pointer
+            )));
+        }
+    }
+
+    let (mut record, _) = load_existing_project_with_path(&state.registry, &body.target)?;
+    record.customization_mut(&body.tool).arguments =
+        body.arguments.into_iter().filter(|constraint| !constraint.is_empty()).collect();
+    record.tool_customizations.retain(|c| !c.is_empty());
+    record.touch();
+    state.registry.store(&record).map_err(ApiError::from)?;
+
+    Ok(Json(ProjectSummary::from(record)))
+}
+
+/// Set the approval requirement of a project, or of one tool when `tool` is given.
+#[derive(Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectRequireApprovalRequest {
+    pub target: String,
+    #[serde(default)]
+    pub tool: Option<String>,
+    /// `null` inherits from rules and parent projects.
+    #[serde(default)]
+    pub required: Option<bool>,
+}
+
+async fn project_require_approval(
+    State(state): State<HttpState>,
+    Json(body): Json<ProjectRequireApprovalRequest>,
+) -> Result<Json<ProjectSummary>, ApiError> {
+    state.registry.ensure().map_err(ApiError::from)?;
+
+    let (mut record, _) = load_existing_project_with_path(&state.registry, &body.target)?;
+    match body.tool.as_deref().map(str::trim) {
+        Some("") => return Err(ApiError::bad_request("tool must not be empty")),
+        Some(tool) => {
+            record.customization_mut(tool).require_approval = body.required;
+            record.tool_customizations.retain(|c| !c.is_empty());
+        }
+        None => record.require_approval = body.required,
+    }
+    record.touch();
+    state.registry.store(&record).map_err(ApiError::from)?;
+
+    Ok(Json(ProjectSummary::from(record)))
+}
+
+/// Switch a project between the full tool list and the search meta-tools.
+#[derive(Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectToolSearchRequest {
+    pub target: String,
+    /// `null` inherits from rules and parent projects.
+    #[serde(default)]
+    pub enabled: Option<bool>,
+}
+
+async fn project_tool_search(
+    State(state): State<HttpState>,
+    Json(body): Json<ProjectToolSearchRequest>,
+) -> Result<Json<ProjectSummary>, ApiError> {
+    state.registry.ensure().map_err(ApiError::from)?;
+
+    let (mut record, _) = load_existing_project_with_path(&state.registry, &body.target)?;
+    record.tool_search = body.enabled;
+    record.touch();
+    state.registry.store(&record).map_err(ApiError::from)?;
+
+    Ok(Json(ProjectSummary::from(record)))
+}
+
+/// Override the annotation hints of one tool; all `null` removes the override.
+#[derive(Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectToolHintsRequest {
+    pub target: String,
+    pub tool: String,
+    #[serde(default)]
+    pub read_only: Option<bool>,
+    #[serde(default)]
+    pub destructive: Option<bool>,
+    #[serde(default)]
+    pub open_world: Option<bool>,
+}
+
+async fn project_set_tool_hints(
+    State(state): State<HttpState>,
+    Json(body): Json<ProjectToolHintsRequest>,
+) -> Result<Json<ProjectSummary>, ApiError> {
+    state.registry.ensure().map_err(ApiError::from)?;
+
+    let (mut record, _) = load_existing_project_with_path(&state.registry, &body.target)?;
+    let patch = AnnotationOverride {
+        read_only: body.read_only,
+        destructive: body.destructive,
+        open_world: body.open_world,
+    };
+    record.customization_mut(&body.tool).annotations = (!patch.is_empty()).then_some(patch);
+    record.tool_customizations.retain(|c| !c.is_empty());
+    record.touch();
+    state.registry.store(&record).map_err(ApiError::from)?;
+
+    Ok(Json(ProjectSummary::from(record)))
+}
+
+#[derive(Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsResponse {
+    pub new_project_policy: NewProjectPolicy,
+    pub new_project_template: Option<String>,
+    pub permission_templates: Vec<String>,
+    pub approval_timeout_secs: u64,
+    pub management_server: bool,
+}
+
+impl From<&WorkspaceSettings> for SettingsResponse {
+    fn from(settings: &WorkspaceSettings) -> Self {
+        SettingsResponse {
+            new_project_policy: settings.new_projects.policy,
+            new_project_template: settings.new_projects.template.clone(),
+            permission_templates: settings.permission_templates.keys().cloned().collect(),
+            approval_timeout_secs: settings.approvals.timeout_secs,
+            management_server: settings.management.enabled,
+        }
+    }
+}
+
+#[derive(Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateSettingsRequest {
+    pub new_project_policy: Option<NewProjectPolicy>,
+    pub new_project_template: Option<String>,
+    pub approval_timeout_secs: Option<u64>,
+    pub management_server: Option<bool>,
+}
+
+async fn get_settings(State(state): State<HttpState>) -> Result<Json<SettingsResponse>, ApiError> {
+    let settings = load_settings(&state.layout)?;
+    Ok(Json(SettingsResponse::from(&settings)))
+}
+
+async fn update_settings(
+    State(state): State<HttpState>,
+    Json(body): Json<UpdateSettingsRequest>,
+) -> Result<Json<SettingsResponse>, ApiError> {
+    let mut settings = load_settings(&state.layout)?;
+    if let Some(policy) = body.new_project_policy {
+        settings.new_projects.policy = policy;
+    }
+    if let Some(template) = body.new_project_template {
+        let template = template.trim().to_string();
+        settings.new_projects.template = (!template.is_empty()).then_some(template);
+    }
+    if let Some(timeout) = body.approval_timeout_secs {
+        if timeout == 0 {
+            return Err(ApiError::bad_request("approvalTimeoutSecs must be positive"));
+        }
+        settings.approvals.timeout_secs = timeout;
+    }
+    if let Some(enabled) = body.management_server {
+        settings.management.enabled = enabled;
+    }
+    if settings.new_projects.policy == NewProjectPolicy::Template {
+        match settings.new_projects.template.as_deref() {
+            Some(name) if settings.permission_templates.contains_key(name) => {}
+            Some(name) => {
+                return Err(ApiError::bad_request(format!("unknown permission template '{name}'")));
+            }
+            None => {
+                return Err(ApiError::bad_request(
+                    "newProjectTemplate is required when newProjectPolicy is 'template'",
+                ));
+            }
+        }
+    }
+    settings.store(&state.layout).map_err(ApiError::from)?;
+    state.registry.notify_changed();
+    Ok(Json(SettingsResponse::from(&settings)))
+}
+
+fn load_settings(layout: &Layout) -> Result<WorkspaceSettings, ApiError> {
+    WorkspaceSettings::load(layout).map_err(ApiError::from)
+}
+
 fn load_or_create_project(
     registry: &ProjectRegistry,
     target: &str,
@@ -1096,10 +1801,10 @@ fn load_existing_project_with_path(
     registry: &ProjectRegistry,
     target: &str,
 ) -> Result<(ProjectRecord, PathBuf), ApiError> {
-    if let Ok(path) = normalize_project_path(target) {
-        if let Some(record) = registry.find_by_path(&path).map_err(ApiError::from)? {
-            return Ok((record, path));
-        }
+    if let Ok(path) = normalize_project_path(target)
+        && let Some(record) = registry.find_by_path(&path).map_err(ApiError::from)?
+    {
+        return Ok((record, path));
     }
     let record =
         registry
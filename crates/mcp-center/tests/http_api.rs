@@ -1,4 +1,4 @@
-use std::{collections::BTreeMap, fs, sync::Arc};
+use std::{fs, sync::Arc, time::Duration};
 
 use axum::{
     Router,
@@ -6,10 +6,10 @@ use axum::{
     http::{Method, Request, StatusCode},
 };
 use mcp_center::{
-    Layout,
-    config::{ServerConfig, ServerDefinition, ServerProtocol},
-    daemon::server_manager::ServerManager,
-    project::ProjectRegistry,
+    Layout, ProjectId, ProjectRecord,
+    config::{ServerConfig, ServerDefinition, WorkspaceSettings},
+    daemon::{approvals::ApprovalOutcome, server_manager::ServerManager},
+    project::{ProjectRegistry, ProjectStatus},
     web::http::{self, HttpState},
 };
 use serde_json::{Value, json};
@@ -31,17 +31,33 @@ async fn make_router(layout: Layout) -> Router {
     http::build_router(state)
 }
 
+async fn send_json(
+    router: &Router,
+    method: Method,
+    uri: &str,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("content-type", "application/json")
+        .header("authorization", "Bearer secret")
+        .header("x-mcp-client", "web")
+        .body(body.map(|value| Body::from(value.to_string())).unwrap_or_else(Body::empty))
+        .unwrap();
+    let response = router.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+}
+
 fn write_server_config(layout: &Layout, id: &str, name: &str, enabled: bool) {
     let definition = ServerDefinition {
         id: id.to_string(),
         name: Some(name.to_string()),
-        protocol: ServerProtocol::StdIo,
         command: "echo".to_string(),
-        args: Vec::new(),
-        env: BTreeMap::new(),
-        endpoint: None,
-        headers: BTreeMap::new(),
         enabled,
+        ..Default::default()
     };
     let config = ServerConfig::new(definition).unwrap();
     let toml = config.to_toml_string().unwrap();
@@ -257,3 +273,568 @@ fn http_api_requires_auth_token() {
         assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
     });
 }
+
+#[test]
+fn http_api_new_project_policy_and_approval() {
+    test_runtime().block_on(async {
+        let tmp = tempdir().unwrap();
+        let layout = Layout::new(tmp.path().to_path_buf());
+        layout.ensure().unwrap();
+        write_server_config(&layout, "demo", "Demo", false);
+        let router = make_router(layout.clone()).await;
+
+        let (status, settings) = send_json(&router, Method::GET, "/api/settings", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(settings["newProjectPolicy"], json!("deny_all"));
+
+        let (status, _) = send_json(
+            &router,
+            Method::PATCH,
+            "/api/settings",
+            Some(json!({ "newProjectPolicy": "template", "newProjectTemplate": "missing" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, settings) = send_json(
+            &router,
+            Method::PATCH,
+            "/api/settings",
+            Some(json!({ "newProjectPolicy": "allow_all" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(settings["newProjectPolicy"], json!("allow_all"));
+        assert_eq!(
+            WorkspaceSettings::load(&layout).unwrap().new_projects.policy,
+            mcp_center::config::settings::NewProjectPolicy::AllowAll
+        );
+
+        // 模拟守护进程首次见到的项目
+        let project_dir = fs::canonicalize(tmp.path()).unwrap().join("workspace");
+        fs::create_dir_all(&project_dir).unwrap();
+        let registry = ProjectRegistry::new(&layout);
+        let mut record = ProjectRecord::new(ProjectId::from_path(&project_dir), project_dir);
+        record.status = ProjectStatus::Pending;
+        registry.store(&record).unwrap();
+
+        let (_, projects) = send_json(&router, Method::GET, "/api/project", None).await;
+        assert_eq!(projects["projects"][0]["status"], json!("pending"));
+
+        let (status, summary) = send_json(
+            &router,
+            Method::POST,
+            "/api/project/approve",
+            Some(json!({ "target": record.id, "servers": ["demo"] })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(summary["status"], json!("active"));
+        assert_eq!(summary["allowedServerIds"], json!(["demo"]));
+    });
+}
+
+#[test]
+fn http_api_project_effective_reports_tools_and_warnings() {
+    test_runtime().block_on(async {
+        let tmp = tempdir().unwrap();
+        let layout = Layout::new(tmp.path().to_path_buf());
+        layout.ensure().unwrap();
+        let router = make_router(layout.clone()).await;
+
+        let project_dir = fs::canonicalize(tmp.path()).unwrap().join("workspace");
+        fs::create_dir_all(&project_dir).unwrap();
+        fs::write(project_dir.join(".mcp-center.toml"), "servers = [\"jira\"]\n").unwrap();
+        let registry = ProjectRegistry::new(&layout);
+        let record = ProjectRecord::new(ProjectId::from_path(&project_dir), project_dir);
+        registry.store(&record).unwrap();
+
+        let (status, body) =
+            send_json(&router, Method::GET, &format!("/api/project/{}/effective", record.id), None)
+                .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["project"]["id"], json!(record.id));
+        assert_eq!(body["tools"], json!([]));
+        assert_eq!(
+            body["warnings"],
+            json!([".mcp-center.toml references MCP server 'jira' which is not installed"])
+        );
+
+        let (status, _) =
+            send_json(&router, Method::GET, "/api/project/unknown/effective", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    });
+}
+
+#[test]
+fn http_api_management_server_is_offered_once_enabled() {
+    test_runtime().block_on(async {
+        let tmp = tempdir().unwrap();
+        let layout = Layout::new(tmp.path().to_path_buf());
+        layout.ensure().unwrap();
+        let router = make_router(layout.clone()).await;
+
+        let project_dir = fs::canonicalize(tmp.path()).unwrap().join("workspace");
+        fs::create_dir_all(&project_dir).unwrap();
+        let registry = ProjectRegistry::new(&layout);
+        let mut record = ProjectRecord::new(ProjectId::from_path(&project_dir), project_dir);
+        record.allowed_server_ids = vec!["mcp-center".into()];
+        registry.store(&record).unwrap();
+        let effective = format!("/api/project/{}/effective", record.id);
+
+        let (status, body) = send_json(&router, Method::GET, &effective, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["tools"], json!([]), "the built-in server is off by default");
+
+        let (status, settings) = send_json(
+            &router,
+            Method::PATCH,
+            "/api/settings",
+            Some(json!({ "managementServer": true })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(settings["managementServer"], json!(true));
+        assert!(WorkspaceSettings::load(&layout).unwrap().management.enabled);
+
+        let (_, body) = send_json(&router, Method::GET, &effective, None).await;
+        let tools = body["tools"].as_array().unwrap();
+        let names = tools.iter().map(|tool| tool["name"].as_str().unwrap()).collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "list_servers",
+                "enable_server",
+                "list_project_permissions",
+                "request_tool_access"
+            ]
+        );
+        assert!(tools.iter().all(|tool| tool["serverId"] == json!("mcp-center")));
+        assert!(tools.iter().all(|tool| tool["allowed"] == json!(true)));
+    });
+}
+
+#[test]
+fn http_api_approving_a_deny_all_project_needs_servers() {
+    test_runtime().block_on(async {
+        let tmp = tempdir().unwrap();
+        let layout = Layout::new(tmp.path().to_path_buf());
+        layout.ensure().unwrap();
+        fs::write(
+            layout.server_config_path("ops"),
+            r#"
+[mcp_server]
+id = "ops"
+name = "Ops"
+protocol = "script"
+enabled = true
+
+[[mcp_server.tools]]
+name = "disk_usage"
+command = "du"
+"#,
+        )
+        .unwrap();
+        let router = make_router(layout.clone()).await;
+
+        // 模拟 deny_all 策略下守护进程首次见到的项目
+        let project_dir = fs::canonicalize(tmp.path()).unwrap().join("workspace");
+        fs::create_dir_all(&project_dir).unwrap();
+        let registry = ProjectRegistry::new(&layout);
+        let mut record = ProjectRecord::new(ProjectId::from_path(&project_dir), project_dir);
+        WorkspaceSettings::default().seed_new_project(&mut record, vec!["ops".into()]);
+        registry.store(&record).unwrap();
+
+        let approve = |all: bool| json!({ "target": record.id, "all": all });
+        let (status, _) =
+            send_json(&router, Method::POST, "/api/project/approve", Some(approve(false))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let effective = format!("/api/project/{}/effective", record.id);
+        let (_, body) = send_json(&router, Method::GET, &effective, None).await;
+        assert_eq!(body["project"]["status"], json!("pending"));
+        let tools = body["tools"].as_array().unwrap();
+        assert_eq!(tools.len(), 1);
+        assert!(tools.iter().all(|tool| tool["allowed"] == json!(false)), "{body}");
+
+        let (status, summary) =
+            send_json(&router, Method::POST, "/api/project/approve", Some(approve(true))).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(summary["status"], json!("active"));
+        let (_, body) = send_json(&router, Method::GET, &effective, None).await;
+        assert_eq!(body["tools"][0]["allowed"], json!(true));
+    });
+}
+
+#[test]
+fn http_api_rejects_invalid_argument_patterns() {
+    test_runtime().block_on(async {
+        let tmp = tempdir().unwrap();
+        let layout = Layout::new(tmp.path().to_path_buf());
+        layout.ensure().unwrap();
+        let router = make_router(layout.clone()).await;
+
+        let project_dir = fs::canonicalize(tmp.path()).unwrap().join("workspace");
+        fs::create_dir_all(&project_dir).unwrap();
+        let registry = ProjectRegistry::new(&layout);
+        let id = ProjectId::from_path(&project_dir);
+        let record = ProjectRecord::new(id.clone(), project_dir);
+        registry.store(&record).unwrap();
+
+        let rule = |pattern: &str| {
+            json!({
+                "target": record.id,
+                "tool": "run",
+                "arguments": [{ "pointer": "/command", "deny": [pattern] }],
+            })
+        };
+        let (status, _) = send_json(
+            &router,
+            Method::POST,
+            "/api/project/tool/arguments",
+            Some(rule("(unclosed")),
+        )
+        .await;
+        assert!(status.is_client_error(), "{status}");
+        assert!(registry.load(&id).unwrap().tool_customizations.is_empty());
+
+        let (status, _) =
+            send_json(&router, Method::POST, "/api/project/tool/arguments", Some(rule("--force")))
+                .await;
+        assert_eq!(status, StatusCode::OK);
+        let stored = registry.load(&id).unwrap();
+        assert_eq!(stored.tool_customizations[0].arguments[0].deny[0].as_str(), "--force");
+    });
+}
+
+#[test]
+fn http_api_script_server_offers_its_declared_tools() {
+    test_runtime().block_on(async {
+        let tmp = tempdir().unwrap();
+        let layout = Layout::new(tmp.path().to_path_buf());
+        layout.ensure().unwrap();
+        fs::write(
+            layout.server_config_path("ops"),
+            r#"
+[mcp_server]
+id = "ops"
+name = "Ops"
+protocol = "script"
+enabled = true
+
+[[mcp_server.tools]]
+name = "disk_usage"
+read_only = true
+command = "du"
+args = ["-sh", "{{path}}"]
+
+[[mcp_server.tools]]
+name = "triage"
+steps = [{ tool = "disk_usage", arguments = { path = "{{path}}" } }]
+"#,
+        )
+        .unwrap();
+        let router = make_router(layout.clone()).await;
+
+        let project_dir = fs::canonicalize(tmp.path()).unwrap().join("workspace");
+        fs::create_dir_all(&project_dir).unwrap();
+        let registry = ProjectRegistry::new(&layout);
+        let mut record = ProjectRecord::new(ProjectId::from_path(&project_dir), project_dir);
+        record.allowed_server_ids = vec!["ops".into()];
+        registry.store(&record).unwrap();
+
+        let effective = format!("/api/project/{}/effective", record.id);
+        let (status, body) = send_json(&router, Method::GET, &effective, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let tools = body["tools"].as_array().unwrap();
+        let names = tools.iter().map(|tool| tool["name"].as_str().unwrap()).collect::<Vec<_>>();
+        assert_eq!(names, ["disk_usage", "triage"]);
+        assert!(tools.iter().all(|tool| tool["serverId"] == json!("ops")));
+
+        let (status, _) = send_json(
+            &router,
+            Method::POST,
+            "/api/mcp",
+            Some(json!({ "name": "More", "protocol": "script" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    });
+}
+
+#[test]
+fn http_api_project_grant_lists_expiring_grants() {
+    test_runtime().block_on(async {
+        let tmp = tempdir().unwrap();
+        let layout = Layout::new(tmp.path().to_path_buf());
+        layout.ensure().unwrap();
+        write_server_config(&layout, "demo", "Demo", false);
+        let router = make_router(layout.clone()).await;
+
+        let project_dir = fs::canonicalize(tmp.path()).unwrap().join("workspace");
+        fs::create_dir_all(&project_dir).unwrap();
+        let registry = ProjectRegistry::new(&layout);
+        let record = ProjectRecord::new(ProjectId::from_path(&project_dir), project_dir.clone());
+        registry.store(&record).unwrap();
+        let target = project_dir.to_str().unwrap();
+
+        let (status, body) = send_json(
+            &router,
+            Method::POST,
+            "/api/project/grant",
+            Some(json!({ "target": target, "tools": ["demo::write"], "duration": "30m" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["grants"][0]["serverId"], json!("demo"));
+        assert_eq!(body["grants"][0]["tools"], json!(["write"]));
+        assert_eq!(body["grants"][0]["active"], json!(true));
+        let granted_at = body["grants"][0]["grantedAt"].as_u64().unwrap();
+        assert_eq!(body["grants"][0]["expiresAt"].as_u64().unwrap(), granted_at + 1800);
+
+        let (status, body) =
+            send_json(&router, Method::GET, &format!("/api/project/{}/grants", record.id), None)
+                .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["grants"].as_array().unwrap().len(), 1);
+
+        let (status, _) = send_json(
+            &router,
+            Method::POST,
+            "/api/project/grant",
+            Some(json!({ "target": target, "servers": ["demo"], "duration": "forever" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = send_json(
+            &router,
+            Method::POST,
+            "/api/project/grant",
+            Some(json!({ "target": target, "servers": ["missing"], "duration": "1h" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    });
+}
+
+#[test]
+fn http_api_project_annotation_policy_and_tool_hints() {
+    test_runtime().block_on(async {
+        let tmp = tempdir().unwrap();
+        let layout = Layout::new(tmp.path().to_path_buf());
+        layout.ensure().unwrap();
+        let router = make_router(layout.clone()).await;
+
+        let project_dir = fs::canonicalize(tmp.path()).unwrap().join("workspace");
+        fs::create_dir_all(&project_dir).unwrap();
+        let registry = ProjectRegistry::new(&layout);
+        let record = ProjectRecord::new(ProjectId::from_path(&project_dir), project_dir.clone());
+        registry.store(&record).unwrap();
+
+        let (status, body) = send_json(
+            &router,
+            Method::POST,
+            "/api/project/annotation-policy",
+            Some(json!({
+                "target": record.id,
+                "switches": { "readOnly": true, "noDestructive": null, "noOpenWorld": false },
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body["annotationPolicy"],
+            json!({ "readOnly": true, "noDestructive": null, "noOpenWorld": false })
+        );
+
+        let (status, _) = send_json(
+            &router,
+            Method::POST,
+            "/api/project/tool/hints",
+            Some(json!({ "target": record.id, "tool": "lookup", "readOnly": true })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let stored = registry.load(&ProjectId::from_path(&project_dir)).unwrap();
+        assert_eq!(stored.annotation_policy.read_only, Some(true));
+        assert_eq!(stored.tool_customizations[0].annotations.unwrap().read_only, Some(true));
+
+        let (status, body) =
+            send_json(&router, Method::GET, &format!("/api/project/{}/effective", record.id), None)
+                .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["switches"][0]["switch"], json!("read_only"));
+        assert_eq!(body["switches"][0]["hidden"], json!([]));
+        assert_eq!(body["switches"].as_array().unwrap().len(), 1);
+    });
+}
+
+#[test]
+fn http_api_project_tool_alias_validates_and_replaces_overrides() {
+    test_runtime().block_on(async {
+        let tmp = tempdir().unwrap();
+        let layout = Layout::new(tmp.path().to_path_buf());
+        layout.ensure().unwrap();
+        let router = make_router(layout.clone()).await;
+
+        let project_dir = fs::canonicalize(tmp.path()).unwrap().join("workspace");
+        fs::create_dir_all(&project_dir).unwrap();
+        let registry = ProjectRegistry::new(&layout);
+        let record = ProjectRecord::new(ProjectId::from_path(&project_dir), project_dir.clone());
+        registry.store(&record).unwrap();
+
+        let (status, _) = send_json(
+            &router,
+            Method::POST,
+            "/api/project/tool/alias",
+            Some(json!({ "target": record.id, "tool": "search", "alias": "issue search" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = send_json(
+            &router,
+            Method::POST,
+            "/api/project/tool/alias",
+            Some(json!({ "target": record.id, "tool": "search", "schemaPatch": "[1]" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = send_json(
+            &router,
+            Method::POST,
+            "/api/project/tool/alias",
+            Some(json!({
+                "target": record.id,
+                "tool": "search",
+                "alias": "issue_search",
+                "title": "Issue search",
+                "schemaPatch": r#"{"properties":{"debug":null}}"#,
+                "hiddenParameters": ["page"],
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let stored = registry.load(&ProjectId::from_path(&project_dir)).unwrap();
+        let search = &stored.tool_customizations[0];
+        assert_eq!(search.alias.as_deref(), Some("issue_search"));
+        assert_eq!(search.schema_patch, Some(json!({ "properties": { "debug": null } })));
+        assert_eq!(search.hidden_parameters, vec!["page".to_string()]);
+
+        let (status, _) = send_json(
+            &router,
+            Method::POST,
+            "/api/project/tool/alias",
+            Some(json!({ "target": record.id, "tool": "lookup", "alias": "issue_search" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = send_json(
+            &router,
+            Method::POST,
+            "/api/project/tool/alias",
+            Some(json!({ "target": record.id, "tool": "search" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let stored = registry.load(&ProjectId::from_path(&project_dir)).unwrap();
+        assert!(stored.tool_customizations.is_empty());
+    });
+}
+
+#[test]
+fn http_api_approvals_resolve_waiting_calls() {
+    test_runtime().block_on(async {
+        let tmp = tempdir().unwrap();
+        let layout = Layout::new(tmp.path().to_path_buf());
+        layout.ensure().unwrap();
+        let manager = Arc::new(ServerManager::start(layout.clone()).await.unwrap());
+        let registry = ProjectRegistry::new(&layout);
+        registry.ensure().unwrap();
+        let router = http::build_router(HttpState {
+            manager: manager.clone(),
+            registry: registry.clone(),
+            layout: layout.clone(),
+            auth: http::HttpAuth::new(Some("secret".into())),
+        });
+
+        let (status, body) = send_json(&router, Method::GET, "/api/approvals", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["approvals"], json!([]));
+
+        let waiting = manager.clone();
+        let call = tokio::spawn(async move {
+            waiting
+                .approvals()
+                .request(
+                    "/work".into(),
+                    "db".into(),
+                    "drop_table".into(),
+                    Some("{}".into()),
+                    Duration::from_secs(60),
+                )
+                .await
+        });
+        while manager.approvals().list().is_empty() {
+            tokio::task::yield_now().await;
+        }
+
+        let (_, body) = send_json(&router, Method::GET, "/api/approvals", None).await;
+        assert_eq!(body["approvals"][0]["toolName"], json!("drop_table"));
+        let id = body["approvals"][0]["id"].clone();
+
+        let (status, body) = send_json(
+            &router,
+            Method::POST,
+            "/api/approvals",
+            Some(json!({ "id": id, "approve": false, "reason": "not in prod" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["serverId"], json!("db"));
+        assert_eq!(
+            call.await.unwrap(),
+            ApprovalOutcome::Denied { reason: Some("not in prod".into()) }
+        );
+
+        let (status, _) = send_json(
+            &router,
+            Method::POST,
+            "/api/approvals",
+            Some(json!({ "id": id, "approve": true })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        // Require approval for one tool of a project and shorten the timeout
+        let project_dir = fs::canonicalize(tmp.path()).unwrap().join("workspace");
+        fs::create_dir_all(&project_dir).unwrap();
+        let record = ProjectRecord::new(ProjectId::from_path(&project_dir), project_dir.clone());
+        registry.store(&record).unwrap();
+
+        let (status, body) = send_json(
+            &router,
+            Method::POST,
+            "/api/project/require-approval",
+            Some(json!({ "target": record.id, "tool": "deploy", "required": true })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["requireApproval"], Value::Null);
+        let stored = registry.load(&ProjectId::from_path(&project_dir)).unwrap();
+        assert_eq!(stored.tool_customizations[0].require_approval, Some(true));
+
+        let (status, settings) = send_json(
+            &router,
+            Method::PATCH,
+            "/api/settings",
+            Some(json!({ "approvalTimeoutSecs": 30 })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(settings["approvalTimeoutSecs"], json!(30));
+        assert_eq!(WorkspaceSettings::load(&layout).unwrap().approvals.timeout_secs, 30);
+    });
+}
@@ -1,5 +1,6 @@
 use assert_cmd::{Command, cargo::cargo_bin};
-use mcp_center::{Layout, ServerConfig};
+use mcp_center::{Layout, ServerConfig, config::cache::CacheConfig, daemon::cache::ToolCache};
+use rmcp::model::CallToolResult;
 use std::{fs, path::Path, process::Command as StdCommand, time::Duration};
 use tempfile::tempdir;
 
@@ -122,3 +123,36 @@ fn mcp_flow_manage_server_configs() {
     let still_exists = configs_after.iter().any(|cfg| cfg.definition().id == server_id);
     assert!(!still_exists, "server config should be removed");
 }
+
+#[test]
+fn cache_stats_and_clear() {
+    let tmp = tempdir().expect("temp dir");
+    let root = tmp.path().to_path_buf();
+    let layout = Layout::new(root.clone());
+
+    let output = cli_with_root(&root, &["cache", "stats"]).success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    assert!(stdout.contains("No cached tool results"), "unexpected stats output: {stdout}");
+
+    let cache = ToolCache::new(&layout, "docs", CacheConfig::default());
+    let result = CallToolResult::structured(serde_json::json!({ "page": "intro" }));
+    let runtime = tokio::runtime::Runtime::new().expect("create tokio runtime");
+    runtime
+        .block_on(cache.put(&ToolCache::key("fetch", None), "fetch", &result))
+        .unwrap();
+
+    let output = cli_with_root(&root, &["cache", "stats"]).success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    assert!(
+        stdout.lines().any(|line| line.starts_with("docs") && line.contains(" 1 ")),
+        "unexpected stats output: {stdout}"
+    );
+
+    let output = cli_with_root(&root, &["cache", "clear", "--server", "docs"]).success();
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    assert!(
+        stdout.contains("Removed 1 cached result(s)"),
+        "unexpected clear output: {stdout}"
+    );
+    assert!(!layout.server_cache_dir("docs").exists());
+}
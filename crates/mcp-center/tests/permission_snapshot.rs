@@ -0,0 +1,149 @@
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use mcp_center::{
+    Layout, ProjectId, ProjectRecord,
+    config::{ServerConfig, ServerDefinition, WorkspaceSettings},
+    daemon::permissions::SessionPermissions,
+    project::{ProjectRegistry, ToolPermission, policy::EffectivePolicy},
+};
+use tempfile::tempdir;
+
+const PROJECTS: usize = 100;
+const SERVERS: usize = 20;
+const TOOLS_PER_SERVER: usize = 10;
+
+fn write_server_config(layout: &Layout, id: &str) {
+    let definition = ServerDefinition {
+        id: id.to_string(),
+        name: Some(id.to_string()),
+        command: "echo".to_string(),
+        enabled: true,
+        ..Default::default()
+    };
+    let config = ServerConfig::new(definition).unwrap();
+    fs::write(layout.server_config_path(id), config.to_toml_string().unwrap()).unwrap();
+}
+
+/// Tools as `(server_id, tool_name)`.
+fn tools() -> Vec<(String, String)> {
+    (0..SERVERS)
+        .flat_map(|server| {
+            (0..TOOLS_PER_SERVER).map(move |tool| (format!("srv{server}"), format!("tool{tool}")))
+        })
+        .collect()
+}
+
+/// `tools/list` before compiled snapshots: settings, server configs and the registry were
+/// consulted again for every tool.
+fn per_tool_resolution(layout: &Layout, registry: &ProjectRegistry, root: &Path) -> usize {
+    tools()
+        .iter()
+        .filter(|(server, tool)| {
+            let rules = WorkspaceSettings::load(layout).unwrap().project_rules;
+            let definitions = layout
+                .list_server_configs()
+                .unwrap()
+                .into_iter()
+                .map(|cfg| cfg.definition().clone())
+                .collect::<Vec<_>>();
+            EffectivePolicy::resolve(registry, &rules, root)
+                .with_repo_config(&definitions)
+                .allows_tool(server, tool)
+        })
+        .count()
+}
+
+/// `tools/list` with a session snapshot: one generation check per tool.
+fn compiled_snapshot(
+    layout: &Layout,
+    registry: &ProjectRegistry,
+    permissions: &SessionPermissions,
+    root: &Path,
+) -> usize {
+    tools()
+        .iter()
+        .filter(|(server, tool)| {
+            permissions.current(layout, registry, vec![root.to_path_buf()]).policies()[0]
+                .allows_tool(server, tool)
+        })
+        .count()
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let value = f();
+    (value, start.elapsed())
+}
+
+/// A registry with [`PROJECTS`] unrelated projects plus a nested project under a parent that
+/// grants half the servers. Returns the nested project's record and root.
+fn populate(layout: &Layout, registry: &ProjectRegistry, repos: &Path) -> ProjectRecord {
+    layout.ensure().unwrap();
+    for server in 0..SERVERS {
+        write_server_config(layout, &format!("srv{server}"));
+    }
+    registry.ensure().unwrap();
+
+    for index in 0..PROJECTS {
+        let path = repos.join(format!("project-{index}"));
+        registry.store(&ProjectRecord::new(ProjectId::from_path(&path), path)).unwrap();
+    }
+    let parent = repos.join("acme");
+    let mut parent_record = ProjectRecord::new(ProjectId::from_path(&parent), parent.clone());
+    parent_record.allowed_server_ids = (0..SERVERS / 2).map(|s| format!("srv{s}")).collect();
+    registry.store(&parent_record).unwrap();
+    let root = parent.join("services/web");
+    let mut record = ProjectRecord::new(ProjectId::from_path(&root), root);
+    record
+        .allowed_server_tools
+        .insert("srv0".into(), ToolPermission::DenyList { tools: vec!["tool0".into()] });
+    registry.store(&record).unwrap();
+    record
+}
+
+#[test]
+fn compiled_snapshot_matches_per_tool_resolution() {
+    let tmp = tempdir().unwrap();
+    let layout = Layout::new(tmp.path().join("center"));
+    let registry = ProjectRegistry::new(&layout);
+    let mut record = populate(&layout, &registry, &tmp.path().join("repos"));
+    let root = record.path.clone();
+
+    let expected = per_tool_resolution(&layout, &registry, &root);
+    let permissions = SessionPermissions::default();
+    let allowed = compiled_snapshot(&layout, &registry, &permissions, &root);
+
+    // Same verdicts: srv0 minus one denied tool plus the parent's other servers.
+    assert_eq!(allowed, expected);
+    assert_eq!(allowed, SERVERS / 2 * TOOLS_PER_SERVER - 1);
+
+    // A change notification invalidates the snapshot.
+    record.allowed_server_tools.insert("srv0".into(), ToolPermission::All);
+    registry.store(&record).unwrap();
+    assert_eq!(
+        compiled_snapshot(&layout, &registry, &permissions, &root),
+        SERVERS / 2 * TOOLS_PER_SERVER
+    );
+}
+
+/// Wall-clock comparison; run with `cargo test -- --ignored` on an idle machine.
+#[test]
+#[ignore = "timing-sensitive"]
+fn compiled_snapshot_outperforms_per_tool_resolution() {
+    let tmp = tempdir().unwrap();
+    let layout = Layout::new(tmp.path().join("center"));
+    let registry = ProjectRegistry::new(&layout);
+    let root = populate(&layout, &registry, &tmp.path().join("repos")).path;
+
+    let (_, naive) = timed(|| per_tool_resolution(&layout, &registry, &root));
+    let permissions = SessionPermissions::default();
+    let (_, compiled) = timed(|| compiled_snapshot(&layout, &registry, &permissions, &root));
+    assert!(
+        compiled * 10 < naive,
+        "compiled snapshot ({compiled:?}) should be far faster than per-tool resolution ({naive:?})"
+    );
+}
@@ -1,7 +1,7 @@
 use assert_cmd::Command;
 use mcp_center::{
     Layout, ProjectId, ProjectRegistry,
-    project::{ToolCustomization, ToolPermission},
+    project::{ProjectStatus, ToolCustomization, ToolPermission},
 };
 use std::{fs, path::Path};
 use tempfile::tempdir;
@@ -109,7 +109,8 @@ fn project_cli_manages_permissions_and_customizations() {
         record
             .tool_customizations
             .iter()
-            .any(|ToolCustomization { tool_name, description }| tool_name == "resolve-library-id"
+            .any(|ToolCustomization { tool_name, description, .. }| tool_name
+                == "resolve-library-id"
                 && description.as_deref() == Some(custom_desc)),
         "custom description stored"
     );
@@ -132,3 +133,526 @@ fn project_cli_manages_permissions_and_customizations() {
     cli_with_root(&root, &["project", "remove", &project_arg, "--yes"]).success();
     assert!(registry.load(&project_id).is_err(), "project record removed");
 }
+
+#[test]
+fn project_detect_explains_matched_rule() {
+    let tmp = tempdir().expect("temp dir");
+    let root = tmp.path().join("center");
+    let repo = fs::canonicalize(tmp.path()).unwrap().join("repo");
+    fs::create_dir_all(repo.join(".zed")).unwrap();
+    fs::create_dir_all(repo.join("crates/core/src")).unwrap();
+    fs::write(repo.join("crates/core/Cargo.toml"), "[package]\n").unwrap();
+    let nested = repo.join("crates/core/src");
+    let nested_arg = nested.to_str().unwrap();
+
+    cli_with_root(&root, &["project", "detect", nested_arg])
+        .success()
+        .stdout(predicates::str::contains(format!("Project root: {}", repo.display())))
+        .stdout(predicates::str::contains("Matched rule: marker '.zed'"))
+        .stdout(predicates::str::contains("built-in defaults"));
+
+    fs::create_dir_all(root.join("config")).unwrap();
+    fs::write(
+        root.join("config/settings.toml"),
+        "[project_detection]\nmonorepo = \"nearest_package\"\n",
+    )
+    .unwrap();
+
+    cli_with_root(&root, &["project", "detect", nested_arg])
+        .success()
+        .stdout(predicates::str::contains(format!(
+            "Project root: {}",
+            repo.join("crates/core").display()
+        )))
+        .stdout(predicates::str::contains("nearest package 'Cargo.toml'"));
+}
+
+#[test]
+fn project_move_rebinds_record_to_new_path() {
+    let tmp = tempdir().expect("temp dir");
+    let root = tmp.path().join("center");
+    let layout = Layout::new(root.clone());
+    let base = fs::canonicalize(tmp.path()).unwrap();
+    let old_path = base.join("old-checkout");
+    let new_path = base.join("new-checkout");
+    fs::create_dir_all(&old_path).unwrap();
+    fs::create_dir_all(&new_path).unwrap();
+
+    cli_with_root(&root, &["project", "add", old_path.to_str().unwrap()]).success();
+    let registry = ProjectRegistry::new(&layout);
+    let mut record = registry.load(&ProjectId::from_path(&old_path)).unwrap();
+    record.allowed_server_ids = vec!["github".into()];
+    registry.store(&record).unwrap();
+    fs::remove_dir(&old_path).unwrap();
+
+    // 目标路径已有记录时需要 --force
+    cli_with_root(&root, &["project", "add", new_path.to_str().unwrap()]).success();
+    cli_with_root(
+        &root,
+        &["project", "move", old_path.to_str().unwrap(), new_path.to_str().unwrap()],
+    )
+    .failure()
+    .stderr(predicates::str::contains("--force"));
+
+    cli_with_root(
+        &root,
+        &[
+            "project",
+            "move",
+            old_path.to_str().unwrap(),
+            new_path.to_str().unwrap(),
+            "--force",
+        ],
+    )
+    .success()
+    .stdout(predicates::str::contains("Moved project"));
+
+    let moved = registry.load(&ProjectId::from_path(&new_path)).expect("record at new path");
+    assert_eq!(moved.path, new_path);
+    assert_eq!(moved.allowed_server_ids, vec!["github".to_string()]);
+    assert!(registry.load(&ProjectId::from_path(&old_path)).is_err());
+}
+
+#[test]
+fn project_approve_clears_pending_state() {
+    let tmp = tempdir().expect("temp dir");
+    let root = tmp.path().join("center");
+    let layout = Layout::new(root.clone());
+    let project_dir = fs::canonicalize(tmp.path()).unwrap().join("pending");
+    fs::create_dir_all(&project_dir).unwrap();
+
+    cli_with_root(&root, &["project", "add", project_dir.to_str().unwrap()]).success();
+    let registry = ProjectRegistry::new(&layout);
+    let id = ProjectId::from_path(&project_dir);
+    let mut record = registry.load(&id).unwrap();
+    record.status = ProjectStatus::Pending;
+    registry.store(&record).unwrap();
+    assert!(!record.allows_tool("github", "search"), "pending project without grants");
+
+    cli_with_root(&root, &["project", "list"])
+        .success()
+        .stdout(predicates::str::contains("[pending]"))
+        .stdout(predicates::str::contains("mcp-center project approve"));
+
+    // Granting nothing would read as "every server", so that takes --all
+    cli_with_root(&root, &["project", "approve", project_dir.to_str().unwrap()])
+        .failure()
+        .stderr(predicates::str::contains("would allow every server"));
+    let record = registry.load(&id).unwrap();
+    assert_eq!(record.status, ProjectStatus::Pending);
+    assert!(!record.allows_tool("github", "search"));
+
+    cli_with_root(&root, &["project", "approve", project_dir.to_str().unwrap(), "--all"])
+        .success()
+        .stdout(predicates::str::contains("Approved project"));
+    let record = registry.load(&id).unwrap();
+    assert_eq!(record.status, ProjectStatus::Active);
+    assert!(record.allows_tool("github", "search"));
+}
+
+#[test]
+fn project_explain_shows_rule_and_parent_sources() {
+    let tmp = tempdir().expect("temp dir");
+    let root = tmp.path().join("center");
+    let layout = Layout::new(root.clone());
+    let repos = fs::canonicalize(tmp.path()).unwrap().join("repos");
+    let service = repos.join("acme/service");
+    fs::create_dir_all(&service).unwrap();
+
+    fs::create_dir_all(root.join("config")).unwrap();
+    fs::write(
+        root.join("config/settings.toml"),
+        format!(
+            "[[project_rules]]\npattern = \"{}/**\"\nservers = [\"github\"]\n",
+            repos.display()
+        ),
+    )
+    .unwrap();
+
+    let acme = repos.join("acme");
+    cli_with_root(&root, &["project", "add", acme.to_str().unwrap()]).success();
+    let registry = ProjectRegistry::new(&layout);
+    let mut parent = registry.load(&ProjectId::from_path(&acme)).unwrap();
+    parent
+        .allowed_server_tools
+        .insert("github".into(), ToolPermission::DenyList { tools: vec!["delete_repo".into()] });
+    registry.store(&parent).unwrap();
+
+    cli_with_root(&root, &["project", "explain", service.to_str().unwrap()])
+        .success()
+        .stdout(predicates::str::contains("Record: none"))
+        .stdout(predicates::str::contains(format!("1. rule '{}/**'", repos.display())))
+        .stdout(predicates::str::contains(format!("2. parent project {}", acme.display())))
+        .stdout(predicates::str::contains(format!(
+            "github: all tools except delete_repo — tool permission from parent project {}",
+            acme.display()
+        )));
+}
+
+#[test]
+fn project_explain_applies_repo_config_and_warns() {
+    let tmp = tempdir().expect("temp dir");
+    let root = tmp.path().join("center");
+    let layout = Layout::new(root.clone());
+    let project = fs::canonicalize(tmp.path()).unwrap().join("widgets");
+    fs::create_dir_all(&project).unwrap();
+
+    cli_with_root(
+        &root,
+        &[
+            "mcp",
+            "add",
+            "github",
+            "--protocol",
+            "http",
+            "--url",
+            "https://example.test/mcp",
+        ],
+    )
+    .success();
+    let server_id = layout.load_server_config_by_name("github").unwrap().definition().id.clone();
+
+    fs::write(
+        project.join(".mcp-center.toml"),
+        "servers = [\"github\", \"jira\"]\n\n[tools.github]\ndeny = [\"delete_repo\"]\n\n[descriptions]\nsearch_code = \"Search the widgets monorepo\"\n",
+    )
+    .unwrap();
+    cli_with_root(&root, &["project", "add", project.to_str().unwrap()]).success();
+
+    let repo_file = project.join(".mcp-center.toml");
+    cli_with_root(&root, &["project", "explain", project.to_str().unwrap()])
+        .success()
+        .stdout(predicates::str::contains(format!("{server_id}: allowed")))
+        .stdout(predicates::str::contains(
+            "repository config narrows it (allow: -; deny: delete_repo)",
+        ))
+        .stdout(predicates::str::contains(format!(
+            "search_code — repository config {}",
+            repo_file.display()
+        )))
+        .stdout(predicates::str::contains(
+            "'jira' is referenced by .mcp-center.toml but not installed",
+        ));
+
+    // Local settings can restrict further: the repo cannot re-grant a denied server.
+    let registry = ProjectRegistry::new(&layout);
+    let mut record = registry.load(&ProjectId::from_path(&project)).unwrap();
+    record.allowed_server_ids = vec!["other".into()];
+    registry.store(&record).unwrap();
+    cli_with_root(&root, &["project", "explain", project.to_str().unwrap()])
+        .success()
+        .stdout(predicates::str::contains(format!("{server_id}: denied")))
+        .stdout(predicates::str::contains(
+            "'github' is required by .mcp-center.toml but not allowed for this project",
+        ));
+}
+
+#[test]
+fn project_allow_for_creates_expiring_grant() {
+    let tmp = tempdir().expect("temp dir");
+    let root = tmp.path().join("center");
+    let layout = Layout::new(root.clone());
+    let project = fs::canonicalize(tmp.path()).unwrap().join("widgets");
+    fs::create_dir_all(&project).unwrap();
+    let project_arg = project.to_str().unwrap();
+
+    cli_with_root(
+        &root,
+        &["mcp", "add", "db", "--protocol", "http", "--url", "https://example.test/mcp"],
+    )
+    .success();
+    let server_id = layout.load_server_config_by_name("db").unwrap().definition().id.clone();
+    cli_with_root(&root, &["project", "add", project_arg]).success();
+
+    cli_with_root(&root, &["project", "allow", project_arg, &server_id, "--for", "2h"])
+        .success()
+        .stdout(predicates::str::contains(format!("Granted {server_id} to")));
+    let registry = ProjectRegistry::new(&layout);
+    let record = registry.load(&ProjectId::from_path(&project)).unwrap();
+    assert!(record.allowed_server_ids.is_empty(), "temporary grants are not permanent");
+    assert_eq!(record.grants.len(), 1);
+    assert_eq!(record.grants[0].server_id, server_id);
+    assert_eq!(record.grants[0].expires_at - record.grants[0].granted_at, 7200);
+
+    cli_with_root(&root, &["project", "explain", project_arg])
+        .success()
+        .stdout(predicates::str::contains(format!("{server_id}: allowed")));
+    cli_with_root(&root, &["project", "grants", project_arg])
+        .success()
+        .stdout(predicates::str::contains(format!("{server_id} (all tools): active until")));
+
+    cli_with_root(&root, &["project", "allow", project_arg, &server_id, "--for", "soon"])
+        .failure()
+        .stderr(predicates::str::contains("soon"));
+}
+
+#[test]
+fn project_set_mode_and_tool_hints() {
+    let tmp = tempdir().expect("temp dir");
+    let root = tmp.path().join("center");
+    let layout = Layout::new(root.clone());
+    let project = fs::canonicalize(tmp.path()).unwrap().join("widgets");
+    fs::create_dir_all(&project).unwrap();
+    let project_arg = project.to_str().unwrap();
+    cli_with_root(&root, &["project", "add", project_arg]).success();
+
+    cli_with_root(&root, &["project", "set-mode", project_arg, "read-only", "on"])
+        .success()
+        .stdout(predicates::str::contains("read-only is now on"));
+    cli_with_root(&root, &["project", "set-mode", project_arg, "no-open-world", "off"]).success();
+    cli_with_root(&root, &["project", "explain", project_arg])
+        .success()
+        .stdout(predicates::str::contains("Annotation switches:"))
+        .stdout(predicates::str::contains("read-only: on (project record)"))
+        .stdout(predicates::str::contains("no-open-world: off (project record)"));
+
+    cli_with_root(&root, &["project", "set-tool-desc", project_arg, "lookup", "Find things"])
+        .success();
+    cli_with_root(
+        &root,
+        &["project", "set-tool-hints", project_arg, "lookup", "--read-only", "true"],
+    )
+    .success()
+    .stdout(predicates::str::contains("Annotation hints for 'lookup' overridden"));
+
+    let registry = ProjectRegistry::new(&layout);
+    let record = registry.load(&ProjectId::from_path(&project)).unwrap();
+    assert_eq!(record.annotation_policy.read_only, Some(true));
+    assert_eq!(record.annotation_policy.no_open_world, Some(false));
+    let lookup = record.tool_customizations.iter().find(|c| c.tool_name == "lookup").unwrap();
+    assert_eq!(lookup.description.as_deref(), Some("Find things"));
+    assert_eq!(lookup.annotations.unwrap().read_only, Some(true));
+
+    // Resetting the description keeps the hint override, clearing both drops the entry.
+    cli_with_root(&root, &["project", "reset-tool-desc", project_arg, "lookup"]).success();
+    cli_with_root(&root, &["project", "set-tool-hints", project_arg, "lookup"])
+        .success()
+        .stdout(predicates::str::contains("Annotation hint overrides for 'lookup' cleared"));
+    cli_with_root(&root, &["project", "set-mode", project_arg, "read-only", "inherit"]).success();
+    let record = registry.load(&ProjectId::from_path(&project)).unwrap();
+    assert!(record.tool_customizations.is_empty());
+    assert_eq!(record.annotation_policy.read_only, None);
+}
+
+#[test]
+fn project_require_approval_per_project_and_tool() {
+    let tmp = tempdir().expect("temp dir");
+    let root = tmp.path().join("center");
+    let layout = Layout::new(root.clone());
+    let project = fs::canonicalize(tmp.path()).unwrap().join("widgets");
+    fs::create_dir_all(&project).unwrap();
+    let project_arg = project.to_str().unwrap();
+    cli_with_root(&root, &["project", "add", project_arg]).success();
+
+    cli_with_root(&root, &["project", "require-approval", project_arg, "on"])
+        .success()
+        .stdout(predicates::str::contains("Approval for all tools is now on"));
+    cli_with_root(&root, &["project", "require-approval", project_arg, "off", "--tool", "search"])
+        .success()
+        .stdout(predicates::str::contains("Approval for search is now off"));
+    cli_with_root(&root, &["project", "explain", project_arg])
+        .success()
+        .stdout(predicates::str::contains("Approval required:"))
+        .stdout(predicates::str::contains("all tools: on (project record)"))
+        .stdout(predicates::str::contains("search: off (project record)"));
+
+    let registry = ProjectRegistry::new(&layout);
+    let record = registry.load(&ProjectId::from_path(&project)).unwrap();
+    assert_eq!(record.require_approval, Some(true));
+    assert_eq!(record.tool_customizations[0].require_approval, Some(false));
+
+    cli_with_root(
+        &root,
+        &["project", "require-approval", project_arg, "inherit", "--tool", "search"],
+    )
+    .success();
+    let record = registry.load(&ProjectId::from_path(&project)).unwrap();
+    assert!(record.tool_customizations.is_empty());
+}
+
+#[test]
+fn project_set_arg_rule_adds_replaces_and_removes_constraints() {
+    let tmp = tempdir().expect("temp dir");
+    let root = tmp.path().join("center");
+    let layout = Layout::new(root.clone());
+    let project = fs::canonicalize(tmp.path()).unwrap().join("widgets");
+    fs::create_dir_all(&project).unwrap();
+    let project_arg = project.to_str().unwrap();
+    cli_with_root(&root, &["project", "add", project_arg]).success();
+
+    cli_with_root(
+        &root,
+        &[
+            "project",
+            "set-arg-rule",
+            project_arg,
+            "write_file",
+            "/path",
+            "--within",
+            "${PROJECT_ROOT}",
+            "--deny",
+            r"\.env$",
+        ],
+    )
+    .success()
+    .stdout(predicates::str::contains("Argument rule for write_file /path saved"));
+    cli_with_root(&root, &["project", "set-arg-rule", project_arg, "run", "/depth", "--max", "3"])
+        .success();
+    cli_with_root(&root, &["project", "explain", project_arg])
+        .success()
+        .stdout(predicates::str::contains("Argument rules:"))
+        .stdout(predicates::str::contains(
+            r"write_file /path: within ${PROJECT_ROOT}, deny /\.env$/ (project record)",
+        ))
+        .stdout(predicates::str::contains("run /depth: max 3 (project record)"));
+
+    let registry = ProjectRegistry::new(&layout);
+    let record = registry.load(&ProjectId::from_path(&project)).unwrap();
+    let write_file = record.tool_customizations.iter().find(|c| c.tool_name == "write_file");
+    assert_eq!(write_file.unwrap().arguments[0].within.as_deref(), Some("${PROJECT_ROOT}"));
+
+    cli_with_root(&root, &["project", "set-arg-rule", project_arg, "run", "depth"])
+        .failure()
+        .stderr(predicates::str::contains("is not a JSON pointer"));
+    cli_with_root(&root, &["project", "set-arg-rule", project_arg, "run", "/cmd", "--allow", "("])
+        .failure()
+        .stderr(predicates::str::contains("Invalid regular expression"));
+    cli_with_root(&root, &["project", "set-arg-rule", project_arg, "run", "/depth"])
+        .success()
+        .stdout(predicates::str::contains("Argument rule for run /depth removed"));
+    let record = registry.load(&ProjectId::from_path(&project)).unwrap();
+    assert_eq!(record.tool_customizations.len(), 1);
+}
+
+#[test]
+fn project_set_preset_pins_typed_values_and_masks_secrets() {
+    let tmp = tempdir().expect("temp dir");
+    let root = tmp.path().join("center");
+    let layout = Layout::new(root.clone());
+    let project = fs::canonicalize(tmp.path()).unwrap().join("widgets");
+    fs::create_dir_all(&project).unwrap();
+    let project_arg = project.to_str().unwrap();
+    cli_with_root(&root, &["project", "add", project_arg]).success();
+
+    cli_with_root(&root, &["project", "set-preset", project_arg, "search", "repo", "acme/widgets"])
+        .success()
+        .stdout(predicates::str::contains("Argument repo of search is now pinned"));
+    cli_with_root(&root, &["project", "set-preset", project_arg, "search", "limit", "20"])
+        .success();
+    cli_with_root(
+        &root,
+        &[
+            "project",
+            "set-preset",
+            project_arg,
+            "search",
+            "token",
+            "sk-live-123",
+            "--secret",
+        ],
+    )
+    .success();
+    cli_with_root(&root, &["project", "explain", project_arg])
+        .success()
+        .stdout(predicates::str::contains("Pinned arguments:"))
+        .stdout(predicates::str::contains(r#"search repo = "acme/widgets" (project record)"#))
+        .stdout(predicates::str::contains("search limit = 20 (project record)"))
+        .stdout(predicates::str::contains("search token = ******** (project record)"))
+        .stdout(predicates::boolean::PredicateBooleanExt::not(predicates::str::contains(
+            "sk-live-123",
+        )));
+
+    let registry = ProjectRegistry::new(&layout);
+    let record = registry.load(&ProjectId::from_path(&project)).unwrap();
+    let presets = &record.tool_customizations[0].presets;
+    assert_eq!(presets["limit"].value, serde_json::json!(20));
+    assert_eq!(presets["repo"].value, serde_json::json!("acme/widgets"));
+    assert!(presets["token"].secret);
+
+    for argument in ["repo", "limit", "token"] {
+        cli_with_root(&root, &["project", "set-preset", project_arg, "search", argument]).success();
+    }
+    let record = registry.load(&ProjectId::from_path(&project)).unwrap();
+    assert!(record.tool_customizations.is_empty());
+}
+
+#[test]
+fn project_set_tool_alias_round_trips_schema_patch() {
+    let tmp = tempdir().expect("temp dir");
+    let root = tmp.path().join("center");
+    let layout = Layout::new(root.clone());
+    let project = fs::canonicalize(tmp.path()).unwrap().join("widgets");
+    fs::create_dir_all(&project).unwrap();
+    let project_arg = project.to_str().unwrap();
+    cli_with_root(&root, &["project", "add", project_arg]).success();
+
+    cli_with_root(
+        &root,
+        &[
+            "project",
+            "set-tool-alias",
+            project_arg,
+            "search",
+            "issue_search",
+            "--title",
+            "Issue search",
+            "--schema-patch",
+            r#"{"properties":{"debug":null}}"#,
+            "--hide",
+            "page",
+        ],
+    )
+    .success()
+    .stdout(predicates::str::contains("Overrides for search saved"));
+    cli_with_root(&root, &["project", "explain", project_arg])
+        .success()
+        .stdout(predicates::str::contains("Tool overrides:"))
+        .stdout(predicates::str::contains(
+            r#"search: alias issue_search, title "Issue search", schema patch {"properties":{"debug":null}}, hidden page (project record)"#,
+        ));
+
+    // The null survives the TOML round trip.
+    let registry = ProjectRegistry::new(&layout);
+    let record = registry.load(&ProjectId::from_path(&project)).unwrap();
+    assert_eq!(
+        record.tool_customizations[0].schema_patch,
+        Some(serde_json::json!({ "properties": { "debug": null } }))
+    );
+
+    cli_with_root(&root, &["project", "set-tool-alias", project_arg, "lookup", "issue_search"])
+        .failure()
+        .stderr(predicates::str::contains("already used by tool search"));
+    cli_with_root(&root, &["project", "set-tool-alias", project_arg, "lookup", "bad name"])
+        .failure()
+        .stderr(predicates::str::contains("is not a valid tool name"));
+    cli_with_root(&root, &["project", "set-tool-alias", project_arg, "search"])
+        .success()
+        .stdout(predicates::str::contains("Overrides for search removed"));
+    let record = registry.load(&ProjectId::from_path(&project)).unwrap();
+    assert!(record.tool_customizations.is_empty());
+}
+
+#[test]
+fn project_tool_search_toggles_and_explains() {
+    let tmp = tempdir().expect("temp dir");
+    let root = tmp.path().join("center");
+    let layout = Layout::new(root.clone());
+    let project = fs::canonicalize(tmp.path()).unwrap().join("widgets");
+    fs::create_dir_all(&project).unwrap();
+    let project_arg = project.to_str().unwrap();
+    cli_with_root(&root, &["project", "add", project_arg]).success();
+
+    cli_with_root(&root, &["project", "tool-search", project_arg, "on"])
+        .success()
+        .stdout(predicates::str::contains("Tool search is now on"));
+    cli_with_root(&root, &["project", "explain", project_arg])
+        .success()
+        .stdout(predicates::str::contains("Tool search: on (project record)"));
+    let registry = ProjectRegistry::new(&layout);
+    let record = registry.load(&ProjectId::from_path(&project)).unwrap();
+    assert_eq!(record.tool_search, Some(true));
+
+    cli_with_root(&root, &["project", "tool-search", project_arg, "inherit"]).success();
+    let record = registry.load(&ProjectId::from_path(&project)).unwrap();
+    assert_eq!(record.tool_search, None);
+}
@@ -6,7 +6,7 @@
 /**
  * Supported MCP server protocols.
  */
-export type ServerProtocol = "stdio" | "sse" | "http" | "unknown"
+export type ServerProtocol = "stdio" | "sse" | "http" | "script" | "unknown"
 
 export type ServerSnapshot = { id: string; name: string; protocol: ServerProtocol; enabled: boolean; toolCount: number; createdAt: number | null; lastSeen: number | null }
 
@@ -22,7 +22,51 @@ export type ServerDetail = { id: string; name: string; protocol: ServerProtocol;
 
 export type ServerDetailResponse = { server: ServerDetail; tools: ToolSummary[] }
 
-export type ProjectSummary = { id: string; path: string; displayName: string | null; agent: string | null; allowedServerIds: string[]; createdAt: number; lastSeenAt: number }
+/**
+ * Approval state of a project record.
+ */
+export type ProjectStatus = "active" | "pending"
+
+/**
+ * One of the [`AnnotationPolicy`] switches.
+ */
+export type AnnotationSwitch = "read_only" | "no_destructive" | "no_open_world"
+
+/**
+ * Annotation switches of a project record; `null` inherits from rules and parents.
+ */
+export type AnnotationSwitches = { readOnly?: boolean | null; noDestructive?: boolean | null; noOpenWorld?: boolean | null }
+
+export type ProjectSummary = { id: string; path: string; displayName: string | null; agent: string | null; allowedServerIds: string[]; toolPermissionServerIds: string[]; status: ProjectStatus; annotationPolicy: AnnotationSwitches; requireApproval: boolean | null; toolSearch: boolean | null; createdAt: number; lastSeenAt: number }
+
+/**
+ * What decided a tool's visibility; `source` on [`EffectiveToolSummary`] names the layer.
+ */
+export type EffectiveReason = "temporary_grant" | "tool_permission" | "server_list" | "repo_config" | "annotation" | "pending_approval" | "unrestricted"
+
+/**
+ * Resolved hints, with the MCP defaults applied to missing annotations: not read-only,
+ * destructive and open-world.
+ */
+export type ToolHints = { readOnly: boolean; destructive: boolean; openWorld: boolean }
+
+export type EffectiveToolSummary = { name: string; serverId: string; serverName: string; allowed: boolean; reason: EffectiveReason; source: string | null; expiresAt: number | null; hiddenBy: AnnotationSwitch | null; hints: ToolHints; description: string | null; descriptionSource: string | null; alias: string | null }
+
+/**
+ * An enabled annotation switch and the tools it hides.
+ */
+export type EffectiveSwitchSummary = { switch: AnnotationSwitch; source: string; hidden: string[] }
+
+export type ProjectEffectiveResponse = { project: ProjectSummary; tools: EffectiveToolSummary[]; switches: EffectiveSwitchSummary[]; warnings: string[] }
+
+/**
+ * Temporary grant: whole `servers` and/or `server::tool` specs for `duration` (e.g. `2h`).
+ */
+export type ProjectGrantRequest = { target: string; servers?: string[]; tools?: string[]; duration: string }
+
+export type GrantSummary = { serverId: string; tools: string[]; grantedAt: number; expiresAt: number; active: boolean }
+
+export type ProjectGrantsResponse = { project: ProjectSummary; grants: GrantSummary[] }
 
 export type CreateMcpRequest = { name: string; protocol: ServerProtocol; command: string | null; args: string | null; endpoint: string | null; env: { [key: string]: string } | null; headers: { [key: string]: string } | null }
 
@@ -30,12 +74,75 @@ export type UpdateMcpEnabled = { enabled: boolean }
 
 export type ProjectAssignRequest = { target: string; servers: string[] }
 
+export type ProjectApproveRequest = { target: string; servers?: string[]; all?: boolean }
+
 export type ProjectToolsRequest = { target: string; tools: string[] }
 
 export type ProjectToolDescRequest = { target: string; tool: string; description: string }
 
 export type ProjectToolResetRequest = { target: string; tool: string }
 
+/**
+ * Replace the alias, title, schema patch and hidden parameters of one tool; `null` and
+ * empty values remove them.
+ */
+export type ProjectToolAliasRequest = { target: string; tool: string; alias?: string | null; title?: string | null; schemaPatch?: string | null; hiddenParameters?: string[] }
+
+export type ProjectAnnotationPolicyRequest = { target: string; switches: AnnotationSwitches }
+
+/**
+ * Override the annotation hints of one tool; all `null` removes the override.
+ */
+export type ProjectToolHintsRequest = { target: string; tool: string; readOnly?: boolean | null; destructive?: boolean | null; openWorld?: boolean | null }
+
+/**
+ * Switch a project between the full tool list and the search meta-tools.
+ */
+export type ProjectToolSearchRequest = { target: string; enabled?: boolean | null }
+
+/**
+ * `[[tool_customizations.arguments]]` entry.
+ */
+export type ArgumentConstraint = { pointer: string; required: boolean; within?: string | null; allow: string[]; deny: string[]; min?: number | null; max?: number | null }
+
+/**
+ * Replace the argument constraints of one tool; an empty list removes them.
+ */
+export type ProjectToolArgumentsRequest = { target: string; tool: string; arguments?: ArgumentConstraint[] }
+
+/**
+ * Set the approval requirement of a project, or of one tool when `tool` is given.
+ */
+export type ProjectRequireApprovalRequest = { target: string; tool?: string | null; required?: boolean | null }
+
+/**
+ * A tool call waiting for a person to confirm it.
+ */
+export type ApprovalRequest = { id: number; projectPath: string; serverId: string; toolName: string; arguments: string | null; requestedAt: number; expiresAt: number }
+
+/**
+ * How a parked call ended.
+ */
+export type ApprovalOutcome = { outcome: "approved" } | { outcome: "denied"; reason: string | null } | { outcome: "timed_out" }
+
+/**
+ * Change notification for SSE subscribers.
+ */
+export type ApprovalEvent = { type: "requested"; request: ApprovalRequest } | { type: "resolved"; id: number; outcome: ApprovalOutcome }
+
+export type ApprovalListResponse = { approvals: ApprovalRequest[] }
+
+export type ResolveApprovalRequest = { id: number; approve: boolean; reason?: string | null }
+
+/**
+ * Starting permissions of a newly seen project while it waits for approval.
+ */
+export type NewProjectPolicy = "allow_all" | "deny_all" | "template"
+
+export type SettingsResponse = { newProjectPolicy: NewProjectPolicy; newProjectTemplate: string | null; permissionTemplates: string[]; approvalTimeoutSecs: number; managementServer: boolean }
+
+export type UpdateSettingsRequest = { newProjectPolicy: NewProjectPolicy | null; newProjectTemplate: string | null; approvalTimeoutSecs: number | null; managementServer: boolean | null }
+
 export type LogFileSummary = { file: string; sizeBytes: number; lineCount: number; from: string | null; to: string | null }
 
 export type LogServerSummary = { serverId: string; files: LogFileSummary[] }
@@ -52,4 +159,4 @@ export type ToolContext = { name: string; callId: string }
 
 export type LogLevel = "trace" | "debug" | "info" | "warn" | "error"
 
-export type LogCategory = "mcpMessage" | "toolRequest" | "toolResponse" | "toolError"
+export type LogCategory = "mcpMessage" | "toolRequest" | "toolResponse" | "toolError" | "policyViolation" | "hook" | "cacheHit"
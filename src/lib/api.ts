@@ -1,9 +1,16 @@
 import { invoke } from "@tauri-apps/api/core";
 import { version as webVersion } from "../../package.json";
 import type {
+  AnnotationSwitches,
+  ApprovalListResponse,
+  ApprovalRequest,
+  ArgumentConstraint,
   LogEntriesResponse,
   LogEntry,
   LogListResponse,
+  ProjectEffectiveResponse,
+  ProjectGrantsResponse,
+  ProjectToolAliasRequest,
 } from "./api-types.generated";
 
 declare global {
@@ -153,6 +160,11 @@ export interface ProjectSummary {
   displayName: string | null;
   agent: string | null;
   allowedServerIds: string[];
+  toolPermissionServerIds?: string[];
+  status: "active" | "pending";
+  annotationPolicy?: AnnotationSwitches;
+  requireApproval?: boolean | null;
+  toolSearch?: boolean | null;
   createdAt: number;
   lastSeenAt: number;
 }
@@ -273,6 +285,51 @@ export const denyProjectServers = async (
   return postJson<ProjectSummary>("/api/project/deny", { target, servers });
 };
 
+export const approveProject = async (
+  target: string,
+  servers: string[] = [],
+  all = false,
+): Promise<ProjectSummary | null> => {
+  return postJson<ProjectSummary>("/api/project/approve", {
+    target,
+    servers,
+    all,
+  });
+};
+
+export const getProjectEffective = async (
+  projectId: string,
+): Promise<ProjectEffectiveResponse | null> => {
+  return getJson<ProjectEffectiveResponse>(
+    `/api/project/${encodeURIComponent(projectId)}/effective`,
+  );
+};
+
+export const grantProjectAccess = async (
+  target: string,
+  duration: string,
+  servers: string[] = [],
+  tools: string[] = [],
+): Promise<ProjectGrantsResponse | null> => {
+  if (servers.length === 0 && tools.length === 0) {
+    return null;
+  }
+  return postJson<ProjectGrantsResponse>("/api/project/grant", {
+    target,
+    servers,
+    tools,
+    duration,
+  });
+};
+
+export const getProjectGrants = async (
+  projectId: string,
+): Promise<ProjectGrantsResponse | null> => {
+  return getJson<ProjectGrantsResponse>(
+    `/api/project/${encodeURIComponent(projectId)}/grants`,
+  );
+};
+
 export const allowProjectTools = async (
   target: string,
   tools: string[],
@@ -318,6 +375,89 @@ export const resetProjectToolDescription = async (
   });
 };
 
+export const setProjectAnnotationPolicy = async (
+  target: string,
+  switches: AnnotationSwitches,
+): Promise<ProjectSummary | null> => {
+  return postJson<ProjectSummary>("/api/project/annotation-policy", {
+    target,
+    switches,
+  });
+};
+
+export const setProjectToolHints = async (
+  target: string,
+  tool: string,
+  hints: { readOnly?: boolean; destructive?: boolean; openWorld?: boolean },
+): Promise<ProjectSummary | null> => {
+  return postJson<ProjectSummary>("/api/project/tool/hints", {
+    target,
+    tool,
+    ...hints,
+  });
+};
+
+export const setProjectToolArguments = async (
+  target: string,
+  tool: string,
+  args: ArgumentConstraint[],
+): Promise<ProjectSummary | null> => {
+  return postJson<ProjectSummary>("/api/project/tool/arguments", {
+    target,
+    tool,
+    arguments: args,
+  });
+};
+
+export const setProjectToolAlias = async (
+  target: string,
+  tool: string,
+  overrides: Omit<ProjectToolAliasRequest, "target" | "tool">,
+): Promise<ProjectSummary | null> => {
+  return postJson<ProjectSummary>("/api/project/tool/alias", {
+    target,
+    tool,
+    ...overrides,
+  });
+};
+
+export const setProjectToolSearch = async (
+  target: string,
+  enabled: boolean | null,
+): Promise<ProjectSummary | null> => {
+  return postJson<ProjectSummary>("/api/project/tool-search", {
+    target,
+    enabled,
+  });
+};
+
+export const setProjectRequireApproval = async (
+  target: string,
+  required: boolean | null,
+  tool?: string,
+): Promise<ProjectSummary | null> => {
+  return postJson<ProjectSummary>("/api/project/require-approval", {
+    target,
+    tool,
+    required,
+  });
+};
+
+// ===== 工具调用审批 =====
+
+export const listApprovals = async (): Promise<ApprovalRequest[]> => {
+  const response = await getJson<ApprovalListResponse>("/api/approvals");
+  return response?.approvals ?? [];
+};
+
+export const resolveApproval = async (
+  id: number,
+  approve: boolean,
+  reason?: string,
+): Promise<ApprovalRequest | null> => {
+  return postJson<ApprovalRequest>("/api/approvals", { id, approve, reason });
+};
+
 // ===== MCP Server 扩展接口 =====
 
 export interface ToolInfo {
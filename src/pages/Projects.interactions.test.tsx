@@ -7,9 +7,12 @@ import {
   listMcpServers,
   listProjects,
   allowProjectServers,
+  approveProject,
   denyProjectServers,
   allowProjectTools,
   denyProjectTools,
+  getProjectEffective,
+  setProjectAnnotationPolicy,
   setProjectToolDescription,
   resetProjectToolDescription,
   type McpServer,
@@ -23,9 +26,12 @@ vi.mock("@/lib/api", async () => {
     listMcpServers: vi.fn(),
     listProjects: vi.fn(),
     allowProjectServers: vi.fn(),
+    approveProject: vi.fn(),
     denyProjectServers: vi.fn(),
     allowProjectTools: vi.fn(),
     denyProjectTools: vi.fn(),
+    getProjectEffective: vi.fn(),
+    setProjectAnnotationPolicy: vi.fn(),
     setProjectToolDescription: vi.fn(),
     resetProjectToolDescription: vi.fn(),
   };
@@ -71,9 +77,12 @@ describe("ProjectsPage", () => {
     vi.mocked(listProjects).mockResolvedValue(projects);
 
     vi.mocked(allowProjectServers).mockResolvedValue(null);
+    vi.mocked(approveProject).mockResolvedValue(null);
     vi.mocked(denyProjectServers).mockResolvedValue(null);
     vi.mocked(allowProjectTools).mockResolvedValue(null);
     vi.mocked(denyProjectTools).mockResolvedValue(null);
+    vi.mocked(getProjectEffective).mockResolvedValue(null);
+    vi.mocked(setProjectAnnotationPolicy).mockResolvedValue(null);
     vi.mocked(setProjectToolDescription).mockResolvedValue(null);
     vi.mocked(resetProjectToolDescription).mockResolvedValue(null);
   });
@@ -385,6 +394,40 @@ describe("ProjectsPage", () => {
     expect(screen.getByText("srv-missing")).toBeInTheDocument();
   });
 
+  it("approves pending projects with tool-level or all-server access", async () => {
+    const user = userEvent.setup();
+
+    vi.mocked(listProjects).mockResolvedValueOnce([
+      {
+        id: "proj-pending",
+        path: "/workspace/pending",
+        displayName: null,
+        agent: null,
+        allowedServerIds: [],
+        toolPermissionServerIds: ["srv-1"],
+        status: "pending",
+        createdAt: Date.now(),
+        lastSeenAt: Date.now(),
+      } as unknown as ProjectSummary,
+    ]);
+
+    renderWithQueryClient(<ProjectsPage />);
+
+    await screen.findByText("/workspace/pending");
+
+    await user.click(screen.getByRole("button", { name: "approve_project" }));
+    await waitFor(() => {
+      expect(approveProject).toHaveBeenCalledWith("proj-pending", [], false);
+    });
+
+    await user.click(
+      screen.getByRole("button", { name: "approve_project_all" }),
+    );
+    await waitFor(() => {
+      expect(approveProject).toHaveBeenCalledWith("proj-pending", [], true);
+    });
+  });
+
   it("closes dialog when escape key is pressed", async () => {
     const user = userEvent.setup();
 
@@ -4,15 +4,22 @@ import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
 import {
   allowProjectServers,
   allowProjectTools,
+  approveProject,
   denyProjectServers,
   denyProjectTools,
+  getProjectEffective,
   listMcpServers,
   listProjects,
   McpServer,
   ProjectSummary,
   resetProjectToolDescription,
+  setProjectAnnotationPolicy,
   setProjectToolDescription,
 } from "@/lib/api";
+import type {
+  AnnotationSwitch,
+  AnnotationSwitches,
+} from "@/lib/api-types.generated";
 import { Button } from "@/components/ui/button";
 import {
   Dialog,
@@ -38,6 +45,27 @@ interface PermissionPayload {
   servers: string[];
 }
 
+const ANNOTATION_SWITCHES: {
+  key: keyof AnnotationSwitches;
+  id: AnnotationSwitch;
+}[] = [
+  { key: "readOnly", id: "read_only" },
+  { key: "noDestructive", id: "no_destructive" },
+  { key: "noOpenWorld", id: "no_open_world" },
+];
+
+type SwitchState = "inherit" | "on" | "off";
+
+const toSwitchState = (value: boolean | null | undefined): SwitchState =>
+  value == null ? "inherit" : value ? "on" : "off";
+
+const fromSwitchState = (state: SwitchState): boolean | null =>
+  state === "inherit" ? null : state === "on";
+
+const hasGrantedAccess = (project: ProjectSummary): boolean =>
+  project.allowedServerIds.length > 0 ||
+  (project.toolPermissionServerIds?.length ?? 0) > 0;
+
 export function ProjectsPage() {
   const { t } = useTranslation();
   const queryClient = useQueryClient();
@@ -68,6 +96,13 @@ export function ProjectsPage() {
   const [toolDenyInput, setToolDenyInput] = useState("");
   const [toolDescName, setToolDescName] = useState("");
   const [toolDescValue, setToolDescValue] = useState("");
+  const [tempSwitches, setTempSwitches] = useState<AnnotationSwitches>({});
+
+  const { data: effective } = useQuery({
+    queryKey: ["project-effective", selectedProject?.id],
+    queryFn: () => getProjectEffective(selectedProject!.id),
+    enabled: isDialogOpen && !!selectedProject,
+  });
 
   const serverNameById = useMemo(() => {
     const map = new Map<string, string>();
@@ -95,6 +130,14 @@ export function ProjectsPage() {
       },
     });
 
+  const { mutate: mutateApprove, isPending: isApproving } = useMutation({
+    mutationFn: ({ target, all }: { target: string; all: boolean }) =>
+      approveProject(target, [], all),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["projects"] });
+    },
+  });
+
   const { mutateAsync: mutateAllowTools, isPending: isAllowingTools } =
     useMutation({
       mutationFn: ({ target, tools }: { target: string; tools: string[] }) =>
@@ -138,7 +181,23 @@ export function ProjectsPage() {
       },
     });
 
+  const { mutateAsync: mutateSwitches, isPending: isSavingSwitches } =
+    useMutation({
+      mutationFn: ({
+        target,
+        switches,
+      }: {
+        target: string;
+        switches: AnnotationSwitches;
+      }) => setProjectAnnotationPolicy(target, switches),
+      onSuccess: () => {
+        queryClient.invalidateQueries({ queryKey: ["projects"] });
+        queryClient.invalidateQueries({ queryKey: ["project-effective"] });
+      },
+    });
+
   const isSaving =
+    isSavingSwitches ||
     isSavingServers ||
     isDenyingServers ||
     isAllowingTools ||
@@ -158,6 +217,7 @@ export function ProjectsPage() {
     setToolDenyInput("");
     setToolDescName("");
     setToolDescValue("");
+    setTempSwitches({});
   };
 
   const handleEditClick = (project: ProjectSummary) => {
@@ -171,6 +231,7 @@ export function ProjectsPage() {
 
     setSelectedProject(project);
     setTempPermissions(initial);
+    setTempSwitches(project.annotationPolicy ?? {});
     setIsDialogOpen(true);
   };
 
@@ -219,6 +280,18 @@ export function ProjectsPage() {
         await mutateDenyTools({ target: selectedProject.id, tools: denyTools });
       }
 
+      const currentSwitches = selectedProject.annotationPolicy ?? {};
+      const switchesChanged = ANNOTATION_SWITCHES.some(
+        ({ key }) =>
+          (currentSwitches[key] ?? null) !== (tempSwitches[key] ?? null),
+      );
+      if (switchesChanged) {
+        await mutateSwitches({
+          target: selectedProject.id,
+          switches: tempSwitches,
+        });
+      }
+
       closeDialog();
     } catch (mutationError) {
       console.error("Failed to update project permissions", mutationError);
@@ -256,7 +329,14 @@ export function ProjectsPage() {
             <TableBody>
               {projects.map((project) => (
                 <TableRow key={project.id}>
-                  <TableCell className="font-mono">{project.path}</TableCell>
+                  <TableCell className="font-mono">
+                    {project.path}
+                    {project.status === "pending" && (
+                      <span className="ml-2 rounded bg-amber-100 px-1.5 py-0.5 font-sans text-xs text-amber-800">
+                        {t("project_pending")}
+                      </span>
+                    )}
+                  </TableCell>
                   <TableCell>
                     {project.allowedServerIds.length > 0
                       ? project.allowedServerIds
@@ -267,7 +347,36 @@ export function ProjectsPage() {
                           .join(", ")
                       : "-"}
                   </TableCell>
-                  <TableCell className="text-right">
+                  <TableCell className="text-right space-x-2">
+                    {project.status === "pending" && (
+                      <>
+                        <Button
+                          size="sm"
+                          disabled={isApproving || !hasGrantedAccess(project)}
+                          title={
+                            hasGrantedAccess(project)
+                              ? undefined
+                              : t("approve_project_needs_servers")
+                          }
+                          onClick={() =>
+                            mutateApprove({ target: project.id, all: false })
+                          }
+                        >
+                          {t("approve_project")}
+                        </Button>
+                        <Button
+                          variant="outline"
+                          size="sm"
+                          disabled={isApproving}
+                          title={t("approve_project_all_hint")}
+                          onClick={() =>
+                            mutateApprove({ target: project.id, all: true })
+                          }
+                        >
+                          {t("approve_project_all")}
+                        </Button>
+                      </>
+                    )}
                     <Button
                       variant="outline"
                       size="sm"
@@ -345,6 +454,52 @@ export function ProjectsPage() {
                 />
               </div>
             </div>
+            <div className="border-t pt-4 space-y-3">
+              <Label className="text-sm font-semibold">
+                {t("project_annotation_switches_label")}
+              </Label>
+              {ANNOTATION_SWITCHES.map(({ key, id }) => {
+                const hidden =
+                  effective?.switches.find((entry) => entry.switch === id)
+                    ?.hidden ?? [];
+                return (
+                  <div key={id} className="space-y-1">
+                    <div className="flex items-center justify-between gap-2">
+                      <Label htmlFor={`switch-${id}`} className="text-sm">
+                        {t(`project_switch_${id}`)}
+                      </Label>
+                      <select
+                        id={`switch-${id}`}
+                        className="rounded border border-border bg-background p-1 text-sm"
+                        value={toSwitchState(tempSwitches[key])}
+                        onChange={(event) =>
+                          setTempSwitches((prev) => ({
+                            ...prev,
+                            [key]: fromSwitchState(
+                              event.target.value as SwitchState,
+                            ),
+                          }))
+                        }
+                      >
+                        <option value="inherit">
+                          {t("project_switch_inherit")}
+                        </option>
+                        <option value="on">{t("project_switch_on")}</option>
+                        <option value="off">{t("project_switch_off")}</option>
+                      </select>
+                    </div>
+                    {hidden.length > 0 && (
+                      <div className="text-xs text-muted-foreground">
+                        {t("project_switch_hides", {
+                          count: hidden.length,
+                          tools: hidden.join(", "),
+                        })}
+                      </div>
+                    )}
+                  </div>
+                );
+              })}
+            </div>
             <div className="border-t pt-4 space-y-3">
               <Label className="text-sm font-semibold">
                 {t("project_tool_description_label")}